@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::ids;
+
+/// GET responses kept on disk between invocations, one file per URL and
+/// credential. Caching is best-effort: a cache that can't be read or written
+/// just means the request goes to the server.
+#[derive(Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// A cached response body with what's needed to revalidate it
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    pub stored_at: DateTime<Utc>,
+    pub body: String,
+}
+
+impl Entry {
+    /// Whether the entry can be used without asking the server
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        let age = Utc::now() - self.stored_at;
+        age.to_std().is_ok_and(|age| age < ttl)
+    }
+}
+
+impl ResponseCache {
+    /// Returns the path to ~/.flexprice/cache
+    pub fn dir() -> PathBuf {
+        let home = dirs::home_dir().expect("Could not determine home directory");
+        home.join(".flexprice").join("cache")
+    }
+
+    pub fn new(ttl: Duration) -> Self {
+        Self { dir: Self::dir(), ttl }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// File name for `url` fetched with `identity`, so profiles, tenants,
+    /// and environments never see each other's responses
+    pub fn key(url: &str, identity: &[Option<&str>]) -> String {
+        let parts = std::iter::once(url).chain(identity.iter().map(|part| part.unwrap_or_default()));
+        format!("{}.json", ids::stable_hash(parts))
+    }
+
+    pub fn load(&self, key: &str) -> Option<Entry> {
+        let data = fs::read_to_string(self.dir.join(key)).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    pub fn store(&self, key: &str, entry: &Entry) {
+        let Ok(data) = serde_json::to_string(entry) else { return };
+        if fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        // Write then rename, so a concurrent reader never sees half a file
+        let tmp = self.dir.join(format!("{}.{}.tmp", key, std::process::id()));
+        if fs::write(&tmp, data).is_ok() && fs::rename(&tmp, self.dir.join(key)).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+
+    /// Drop every cached response, e.g. after a request that changed something
+    pub fn clear() {
+        let Ok(entries) = fs::read_dir(Self::dir()) else { return };
+        for entry in entries.flatten() {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
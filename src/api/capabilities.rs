@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::client::{ApiClient, ServerInfo};
+use crate::api::error::ApiClientError;
+use crate::config::Credentials;
+use crate::utils::output;
+
+/// Oldest server release this CLI is built against, as (major, minor)
+pub const MIN_SERVER_VERSION: (u64, u64) = (1, 0);
+
+/// How long what a server said about itself is trusted before asking again
+const REFRESH_AFTER_HOURS: i64 = 24;
+
+/// How long to wait before asking again after the server didn't answer
+const RETRY_PROBE_AFTER_MINUTES: i64 = 5;
+
+/// How long the check before a command waits for the server to answer
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// A command that needs endpoints added after `MIN_SERVER_VERSION`
+pub struct Requirement {
+    /// Subcommand path, e.g. `["invoices", "comment"]`; covers its subcommands too
+    pub command: &'static [&'static str],
+    /// What the server lists under `capabilities` when it has the endpoints
+    pub capability: &'static str,
+    /// First release with the endpoints, for servers that report a version
+    /// but no capabilities
+    pub since: (u64, u64),
+}
+
+pub const REQUIREMENTS: &[Requirement] = &[
+    Requirement { command: &["addons"], capability: "addons", since: (1, 2) },
+    Requirement { command: &["subscriptions", "add-addon"], capability: "addons", since: (1, 2) },
+    Requirement { command: &["taxes"], capability: "taxes", since: (1, 2) },
+    Requirement { command: &["audit"], capability: "audit_logs", since: (1, 3) },
+    Requirement { command: &["invoices", "comment"], capability: "invoice_comments", since: (1, 3) },
+    Requirement { command: &["invoices", "comments"], capability: "invoice_comments", since: (1, 3) },
+];
+
+/// What a server last said about itself, kept per API URL in
+/// ~/.flexprice/servers.json so commands don't ask every time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Known {
+    pub checked_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<String>>,
+    /// The server didn't answer at `checked_at`; what else is here is from
+    /// the last time it did, if ever
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub probe_failed: bool,
+}
+
+impl Known {
+    fn is_fresh(&self) -> bool {
+        let ttl = if self.probe_failed {
+            Duration::minutes(RETRY_PROBE_AFTER_MINUTES)
+        } else {
+            Duration::hours(REFRESH_AFTER_HOURS)
+        };
+        Utc::now() - self.checked_at < ttl
+    }
+
+    /// Whether the server reports a release older than this CLI supports
+    pub fn too_old(&self) -> bool {
+        self.version.as_deref().and_then(parse_version).is_some_and(|v| v < MIN_SERVER_VERSION)
+    }
+
+    /// Whether the server has what `requirement` needs. A server that lists
+    /// its capabilities is taken at its word; otherwise its version decides,
+    /// and one that reports neither is assumed to have everything.
+    pub fn supports(&self, requirement: &Requirement) -> bool {
+        if let Some(capabilities) = &self.capabilities {
+            return capabilities.iter().any(|c| c == requirement.capability);
+        }
+        match self.version.as_deref().and_then(parse_version) {
+            Some(version) => version >= requirement.since,
+            None => true,
+        }
+    }
+
+    /// Why `command` can't run against this server, if it can't: a missing
+    /// capability when the server lists them, else a version that's too old
+    pub fn unsupported(&self, command: &[String]) -> Option<String> {
+        let requirement = requirement(command).filter(|r| !self.supports(r))?;
+        let command = requirement.command.join(" ");
+        let reason = match (&self.capabilities, &self.version) {
+            (Some(_), Some(version)) => format!(
+                "`flexprice {}` needs the server's `{}` capability, which this server (FlexPrice {}) doesn't list. Turn it on or upgrade the server",
+                command, requirement.capability, version
+            ),
+            (Some(_), None) => format!(
+                "`flexprice {}` needs the server's `{}` capability, which this server doesn't list. Turn it on or upgrade the server",
+                command, requirement.capability
+            ),
+            (None, version) => {
+                let (major, minor) = requirement.since;
+                format!(
+                    "`flexprice {}` needs FlexPrice {}.{} or later, and this server runs {}. Upgrade the server",
+                    command,
+                    major,
+                    minor,
+                    version.as_deref().unwrap_or("an older release")
+                )
+            }
+        };
+        Some(format!("{}, or run `flexprice doctor` to see what else is unavailable.", reason))
+    }
+}
+
+/// The requirement covering `command`, if any
+pub fn requirement(command: &[String]) -> Option<&'static Requirement> {
+    REQUIREMENTS.iter().find(|r| command.len() >= r.command.len() && r.command.iter().zip(command).all(|(a, b)| a == b))
+}
+
+/// `v1.4.2` or `1.4` as (major, minor)
+pub fn parse_version(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split(['.', '-', '+']);
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().and_then(|m| m.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+/// Returns the path to ~/.flexprice/servers.json
+pub fn path() -> PathBuf {
+    let home = dirs::home_dir().expect("Could not determine home directory");
+    home.join(".flexprice").join("servers.json")
+}
+
+fn load_all() -> BTreeMap<String, Known> {
+    fs::read_to_string(path()).ok().and_then(|data| serde_json::from_str(&data).ok()).unwrap_or_default()
+}
+
+/// What's known about the server at `api_url`, however old
+pub fn cached(api_url: &str) -> Option<Known> {
+    load_all().remove(api_url)
+}
+
+/// Best-effort, like the response cache: a file that can't be written just
+/// means asking the server again next time
+fn store(api_url: &str, known: &Known) {
+    let mut all = load_all();
+    all.insert(api_url.to_string(), known.clone());
+    let Ok(data) = serde_json::to_string_pretty(&all) else { return };
+    if let Some(dir) = path().parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(path(), data);
+}
+
+/// Remember what the server at `api_url` just said about itself
+pub fn remember(api_url: &str, info: &ServerInfo) -> Known {
+    let known = Known {
+        checked_at: Utc::now(),
+        version: info.version.clone(),
+        capabilities: info.capabilities.clone(),
+        probe_failed: false,
+    };
+    store(api_url, &known);
+    known
+}
+
+/// Ask the server about itself and remember the answer
+pub async fn refresh(client: &ApiClient) -> Result<Known> {
+    let info = client.server_info().await?;
+    Ok(remember(client.base_url(), &info))
+}
+
+/// Check the invoked command against what the server supports, asking the
+/// server again once a day. Warns when the server is older than this CLI
+/// supports, and fails a command whose endpoints it doesn't have. The server
+/// gets one short attempt to answer; one that doesn't isn't reported here, since
+/// the command's own requests will, and isn't asked again for a few minutes.
+pub async fn negotiate(command: &[String]) -> Result<()> {
+    let Ok(creds) = Credentials::load() else { return Ok(()) };
+    if !creds.is_authenticated() {
+        return Ok(());
+    }
+    let Ok(client) = ApiClient::new(creds) else { return Ok(()) };
+    let api_url = client.base_url().to_string();
+    let cached = cached(&api_url);
+    let known = match cached.clone().filter(Known::is_fresh) {
+        Some(known) => known,
+        None => {
+            let Ok(known) = refresh(&client.without_cache().for_probe(PROBE_TIMEOUT)).await else {
+                // Don't ask again for a few minutes, going by what the server
+                // said last time, if it ever answered
+                let known = Known { checked_at: Utc::now(), probe_failed: true, ..cached.unwrap_or_default() };
+                store(&api_url, &known);
+                return check(&known, command);
+            };
+            if known.too_old() {
+                let (major, minor) = MIN_SERVER_VERSION;
+                output::warning(&format!(
+                    "{} runs FlexPrice {}, older than the {}.{} this CLI needs; some commands may fail. Run `flexprice doctor` for details.",
+                    api_url,
+                    known.version.as_deref().unwrap_or_default(),
+                    major,
+                    minor
+                ));
+            }
+            known
+        }
+    };
+    check(&known, command)
+}
+
+fn check(known: &Known, command: &[String]) -> Result<()> {
+    match known.unsupported(command) {
+        Some(reason) => Err(ApiClientError::unsupported(reason).into()),
+        None => Ok(()),
+    }
+}
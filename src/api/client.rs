@@ -1,26 +1,122 @@
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
 use anyhow::{Context, Result};
-use reqwest::{Client, Response, StatusCode};
+use colored::Colorize;
+use dialoguer::{Input, Password};
+use futures::stream::{self, Stream, StreamExt};
+use reqwest::header::{HeaderValue, AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{Certificate, Client, Method, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::config::Credentials;
+use crate::api::cache::{Entry, ResponseCache};
+use crate::api::error::ApiClientError;
+use crate::api::tls;
+use crate::api::models::{AuthResponse, ListResponse, LoginRequest};
+use crate::api::query::QueryBuilder;
+use crate::config::store::token_expiry;
+use crate::config::{globals, ConfigFile, Credentials};
+use crate::utils::spinner::{self, Progress};
+use crate::utils::{log, output};
+
+/// POST endpoints that only read, so they're allowed in read-only mode
+const QUERY_ENDPOINTS: &[&str] = &["/v1/auth/login", "/v1/events/usage", "/v1/events/usage/meter", "/v1/subscriptions/usage"];
+
+/// Longest wait between attempts, whether computed or asked for by the server
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+/// A client certificate, as the paths of its certificate and key files
+type ClientCert = Option<(String, Option<String>)>;
+
+/// The process's HTTP clients, one per client certificate in use. Every
+/// `ApiClient` sends through one, so commands that make many requests, or
+/// talk to two profiles, reuse open connections.
+static HTTP: OnceLock<Mutex<HashMap<ClientCert, Client>>> = OnceLock::new();
+
+/// The shared HTTP client for a profile, built on first use from the
+/// connection flags and the profile's client certificate: pooled keep-alive
+/// connections, HTTP/2 where the server offers it, and gzip responses
+fn http(credentials: &Credentials) -> Result<Client> {
+    let cert: ClientCert = credentials.client_cert.clone().map(|c| (c, credentials.client_key.clone()));
+    let clients = HTTP.get_or_init(Mutex::default);
+    if let Some(client) = clients.lock().unwrap().get(&cert) {
+        return Ok(client.clone());
+    }
+    let opts = globals::get();
+    // Shell completion builds clients before the flags are parsed
+    let or = |value: Duration, default: u64| if value.is_zero() { Duration::from_secs(default) } else { value };
+    let mut builder = Client::builder()
+        .timeout(or(opts.request_timeout, 30))
+        .connect_timeout(or(opts.connect_timeout, 10))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(16)
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_adaptive_window(true)
+        .gzip(true);
+    if let Some(proxy) = &opts.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).with_context(|| format!("Invalid proxy URL '{}'", proxy))?);
+    }
+    if let Some(path) = &opts.ca_cert {
+        let pem = std::fs::read(path).with_context(|| format!("Failed to read CA certificate {}", path.display()))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("No PEM certificates found in {}", path.display()))?;
+        if certs.is_empty() {
+            anyhow::bail!("No PEM certificates found in {}", path.display());
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    if opts.insecure {
+        // Once per run: it may come from config.toml or the environment unnoticed
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        WARNED.call_once(|| {
+            output::warning("TLS certificate checks are off (--insecure or FLEXPRICE_INSECURE); only use this with a server you control.")
+        });
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if let Some((cert, key)) = &cert {
+        builder = builder.identity(tls::identity(cert, key.as_deref())?);
+    }
+    let client = builder.build().context("Failed to create HTTP client")?;
+    Ok(clients.lock().unwrap().entry(cert).or_insert(client).clone())
+}
+
+/// Returned in place of a response when `--dry-run` held back a request that
+/// would have changed something, after the request has been printed
+#[derive(Debug)]
+pub struct DryRun;
+
+impl std::fmt::Display for DryRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Dry run: request not sent")
+    }
+}
+
+impl std::error::Error for DryRun {}
 
 /// FlexPrice API client with automatic auth and error handling
 #[derive(Clone)]
 pub struct ApiClient {
     client: Client,
     base_url: String,
-    credentials: Credentials,
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct ApiError {
-    #[serde(default)]
-    error: Option<String>,
-    #[serde(default)]
-    message: Option<String>,
-    #[serde(default)]
-    hint: Option<String>,
+    /// Shared by clones so a renewed login session reaches all of them
+    credentials: Arc<Mutex<Credentials>>,
+    /// Held while a session is being renewed, so concurrent requests wait
+    /// for one login prompt instead of each showing their own
+    renewing: Arc<tokio::sync::Mutex<()>>,
+    /// Whether an expired session is renewed by prompting on the terminal
+    login_prompt: bool,
+    /// Where GET responses are reused from, with `--cache-ttl`
+    cache: Option<ResponseCache>,
+    /// Set by `for_probe`: one attempt per request, bounded by this timeout
+    probe_timeout: Option<Duration>,
+    /// Set when the server rate-limits a request; shared by clones so that
+    /// concurrent requests all hold off, not just the one that got the 429
+    paused_until: Arc<Mutex<Option<Instant>>>,
 }
 
 impl ApiClient {
@@ -31,58 +127,262 @@ impl ApiClient {
             credentials.api_url.trim_end_matches('/').to_string()
         };
 
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
-
         Ok(Self {
-            client,
+            client: http(&credentials)?,
             base_url,
-            credentials,
+            credentials: Arc::new(Mutex::new(credentials)),
+            renewing: Arc::default(),
+            login_prompt: std::io::stdin().is_terminal() && std::io::stderr().is_terminal(),
+            cache: globals::get().cache_ttl.filter(|_| !globals::get().no_cache).map(ResponseCache::new),
+            probe_timeout: None,
+            paused_until: Arc::default(),
         })
     }
 
+    /// The credentials requests are sent with, including a renewed session
+    pub fn credentials(&self) -> Credentials {
+        self.credentials.lock().unwrap().clone()
+    }
+
+    /// The server requests go to, without a trailing slash
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Always ask the server, for callers that show live data
+    pub fn without_cache(mut self) -> Self {
+        self.cache = None;
+        self
+    }
+
+    /// Fail requests once the login session expires instead of prompting, for
+    /// callers that own the terminal and call `renew_session` themselves
+    pub fn without_login_prompt(mut self) -> Self {
+        self.login_prompt = false;
+        self
+    }
+
+    /// Send each request once, without `--retries`, and give up after
+    /// `timeout`, for checks that mustn't hold up the command they precede
+    pub fn for_probe(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+
     fn url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 
     fn apply_auth(&self, mut req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-        if let Some((header, value)) = self.credentials.get_auth_header() {
+        let credentials = self.credentials.lock().unwrap();
+        if let Some((header, value)) = credentials.get_auth_header() {
             req = req.header(header, value);
         }
-        if let Some(ref env_id) = self.credentials.environment_id {
+        if let Some(ref env_id) = credentials.environment_id {
             req = req.header("x-environment-id", env_id);
         }
+        // An API key belongs to one tenant; a login session can span several
+        if credentials.api_key.is_none() {
+            if let Some(ref tenant_id) = credentials.tenant_id {
+                req = req.header("x-tenant-id", tenant_id);
+            }
+        }
         req
     }
 
+    /// Send a request, recording it in the request log. Transient failures are
+    /// retried up to `--retries` times with jittered exponential backoff.
+    async fn send(&self, req: reqwest::RequestBuilder) -> Result<Response> {
+        let mut req = req.build().context("Failed to build request")?;
+        let opts = globals::get();
+        if (opts.read_only || self.credentials.lock().unwrap().read_only) && !is_read(&req) {
+            anyhow::bail!(
+                "Refusing {} {}: read-only mode is on (--read-only or the profile's read_only setting)",
+                req.method(),
+                req.url().path()
+            );
+        }
+        if opts.dry_run && !is_read(&req) {
+            print_dry_run(&req);
+            return Err(DryRun.into());
+        }
+        // Whatever this changes could be in any cached response
+        if !is_read(&req) {
+            ResponseCache::clear();
+        }
+        let uses_session = req.headers().contains_key(AUTHORIZATION);
+        if uses_session && self.session_expired() {
+            if !self.login_prompt {
+                let message = format!("{}. Run `flexprice auth login` to sign in again.", self.expired_message());
+                return Err(ApiClientError::auth(message).into());
+            }
+            self.renew_session().await?;
+            self.authorize(&mut req);
+        }
+        if let Some(timeout) = self.probe_timeout {
+            *req.timeout_mut() = Some(timeout);
+        }
+        let max_attempts = if self.probe_timeout.is_some() { 1 } else { opts.retries + 1 };
+        let mut attempt = 1;
+        let mut renewed = false;
+        loop {
+            self.wait_if_paused().await;
+            // Requests whose body cannot be replayed get a single attempt
+            let replay = req.try_clone();
+            let idempotent = is_idempotent(req.method()) || req.headers().contains_key(IDEMPOTENCY_KEY);
+            let outcome = self.execute(req).await;
+            // A session the server no longer accepts gets one fresh login,
+            // whatever expiry the token claimed
+            let rejected = outcome.as_ref().is_ok_and(|resp| resp.status() == StatusCode::UNAUTHORIZED);
+            let replay = match replay {
+                Some(mut retry) if uses_session && rejected && !renewed => {
+                    self.end_session();
+                    if !self.login_prompt {
+                        return outcome.map_err(|e| ApiClientError::network(e).into());
+                    }
+                    self.renew_session().await?;
+                    self.authorize(&mut retry);
+                    renewed = true;
+                    req = retry;
+                    continue;
+                }
+                replay => replay,
+            };
+            let next = replay.filter(|_| attempt < max_attempts);
+            // A refused connection never reached the server, so any method is
+            // safe to send again
+            let retry = next.filter(|_| match &outcome {
+                Ok(resp) => is_transient(resp.status(), idempotent),
+                Err(e) => idempotent || e.is_connect(),
+            });
+            let Some(next) = retry else {
+                return outcome.map_err(|e| ApiClientError::network(e).into());
+            };
+            let rate_limited = outcome.as_ref().is_ok_and(|resp| resp.status() == StatusCode::TOO_MANY_REQUESTS);
+            let server_wait = outcome.ok().and_then(|resp| retry_after(&resp));
+            attempt += 1;
+            let wait = server_wait
+                .unwrap_or_else(|| jittered_backoff(opts.retry_delay, attempt))
+                .min(MAX_RETRY_WAIT);
+            if rate_limited {
+                self.pause(wait);
+            }
+            spinner::backoff(attempt, max_attempts, wait).await;
+            req = next;
+        }
+    }
+
+    /// Whether requests are sent with a login session that has expired
+    pub fn session_expired(&self) -> bool {
+        self.credentials.lock().unwrap().session_expired()
+    }
+
+    /// Log in again for an expired session, asking for the password on the
+    /// terminal, and save the new token to the selected profile. Concurrent
+    /// callers share one prompt.
+    pub async fn renew_session(&self) -> Result<()> {
+        let _renewing = self.renewing.lock().await;
+        let email = {
+            let credentials = self.credentials.lock().unwrap();
+            if !credentials.session_expired() {
+                return Ok(());
+            }
+            credentials.email.clone()
+        };
+        let message = self.expired_message();
+        let (email, password) = spinner::suspend_active(|| -> Result<(String, String)> {
+            output::warning(&format!("{}. Log in again to continue.", message));
+            let email = match email {
+                Some(email) => email,
+                None => Input::new().with_prompt("  Email").interact_text()?,
+            };
+            let password = Password::new().with_prompt(format!("  Password for {}", email)).interact()?;
+            Ok((email, password))
+        })?;
+
+        let login = LoginRequest { email: email.clone(), password };
+        let req = self.client.post(self.url("/v1/auth/login")).json(&login).build().context("Failed to build request")?;
+        let resp = self.execute(req).await.map_err(ApiClientError::network)?;
+        let auth: AuthResponse = Self::handle_response(resp).await.context("Login failed")?;
+        let renewed = {
+            let mut credentials = self.credentials.lock().unwrap();
+            credentials.token_expires_at = token_expiry(&auth.token);
+            credentials.auth_token = Some(auth.token);
+            credentials.email = Some(email);
+            credentials.clone()
+        };
+        if let Err(e) = save_session(&renewed) {
+            output::warning(&format!("Logged in, but the new session couldn't be saved: {:#}", e));
+        }
+        Ok(())
+    }
+
+    /// Treat the current login session as over, after the server rejected it
+    fn end_session(&self) {
+        self.credentials.lock().unwrap().token_expires_at = Some(chrono::Utc::now());
+    }
+
+    fn expired_message(&self) -> String {
+        match self.credentials.lock().unwrap().session_expires_at() {
+            Some(at) => format!("Your login session expired at {}", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")),
+            None => "Your login session has expired".to_string(),
+        }
+    }
+
+    /// Put the current session's token on a request built before it was renewed
+    fn authorize(&self, req: &mut reqwest::Request) {
+        let token = self.credentials.lock().unwrap().auth_token.clone();
+        if let Some(value) = token.and_then(|t| HeaderValue::from_str(&format!("Bearer {}", t)).ok()) {
+            req.headers_mut().insert(AUTHORIZATION, value);
+        }
+    }
+
+    /// Hold further requests from this client (and its clones) for `wait`
+    fn pause(&self, wait: Duration) {
+        let until = Instant::now() + wait;
+        let mut paused = self.paused_until.lock().unwrap();
+        if paused.is_none_or(|p| p < until) {
+            *paused = Some(until);
+        }
+    }
+
+    async fn wait_if_paused(&self) {
+        let until = *self.paused_until.lock().unwrap();
+        if let Some(until) = until.filter(|u| *u > Instant::now()) {
+            tokio::time::sleep_until(until.into()).await;
+        }
+    }
+
+    /// Send a single attempt, recording it in the request log
+    async fn execute(&self, req: reqwest::Request) -> reqwest::Result<Response> {
+        let (method, url) = (req.method().to_string(), req.url().to_string());
+        log::headers("request", req.headers());
+        let started = Instant::now();
+        let result = self.client.execute(req).await;
+        match &result {
+            Ok(resp) => {
+                let request_id = request_id(resp);
+                log::request(&method, &url, Some(resp.status().as_u16()), started.elapsed(), request_id.as_deref());
+                log::headers("response", resp.headers());
+            }
+            Err(_) => log::request(&method, &url, None, started.elapsed(), None),
+        }
+        result
+    }
+
+    /// Parse a successful response as JSON, taking an empty body as `null`
     async fn handle_response<T: DeserializeOwned>(response: Response) -> Result<T> {
         let status = response.status();
         if status.is_success() {
-            let body = response.json::<T>().await
+            let text = response.text().await.context("Failed to read response body")?;
+            let text = if text.trim().is_empty() { "null" } else { &text };
+            let body = serde_json::from_str::<T>(text)
                 .context("Failed to parse response body")?;
             Ok(body)
         } else {
+            let request_id = request_id(&response);
             let body_text = response.text().await.unwrap_or_default();
-            let err_msg = if let Ok(api_err) = serde_json::from_str::<ApiError>(&body_text) {
-                let msg = api_err.error
-                    .or(api_err.message)
-                    .unwrap_or_else(|| "Unknown error".to_string());
-                if let Some(hint) = api_err.hint {
-                    format!("{} ({}): {} — {}", status.as_u16(), status.canonical_reason().unwrap_or(""), msg, hint)
-                } else {
-                    format!("{} ({}): {}", status.as_u16(), status.canonical_reason().unwrap_or(""), msg)
-                }
-            } else {
-                match status {
-                    StatusCode::UNAUTHORIZED => "Authentication failed. Run `flexprice auth login` or check your API key.".to_string(),
-                    StatusCode::FORBIDDEN => "Permission denied. Your credentials may not have access to this resource.".to_string(),
-                    StatusCode::NOT_FOUND => "Resource not found. Verify the ID is correct.".to_string(),
-                    _ => format!("{}: {}", status, body_text),
-                }
-            };
-            anyhow::bail!("{}", err_msg)
+            Err(ApiClientError::from_response(status, &body_text, request_id).into())
         }
     }
 
@@ -91,74 +391,324 @@ impl ApiClient {
         if status.is_success() {
             Ok(response.text().await.unwrap_or_default())
         } else {
+            let request_id = request_id(&response);
             let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("{}: {}", status, body)
+            Err(ApiClientError::from_response(status, &body, request_id).into())
         }
     }
 
-    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
-        let req = self.client.get(self.url(path));
+    /// Send an arbitrary request, returning the body as JSON (`null` when
+    /// empty, a string when not JSON)
+    pub async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let mut req = self.client.request(method, self.url(path));
+        if let Some(body) = body {
+            req = req.json(body);
+        }
         let req = self.apply_auth(req);
-        let resp = req.send().await.context("Request failed")?;
+        let resp = self.send(req).await?;
+        let status = resp.status();
+        let request_id = request_id(&resp);
+        let text = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(ApiClientError::from_response(status, &text, request_id).into());
+        }
+        if text.trim().is_empty() {
+            return Ok(serde_json::Value::Null);
+        }
+        Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text)))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = self.url(path);
+        let req = self.apply_auth(self.client.get(&url));
+        if let Some(cache) = &self.cache {
+            return self.get_cached(cache, &url, req).await;
+        }
+        let resp = self.send(req).await?;
         Self::handle_response(resp).await
     }
 
+    /// GET `path` with query parameters, encoded for the URL; a key may be
+    /// repeated to send several values
+    pub async fn get_with_query<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+        self.get(&query.iter().copied().collect::<QueryBuilder>().to_path(path)).await
+    }
+
+    /// GET through the response cache: an entry younger than the TTL is used
+    /// without asking the server, an older one is revalidated with its ETag,
+    /// and successful responses are stored for next time
+    async fn get_cached<T: DeserializeOwned>(&self, cache: &ResponseCache, url: &str, mut req: reqwest::RequestBuilder) -> Result<T> {
+        let key = {
+            let credentials = self.credentials.lock().unwrap();
+            let auth = credentials.get_auth_header().map(|(_, value)| value);
+            ResponseCache::key(url, &[auth.as_deref(), credentials.environment_id.as_deref(), credentials.tenant_id.as_deref()])
+        };
+        let cached = cache.load(&key);
+        if let Some(entry) = &cached {
+            if entry.is_fresh(cache.ttl()) {
+                log::cache_hit(url, (chrono::Utc::now() - entry.stored_at).to_std().unwrap_or_default());
+                return serde_json::from_str(&entry.body).context("Failed to parse cached response");
+            }
+            if let Some(etag) = &entry.etag {
+                req = req.header(IF_NONE_MATCH, etag);
+            }
+        }
+        let resp = self.send(req).await?;
+        if let Some(mut entry) = cached.filter(|_| resp.status() == StatusCode::NOT_MODIFIED) {
+            entry.stored_at = chrono::Utc::now();
+            cache.store(&key, &entry);
+            return serde_json::from_str(&entry.body).context("Failed to parse cached response");
+        }
+        if !resp.status().is_success() {
+            return Self::handle_response(resp).await;
+        }
+        let etag = resp.headers().get(ETAG).and_then(|v| v.to_str().ok()).map(String::from);
+        let body = resp.text().await.context("Failed to read response body")?;
+        let value = serde_json::from_str(&body).context("Failed to parse response body")?;
+        cache.store(&key, &Entry { url: url.to_string(), etag, stored_at: chrono::Utc::now(), body });
+        Ok(value)
+    }
+
+    /// Fetch every page of a list endpoint using `limit`/`offset` pagination
+    pub async fn get_all<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>> {
+        const PAGE_SIZE: usize = 100;
+        let mut all = Vec::new();
+        loop {
+            let page_path = QueryBuilder::new().param("limit", PAGE_SIZE).param("offset", all.len()).to_path(path);
+            let page: ListResponse<T> = self.get(&page_path).await?;
+            let fetched = page.items.len();
+            all.extend(page.items);
+            let reached_total = page.total_count.is_some_and(|t| all.len() as i64 >= t);
+            if fetched < PAGE_SIZE || reached_total {
+                return Ok(all);
+            }
+        }
+    }
+
+    /// GET each path with at most `concurrency` requests in flight, yielding
+    /// results in the order of `paths`
+    pub fn get_many<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        paths: Vec<String>,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<T>> + 'a {
+        stream::iter(paths)
+            .map(move |path| async move { self.get::<T>(&path).await })
+            .buffered(concurrency.max(1))
+    }
+
     pub async fn get_text(&self, path: &str) -> Result<String> {
         let req = self.client.get(self.url(path));
         let req = self.apply_auth(req);
-        let resp = req.send().await.context("Request failed")?;
+        let resp = self.send(req).await?;
         Self::handle_response_text(resp).await
     }
 
+    /// Download a binary body (e.g. a PDF) into `out` chunk by chunk,
+    /// advancing `progress` by bytes received. Returns the bytes written.
+    pub async fn get_bytes(&self, path: &str, out: &mut impl std::io::Write, progress: Option<&Progress>) -> Result<u64> {
+        let req = self.client.get(self.url(path));
+        let req = self.apply_auth(req);
+        let mut resp = self.send(req).await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let request_id = request_id(&resp);
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ApiClientError::from_response(status, &body, request_id).into());
+        }
+        if let (Some(progress), Some(len)) = (progress, resp.content_length()) {
+            progress.set_length(len);
+        }
+        let mut written = 0;
+        while let Some(chunk) = resp.chunk().await.context("Download interrupted")? {
+            out.write_all(&chunk).context("Failed to write download")?;
+            written += chunk.len() as u64;
+            if let Some(progress) = progress {
+                progress.inc(chunk.len() as u64);
+            }
+        }
+        Ok(written)
+    }
+
     pub async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
         let req = self.client.post(self.url(path)).json(body);
         let req = self.apply_auth(req);
-        let resp = req.send().await.context("Request failed")?;
+        let resp = self.send(req).await?;
+        Self::handle_response(resp).await
+    }
+
+    /// POST with an `Idempotency-Key`, so the server applies it once however
+    /// often it is sent; such requests are retried like idempotent methods
+    pub async fn post_idempotent<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B, key: &str) -> Result<T> {
+        let req = self.client.post(self.url(path)).json(body).header(IDEMPOTENCY_KEY, key);
+        let req = self.apply_auth(req);
+        let resp = self.send(req).await?;
         Self::handle_response(resp).await
     }
 
     pub async fn post_empty<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
         let req = self.client.post(self.url(path));
         let req = self.apply_auth(req);
-        let resp = req.send().await.context("Request failed")?;
+        let resp = self.send(req).await?;
         Self::handle_response(resp).await
     }
 
     pub async fn put<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
         let req = self.client.put(self.url(path)).json(body);
         let req = self.apply_auth(req);
-        let resp = req.send().await.context("Request failed")?;
+        let resp = self.send(req).await?;
         Self::handle_response(resp).await
     }
 
     pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
         let req = self.client.delete(self.url(path));
         let req = self.apply_auth(req);
-        let resp = req.send().await.context("Request failed")?;
+        let resp = self.send(req).await?;
         Self::handle_response(resp).await
     }
 
+    /// Delete, ignoring whatever the server sends back
     pub async fn delete_empty(&self, path: &str) -> Result<()> {
-        let req = self.client.delete(self.url(path));
-        let req = self.apply_auth(req);
-        let resp = req.send().await.context("Request failed")?;
-        let status = resp.status();
-        if status.is_success() {
-            Ok(())
-        } else {
-            let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("{}: {}", status, body)
-        }
+        self.delete::<serde::de::IgnoredAny>(path).await.map(|_| ())
     }
 
     /// Health check — used for validating connection + credentials
     pub async fn health_check(&self) -> Result<()> {
+        self.server_info().await.map(|_| ())
+    }
+
+    /// Check the server is up, noting its clock, version, and capabilities as
+    /// `/health` reports them
+    pub async fn server_info(&self) -> Result<ServerInfo> {
         let req = self.client.get(self.url("/health"));
-        let resp = req.send().await.context("Cannot reach FlexPrice API")?;
-        if resp.status().is_success() {
-            Ok(())
-        } else {
-            anyhow::bail!("API returned status {}", resp.status())
+        let resp = self.send(req).await.context("Cannot reach FlexPrice API")?;
+        let status = resp.status();
+        if !status.is_success() {
+            let request_id = request_id(&resp);
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ApiClientError::from_response(status, &body, request_id).into());
+        }
+        let header = |name: &str| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let date = header("date")
+            .and_then(|d| chrono::DateTime::parse_from_rfc2822(&d).ok())
+            .map(|d| d.with_timezone(&chrono::Utc));
+        let version = header("x-flexprice-version");
+        let body: serde_json::Value = resp.json().await.unwrap_or_default();
+        let version = version.or_else(|| body.get("version").and_then(|v| v.as_str()).map(str::to_string));
+        let capabilities = body.get("capabilities").and_then(|c| serde_json::from_value(c.clone()).ok());
+        Ok(ServerInfo { date, version, capabilities })
+    }
+}
+
+/// What the server says about itself on `/health`
+#[derive(Debug, Clone, Default)]
+pub struct ServerInfo {
+    /// The server's clock, from the `Date` header
+    pub date: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only reported by some deployments
+    pub version: Option<String>,
+    /// Optional features the server has, e.g. `taxes`, when it lists them
+    pub capabilities: Option<Vec<String>>,
+}
+
+/// Keep a renewed session for later commands, if it came from the selected
+/// profile rather than flags or environment variables
+fn save_session(renewed: &Credentials) -> Result<()> {
+    let mut config = ConfigFile::load()?;
+    let profile = config.selected_profile();
+    let mut stored = config.credentials(&profile)?;
+    if stored.api_key.is_some() || stored.auth_token.is_none() || stored.api_url != renewed.api_url {
+        return Ok(());
+    }
+    stored.auth_token = renewed.auth_token.clone();
+    stored.token_expires_at = renewed.token_expires_at;
+    stored.email = renewed.email.clone();
+    config.store_credentials(&profile, stored)?;
+    Ok(())
+}
+
+/// Header that makes a POST safe to send again
+const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Methods that may be repeated without changing the outcome
+fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS)
+}
+
+/// Show the method, URL, and body of a request `--dry-run` is holding back
+fn print_dry_run(req: &reqwest::Request) {
+    let body = req
+        .body()
+        .and_then(|b| b.as_bytes())
+        .map(|bytes| serde_json::from_slice(bytes).unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(bytes).into_owned())));
+    let text = if output::human() {
+        let mut text = format!("{} {} {}", "DRY RUN".yellow().bold(), req.method().as_str().bold(), req.url());
+        if let Some(body) = &body {
+            text.push('\n');
+            text.push_str(&serde_json::to_string_pretty(body).unwrap_or_default());
         }
+        text
+    } else {
+        output::print_record(&serde_json::json!({
+            "method": req.method().as_str(),
+            "url": req.url().as_str(),
+            "body": body,
+        }))
+    };
+    spinner::suspend_active(|| println!("{}", text));
+}
+
+/// Whether a request only reads data
+fn is_read(req: &reqwest::Request) -> bool {
+    match *req.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => true,
+        // Plan-change previews are per subscription, so matched by suffix
+        Method::POST => QUERY_ENDPOINTS.contains(&req.url().path()) || req.url().path().ends_with("/change/preview"),
+        _ => false,
+    }
+}
+
+/// Whether a response is worth retrying. Rate limiting means the request was
+/// not processed, so it is retried for any method; server errors only for
+/// idempotent ones.
+fn is_transient(status: StatusCode, idempotent: bool) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => true,
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => idempotent,
+        _ => false,
+    }
+}
+
+/// The wait requested by a `Retry-After` header, in seconds or as an HTTP date
+fn retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
     }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok()
+}
+
+/// `base` doubled for each retry so far, randomised over its upper half so
+/// that parallel workers don't retry in lockstep
+fn jittered_backoff(base: Duration, attempt: u32) -> Duration {
+    let exp = base.saturating_mul(1 << attempt.saturating_sub(2).min(16));
+    exp / 2 + (exp / 2).mul_f64(fastrand::f64())
+}
+
+/// The server's request ID for a response, if it sent one
+fn request_id(resp: &Response) -> Option<String> {
+    ["x-request-id", "request-id", "x-amzn-requestid"]
+        .iter()
+        .find_map(|h| resp.headers().get(*h))
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
 }
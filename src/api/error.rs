@@ -0,0 +1,142 @@
+use reqwest::StatusCode;
+
+/// What is known about a failed request
+#[derive(Debug)]
+pub struct Failure {
+    /// HTTP status, when the server answered
+    pub status: Option<u16>,
+    pub message: String,
+    pub request_id: Option<String>,
+}
+
+/// Why an API request failed. Each kind has its own exit code, so scripts can
+/// tell a mistyped ID from an outage.
+#[derive(Debug)]
+pub enum ApiClientError {
+    /// Missing, expired, or rejected credentials (401, 403)
+    Auth(Failure),
+    /// The resource doesn't exist (404)
+    NotFound(Failure),
+    /// The server rejected what was sent (400, 409, 422)
+    Validation(Failure),
+    /// Still rate-limited after retrying (429)
+    RateLimited(Failure),
+    /// No response at all: connection refused, timeout, DNS failure
+    Network(Failure),
+    /// Any other error status, e.g. a 5xx after retrying
+    Server(Failure),
+    /// The server lacks what the command needs, found before sending anything
+    Unsupported(Failure),
+}
+
+/// Error body returned by the API
+#[derive(Debug, serde::Deserialize)]
+struct ApiError {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    hint: Option<String>,
+}
+
+impl ApiClientError {
+    /// Classify an error response, preferring the API's own error text
+    pub fn from_response(status: StatusCode, body_text: &str, request_id: Option<String>) -> Self {
+        let failure = Failure { status: Some(status.as_u16()), message: describe(status, body_text), request_id };
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Auth(failure),
+            StatusCode::NOT_FOUND => Self::NotFound(failure),
+            StatusCode::BAD_REQUEST | StatusCode::CONFLICT | StatusCode::UNPROCESSABLE_ENTITY => Self::Validation(failure),
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited(failure),
+            _ => Self::Server(failure),
+        }
+    }
+
+    /// A request that got no response
+    pub fn network(e: reqwest::Error) -> Self {
+        let message = format!("Request failed: {:#}", anyhow::Error::from(e));
+        Self::Network(Failure { status: None, message, request_id: None })
+    }
+
+    /// Credentials that can't be used, found before sending anything
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::Auth(Failure { status: None, message: message.into(), request_id: None })
+    }
+
+    /// A command the server is too old for
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::Unsupported(Failure { status: None, message: message.into(), request_id: None })
+    }
+
+    pub fn failure(&self) -> &Failure {
+        match self {
+            Self::Auth(f)
+            | Self::NotFound(f)
+            | Self::Validation(f)
+            | Self::RateLimited(f)
+            | Self::Network(f)
+            | Self::Server(f)
+            | Self::Unsupported(f) => f,
+        }
+    }
+
+    /// Name of the kind in `--error-format json`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Auth(_) => "auth",
+            Self::NotFound(_) => "not_found",
+            Self::Validation(_) => "validation",
+            Self::RateLimited(_) => "rate_limit",
+            Self::Network(_) => "network",
+            Self::Server(_) => "server",
+            Self::Unsupported(_) => "unsupported",
+        }
+    }
+
+    /// Process exit code; server errors share the generic 1
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Auth(_) => 2,
+            Self::NotFound(_) => 3,
+            Self::Validation(_) => 4,
+            Self::Network(_) => 5,
+            Self::RateLimited(_) => 6,
+            Self::Server(_) => 1,
+            Self::Unsupported(_) => 7,
+        }
+    }
+}
+
+/// The message, ending with the server's request ID when it sent one
+impl std::fmt::Display for ApiClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let failure = self.failure();
+        match &failure.request_id {
+            Some(id) => write!(f, "{} [request ID: {}]", failure.message, id),
+            None => f.write_str(&failure.message),
+        }
+    }
+}
+
+impl std::error::Error for ApiClientError {}
+
+fn describe(status: StatusCode, body_text: &str) -> String {
+    if let Ok(api_err) = serde_json::from_str::<ApiError>(body_text) {
+        let msg = api_err.error
+            .or(api_err.message)
+            .unwrap_or_else(|| "Unknown error".to_string());
+        if let Some(hint) = api_err.hint {
+            format!("{} ({}): {} — {}", status.as_u16(), status.canonical_reason().unwrap_or(""), msg, hint)
+        } else {
+            format!("{} ({}): {}", status.as_u16(), status.canonical_reason().unwrap_or(""), msg)
+        }
+    } else {
+        match status {
+            StatusCode::UNAUTHORIZED => "Authentication failed. Run `flexprice auth login` or check your API key.".to_string(),
+            StatusCode::FORBIDDEN => "Permission denied. Your credentials may not have access to this resource.".to_string(),
+            StatusCode::NOT_FOUND => "Resource not found. Verify the ID is correct.".to_string(),
+            _ => format!("{}: {}", status, body_text),
+        }
+    }
+}
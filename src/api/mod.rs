@@ -1,4 +1,9 @@
+pub mod cache;
+pub mod capabilities;
 pub mod client;
+pub mod error;
 pub mod models;
+pub mod query;
+pub mod tls;
 
 pub use client::ApiClient;
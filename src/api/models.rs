@@ -18,6 +18,7 @@ pub struct AuthResponse {
 // ─── Generic List Wrapper ───────────────────────────────────────────
 
 #[derive(Debug, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
 pub struct ListResponse<T> {
     #[serde(default)]
     pub items: Vec<T>,
@@ -59,6 +60,87 @@ pub struct Plan {
     pub created_at: Option<String>,
 }
 
+// ─── Addon ──────────────────────────────────────────────────────────
+
+/// A product sold on top of a plan, e.g. extra seats or priority support
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Addon {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub lookup_key: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// `onetime` (at most one per subscription) or `multiple_instance`
+    #[serde(default, rename = "type")]
+    pub addon_type: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+// ─── Price ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Price {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub plan_id: Option<String>,
+    #[serde(default)]
+    pub lookup_key: Option<String>,
+    /// `FIXED` or `USAGE`
+    #[serde(default, rename = "type")]
+    pub price_type: Option<String>,
+    /// `FLAT_FEE`, `PACKAGE`, or `TIERED`
+    #[serde(default)]
+    pub billing_model: Option<String>,
+    /// `VOLUME` or `SLAB`, for tiered prices
+    #[serde(default)]
+    pub tier_mode: Option<String>,
+    /// Decimal string, e.g. `"0.002"`
+    #[serde(default)]
+    pub amount: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// `MONTHLY`, `ANNUAL`, `WEEKLY`, …
+    #[serde(default)]
+    pub billing_period: Option<String>,
+    #[serde(default)]
+    pub billing_period_count: Option<i64>,
+    #[serde(default)]
+    pub billing_cadence: Option<String>,
+    #[serde(default)]
+    pub invoice_cadence: Option<String>,
+    #[serde(default)]
+    pub meter_id: Option<String>,
+    #[serde(default)]
+    pub tiers: Option<Vec<PriceTier>>,
+    /// `{"divide_by": 1000, "round": "up"}` for package pricing
+    #[serde(default)]
+    pub transform_quantity: Option<serde_json::Value>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceTier {
+    /// Upper bound of the tier; `None` for the last, unbounded tier
+    #[serde(default)]
+    pub up_to: Option<u64>,
+    #[serde(default)]
+    pub unit_amount: Option<String>,
+    #[serde(default)]
+    pub flat_amount: Option<String>,
+}
+
 // ─── Subscription ───────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -75,10 +157,42 @@ pub struct Subscription {
     pub current_period_start: Option<String>,
     #[serde(default)]
     pub current_period_end: Option<String>,
+    /// `MONTHLY`, `ANNUAL`, …
+    #[serde(default)]
+    pub billing_period: Option<String>,
+    #[serde(default)]
+    pub billing_period_count: Option<i64>,
+    #[serde(default)]
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub cancelled_at: Option<String>,
+    #[serde(default)]
+    pub pause_status: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
 }
 
+/// One pause of a subscription, past, active, or scheduled
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionPause {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub pause_status: Option<String>,
+    #[serde(default)]
+    pub pause_start: Option<String>,
+    #[serde(default)]
+    pub pause_end: Option<String>,
+    #[serde(default)]
+    pub resumed_at: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
 // ─── Invoice ────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -86,6 +200,8 @@ pub struct Invoice {
     #[serde(default)]
     pub id: String,
     #[serde(default)]
+    pub invoice_number: Option<String>,
+    #[serde(default)]
     pub customer_id: Option<String>,
     #[serde(default)]
     pub subscription_id: Option<String>,
@@ -95,8 +211,176 @@ pub struct Invoice {
     pub payment_status: Option<String>,
     #[serde(default)]
     pub amount_due: Option<f64>,
+    /// Amount before tax
+    #[serde(default)]
+    pub subtotal: Option<f64>,
+    #[serde(default)]
+    pub total_tax: Option<f64>,
+    /// Amount after tax and discounts
+    #[serde(default)]
+    pub total: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub period_start: Option<String>,
+    #[serde(default)]
+    pub period_end: Option<String>,
+    #[serde(default)]
+    pub billing_period: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// One entry per tax rate applied
+    #[serde(default)]
+    pub taxes: Vec<InvoiceTax>,
+    #[serde(default)]
+    pub line_items: Vec<InvoiceLineItem>,
+}
+
+/// One charge on an invoice: a fixed fee or the usage of one meter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InvoiceLineItem {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub plan_display_name: Option<String>,
+    #[serde(default)]
+    pub price_id: Option<String>,
+    /// `FIXED` or `USAGE`
+    #[serde(default)]
+    pub price_type: Option<String>,
+    #[serde(default)]
+    pub meter_id: Option<String>,
+    #[serde(default)]
+    pub meter_display_name: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<f64>,
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub period_start: Option<String>,
+    #[serde(default)]
+    pub period_end: Option<String>,
+}
+
+/// Tax charged on an invoice under one tax rate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InvoiceTax {
+    #[serde(default)]
+    pub tax_rate_id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub code: Option<String>,
+    /// Where the tax is owed, e.g. `US-CA`
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
+    /// Percentage, e.g. `8.25`
+    #[serde(default)]
+    pub rate: Option<f64>,
+    /// The part of the invoice the rate applies to
+    #[serde(default)]
+    pub taxable_amount: Option<f64>,
+    #[serde(default)]
+    pub tax_amount: Option<f64>,
+}
+
+/// A note attached to an invoice, e.g. collection context or a dispute
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InvoiceComment {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+// ─── Tax ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxRate {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// `percentage` or `fixed`
+    #[serde(default)]
+    pub tax_rate_type: Option<String>,
+    /// Decimal string, e.g. `"8.25"` for 8.25%
+    #[serde(default)]
+    pub percentage_value: Option<String>,
+    /// Decimal string charged per invoice, for fixed rates
+    #[serde(default)]
+    pub fixed_value: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub tax_rate_status: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// A tax rate applied to a customer's invoices
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxAssociation {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub tax_rate_id: String,
+    /// `customer`, …
+    #[serde(default)]
+    pub entity_type: Option<String>,
+    #[serde(default)]
+    pub entity_id: Option<String>,
+    /// Order rates are applied in, lowest first
+    #[serde(default)]
+    pub priority: Option<i64>,
+    /// Whether the rate is added to new invoices automatically
+    #[serde(default)]
+    pub auto_apply: Option<bool>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+// ─── Payment ────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Payment {
+    #[serde(default)]
+    pub id: String,
+    /// Decimal string, e.g. `"49.00"`
+    #[serde(default)]
+    pub amount: Option<String>,
     #[serde(default)]
     pub currency: Option<String>,
+    /// `CARD`, `ACH`, `OFFLINE`, or `CREDITS`
+    #[serde(default)]
+    pub payment_method_type: Option<String>,
+    #[serde(default)]
+    pub payment_method_id: Option<String>,
+    #[serde(default)]
+    pub payment_status: Option<String>,
+    /// What the payment settles; `INVOICE` for invoice payments
+    #[serde(default)]
+    pub destination_type: Option<String>,
+    #[serde(default)]
+    pub destination_id: Option<String>,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub succeeded_at: Option<String>,
+    #[serde(default)]
+    pub failed_at: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
 }
@@ -111,14 +395,33 @@ pub struct Meter {
     pub name: Option<String>,
     #[serde(default)]
     pub event_name: Option<String>,
+    /// `{"type": "SUM", "field": "tokens"}`
     #[serde(default)]
-    pub aggregation: Option<String>,
+    pub aggregation: Option<serde_json::Value>,
     #[serde(default)]
     pub status: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
 }
 
+/// A meter's usage over a time range, bucketed by `window_size`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeterUsage {
+    #[serde(default)]
+    pub value: Option<f64>,
+    #[serde(default)]
+    pub results: Vec<UsageBucket>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageBucket {
+    /// Start of the bucket
+    #[serde(default)]
+    pub window_size: Option<String>,
+    #[serde(default)]
+    pub value: Option<f64>,
+}
+
 // ─── Event ──────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -163,6 +466,26 @@ pub struct WalletBalance {
     pub currency: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WalletTransaction {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub wallet_id: Option<String>,
+    #[serde(default, rename = "type")]
+    pub transaction_type: Option<String>,
+    #[serde(default)]
+    pub credit_amount: Option<f64>,
+    #[serde(default)]
+    pub credits_available: Option<f64>,
+    #[serde(default)]
+    pub expiry_date: Option<String>,
+    #[serde(default)]
+    pub transaction_status: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
 // ─── Feature ────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -200,3 +523,150 @@ pub struct Entitlement {
     #[serde(default)]
     pub created_at: Option<String>,
 }
+
+/// `/v1/customers/{id}/usage`: each entitled feature's usage against its limit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomerUsageSummary {
+    #[serde(default)]
+    pub customer_id: Option<String>,
+    #[serde(default)]
+    pub features: Vec<FeatureUsage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeatureUsage {
+    #[serde(default)]
+    pub feature: Feature,
+    /// `None` for unlimited usage
+    #[serde(default)]
+    pub total_limit: Option<f64>,
+    #[serde(default)]
+    pub current_usage: Option<f64>,
+    #[serde(default)]
+    pub is_enabled: Option<bool>,
+    #[serde(default)]
+    pub is_soft_limit: Option<bool>,
+}
+
+// ─── Usage ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageCharge {
+    #[serde(default)]
+    pub meter_id: Option<String>,
+    #[serde(default)]
+    pub meter_display_name: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<f64>,
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub display_amount: Option<String>,
+    #[serde(default)]
+    pub price: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionUsage {
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub display_amount: Option<String>,
+    #[serde(default)]
+    pub start_time: Option<String>,
+    #[serde(default)]
+    pub end_time: Option<String>,
+    #[serde(default)]
+    pub charges: Vec<UsageCharge>,
+}
+
+// ─── Task ───────────────────────────────────────────────────────────
+
+/// A long-running server-side job such as an import or meter recompute
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Task {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub task_type: Option<String>,
+    #[serde(default)]
+    pub entity_type: Option<String>,
+    #[serde(default)]
+    pub task_status: Option<String>,
+    #[serde(default)]
+    pub total_records: Option<u64>,
+    #[serde(default)]
+    pub processed_records: Option<u64>,
+    #[serde(default)]
+    pub failed_records: Option<u64>,
+    #[serde(default)]
+    pub error_summary: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+// ─── Environment ────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Environment {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// `development` or `production`
+    #[serde(default, rename = "type")]
+    pub environment_type: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+// ─── Tenant ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Tenant {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenantBillingUsage {
+    #[serde(default)]
+    pub subscriptions: Vec<Subscription>,
+    #[serde(default)]
+    pub usage: Option<SubscriptionUsage>,
+}
+
+// ─── Audit Log ──────────────────────────────────────────────────────
+
+/// One recorded change to a tenant resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLog {
+    #[serde(default)]
+    pub id: String,
+    /// `create`, `update`, `delete`, …
+    #[serde(default)]
+    pub action: Option<String>,
+    /// Kind of resource changed, e.g. `customer`
+    #[serde(default)]
+    pub entity_type: Option<String>,
+    #[serde(default)]
+    pub entity_id: Option<String>,
+    /// User or API key that made the change
+    #[serde(default)]
+    pub actor: Option<String>,
+    /// Changed fields, as `{"field": {"old": …, "new": …}}`
+    #[serde(default)]
+    pub changes: Option<serde_json::Value>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
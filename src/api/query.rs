@@ -0,0 +1,77 @@
+use std::fmt;
+
+/// Query parameters for a request path, percent-encoded as they're added.
+/// A key added more than once is sent once per value, e.g.
+/// `plan_ids=a&plan_ids=b`.
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder {
+    pairs: Vec<(String, String)>,
+}
+
+impl QueryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `key=value`
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.pairs.push((encode(key), encode(&value.to_string())));
+        self
+    }
+
+    /// Add `key=value` if there is a value, for optional filters
+    pub fn opt(self, key: &str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.param(key, value),
+            None => self,
+        }
+    }
+
+    /// Add `key=value` once for each value
+    pub fn each<V: ToString>(self, key: &str, values: impl IntoIterator<Item = V>) -> Self {
+        values.into_iter().fold(self, |query, value| query.param(key, value))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// `path` with these parameters appended after any it already has
+    pub fn to_path(&self, path: &str) -> String {
+        match (self.is_empty(), path.contains('?')) {
+            (true, _) => path.to_string(),
+            (false, true) => format!("{}&{}", path, self),
+            (false, false) => format!("{}?{}", path, self),
+        }
+    }
+}
+
+impl<K: AsRef<str>, V: ToString> FromIterator<(K, V)> for QueryBuilder {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(pairs: I) -> Self {
+        pairs.into_iter().fold(Self::new(), |query, (key, value)| query.param(key.as_ref(), value))
+    }
+}
+
+impl fmt::Display for QueryBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Percent-encode a query parameter key or value, e.g. the `+` in an email
+/// address, or an ID used as a path segment
+pub fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
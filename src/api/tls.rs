@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use p12_keystore::KeyStore;
+use reqwest::Identity;
+
+/// Password for a PKCS#12 client certificate, which isn't stored with the profile
+pub const PASSWORD_VAR: &str = "FLEXPRICE_CLIENT_CERT_PASSWORD";
+
+/// Load the client certificate presented to mutual-TLS gateways. `cert` is a
+/// PEM file holding the certificate chain (and the private key, unless `key`
+/// names a separate PEM file) or a PKCS#12 bundle (`.p12`/`.pfx`), unlocked
+/// with `FLEXPRICE_CLIENT_CERT_PASSWORD`.
+pub fn identity(cert: &str, key: Option<&str>) -> Result<Identity> {
+    let bytes = read(cert, "client certificate")?;
+    let pem = match is_pem(&bytes) {
+        true => bytes,
+        false if key.is_some() => anyhow::bail!(
+            "Client certificate {} isn't PEM; --client-key only goes with a PEM certificate",
+            cert
+        ),
+        false => pkcs12_to_pem(cert, &bytes)?,
+    };
+    let mut pem = String::from_utf8(pem).with_context(|| format!("Client certificate {} isn't valid PEM text", cert))?;
+    if !pem.contains("-----BEGIN CERTIFICATE-----") {
+        anyhow::bail!("No certificate found in {}", cert);
+    }
+    if let Some(key) = key {
+        let key_pem = String::from_utf8(read(key, "client key")?).with_context(|| format!("Client key {} isn't valid PEM text", key))?;
+        if !has_private_key(&key_pem) {
+            anyhow::bail!("No private key found in {}", key);
+        }
+        pem.push('\n');
+        pem.push_str(&key_pem);
+    } else if !has_private_key(&pem) {
+        anyhow::bail!("No private key found in {}; pass the key file with --client-key", cert);
+    }
+    Identity::from_pem(pem.as_bytes()).with_context(|| match key {
+        Some(key) => format!("Failed to load client certificate {} with key {}", cert, key),
+        None => format!("Failed to load client certificate {}", cert),
+    })
+}
+
+fn read(path: &str, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(Path::new(path)).with_context(|| format!("Failed to read {} {}", what, path))
+}
+
+fn is_pem(bytes: &[u8]) -> bool {
+    String::from_utf8_lossy(bytes).contains("-----BEGIN ")
+}
+
+fn has_private_key(pem: &str) -> bool {
+    pem.lines().any(|l| l.starts_with("-----BEGIN ") && l.ends_with("PRIVATE KEY-----"))
+}
+
+/// The key and certificate chain of a PKCS#12 bundle, as PEM
+fn pkcs12_to_pem(path: &str, bytes: &[u8]) -> Result<Vec<u8>> {
+    let password = std::env::var(PASSWORD_VAR).unwrap_or_default();
+    let store = KeyStore::from_pkcs12(bytes, &password).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to open {} as a PKCS#12 bundle: {}. If it's password-protected, set {}.",
+            path,
+            e,
+            PASSWORD_VAR
+        )
+    })?;
+    let Some((_, chain)) = store.private_key_chain() else {
+        anyhow::bail!("PKCS#12 bundle {} has no private key", path);
+    };
+    let mut pem = block("PRIVATE KEY", chain.key());
+    for cert in chain.chain() {
+        pem.push_str(&block("CERTIFICATE", cert.as_der()));
+    }
+    Ok(pem.into_bytes())
+}
+
+fn block(label: &str, der: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(der);
+    let lines: Vec<&str> = encoded.as_bytes().chunks(64).map(|c| std::str::from_utf8(c).unwrap_or_default()).collect();
+    format!("-----BEGIN {}-----\n{}\n-----END {}-----\n", label, lines.join("\n"), label)
+}
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use clap::Subcommand;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::models::Addon;
+use crate::cli::completions;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::fanout::{self, DeleteManyArgs, IdsArgs};
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::{output, spinner};
+
+#[derive(Subcommand)]
+pub enum AddonCommands {
+    /// List add-ons, a page at a time
+    List {
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Get an add-on by ID, or many with --ids-file
+    Get {
+        #[arg(add = completions::ids("/v1/addons"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Create add-ons interactively or from a JSON file (object or array of objects)
+    Create {
+        #[arg(long)]
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Delete an add-on by ID, or many from --ids-file or --filter
+    Delete {
+        #[arg(
+            add = completions::ids("/v1/addons"),
+            required_unless_present_any = ["ids_file", "filters"],
+            conflicts_with_all = ["ids_file", "filters"]
+        )]
+        id: Option<String>,
+        #[command(flatten)]
+        many: DeleteManyArgs,
+    },
+}
+
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "add-on",
+    fields: &[
+        Field::text("name", "Name").required(),
+        Field::text("lookup_key", "Lookup key").required(),
+        Field::choice("type", "Type", &["onetime", "multiple_instance"]),
+        Field::text("description", "Description"),
+    ],
+    check: None,
+};
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "name": "",
+  "lookup_key": "",
+  "type": "onetime",
+  "description": ""
+}"#;
+
+#[derive(Tabled, serde::Serialize)]
+pub struct AddonRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Lookup Key")]
+    lookup_key: String,
+    #[tabled(rename = "Type")]
+    addon_type: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+impl From<Addon> for AddonRow {
+    fn from(a: Addon) -> Self {
+        Self {
+            id: a.id,
+            name: a.name.unwrap_or_default(),
+            lookup_key: a.lookup_key.unwrap_or_default(),
+            addon_type: a.addon_type.unwrap_or_default(),
+            status: a.status.map(|s| output::status_badge(&s)).unwrap_or_default(),
+        }
+    }
+}
+
+pub async fn handle(cmd: AddonCommands, client: ApiClient) -> Result<()> {
+    match cmd {
+        AddonCommands::List { page } => {
+            let sp = spinner::create_spinner("Fetching add-ons...");
+            let (items, pagination) = page.fetch::<Addon>(&client, "/v1/addons").await?;
+            sp.finish_and_clear();
+            let rows: Vec<AddonRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "add-on"));
+        }
+        AddonCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/addons", &many).await };
+            let sp = spinner::create_spinner("Fetching add-on...");
+            let addon: Addon = client.get(&format!("/v1/addons/{}", id)).await?;
+            sp.finish_and_clear();
+            println!("{}", output::print_detail(&addon));
+        }
+        AddonCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<Addon>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                let sp = spinner::create_spinner("Creating add-on...");
+                let addon: Addon = client.post("/v1/addons", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Add-on created: {}", addon.id));
+                println!("{}", output::print_detail(&addon));
+            }
+            Some(Payload::Many(items)) => {
+                batch::create_many(&client, items, file.as_deref(), &opts, "Add-on created", "/v1/addons").await?;
+            }
+        },
+        AddonCommands::Delete { id, many } => {
+            let Some(id) = id else { return fanout::delete_many(&client, "/v1/addons", "add-ons", &many).await };
+            let sp = spinner::create_spinner("Deleting add-on...");
+            client.delete_empty(&format!("/v1/addons/{}", id)).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Add-on {} deleted.", id));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use reqwest::Method;
+
+use crate::api::client::ApiClient;
+use crate::utils::{output, spinner};
+
+/// Send an authenticated request to any API endpoint
+#[derive(Args)]
+pub struct ApiArgs {
+    /// HTTP method
+    #[arg(value_enum)]
+    method: HttpMethod,
+    /// Endpoint path including any query string, e.g. /v1/customers?limit=5
+    path: String,
+    /// JSON request body file (`-` reads stdin)
+    #[arg(long, value_name = "FILE")]
+    body: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl From<HttpMethod> for Method {
+    fn from(m: HttpMethod) -> Self {
+        match m {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Patch => Method::PATCH,
+            HttpMethod::Delete => Method::DELETE,
+        }
+    }
+}
+
+pub async fn handle(args: ApiArgs, client: ApiClient) -> Result<()> {
+    let body = args.body.as_deref().map(read_body).transpose()?;
+    let path = if args.path.starts_with('/') {
+        args.path
+    } else {
+        format!("/{}", args.path)
+    };
+    let method = Method::from(args.method);
+
+    let sp = spinner::create_spinner(&format!("{} {}", method, path));
+    let resp = client.request(method, &path, body.as_ref()).await;
+    sp.finish_and_clear();
+
+    match resp? {
+        serde_json::Value::Null => {}
+        serde_json::Value::String(text) => println!("{}", text),
+        value => println!("{}", output::print_raw(&value)),
+    }
+    Ok(())
+}
+
+fn read_body(path: &str) -> Result<serde_json::Value> {
+    let data = if path == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?
+    };
+    serde_json::from_str(&data).with_context(|| format!("Invalid JSON in {}", path))
+}
@@ -0,0 +1,463 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io::IsTerminal;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use dialoguer::Confirm;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::config::globals;
+use crate::utils::{output, spinner};
+
+/// Make the tenant's pricing match a manifest, creating and updating as needed
+#[derive(Args)]
+pub struct ApplyArgs {
+    /// Manifest describing meters, features, plans, prices, and entitlements (YAML or JSON)
+    #[arg(long, short, value_name = "FILE")]
+    file: String,
+    /// Apply without asking for confirmation
+    #[arg(long, short)]
+    yes: bool,
+}
+
+/// Pricing as code. Resources are matched to live ones by key: meters by
+/// `event_name`; features, plans, and prices by `lookup_key`; entitlements
+/// by their plan and `feature`.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Manifest {
+    meters: Vec<Map<String, Value>>,
+    features: Vec<Map<String, Value>>,
+    plans: Vec<PlanSpec>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PlanSpec {
+    prices: Vec<Map<String, Value>>,
+    entitlements: Vec<Map<String, Value>>,
+    #[serde(flatten)]
+    fields: Map<String, Value>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Kind {
+    Meter,
+    Feature,
+    Plan,
+    Price,
+    Entitlement,
+}
+
+impl Kind {
+    fn endpoint(self) -> &'static str {
+        match self {
+            Kind::Meter => "/v1/meters",
+            Kind::Feature => "/v1/features",
+            Kind::Plan => "/v1/plans",
+            Kind::Price => "/v1/prices",
+            Kind::Entitlement => "/v1/entitlements",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Meter => "meter",
+            Kind::Feature => "feature",
+            Kind::Plan => "plan",
+            Kind::Price => "price",
+            Kind::Entitlement => "entitlement",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Action {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// A field whose value is the ID of another manifest resource, filled in
+/// once that resource exists
+#[derive(serde::Serialize)]
+struct Reference {
+    field: &'static str,
+    kind: Kind,
+    key: String,
+}
+
+/// What `apply` will do to one resource
+#[derive(serde::Serialize)]
+struct Change {
+    kind: Kind,
+    key: String,
+    action: Action,
+    /// The live resource being updated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    /// Fields to send: everything for a create, only what differs for an update
+    body: Map<String, Value>,
+    /// Current values of the fields being updated
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    current: Map<String, Value>,
+    /// References to resources this run creates
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pending: Vec<Reference>,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ChangeRow {
+    #[tabled(rename = "")]
+    action: String,
+    #[tabled(rename = "Resource")]
+    kind: String,
+    #[tabled(rename = "Key")]
+    key: String,
+    #[tabled(rename = "Changes")]
+    changes: String,
+}
+
+/// Live resources by kind and key, and the IDs that references resolve to
+struct Live {
+    items: HashMap<(Kind, String), Value>,
+    ids: HashMap<(Kind, String), String>,
+}
+
+impl Live {
+    fn insert(&mut self, kind: Kind, key: String, item: Value) {
+        let id = str_field(&item, "id");
+        self.ids.insert((kind, key.clone()), id);
+        self.items.insert((kind, key), item);
+    }
+
+    fn get(&self, kind: Kind, key: &str) -> Option<&Value> {
+        self.items.get(&(kind, key.to_string()))
+    }
+}
+
+pub async fn handle(args: ApplyArgs, client: ApiClient) -> Result<()> {
+    let data = std::fs::read_to_string(&args.file).with_context(|| format!("Failed to read {}", args.file))?;
+    let manifest: Manifest = serde_yaml::from_str(&data).with_context(|| format!("Invalid manifest {}", args.file))?;
+
+    let sp = spinner::create_spinner("Comparing manifest with the live tenant...");
+    let live = fetch_live(&client).await;
+    sp.finish_and_clear();
+    let mut live = live?;
+
+    let changes = plan(&manifest, &live)?;
+    let pending: Vec<&Change> = changes.iter().filter(|c| c.action != Action::Unchanged).collect();
+    let count = |action: Action| changes.iter().filter(|c| c.action == action).count();
+    let summary = format!(
+        "{} to create, {} to update, {} unchanged",
+        count(Action::Create),
+        count(Action::Update),
+        count(Action::Unchanged)
+    );
+
+    if !output::human() {
+        println!("{}", output::print_detail(&pending));
+    } else {
+        if !pending.is_empty() {
+            let rows: Vec<ChangeRow> = pending.iter().map(|c| change_row(c)).collect();
+            println!("{}", output::print_table(&rows));
+            println!();
+        }
+        output::info(&format!("Plan: {}.", summary));
+    }
+
+    if pending.is_empty() || globals::get().dry_run {
+        if output::human() && pending.is_empty() {
+            output::success(&format!("{} already matches the live tenant.", args.file));
+        } else if output::human() {
+            output::info("Dry run: nothing was changed.");
+        }
+        return Ok(());
+    }
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Refusing to apply {} change(s) without confirmation; pass --yes", pending.len());
+        }
+        let confirmed = Confirm::new()
+            .with_prompt(format!("  Apply {} change(s)?", pending.len()))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            output::info("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let total = pending.len();
+    let mut failed = 0;
+    for change in changes.into_iter().filter(|c| c.action != Action::Unchanged) {
+        let label = format!("{} {}", change.kind.name(), change.key);
+        match execute(&client, &mut live, change).await {
+            Ok(msg) => output::success(&format!("{}: {}", label, msg)),
+            Err(e) => {
+                failed += 1;
+                output::error(&format!("{}: {:#}", label, e));
+            }
+        }
+    }
+    println!();
+    output::info(&format!("{} applied, {} failed", total - failed, failed));
+    if failed > 0 {
+        anyhow::bail!("{} of {} changes failed", failed, total);
+    }
+    Ok(())
+}
+
+async fn fetch_live(client: &ApiClient) -> Result<Live> {
+    let (meters, features, plans, prices, entitlements) = tokio::try_join!(
+        client.get_all::<Value>(Kind::Meter.endpoint()),
+        client.get_all::<Value>(Kind::Feature.endpoint()),
+        client.get_all::<Value>(Kind::Plan.endpoint()),
+        client.get_all::<Value>(Kind::Price.endpoint()),
+        client.get_all::<Value>(Kind::Entitlement.endpoint()),
+    )?;
+    let mut live = Live { items: HashMap::new(), ids: HashMap::new() };
+    // Archived resources can't be updated, so a manifest entry for one creates a new one
+    let active = |item: &Value| !matches!(item.get("status").and_then(|v| v.as_str()), Some("archived" | "deleted"));
+    for (kind, items, key_field) in [
+        (Kind::Meter, meters, "event_name"),
+        (Kind::Feature, features, "lookup_key"),
+        (Kind::Plan, plans, "lookup_key"),
+        (Kind::Price, prices, "lookup_key"),
+    ] {
+        for item in items.into_iter().filter(active) {
+            let key = str_field(&item, key_field);
+            if !key.is_empty() {
+                live.insert(kind, key, item);
+            }
+        }
+    }
+    // Entitlements are keyed by plan and feature lookup keys
+    let key_of = |kind: Kind, id: &str| {
+        live.ids.iter().find(|((k, _), v)| *k == kind && v.as_str() == id).map(|((_, key), _)| key.clone())
+    };
+    let keyed: Vec<(String, Value)> = entitlements
+        .into_iter()
+        .filter(active)
+        .filter_map(|item| {
+            let plan = key_of(Kind::Plan, &str_field(&item, "plan_id"))?;
+            let feature = key_of(Kind::Feature, &str_field(&item, "feature_id"))?;
+            Some((entitlement_key(&plan, &feature), item))
+        })
+        .collect();
+    for (key, item) in keyed {
+        live.insert(Kind::Entitlement, key, item);
+    }
+    Ok(live)
+}
+
+fn entitlement_key(plan: &str, feature: &str) -> String {
+    format!("{}/{}", plan, feature)
+}
+
+fn str_field(item: &Value, field: &str) -> String {
+    item.get(field).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+}
+
+/// Work out the change for every manifest resource, in the order they must
+/// be applied: meters and features before the plans, prices, and
+/// entitlements that refer to them
+fn plan(manifest: &Manifest, live: &Live) -> Result<Vec<Change>> {
+    let mut declared: BTreeSet<(&str, String)> = BTreeSet::new();
+    let mut changes = vec![];
+    let mut declare = |kind: Kind, key: String| -> Result<String> {
+        if key.is_empty() {
+            anyhow::bail!("Every {} in the manifest needs a {}", kind.name(), key_field(kind));
+        }
+        if !declared.insert((kind.name(), key.clone())) {
+            anyhow::bail!("The manifest declares {} '{}' more than once", kind.name(), key);
+        }
+        Ok(key)
+    };
+
+    for spec in &manifest.meters {
+        let key = declare(Kind::Meter, spec_key(spec, "event_name"))?;
+        changes.push(diff(Kind::Meter, key, spec.clone(), vec![], live));
+    }
+    for spec in &manifest.features {
+        let key = declare(Kind::Feature, spec_key(spec, "lookup_key"))?;
+        let mut body = spec.clone();
+        let refs = take_ref(&mut body, "meter", "meter_id", Kind::Meter).into_iter().collect();
+        changes.push(diff(Kind::Feature, key, body, refs, live));
+    }
+    for spec in &manifest.plans {
+        let key = declare(Kind::Plan, spec_key(&spec.fields, "lookup_key"))?;
+        changes.push(diff(Kind::Plan, key, spec.fields.clone(), vec![], live));
+    }
+    for plan_spec in &manifest.plans {
+        let plan_key = spec_key(&plan_spec.fields, "lookup_key");
+        for spec in &plan_spec.prices {
+            let key = declare(Kind::Price, spec_key(spec, "lookup_key"))?;
+            let mut body = spec.clone();
+            let mut refs = vec![Reference { field: "plan_id", kind: Kind::Plan, key: plan_key.clone() }];
+            refs.extend(take_ref(&mut body, "meter", "meter_id", Kind::Meter));
+            changes.push(diff(Kind::Price, key, body, refs, live));
+        }
+        for spec in &plan_spec.entitlements {
+            let mut body = spec.clone();
+            let feature = body.remove("feature").and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+            if feature.is_empty() {
+                anyhow::bail!("Every entitlement in plan '{}' needs a feature lookup key", plan_key);
+            }
+            let key = declare(Kind::Entitlement, entitlement_key(&plan_key, &feature))?;
+            if !body.contains_key("feature_type") {
+                let declared_type = manifest.features.iter().find(|f| spec_key(f, "lookup_key") == feature).and_then(|f| f.get("type"));
+                let feature_type = declared_type.or_else(|| live.get(Kind::Feature, &feature).and_then(|f| f.get("type")));
+                body.insert("feature_type".into(), feature_type.cloned().unwrap_or_else(|| "boolean".into()));
+            }
+            let refs = vec![
+                Reference { field: "plan_id", kind: Kind::Plan, key: plan_key.clone() },
+                Reference { field: "feature_id", kind: Kind::Feature, key: feature },
+            ];
+            changes.push(diff(Kind::Entitlement, key, body, refs, live));
+        }
+    }
+
+    // Every reference must point at something in the manifest or already live
+    for change in &changes {
+        for r in &change.pending {
+            if !declared.contains(&(r.kind.name(), r.key.clone())) && !live.ids.contains_key(&(r.kind, r.key.clone())) {
+                anyhow::bail!(
+                    "{} '{}' refers to {} '{}', which is neither in the manifest nor in the tenant",
+                    change.kind.name(),
+                    change.key,
+                    r.kind.name(),
+                    r.key
+                );
+            }
+        }
+    }
+    Ok(changes)
+}
+
+fn key_field(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Meter => "event_name",
+        Kind::Entitlement => "feature",
+        _ => "lookup_key",
+    }
+}
+
+fn spec_key(spec: &Map<String, Value>, field: &str) -> String {
+    spec.get(field).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+}
+
+/// Replace a by-key reference such as `meter: api_call` with the field that
+/// will carry its ID
+fn take_ref(body: &mut Map<String, Value>, from: &str, field: &'static str, kind: Kind) -> Option<Reference> {
+    let key = body.remove(from)?.as_str()?.to_string();
+    Some(Reference { field, kind, key })
+}
+
+/// Compare the desired body with the live resource. References to resources
+/// that already exist are resolved now; the rest stay pending until apply.
+fn diff(kind: Kind, key: String, mut body: Map<String, Value>, refs: Vec<Reference>, live: &Live) -> Change {
+    let mut pending = vec![];
+    for r in refs {
+        match live.ids.get(&(r.kind, r.key.clone())) {
+            Some(id) => {
+                body.insert(r.field.into(), id.clone().into());
+            }
+            None => pending.push(r),
+        }
+    }
+
+    let Some(current) = live.get(kind, &key) else {
+        return Change { kind, key, action: Action::Create, id: None, body, current: Map::new(), pending };
+    };
+    let mut changed: Map<String, Value> = body.into_iter().filter(|(k, v)| !satisfies(current.get(k), v)).collect();
+    // A pending reference points at a resource this run creates, so it always differs
+    for r in &pending {
+        changed.insert(r.field.into(), Value::Null);
+    }
+    let before = changed.keys().map(|k| (k.clone(), current.get(k).cloned().unwrap_or(Value::Null))).collect();
+    let action = if changed.is_empty() { Action::Unchanged } else { Action::Update };
+    changed.retain(|k, _| !pending.iter().any(|r| r.field == k));
+    Change { kind, key, action, id: Some(str_field(current, "id")), body: changed, current: before, pending }
+}
+
+/// Whether the live value already matches the manifest. Objects only need
+/// the keys the manifest sets, and numbers match their string forms, so
+/// `amount: 10` matches a live `"10.00"`.
+pub fn satisfies(live: Option<&Value>, want: &Value) -> bool {
+    match (live, want) {
+        (live, Value::Null) => live.is_none_or(Value::is_null),
+        (Some(Value::Object(l)), Value::Object(w)) => w.iter().all(|(k, v)| satisfies(l.get(k), v)),
+        (Some(Value::Array(l)), Value::Array(w)) => l.len() == w.len() && l.iter().zip(w).all(|(l, w)| satisfies(Some(l), w)),
+        (Some(l), w) => l == w || number(l).zip(number(w)).is_some_and(|(a, b)| a == b),
+        (None, _) => false,
+    }
+}
+
+fn number(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn change_row(change: &Change) -> ChangeRow {
+    let (action, changes) = match change.action {
+        Action::Create => {
+            let mut fields: Vec<&str> = change.body.keys().map(String::as_str).collect();
+            fields.extend(change.pending.iter().map(|r| r.field));
+            ("+".green().bold().to_string(), fields.join(", "))
+        }
+        _ => {
+            let updates = change.current.iter().map(|(field, before)| {
+                let after = match change.pending.iter().find(|r| r.field == field) {
+                    Some(r) => format!("(new {} {})", r.kind.name(), r.key),
+                    None => show(change.body.get(field)),
+                };
+                format!("{}: {} → {}", field, show(Some(before)), after)
+            });
+            ("~".yellow().bold().to_string(), updates.collect::<Vec<_>>().join("\n"))
+        }
+    };
+    ChangeRow { action, kind: change.kind.name().to_string(), key: change.key.clone(), changes }
+}
+
+pub fn show(v: Option<&Value>) -> String {
+    match v {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+    }
+}
+
+/// Send one change, resolving references to resources created earlier in the run
+async fn execute(client: &ApiClient, live: &mut Live, change: Change) -> Result<String> {
+    let mut body = change.body;
+    for r in &change.pending {
+        let id = live
+            .ids
+            .get(&(r.kind, r.key.clone()))
+            .with_context(|| format!("skipped: {} '{}' was not created", r.kind.name(), r.key))?;
+        body.insert(r.field.into(), id.clone().into());
+    }
+    let body = Value::Object(body);
+    match change.id {
+        None => {
+            let created: Value = client.post(change.kind.endpoint(), &body).await?;
+            let id = str_field(&created, "id");
+            live.insert(change.kind, change.key, created);
+            Ok(format!("created ({})", id))
+        }
+        Some(id) => {
+            let _: Value = client.put(&format!("{}/{}", change.kind.endpoint(), id), &body).await?;
+            Ok(format!("updated ({})", id))
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeSet;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use serde_json::Value;
+
+use crate::utils::output;
+
+/// Subcommands that only read data and are safe to run from `assert`, matched
+/// against the last word of the parsed subcommand path
+const READ_VERBS: &[&str] = &[
+    "list", "get", "balance", "usage", "entitlements", "expiring-credits", "sample", "status", "whoami",
+];
+
+/// Compare a read command's JSON output against an expected snapshot
+#[derive(Args)]
+pub struct AssertArgs {
+    /// Read command to run, e.g. "plans get plan_123"
+    #[arg(long)]
+    command: String,
+    /// Expected JSON snapshot file
+    #[arg(long, value_name = "FILE")]
+    matches: String,
+    /// Field names to ignore at any depth (or dotted paths like items.id)
+    #[arg(long, value_delimiter = ',')]
+    ignore_fields: Vec<String>,
+    /// Overwrite the snapshot with the current output instead of comparing
+    #[arg(long)]
+    update: bool,
+}
+
+pub async fn handle(args: AssertArgs, cli: clap::Command) -> Result<()> {
+    let mut argv = split_command(&args.command)?;
+    if argv.first().is_some_and(|a| a == "flexprice") {
+        argv.remove(0);
+    }
+    let matches = cli
+        .try_get_matches_from(std::iter::once("flexprice".to_string()).chain(argv.iter().cloned()))
+        .map_err(|e| anyhow::anyhow!("Invalid command: {}", e.render().to_string().trim()))?;
+    let path = super::command_path(&matches);
+    if !path.last().is_some_and(|verb| READ_VERBS.contains(&verb.as_str())) {
+        anyhow::bail!(
+            "`assert` only runs read commands ({}), got `{}`",
+            READ_VERBS.join(", "),
+            args.command
+        );
+    }
+    // Should a read command ever send a write, the child refuses it
+    if !argv.iter().any(|a| a == "--read-only" || a.starts_with("--read-only=")) {
+        argv.push("--read-only".into());
+    }
+    let sets_output = |a: &String| a == "-o" || a == "--output" || a.starts_with("--output=");
+    if !argv.iter().any(sets_output) {
+        argv.extend(["--output".into(), "json".into()]);
+    }
+
+    let actual = run(&argv)?;
+
+    if args.update {
+        std::fs::write(&args.matches, serde_json::to_string_pretty(&actual)? + "\n")
+            .with_context(|| format!("Failed to write {}", args.matches))?;
+        output::success(&format!("Snapshot written to {}", args.matches));
+        return Ok(());
+    }
+
+    let data = std::fs::read_to_string(&args.matches)
+        .with_context(|| format!("Failed to read {}", args.matches))?;
+    let expected: Value = serde_json::from_str(&data)
+        .with_context(|| format!("Invalid JSON in {}", args.matches))?;
+
+    let ignore: BTreeSet<&str> = args.ignore_fields.iter().map(|f| f.trim()).collect();
+    let mut diffs = vec![];
+    diff("", &expected, &actual, &ignore, &mut diffs);
+
+    if diffs.is_empty() {
+        output::success(&format!("`{}` matches {}", args.command, args.matches));
+        return Ok(());
+    }
+    for d in &diffs {
+        println!("  {}", d);
+    }
+    println!();
+    anyhow::bail!("{} difference(s) from {}", diffs.len(), args.matches)
+}
+
+/// Run this binary with `argv` and parse its stdout as JSON
+fn run(argv: &[String]) -> Result<Value> {
+    let exe = std::env::current_exe().context("Cannot locate the flexprice binary")?;
+    let out = Command::new(exe)
+        .args(argv)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run command")?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "`flexprice {}` failed: {}",
+            argv.join(" "),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    serde_json::from_slice(&out.stdout)
+        .with_context(|| format!("`flexprice {}` did not print JSON", argv.join(" ")))
+}
+
+/// Split a command string into words, honoring single and double quotes
+pub fn split_command(s: &str) -> Result<Vec<String>> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        anyhow::bail!("Unterminated quote in command");
+    }
+    if in_word {
+        words.push(word);
+    }
+    if words.is_empty() {
+        anyhow::bail!("Command is empty");
+    }
+    Ok(words)
+}
+
+fn is_ignored(path: &str, key: &str, ignore: &BTreeSet<&str>) -> bool {
+    // Array indices are dropped so `items.id` matches every element
+    let bare: String = path
+        .split('.')
+        .map(|seg| seg.split('[').next().unwrap_or(seg))
+        .collect::<Vec<_>>()
+        .join(".");
+    ignore.contains(key) || ignore.contains(bare.as_str())
+}
+
+/// Collect human-readable differences between `expected` and `actual`
+fn diff(path: &str, expected: &Value, actual: &Value, ignore: &BTreeSet<&str>, out: &mut Vec<String>) {
+    let child = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    let shown = if path.is_empty() { "(root)" } else { path };
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            let keys: BTreeSet<&String> = e.keys().chain(a.keys()).collect();
+            for key in keys {
+                let p = child(key);
+                if is_ignored(&p, key, ignore) {
+                    continue;
+                }
+                match (e.get(key), a.get(key)) {
+                    (Some(ev), Some(av)) => diff(&p, ev, av, ignore, out),
+                    (Some(ev), None) => out.push(format!("{} {}: expected {}, missing", "-".red(), p, ev)),
+                    (None, Some(av)) => out.push(format!("{} {}: unexpected {}", "+".green(), p, av)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(e), Value::Array(a)) => {
+            for i in 0..e.len().max(a.len()) {
+                let p = format!("{}[{}]", path, i);
+                match (e.get(i), a.get(i)) {
+                    (Some(ev), Some(av)) => diff(&p, ev, av, ignore, out),
+                    (Some(ev), None) => out.push(format!("{} {}: expected {}, missing", "-".red(), p, ev)),
+                    (None, Some(av)) => out.push(format!("{} {}: unexpected {}", "+".green(), p, av)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (e, a) if e != a => out.push(format!("{} {}: expected {}, got {}", "~".yellow(), shown, e, a)),
+        _ => {}
+    }
+}
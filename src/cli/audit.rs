@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use clap::Subcommand;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::models::AuditLog;
+use crate::api::query::QueryBuilder;
+use crate::utils::{output, spinner, time};
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// List who changed what and when, newest first
+    List {
+        /// How far back to look (e.g. 24h, 7d) or a start date
+        #[arg(long, default_value = "24h")]
+        since: String,
+        /// Only changes made by this user or API key (email or ID)
+        #[arg(long)]
+        actor: Option<String>,
+        /// Only changes to this kind of resource, e.g. customer or plan
+        #[arg(long)]
+        resource: Option<String>,
+        /// Only this action, e.g. create, update, or delete
+        #[arg(long)]
+        action: Option<String>,
+    },
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct AuditRow {
+    #[tabled(skip)]
+    id: String,
+    #[tabled(rename = "Time")]
+    created_at: String,
+    #[tabled(rename = "Actor")]
+    actor: String,
+    #[tabled(rename = "Action")]
+    action: String,
+    #[tabled(rename = "Resource")]
+    entity_type: String,
+    #[tabled(rename = "Resource ID")]
+    entity_id: String,
+    #[tabled(rename = "Changed")]
+    #[serde(skip)]
+    changed: String,
+    /// Full before and after values, kept for JSON exports
+    #[tabled(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changes: Option<serde_json::Value>,
+}
+
+impl From<AuditLog> for AuditRow {
+    fn from(log: AuditLog) -> Self {
+        Self {
+            id: log.id,
+            changed: changed_fields(log.changes.as_ref()),
+            created_at: log.created_at.unwrap_or_default(),
+            actor: log.actor.unwrap_or_default(),
+            action: log.action.unwrap_or_default(),
+            entity_type: log.entity_type.unwrap_or_default(),
+            entity_id: log.entity_id.unwrap_or_default(),
+            changes: log.changes,
+        }
+    }
+}
+
+pub async fn handle(cmd: AuditCommands, client: ApiClient) -> Result<()> {
+    match cmd {
+        AuditCommands::List { since, actor, resource, action } => {
+            let start = match time::parse_instant(&since) {
+                Ok(at) => at,
+                Err(_) => {
+                    let ago = time::parse_duration(&since)
+                        .with_context(|| format!("Invalid --since '{}': expected e.g. 24h, 7d, or a date", since))?;
+                    time::before(Utc::now(), ago)?
+                }
+            };
+            let resource = resource.map(|r| singular(&r));
+            let path = QueryBuilder::new()
+                .param("start_time", start.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .opt("actor", actor.as_deref())
+                .opt("entity_type", resource.as_deref())
+                .opt("action", action.as_deref())
+                .to_path("/v1/audit-logs");
+
+            let sp = spinner::create_spinner("Fetching audit log...");
+            let logs = client.get_all::<AuditLog>(&path).await;
+            sp.finish_and_clear();
+            let logs = logs.context("Failed to fetch the audit log (this FlexPrice server may not support audit logs)")?;
+
+            // The same filters again, in case the server ignores some of them
+            let mut logs: Vec<AuditLog> = logs
+                .into_iter()
+                .filter(|log| log.created_at.as_deref().and_then(|t| time::parse_instant(t).ok()).is_none_or(|t| t >= start))
+                .filter(|log| is(actor.as_deref(), log.actor.as_deref()))
+                .filter(|log| is(resource.as_deref(), log.entity_type.as_deref().map(singular).as_deref()))
+                .filter(|log| is(action.as_deref(), log.action.as_deref()))
+                .collect();
+            logs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            let rows: Vec<AuditRow> = logs.into_iter().map(Into::into).collect();
+            println!("{}", output::print_table(&rows));
+        }
+    }
+    Ok(())
+}
+
+/// Whether a filter is unset or matches the value, ignoring case
+fn is(wanted: Option<&str>, have: Option<&str>) -> bool {
+    wanted.is_none_or(|w| have.is_some_and(|h| h.eq_ignore_ascii_case(w)))
+}
+
+/// Lower-case and without a plural `s`, so `--resource Customers` matches `customer`
+fn singular(s: &str) -> String {
+    let s = s.trim().to_lowercase();
+    s.strip_suffix('s').map(String::from).unwrap_or(s)
+}
+
+/// The changed field names, e.g. `email, metadata.tier`
+fn changed_fields(changes: Option<&serde_json::Value>) -> String {
+    match changes {
+        Some(serde_json::Value::Object(fields)) => fields.keys().cloned().collect::<Vec<_>>().join(", "),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|c| c.get("field").and_then(|f| f.as_str()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => String::new(),
+    }
+}
@@ -1,11 +1,21 @@
-use anyhow::Result;
+use std::io::IsTerminal;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use colored::Colorize;
 use dialoguer::{Input, Password};
+use futures::future::join_all;
+use tabled::Tabled;
 
 use crate::api::client::ApiClient;
+use crate::api::error::ApiClientError;
 use crate::api::models::AuthResponse;
+use crate::api::models::Environment;
 use crate::api::models::LoginRequest;
-use crate::config::Credentials;
+use crate::config::file::{CredentialStore, DEFAULT_PROFILE};
+use crate::config::store::token_expiry;
+use crate::config::{globals, secrets, ConfigFile, Credentials};
 use crate::utils::output;
 use crate::utils::spinner;
 
@@ -18,9 +28,12 @@ pub enum AuthCommands {
         api_url: Option<String>,
     },
     /// Set an API key directly (for CI/CD or pre-provisioned keys)
+    ///
+    /// Without KEY, prompts for it on a terminal or reads it from stdin, which
+    /// keeps it out of shell history.
     SetApiKey {
         /// The API key to store
-        key: String,
+        key: Option<String>,
         /// API endpoint URL
         #[arg(long, default_value = "http://localhost:8080")]
         api_url: String,
@@ -28,24 +41,44 @@ pub enum AuthCommands {
     /// Show current authenticated user and tenant
     Whoami,
     /// Show authentication status
-    Status,
+    Status {
+        /// Check every configured profile concurrently
+        #[arg(long)]
+        all_profiles: bool,
+    },
+    /// Print the stored bearer token or API key, for scripts and curl
+    Token {
+        /// Print the secret itself; without this only a masked form is shown
+        #[arg(long)]
+        show: bool,
+        /// Print as an HTTP header, e.g. `Authorization: Bearer …`
+        #[arg(long)]
+        header: bool,
+    },
     /// Remove stored credentials
     Logout,
+    /// Move plaintext API keys and tokens from every profile into the OS keyring
+    MigrateKeyring,
 }
 
 pub async fn handle(cmd: AuthCommands) -> Result<()> {
     match cmd {
-        AuthCommands::Login { api_url } => login(api_url).await,
-        AuthCommands::SetApiKey { key, api_url } => set_api_key(key, api_url).await,
+        AuthCommands::Login { api_url } => {
+            output::print_banner();
+            login(api_url).await
+        }
+        AuthCommands::SetApiKey { key, api_url } => set_api_key(key.map_or_else(read_api_key, Ok)?, api_url).await,
         AuthCommands::Whoami => whoami().await,
-        AuthCommands::Status => status().await,
+        AuthCommands::Status { all_profiles: false } => status().await,
+        AuthCommands::Status { all_profiles: true } => status_all().await,
+        AuthCommands::Token { show, header } => token(show, header),
         AuthCommands::Logout => logout(),
+        AuthCommands::MigrateKeyring => migrate_keyring(),
     }
 }
 
-async fn login(override_url: Option<String>) -> Result<()> {
-    output::print_banner();
-
+/// Prompt for an email and password, log in, and store the session
+pub async fn login(override_url: Option<String>) -> Result<()> {
     let api_url: String = if let Some(url) = override_url {
         url
     } else {
@@ -67,7 +100,7 @@ async fn login(override_url: Option<String>) -> Result<()> {
 
     let creds = Credentials {
         api_url: api_url.clone(),
-        ..Default::default()
+        ..stored_tls()
     };
     let client = ApiClient::new(creds)?;
 
@@ -79,34 +112,48 @@ async fn login(override_url: Option<String>) -> Result<()> {
     // Store credentials
     let creds = Credentials {
         api_url,
+        token_expires_at: token_expiry(&auth_resp.token),
         auth_token: Some(auth_resp.token),
         tenant_id: Some(auth_resp.tenant_id.clone()),
         user_id: Some(auth_resp.user_id.clone()),
-        api_key: None,
-        environment_id: None,
+        email: Some(email.clone()),
+        ..Default::default()
     };
-    creds.save()?;
+    let path = store(creds)?;
 
     println!();
     output::success("Authenticated successfully!");
     output::success(&format!("Tenant: {}", auth_resp.tenant_id));
     output::success(&format!("User: {} ({})", email, auth_resp.user_id));
-    output::success(&format!(
-        "Credentials saved to {}",
-        Credentials::credentials_path().display()
-    ));
+    output::success(&format!("Credentials saved to {}", path.display()));
     println!();
 
     Ok(())
 }
 
-async fn set_api_key(key: String, api_url: String) -> Result<()> {
+/// Prompt for an API key on a terminal, else read it from stdin
+fn read_api_key() -> Result<String> {
+    let key = if std::io::stdin().is_terminal() {
+        Password::new().with_prompt("  API Key").interact()?
+    } else {
+        let mut key = String::new();
+        std::io::stdin().read_line(&mut key).context("Failed to read the API key from stdin")?;
+        key.trim().to_string()
+    };
+    if key.is_empty() {
+        anyhow::bail!("No API key given");
+    }
+    Ok(key)
+}
+
+/// Check an API key against the server and store it
+pub async fn set_api_key(key: String, api_url: String) -> Result<()> {
     let sp = spinner::create_spinner("Validating API key...");
 
     let creds = Credentials {
         api_url: api_url.clone(),
         api_key: Some(key.clone()),
-        ..Default::default()
+        ..stored_tls()
     };
 
     let client = ApiClient::new(creds.clone())?;
@@ -114,14 +161,11 @@ async fn set_api_key(key: String, api_url: String) -> Result<()> {
 
     sp.finish_and_clear();
 
-    creds.save()?;
+    let path = store(creds)?;
 
     output::success("API key validated and saved!");
     output::success(&format!("API URL: {}", api_url));
-    output::success(&format!(
-        "Credentials saved to {}",
-        Credentials::credentials_path().display()
-    ));
+    output::success(&format!("Credentials saved to {}", path.display()));
 
     Ok(())
 }
@@ -149,18 +193,25 @@ async fn whoami() -> Result<()> {
     output::info(&format!("Auth:       {}", if creds.api_key.is_some() { "API Key" } else { "JWT Token" }));
     println!();
 
-    println!("{}", output::print_detail(&user_info, false));
+    println!("{}", output::print_detail(&user_info));
 
     Ok(())
 }
 
 async fn status() -> Result<()> {
-    match Credentials::load_from_file() {
-        Ok(creds) => {
+    let config = ConfigFile::load()?;
+    let profile = config.selected_profile();
+    match config.credentials(&profile)? {
+        creds if creds.is_authenticated() => {
             output::success("Credentials found");
+            output::info(&format!("Profile:    {}", profile));
             output::info(&format!("API URL:    {}", creds.api_url));
             output::info(&format!("API Key:    {}", creds.masked_api_key()));
-            output::info(&format!("Auth:       {}", if creds.api_key.is_some() { "API Key" } else if creds.auth_token.is_some() { "JWT Token" } else { "(none)" }));
+            output::info(&format!("Auth:       {}", auth_kind(&creds)));
+            if let Some(at) = creds.session_expires_at() {
+                let when = at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                output::info(&format!("Session:    {} {}", if creds.session_expired() { "expired" } else { "expires" }, when));
+            }
             if let Some(ref tid) = creds.tenant_id {
                 output::info(&format!("Tenant ID:  {}", tid));
             }
@@ -182,26 +233,261 @@ async fn status() -> Result<()> {
                 }
             }
         }
-        Err(_) => {
-            output::warning("Not authenticated.");
-            output::info("Run `flexprice auth login` or `flexprice auth set-api-key <KEY>` to get started.");
+        _ => {
+            output::warning(&format!("Not authenticated (profile '{}').", profile));
+            output::info("Run `flexprice auth login` or `flexprice auth set-api-key` to get started.");
         }
     }
     Ok(())
 }
 
+#[derive(Tabled, serde::Serialize)]
+struct ProfileHealthRow {
+    #[tabled(rename = "Profile")]
+    name: String,
+    #[tabled(rename = "API URL")]
+    api_url: String,
+    #[tabled(rename = "Auth")]
+    auth: String,
+    #[tabled(rename = "Reachable")]
+    reachable: String,
+    #[tabled(rename = "Authenticated")]
+    authenticated: String,
+    #[tabled(rename = "Latency")]
+    latency: String,
+    #[tabled(rename = "Error")]
+    error: String,
+}
+
+/// Check connectivity and credentials for one profile
+async fn check_profile(name: String, creds: Credentials) -> ProfileHealthRow {
+    let mut row = ProfileHealthRow {
+        name,
+        api_url: creds.api_url.clone(),
+        auth: auth_kind(&creds).to_string(),
+        reachable: "✗".red().to_string(),
+        authenticated: "-".dimmed().to_string(),
+        latency: String::new(),
+        error: String::new(),
+    };
+    // Checks run concurrently, so an expired session is reported rather than prompted for
+    let client = match ApiClient::new(creds.clone()) {
+        Ok(c) => c.without_login_prompt(),
+        Err(e) => {
+            row.error = format!("{:#}", e);
+            return row;
+        }
+    };
+
+    let started = Instant::now();
+    if let Err(e) = client.health_check().await {
+        row.error = format!("{:#}", e);
+        return row;
+    }
+    row.latency = format!("{}ms", started.elapsed().as_millis());
+    row.reachable = "✓".green().to_string();
+
+    if creds.is_authenticated() {
+        match client.get::<serde_json::Value>("/v1/users/me").await {
+            Ok(_) => row.authenticated = "✓".green().to_string(),
+            Err(e) => {
+                row.authenticated = "✗".red().to_string();
+                row.error = format!("{:#}", e);
+            }
+        }
+    }
+    row
+}
+
+async fn status_all() -> Result<()> {
+    let profiles = ConfigFile::load()?.all_profiles()?;
+    if profiles.is_empty() {
+        output::warning("No profiles configured.");
+        return Ok(());
+    }
+
+    let sp = spinner::create_spinner(&format!("Checking {} profiles...", profiles.len()));
+    let rows = join_all(profiles.into_iter().map(|(name, creds)| check_profile(name, creds))).await;
+    sp.finish_and_clear();
+
+    println!("{}", output::print_table(&rows));
+    Ok(())
+}
+
+fn token(show: bool, header: bool) -> Result<()> {
+    let creds = require_auth()?;
+    if creds.session_expired() {
+        anyhow::bail!("The login session has expired. Run `flexprice auth login` to sign in again.");
+    }
+    let Some((name, value)) = creds.get_auth_header() else {
+        anyhow::bail!("No API key or auth token is stored for this profile.");
+    };
+    if !show {
+        let masked = match &creds.api_key {
+            Some(_) => creds.masked_api_key(),
+            None => format!("{}...", value.chars().take(15).collect::<String>()),
+        };
+        output::info(&format!("{}: {}", name, masked));
+        output::info("Pass --show to print it in full. Anyone with it can act as you.");
+        return Ok(());
+    }
+    // Bare on stdout so it can be captured, e.g. `$(flexprice auth token --show)`
+    if header {
+        println!("{}: {}", name, value);
+    } else {
+        println!("{}", creds.api_key.as_deref().or(creds.auth_token.as_deref()).unwrap_or_default());
+    }
+    Ok(())
+}
+
+fn auth_kind(creds: &Credentials) -> &'static str {
+    if creds.api_key.is_some() {
+        "API Key"
+    } else if creds.auth_token.is_some() {
+        "JWT Token"
+    } else {
+        "(none)"
+    }
+}
+
 fn logout() -> Result<()> {
-    Credentials::delete()?;
-    output::success("Credentials removed. You are now logged out.");
+    let mut config = ConfigFile::load()?;
+    let profile = config.selected_profile();
+    if profile == DEFAULT_PROFILE {
+        Credentials::delete()?;
+        let _ = secrets::delete(DEFAULT_PROFILE);
+    } else {
+        // Keep the profile's endpoint so `auth login` can reuse it
+        let creds = config.credentials(&profile)?;
+        config.store_credentials(&profile, Credentials {
+            api_url: creds.api_url,
+            environment_id: creds.environment_id,
+            read_only: creds.read_only,
+            ..Default::default()
+        })?;
+    }
+    output::success(&format!("Credentials removed from profile '{}'. You are now logged out.", profile));
+    Ok(())
+}
+
+fn migrate_keyring() -> Result<()> {
+    let mut config = ConfigFile::load()?;
+    let mut moved = 0;
+    for name in config.profile_names() {
+        let mut creds = config.stored_credentials(&name)?;
+        if creds.keyring || !creds.is_authenticated() {
+            continue;
+        }
+        creds
+            .move_secrets_to_keyring(&name)
+            .map_err(|e| anyhow::anyhow!("Can't migrate profile '{}': {:#}", name, e))?;
+        let path = config.write_credentials(&name, creds)?;
+        output::success(&format!("Profile '{}': secrets moved to the OS keyring ({} updated)", name, path.display()));
+        moved += 1;
+    }
+
+    if config.credential_store == Some(CredentialStore::File) {
+        config.credential_store = None;
+        config.save()?;
+        output::info("Removed `credential_store = \"file\"` so new logins use the keyring too.");
+    }
+    if moved == 0 {
+        output::info("No plaintext secrets found; nothing to migrate.");
+    }
+    Ok(())
+}
+
+/// Save credentials to the profile selected for this invocation
+fn store(mut creds: Credentials) -> Result<std::path::PathBuf> {
+    let mut config = ConfigFile::load()?;
+    let profile = config.selected_profile();
+    // A read-only profile stays read-only across logins, and keeps its client certificate
+    if let Ok(stored) = config.stored_credentials(&profile) {
+        creds.read_only = stored.read_only;
+        creds.client_cert = stored.client_cert;
+        creds.client_key = stored.client_key;
+    }
+    config.store_credentials(&profile, creds)
+}
+
+/// The selected profile's client certificate and nothing else, so that
+/// logging in through a mutual-TLS gateway presents it
+fn stored_tls() -> Credentials {
+    let stored = ConfigFile::load().and_then(|config| config.stored_credentials(&config.selected_profile()));
+    let stored = stored.unwrap_or_default();
+    Credentials { client_cert: stored.client_cert, client_key: stored.client_key, ..Default::default() }
+}
+
+/// Environments page; older servers return `items`
+#[derive(serde::Deserialize)]
+struct EnvironmentPage {
+    #[serde(default, alias = "items")]
+    environments: Vec<Environment>,
+}
+
+/// Resolve `--env` to an environment ID, looking names up through the
+/// environments API. IDs (`env_…`) are used as given.
+pub async fn resolve_environment() -> Result<()> {
+    let Some(wanted) = globals::get().env.as_deref() else { return Ok(()) };
+    if wanted.starts_with("env_") {
+        return Ok(());
+    }
+    let creds = Credentials::load()?;
+    if !creds.is_authenticated() {
+        return Ok(());
+    }
+    let environments = environments(creds)
+        .await
+        .with_context(|| format!("Failed to look up environment '{}'", wanted))?;
+
+    let by_id = environments.iter().find(|e| e.id == wanted);
+    let by_name: Vec<&Environment> = environments
+        .iter()
+        .filter(|e| e.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(wanted)))
+        .collect();
+    let env = match (by_id, by_name.as_slice()) {
+        (Some(env), _) => env,
+        (None, [env]) => *env,
+        (None, []) => {
+            let names: Vec<&str> = environments.iter().filter_map(|e| e.name.as_deref()).collect();
+            anyhow::bail!("No environment named '{}'. Available: {}", wanted, names.join(", "));
+        }
+        (None, matches) => {
+            let ids: Vec<&str> = matches.iter().map(|e| e.id.as_str()).collect();
+            anyhow::bail!("Several environments are named '{}'; pass an ID instead: {}", wanted, ids.join(", "));
+        }
+    };
+    globals::set_environment_id(env.id.clone());
     Ok(())
 }
 
-/// Require authentication before proceeding. Returns credentials or exits.
+/// The tenant's environments
+pub async fn environments(mut creds: Credentials) -> Result<Vec<Environment>> {
+    // Listing environments shouldn't be scoped to one
+    creds.environment_id = None;
+    let client = ApiClient::new(creds)?;
+    let page: EnvironmentPage = client.get("/v1/environments").await?;
+    Ok(page.environments)
+}
+
+/// Require authentication before proceeding. Returns credentials, or an auth
+/// error when there are none.
 pub fn require_auth() -> Result<Credentials> {
-    let creds = Credentials::load(None, None)?;
+    let creds = Credentials::load()?;
+    if !creds.is_authenticated() {
+        let message = "Not authenticated. Run `flexprice auth login` or `flexprice auth set-api-key` first.";
+        return Err(ApiClientError::auth(message).into());
+    }
+    Ok(creds)
+}
+
+/// Require stored credentials for a named profile, for commands that work
+/// across environments
+pub fn require_profile_auth(profile: &str) -> Result<Credentials> {
+    let creds = ConfigFile::load()?.credentials(profile)?;
     if !creds.is_authenticated() {
-        output::warning("Not authenticated. Run `flexprice auth login` or `flexprice auth set-api-key <KEY>` first.");
-        std::process::exit(1);
+        let message = format!("Profile '{}' has no credentials. Run `flexprice --profile {} auth login` first.", profile, profile);
+        return Err(ApiClientError::auth(message).into());
     }
     Ok(creds)
 }
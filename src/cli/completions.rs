@@ -0,0 +1,108 @@
+use std::ffi::OsStr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{Args, Command};
+use clap_complete::engine::{ArgValueCompleter, CompletionCandidate};
+use clap_complete::env::Shells;
+use clap_complete::Shell;
+
+use crate::api::client::ApiClient;
+use crate::api::models::ListResponse;
+use crate::config::Credentials;
+use crate::utils::output;
+
+/// Environment variable the shell sets when asking the binary for completions
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// How long ID completion may wait on the API before giving up
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Print a shell completion script
+#[derive(Args)]
+pub struct CompletionsArgs {
+    #[arg(value_enum)]
+    shell: Shell,
+    /// Emit a self-contained script without resource ID completion
+    #[arg(long = "static")]
+    static_script: bool,
+}
+
+/// Write man pages for every command
+#[derive(Args)]
+pub struct ManpagesArgs {
+    /// Directory to write the pages into (created if missing)
+    #[arg(long, value_name = "PATH")]
+    dir: String,
+}
+
+pub fn completions(args: CompletionsArgs, mut cmd: Command) -> Result<()> {
+    let name = cmd.get_name().to_string();
+    let mut stdout = std::io::stdout();
+    if args.static_script {
+        clap_complete::generate(args.shell, &mut cmd, name, &mut stdout);
+        return Ok(());
+    }
+
+    // The dynamic script calls back into this binary on every <TAB>, which
+    // lets resource IDs be looked up from the API
+    let shell = args.shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&shell)
+        .with_context(|| format!("No completion support for {}", shell))?;
+    let exe = std::env::current_exe().context("Cannot locate the flexprice binary")?;
+    completer.write_registration(COMPLETE_VAR, &name, &name, &exe.to_string_lossy(), &mut stdout)?;
+    Ok(())
+}
+
+pub fn manpages(args: ManpagesArgs, cmd: Command) -> Result<()> {
+    std::fs::create_dir_all(&args.dir).with_context(|| format!("Failed to create {}", args.dir))?;
+    clap_mangen::generate_to(cmd, &args.dir).with_context(|| format!("Failed to write man pages to {}", args.dir))?;
+    output::success(&format!("Man pages written to {}", args.dir));
+    Ok(())
+}
+
+/// Complete a resource ID argument from the list at `endpoint`, with the
+/// resource's name or email as the description
+pub fn ids(endpoint: &'static str) -> ArgValueCompleter {
+    ArgValueCompleter::new(move |current: &OsStr| {
+        let prefix = current.to_string_lossy();
+        lookup(endpoint)
+            .into_iter()
+            .filter(|(id, _)| id.starts_with(prefix.as_ref()))
+            .map(|(id, help)| CompletionCandidate::new(id).help(help.map(Into::into)))
+            .collect()
+    })
+}
+
+/// IDs and labels from the first page of a list endpoint. Any failure (not
+/// logged in, offline, slow server) yields no candidates rather than an error.
+fn lookup(endpoint: &str) -> Vec<(String, Option<String>)> {
+    let Ok(creds) = Credentials::load() else { return vec![] };
+    if !creds.is_authenticated() {
+        return vec![];
+    }
+    let Ok(client) = ApiClient::new(creds) else { return vec![] };
+    let Ok(handle) = tokio::runtime::Handle::try_current() else { return vec![] };
+
+    let fetch = async {
+        let path = format!("{}?limit=100", endpoint);
+        tokio::time::timeout(LOOKUP_TIMEOUT, client.get::<ListResponse<serde_json::Value>>(&path)).await
+    };
+    let Ok(Ok(resp)) = tokio::task::block_in_place(|| handle.block_on(fetch)) else {
+        return vec![];
+    };
+    resp.items
+        .iter()
+        .filter_map(|item| {
+            let id = item.get("id")?.as_str()?.to_string();
+            let label = ["name", "email", "lookup_key", "external_id"]
+                .iter()
+                .find_map(|k| item.get(*k).and_then(|v| v.as_str()))
+                .filter(|s| !s.is_empty())
+                .map(String::from);
+            Some((id, label))
+        })
+        .collect()
+}
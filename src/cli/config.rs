@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use tabled::Tabled;
+
+use crate::api::tls;
+use crate::config::file::DEFAULT_PROFILE;
+use crate::config::{secrets, ConfigFile, Credentials};
+use crate::utils::output;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Manage named credential profiles
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    /// Add a profile
+    Add {
+        /// Profile name, e.g. prod, staging, local
+        name: String,
+        /// API endpoint URL
+        #[arg(long)]
+        api_url: String,
+        /// API key (omit to log in later with `auth login --profile <NAME>`)
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Environment ID sent with every request
+        #[arg(long)]
+        environment_id: Option<String>,
+        /// Refuse requests that could change billing state when using this profile
+        #[arg(long)]
+        read_only: bool,
+        /// Client certificate for a mutual-TLS gateway (PEM, or PKCS#12 .p12/.pfx)
+        #[arg(long, value_name = "PATH")]
+        client_cert: Option<PathBuf>,
+        /// PEM private key, if it isn't in the --client-cert file
+        #[arg(long, value_name = "PATH", requires = "client_cert")]
+        client_key: Option<PathBuf>,
+    },
+    /// List profiles
+    List,
+    /// Make a profile the default for future commands
+    Use { name: String },
+    /// Make a profile read-only, or writable again with --off
+    ReadOnly {
+        name: String,
+        /// Allow changes again
+        #[arg(long)]
+        off: bool,
+    },
+    /// Present a client certificate to a mutual-TLS gateway, or stop with --off
+    Tls {
+        name: String,
+        /// Client certificate (PEM, or PKCS#12 .p12/.pfx unlocked with
+        /// FLEXPRICE_CLIENT_CERT_PASSWORD)
+        #[arg(long, value_name = "PATH", required_unless_present = "off", conflicts_with = "off")]
+        client_cert: Option<PathBuf>,
+        /// PEM private key, if it isn't in the --client-cert file
+        #[arg(long, value_name = "PATH", requires = "client_cert")]
+        client_key: Option<PathBuf>,
+        /// Stop sending a client certificate
+        #[arg(long)]
+        off: bool,
+    },
+    /// Remove a profile
+    Remove { name: String },
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ProfileRow {
+    #[tabled(rename = "")]
+    active: String,
+    #[tabled(rename = "Profile")]
+    name: String,
+    #[tabled(rename = "API URL")]
+    api_url: String,
+    #[tabled(rename = "API Key")]
+    api_key: String,
+    #[tabled(rename = "Env ID")]
+    environment_id: String,
+    #[tabled(rename = "Mode")]
+    mode: String,
+}
+
+pub fn handle(cmd: ConfigCommands) -> Result<()> {
+    let ConfigCommands::Profile { command } = cmd;
+    let mut config = ConfigFile::load()?;
+
+    match command {
+        ProfileCommands::Add { name, api_url, api_key, environment_id, read_only, client_cert, client_key } => {
+            if name == DEFAULT_PROFILE || config.profiles.contains_key(&name) {
+                anyhow::bail!(
+                    "Profile '{}' already exists. Remove it first or use `auth login --profile {}`.",
+                    name,
+                    name
+                );
+            }
+            let (client_cert, client_key) = client_certificate(client_cert, client_key)?;
+            let creds = Credentials {
+                api_url,
+                api_key,
+                environment_id,
+                read_only,
+                client_cert,
+                client_key,
+                ..Default::default()
+            };
+            config.store_credentials(&name, creds)?;
+            output::success(&format!("Profile '{}' added.", name));
+            output::info(&format!("Switch to it with `flexprice config profile use {}`.", name));
+        }
+        ProfileCommands::List => {
+            let active = config.selected_profile();
+            let mut profiles = config.all_profiles()?;
+            if !profiles.iter().any(|(name, _)| name == DEFAULT_PROFILE) {
+                profiles.insert(0, (DEFAULT_PROFILE.to_string(), Credentials::default()));
+            }
+            let rows: Vec<ProfileRow> = profiles
+                .into_iter()
+                .map(|(name, creds)| ProfileRow {
+                    active: if name == active { "*".green().bold().to_string() } else { String::new() },
+                    api_url: creds.api_url.clone(),
+                    api_key: creds.masked_api_key(),
+                    environment_id: creds.environment_id.clone().unwrap_or_default(),
+                    mode: mode(&creds),
+                    name,
+                })
+                .collect();
+            println!("{}", output::print_table(&rows));
+        }
+        ProfileCommands::Use { name } => {
+            config.credentials(&name)?;
+            config.active_profile = (name != DEFAULT_PROFILE).then(|| name.clone());
+            config.save()?;
+            output::success(&format!("Now using profile '{}'.", name));
+        }
+        ProfileCommands::ReadOnly { name, off } => {
+            let mut creds = config.stored_credentials(&name)?;
+            creds.read_only = !off;
+            config.write_credentials(&name, creds)?;
+            if off {
+                output::success(&format!("Profile '{}' can make changes again.", name));
+            } else {
+                output::success(&format!("Profile '{}' is now read-only.", name));
+            }
+        }
+        ProfileCommands::Tls { name, client_cert, client_key, off } => {
+            let mut creds = config.stored_credentials(&name)?;
+            (creds.client_cert, creds.client_key) = client_certificate(client_cert, client_key)?;
+            config.write_credentials(&name, creds)?;
+            if off {
+                output::success(&format!("Profile '{}' no longer sends a client certificate.", name));
+            } else {
+                output::success(&format!("Profile '{}' now sends a client certificate.", name));
+            }
+        }
+        ProfileCommands::Remove { name } => {
+            if name == DEFAULT_PROFILE {
+                anyhow::bail!("The default profile can't be removed; use `flexprice auth logout` to clear it.");
+            }
+            if config.profiles.remove(&name).is_none() {
+                anyhow::bail!("Unknown profile '{}'.", name);
+            }
+            let _ = secrets::delete(&name);
+            config.queries.remove(&name);
+            if config.active_profile.as_deref() == Some(name.as_str()) {
+                config.active_profile = None;
+                output::info("It was the active profile; switched back to 'default'.");
+            }
+            config.save()?;
+            output::success(&format!("Profile '{}' removed.", name));
+        }
+    }
+    Ok(())
+}
+
+/// A profile's modes, e.g. `read-only, mTLS`
+fn mode(creds: &Credentials) -> String {
+    let mut modes = vec![];
+    if creds.read_only {
+        modes.push("read-only".yellow().to_string());
+    }
+    if creds.client_cert.is_some() {
+        modes.push("mTLS".cyan().to_string());
+    }
+    modes.join(", ")
+}
+
+/// Absolute paths for a client certificate and key, so they work from any
+/// directory, once they've been checked to load
+fn client_certificate(cert: Option<PathBuf>, key: Option<PathBuf>) -> Result<(Option<String>, Option<String>)> {
+    let Some(cert) = cert else { return Ok((None, None)) };
+    let absolute = |path: PathBuf| -> Result<String> {
+        let path = std::path::absolute(&path).with_context(|| format!("Invalid path {}", path.display()))?;
+        Ok(path.display().to_string())
+    };
+    let cert = absolute(cert)?;
+    let key = key.map(absolute).transpose()?;
+    tls::identity(&cert, key.as_deref())?;
+    Ok((Some(cert), key))
+}
@@ -1,59 +1,256 @@
-use anyhow::Result;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
 use clap::Subcommand;
+use colored::Colorize;
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
 use tabled::Tabled;
 
 use crate::api::client::ApiClient;
-use crate::api::models::{Customer, ListResponse};
-use crate::cli::auth::require_auth;
-use crate::utils::{output, spinner};
+use crate::api::query::QueryBuilder;
+use crate::api::models::{
+    Customer, CustomerUsageSummary, FeatureUsage, Invoice, ListResponse, Subscription, SubscriptionUsage, UsageCharge, Wallet,
+    WalletBalance,
+};
+use crate::config::globals;
+use crate::cli::{completions, invoices, subscriptions};
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::fanout::{self, DeleteManyArgs, IdsArgs};
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::time::{self, Period};
+use crate::utils::output::{self, Format};
+use crate::utils::{chart, spinner};
 
 #[derive(Subcommand)]
 pub enum CustomerCommands {
-    /// List all customers
+    /// List customers, a page at a time
     List {
-        /// Output as JSON
-        #[arg(long)]
-        json: bool,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get a customer by ID
+    /// Get a customer by ID, or many with --ids-file
     Get {
-        /// Customer ID
-        id: String,
-        /// Output as JSON
-        #[arg(long)]
-        json: bool,
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(add = completions::ids("/v1/customers"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
     },
-    /// Create a new customer from a JSON file
+    /// Create customers from flags, interactively, or from a JSON file (object or array of objects)
     Create {
         /// Path to JSON file with customer data
         #[arg(long)]
-        json: String,
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        fields: CustomerFields,
+        #[command(flatten)]
+        batch: BatchArgs,
     },
-    /// Delete a customer by ID
-    Delete {
-        /// Customer ID
+    /// Import customers from a CSV or JSON file, detecting duplicates
+    Import {
+        /// CSV (header row) or JSON file with customer records
+        #[arg(long)]
+        file: String,
+        /// What to do with rows matching an existing customer by external ID or email
+        #[arg(long, value_enum, default_value_t = OnDuplicate::Fail)]
+        on_duplicate: OnDuplicate,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Create, update, and optionally delete customers so FlexPrice matches a CSV or JSON source
+    ///
+    /// With --dry-run, shows the planned changes without applying them.
+    Sync {
+        /// CSV (header row) or JSON file that is the source of truth
+        #[arg(long)]
+        source: String,
+        /// Field identifying the same customer in both places
+        #[arg(long, default_value = "external_id")]
+        key: String,
+        /// Delete customers whose key is not in the source
+        #[arg(long)]
+        delete_missing: bool,
+        /// Refuse to run if --delete-missing would delete more customers than this
+        #[arg(long, value_name = "N", default_value_t = 10, requires = "delete_missing")]
+        max_deletes: usize,
+    },
+    /// Update a customer from flags, a JSON file, or in $EDITOR
+    Update {
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(add = completions::ids("/v1/customers"))]
+        id: String,
+        #[arg(long, required_unless_present_any = ["edit", "name", "email", "external_id", "metadata"])]
+        json: Option<String>,
+        /// Edit the customer's current state in $EDITOR
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        fields: CustomerFields,
+    },
+    /// Add or change metadata keys, keeping the others
+    SetMetadata {
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(add = completions::ids("/v1/customers"))]
         id: String,
+        /// Entries to set, e.g. `region=eu tier=gold`
+        #[arg(value_name = "KEY=VALUE", required = true, value_parser = parse_metadata)]
+        entries: Vec<(String, String)>,
     },
-    /// View customer usage summary
+    /// Remove metadata keys, keeping the others
+    UnsetMetadata {
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(add = completions::ids("/v1/customers"))]
+        id: String,
+        /// Keys to remove
+        #[arg(value_name = "KEY", required = true)]
+        keys: Vec<String>,
+    },
+    /// Delete a customer by ID, or many from --ids-file or --filter
+    Delete {
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(
+            add = completions::ids("/v1/customers"),
+            required_unless_present_any = ["ids_file", "filters"],
+            conflicts_with_all = ["ids_file", "filters"]
+        )]
+        id: Option<String>,
+        #[command(flatten)]
+        many: DeleteManyArgs,
+    },
+    /// View customer usage with per-meter cost attribution
     Usage {
-        /// Customer ID
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(add = completions::ids("/v1/customers"))]
         id: String,
-        /// Output as JSON
+        /// Billing period: `current`, `YYYY-MM`, or `START..END`
+        #[arg(long, default_value = "current")]
+        period: String,
+        /// Only count usage recorded up to this date or RFC 3339 timestamp
         #[arg(long)]
-        json: bool,
+        as_of: Option<String>,
     },
     /// View customer entitlements
     Entitlements {
-        /// Customer ID
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(add = completions::ids("/v1/customers"))]
         id: String,
-        /// Output as JSON
+        /// Show entitlements from the subscriptions active at this date or RFC 3339 timestamp
         #[arg(long)]
-        json: bool,
+        as_of: Option<String>,
+    },
+    /// Show a customer's subscriptions, entitlements, wallets, and recent invoices in one report
+    Summary {
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(add = completions::ids("/v1/customers"))]
+        id: String,
+        /// Number of recent invoices to show
+        #[arg(long, default_value_t = 5)]
+        invoices: usize,
     },
 }
 
+/// Customer fields set with flags instead of a JSON file
+#[derive(clap::Args)]
+pub struct CustomerFields {
+    /// Name
+    #[arg(long, conflicts_with_all = ["json", "edit"])]
+    pub name: Option<String>,
+    /// Email address
+    #[arg(long, conflicts_with_all = ["json", "edit"])]
+    pub email: Option<String>,
+    /// External ID, the customer's identifier in your system
+    #[arg(long, conflicts_with_all = ["json", "edit"])]
+    pub external_id: Option<String>,
+    /// Metadata entry, e.g. `region=eu` (repeatable)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_metadata, conflicts_with_all = ["json", "edit"])]
+    pub metadata: Vec<(String, String)>,
+}
+
+impl CustomerFields {
+    /// The flags as a request body; `None` when none were given
+    fn body(self) -> Option<serde_json::Value> {
+        let metadata: serde_json::Map<String, serde_json::Value> =
+            self.metadata.into_iter().map(|(k, v)| (k, v.into())).collect();
+        editor::field_updates(&[
+            ("name", self.name.map(Into::into)),
+            ("email", self.email.map(Into::into)),
+            ("external_id", self.external_id.map(Into::into)),
+            ("metadata", (!metadata.is_empty()).then_some(metadata.into())),
+        ])
+    }
+}
+
+/// Apply `edit` to a customer's current metadata and save the result, so keys
+/// it doesn't touch keep their values
+async fn edit_metadata(
+    client: &ApiClient,
+    id: &str,
+    edit: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+) -> Result<()> {
+    let path = format!("/v1/customers/{}", id);
+    let current: serde_json::Value = client.get(&path).await?;
+    let before = current.get("metadata").and_then(|m| m.as_object()).cloned().unwrap_or_default();
+    let mut metadata = before.clone();
+    edit(&mut metadata);
+    if metadata == before {
+        output::info(&format!("Customer {} metadata is unchanged.", id));
+        return Ok(());
+    }
+    let sp = spinner::create_spinner("Updating customer metadata...");
+    let customer: serde_json::Value = client.put(&path, &serde_json::json!({ "metadata": metadata })).await?;
+    sp.finish_and_clear();
+    output::success(&format!("Customer metadata updated: {}", id));
+    println!("{}", output::print_detail(customer.get("metadata").unwrap_or(&metadata.into())));
+    Ok(())
+}
+
+fn parse_metadata(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+        _ => Err(format!("'{}' is not KEY=VALUE", s)),
+    }
+}
+
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "customer",
+    fields: &[
+        Field::text("external_id", "External ID").required(),
+        Field::text("name", "Name"),
+        Field::text("email", "Email"),
+        Field::map("metadata", "Metadata"),
+    ],
+    check: None,
+};
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "external_id": "",
+  "name": "",
+  "email": "",
+  "metadata": {}
+}"#;
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnDuplicate {
+    /// Leave the existing customer untouched
+    Skip,
+    /// Update the existing customer with the row's fields
+    Update,
+    /// Report the row as a failure
+    Fail,
+}
+
 #[derive(Tabled, serde::Serialize)]
-struct CustomerRow {
+pub struct CustomerRow {
     #[tabled(rename = "ID")]
     id: String,
     #[tabled(rename = "Name")]
@@ -78,52 +275,882 @@ impl From<Customer> for CustomerRow {
     }
 }
 
-pub async fn handle(cmd: CustomerCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
-
+pub async fn handle(cmd: CustomerCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        CustomerCommands::List { json } => {
+        CustomerCommands::List { page } => {
             let sp = spinner::create_spinner("Fetching customers...");
-            let resp: ListResponse<Customer> = client.get("/v1/customers").await?;
+            let (items, pagination) = page.fetch::<Customer>(&client, "/v1/customers").await?;
             sp.finish_and_clear();
-
-            let rows: Vec<CustomerRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            let rows: Vec<CustomerRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "customer"));
         }
-        CustomerCommands::Get { id, json } => {
+        CustomerCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/customers", &many).await };
+            let id = resolve(&client, &id).await?;
             let sp = spinner::create_spinner("Fetching customer...");
             let customer: Customer = client.get(&format!("/v1/customers/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&customer, json));
+            println!("{}", output::print_detail(&customer));
         }
-        CustomerCommands::Create { json: file } => {
-            let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Creating customer...");
-            let customer: Customer = client.post("/v1/customers", &body).await?;
-            sp.finish_and_clear();
-            output::success(&format!("Customer created: {}", customer.id));
-            println!("{}", output::print_detail(&customer, false));
+        CustomerCommands::Create { json: file, edit, fields, batch: opts } => {
+            let payload = match fields.body() {
+                Some(body) if body.get("external_id").is_none() => {
+                    anyhow::bail!("--external-id is required to create a customer from flags")
+                }
+                Some(body) => Some(Payload::Single(body)),
+                None => editor::load_or_edit::<Customer>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))?,
+            };
+            match payload {
+                None => {}
+                Some(Payload::Single(body)) => {
+                    let sp = spinner::create_spinner("Creating customer...");
+                    let customer: Customer = client.post("/v1/customers", &body).await?;
+                    sp.finish_and_clear();
+                    output::success(&format!("Customer created: {}", customer.id));
+                    println!("{}", output::print_detail(&customer));
+                }
+                Some(Payload::Many(items)) => {
+                    batch::create_many(&client, items, file.as_deref(), &opts, "Customer created", "/v1/customers").await?;
+                }
+            }
+        }
+        CustomerCommands::Import { file, on_duplicate, batch: opts } => {
+            import(&client, &file, on_duplicate, &opts).await?;
+        }
+        CustomerCommands::Sync { source, key, delete_missing, max_deletes } => {
+            let max_deletes = delete_missing.then_some(max_deletes);
+            sync(&client, &source, &key, max_deletes, globals::get().dry_run).await?;
+        }
+        CustomerCommands::Update { id, json: file, fields, .. } => {
+            let id = resolve(&client, &id).await?;
+            let path = format!("/v1/customers/{}", id);
+            let body = match fields.body() {
+                Some(mut body) => {
+                    // Keys not given keep their values, as with `sync`
+                    if let Some(metadata) = body.get_mut("metadata").and_then(serde_json::Value::as_object_mut) {
+                        let current: serde_json::Value = client.get(&path).await?;
+                        for (key, value) in current.get("metadata").and_then(|m| m.as_object()).into_iter().flatten() {
+                            metadata.entry(key.clone()).or_insert_with(|| value.clone());
+                        }
+                    }
+                    Some(body)
+                }
+                None => editor::update_body::<Customer>(&client, &path, file.as_deref()).await?,
+            };
+            if let Some(body) = body {
+                let sp = spinner::create_spinner("Updating customer...");
+                let customer: Customer = client.put(&path, &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Customer updated: {}", customer.id));
+                println!("{}", output::print_detail(&customer));
+            }
+        }
+        CustomerCommands::SetMetadata { id, entries } => {
+            let id = resolve(&client, &id).await?;
+            edit_metadata(&client, &id, |metadata| {
+                for (key, value) in entries {
+                    metadata.insert(key, value.into());
+                }
+            })
+            .await?;
+        }
+        CustomerCommands::UnsetMetadata { id, keys } => {
+            let id = resolve(&client, &id).await?;
+            edit_metadata(&client, &id, |metadata| {
+                for key in keys {
+                    if metadata.remove(&key).is_none() {
+                        output::warning(&format!("Customer {} has no metadata key '{}'.", id, key));
+                    }
+                }
+            })
+            .await?;
         }
-        CustomerCommands::Delete { id } => {
+        CustomerCommands::Delete { id, many } => {
+            let Some(id) = id else { return fanout::delete_many(&client, "/v1/customers", "customers", &many).await };
+            let id = resolve(&client, &id).await?;
             let sp = spinner::create_spinner("Deleting customer...");
             client.delete_empty(&format!("/v1/customers/{}", id)).await?;
             sp.finish_and_clear();
             output::success(&format!("Customer {} deleted.", id));
         }
-        CustomerCommands::Usage { id, json } => {
+        CustomerCommands::Usage { id, period, as_of } => {
+            let id = resolve(&client, &id).await?;
+            let mut period = time::parse_period(&period)?;
+            if let Some(at) = as_of.as_deref().map(time::parse_instant).transpose()? {
+                period = period.until(at);
+            }
             let sp = spinner::create_spinner("Fetching usage...");
-            let usage: serde_json::Value = client.get(&format!("/v1/customers/{}/usage", id)).await?;
+            let rows = usage_breakdown(&client, &id, &period).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&usage, json));
+            print_usage(rows);
         }
-        CustomerCommands::Entitlements { id, json } => {
+        CustomerCommands::Entitlements { id, as_of } => {
+            let id = resolve(&client, &id).await?;
+            let mut query = QueryBuilder::new();
             let sp = spinner::create_spinner("Fetching entitlements...");
-            let ents: serde_json::Value = client.get(&format!("/v1/customers/{}/entitlements", id)).await?;
+            if let Some(at) = as_of.as_deref().map(time::parse_instant).transpose()? {
+                let subs: ListResponse<Subscription> = client
+                    .get_with_query("/v1/subscriptions", &[("customer_id", &id)])
+                    .await?;
+                let active: Vec<String> = subs
+                    .items
+                    .into_iter()
+                    .filter(|sub| active_at(sub, at))
+                    .map(|sub| sub.id)
+                    .collect();
+                if active.is_empty() {
+                    sp.finish_and_clear();
+                    output::info(&format!("Customer {} had no active subscriptions at {}.", id, at.to_rfc3339()));
+                    return Ok(());
+                }
+                query = query.each("subscription_ids", active);
+            }
+            if !output::human() {
+                let ents: serde_json::Value = client.get(&query.to_path(&format!("/v1/customers/{}/entitlements", id))).await?;
+                sp.finish_and_clear();
+                println!("{}", output::print_detail(&ents));
+                return Ok(());
+            }
+            // The usage summary carries each entitlement's limit and current usage
+            let summary: CustomerUsageSummary = client.get(&query.to_path(&format!("/v1/customers/{}/usage", id))).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&ents, json));
+            print_entitlements(&summary.features);
+        }
+        CustomerCommands::Summary { id, invoices } => {
+            let id = resolve(&client, &id).await?;
+            let sp = spinner::create_spinner("Fetching customer summary...");
+            let report = summary(&client, &id, invoices).await;
+            sp.finish_and_clear();
+            print_summary(report?);
+        }
+    }
+    Ok(())
+}
+
+/// The ID of the customer `reference` names: a customer ID as-is, or
+/// `ext:<external_id>` / `email:<address>` looked up through the API
+pub async fn resolve(client: &ApiClient, reference: &str) -> Result<String> {
+    let (field, value) = if let Some(external_id) = reference.strip_prefix("ext:") {
+        ("external_id", external_id)
+    } else if let Some(email) = reference.strip_prefix("email:") {
+        ("email", email)
+    } else {
+        return Ok(reference.to_string());
+    };
+    if value.is_empty() {
+        anyhow::bail!("'{}' is missing a value after the prefix", reference);
+    }
+    let resp: ListResponse<Customer> = client
+        .get_with_query("/v1/customers", &[(field, value)])
+        .await
+        .with_context(|| format!("Failed to look up customer {}", reference))?;
+    // Match exactly; emails compare case-insensitively
+    let matches: Vec<Customer> = resp
+        .items
+        .into_iter()
+        .filter(|c| match field {
+            "email" => c.email.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(value)),
+            _ => c.external_id.as_deref() == Some(value),
+        })
+        .collect();
+    match matches.as_slice() {
+        [customer] => Ok(customer.id.clone()),
+        [] => anyhow::bail!("No customer found with {} '{}'", field.replace('_', " "), value),
+        many => {
+            let ids: Vec<&str> = many.iter().map(|c| c.id.as_str()).collect();
+            anyhow::bail!("{} customers have {} '{}' ({}); pass one of their IDs instead", many.len(), field.replace('_', " "), value, ids.join(", "))
+        }
+    }
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct EntitlementRow {
+    #[tabled(rename = "Feature")]
+    feature: String,
+    #[tabled(rename = "Type")]
+    feature_type: String,
+    #[tabled(rename = "Usage")]
+    usage: String,
+    #[tabled(rename = "Limit")]
+    limit: String,
+    #[tabled(rename = "Used")]
+    gauge: String,
+}
+
+/// Share of its limit a metered feature has used; `None` when it is
+/// unlimited or not metered. A zero limit counts as used up.
+pub fn usage_ratio(usage: &FeatureUsage) -> Option<f64> {
+    if usage.feature.feature_type.as_deref() != Some("METERED") {
+        return None;
+    }
+    let limit = usage.total_limit?;
+    let used = usage.current_usage.unwrap_or_default();
+    Some(if limit > 0.0 { used / limit } else { 1.0 })
+}
+
+/// Entitlements with a usage gauge for each limited metered feature, then a
+/// warning for those close to or over their limit
+fn print_entitlements(features: &[FeatureUsage]) {
+    let rows: Vec<EntitlementRow> = features
+        .iter()
+        .map(|f| {
+            let metered = f.feature.feature_type.as_deref() == Some("METERED");
+            let enabled = f.is_enabled != Some(false);
+            let (usage, limit, gauge) = match usage_ratio(f) {
+                _ if !enabled => ("-".to_string(), "-".to_string(), "✗ disabled".red().to_string()),
+                Some(ratio) => {
+                    let soft = if f.is_soft_limit == Some(true) { " (soft)" } else { "" };
+                    let limit = format!("{}{}", chart::format_value(f.total_limit.unwrap_or_default()), soft);
+                    (chart::format_value(f.current_usage.unwrap_or_default()), limit, chart::gauge(ratio, 20))
+                }
+                None if metered => (chart::format_value(f.current_usage.unwrap_or_default()), "unlimited".to_string(), String::new()),
+                None => ("-".to_string(), "-".to_string(), "✓ enabled".green().to_string()),
+            };
+            EntitlementRow {
+                feature: f.feature.name.clone().or(f.feature.lookup_key.clone()).unwrap_or_else(|| f.feature.id.clone()),
+                feature_type: f.feature.feature_type.clone().unwrap_or_default().to_lowercase(),
+                usage,
+                limit,
+                gauge,
+            }
+        })
+        .collect();
+    println!("{}", output::print_table(&rows));
+
+    let critical = features
+        .iter()
+        .filter(|f| f.is_enabled != Some(false) && usage_ratio(f).is_some_and(|r| chart::UsageLevel::of(r) == chart::UsageLevel::Critical))
+        .count();
+    if critical > 0 {
+        output::warning(&format!("{} of {} features at 90% or more of their limit", critical, features.len()));
+    }
+}
+
+/// Everything `customers summary` shows; a section that failed to load
+/// carries its error instead of failing the whole report
+#[derive(serde::Serialize)]
+struct CustomerSummary {
+    customer: Customer,
+    subscriptions: Section<Vec<Subscription>>,
+    entitlements: Section<Vec<FeatureUsage>>,
+    wallets: Section<Vec<WalletSummary>>,
+    invoices: Section<Vec<Invoice>>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum Section<T> {
+    Loaded(T),
+    Failed { error: String },
+}
+
+impl<T> From<Result<T>> for Section<T> {
+    fn from(result: Result<T>) -> Self {
+        match result {
+            Ok(items) => Section::Loaded(items),
+            Err(e) => Section::Failed { error: format!("{:#}", e) },
+        }
+    }
+}
+
+/// A wallet with its real-time balance, falling back to the stored one
+#[derive(serde::Serialize)]
+struct WalletSummary {
+    #[serde(flatten)]
+    wallet: Wallet,
+    real_time_balance: Option<f64>,
+}
+
+/// Fetch the customer and, concurrently, everything the summary reports on.
+/// Only a failure to find the customer fails the report.
+async fn summary(client: &ApiClient, id: &str, invoice_count: usize) -> Result<CustomerSummary> {
+    let wallets = async {
+        let wallets: Vec<Wallet> = client.get_all("/v1/wallets").await?;
+        let wallets = wallets.into_iter().filter(|w| w.customer_id.as_deref() == Some(id));
+        Ok::<_, anyhow::Error>(
+            join_all(wallets.map(|wallet| async move {
+                let balance = client
+                    .get::<WalletBalance>(&format!("/v1/wallets/{}/balance/real-time", wallet.id))
+                    .await
+                    .ok()
+                    .and_then(|b| b.real_time_balance.or(b.balance));
+                WalletSummary { real_time_balance: balance.or(wallet.balance), wallet }
+            }))
+            .await,
+        )
+    };
+    let invoices = async {
+        let resp: ListResponse<Invoice> = client
+            .get_with_query("/v1/invoices", &[("customer_id", id), ("limit", &invoice_count.to_string())])
+            .await?;
+        let mut invoices = resp.items;
+        // Newest first, whatever order the server returns them in
+        invoices.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        invoices.truncate(invoice_count);
+        Ok(invoices)
+    };
+    let (customer_path, subscriptions_path, usage_path) = (
+        format!("/v1/customers/{}", id),
+        QueryBuilder::new().param("customer_id", id).to_path("/v1/subscriptions"),
+        format!("/v1/customers/{}/usage", id),
+    );
+    let (customer, subscriptions, usage, wallets, invoices) = tokio::join!(
+        client.get::<Customer>(&customer_path),
+        client.get::<ListResponse<Subscription>>(&subscriptions_path),
+        client.get::<CustomerUsageSummary>(&usage_path),
+        wallets,
+        invoices,
+    );
+    Ok(CustomerSummary {
+        customer: customer?,
+        subscriptions: subscriptions.map(|s| s.items).into(),
+        entitlements: usage.map(|u| u.features).into(),
+        wallets: wallets.into(),
+        invoices: invoices.into(),
+    })
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct SummarySubscriptionRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Plan")]
+    plan_id: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Current Period")]
+    period: String,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct SummaryWalletRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Balance")]
+    balance: String,
+    #[tabled(rename = "Currency")]
+    currency: String,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct SummaryInvoiceRow {
+    #[tabled(rename = "Invoice")]
+    number: String,
+    #[tabled(rename = "Status")]
+    invoice_status: String,
+    #[tabled(rename = "Payment")]
+    payment_status: String,
+    #[tabled(rename = "Amount Due")]
+    amount: String,
+    #[tabled(rename = "Currency")]
+    currency: String,
+    #[tabled(rename = "Period")]
+    period: String,
+}
+
+/// The report as one record in machine formats; in table output, a header
+/// for the customer followed by a titled section per resource
+fn print_summary(report: CustomerSummary) {
+    if !output::human() {
+        println!("{}", output::print_detail(&report));
+        return;
+    }
+    let c = &report.customer;
+    println!();
+    println!("  {}  {}", c.name.as_deref().unwrap_or("(unnamed)").bold(), c.id.dimmed());
+    let facts: Vec<String> = [
+        c.email.clone(),
+        c.external_id.as_ref().map(|e| format!("ext:{}", e)),
+        c.status.as_deref().map(output::status_badge),
+        c.created_at.as_deref().map(|t| format!("since {}", t.get(..10).unwrap_or(t))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !facts.is_empty() {
+        println!("  {}", facts.join(" · "));
+    }
+
+    section("Subscriptions", report.subscriptions, |subs| {
+        let rows: Vec<SummarySubscriptionRow> = subs
+            .into_iter()
+            .map(|s| SummarySubscriptionRow {
+                period: subscriptions::current_period(&s).unwrap_or_default(),
+                id: s.id,
+                plan_id: s.plan_id.unwrap_or_default(),
+                status: s.subscription_status.map(|st| output::status_badge(&st)).unwrap_or_default(),
+            })
+            .collect();
+        println!("{}", output::print_table(&rows));
+    });
+    section("Entitlements", report.entitlements, |features| print_entitlements(&features));
+    section("Wallets", report.wallets, |wallets| {
+        let rows: Vec<SummaryWalletRow> = wallets
+            .into_iter()
+            .map(|w| SummaryWalletRow {
+                balance: w.real_time_balance.map(|b| format!("{:.2}", b)).unwrap_or_default(),
+                id: w.wallet.id,
+                status: w.wallet.wallet_status.map(|s| output::status_badge(&s)).unwrap_or_default(),
+                currency: w.wallet.currency.unwrap_or_default().to_uppercase(),
+            })
+            .collect();
+        println!("{}", output::print_table(&rows));
+    });
+    section("Recent invoices", report.invoices, |items| {
+        let rows: Vec<SummaryInvoiceRow> = items
+            .into_iter()
+            .map(|i| SummaryInvoiceRow {
+                period: invoices::billing_period(&i).unwrap_or_default(),
+                number: i.invoice_number.unwrap_or(i.id),
+                invoice_status: i.invoice_status.map(|s| output::status_badge(&s)).unwrap_or_default(),
+                payment_status: i.payment_status.map(|s| output::status_badge(&s)).unwrap_or_default(),
+                amount: i.amount_due.map(|a| format!("{:.2}", a)).unwrap_or_default(),
+                currency: i.currency.unwrap_or_default().to_uppercase(),
+            })
+            .collect();
+        println!("{}", output::print_table(&rows));
+    });
+}
+
+/// One titled part of the summary: its table, a note when it is empty, or
+/// why it couldn't be loaded
+fn section<T>(title: &str, items: Section<Vec<T>>, print: impl FnOnce(Vec<T>)) {
+    println!();
+    println!("  {}", title.bold());
+    match items {
+        Section::Failed { error } => output::warning(&format!("Could not load {}: {}", title.to_lowercase(), error)),
+        Section::Loaded(items) if items.is_empty() => output::info(&format!("No {}.", title.to_lowercase())),
+        Section::Loaded(items) => print(items),
+    }
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct UsageRow {
+    #[tabled(rename = "Meter")]
+    meter: String,
+    #[tabled(rename = "Quantity")]
+    quantity: f64,
+    #[tabled(rename = "Unit Price")]
+    unit_price: String,
+    #[tabled(rename = "Cost", display_with = "money")]
+    cost: f64,
+    #[tabled(rename = "Currency")]
+    currency: String,
+}
+
+fn money(v: &f64) -> String {
+    format!("{:.2}", v)
+}
+
+/// Read a number that the API may encode either as a JSON number or a decimal string
+fn number(v: &serde_json::Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+}
+
+/// Unit price of a charge, preferring the price embedded in the usage
+/// response and falling back to the plan's price for the same meter
+fn unit_price(charge: &UsageCharge, plan_prices: &[serde_json::Value]) -> String {
+    let price = charge.price.as_ref().or_else(|| {
+        plan_prices.iter().find(|p| {
+            p.get("meter_id").and_then(|m| m.as_str()) == charge.meter_id.as_deref()
+        })
+    });
+    let Some(price) = price else { return "-".to_string() };
+    if price.get("billing_model").and_then(|m| m.as_str()) == Some("TIERED") {
+        return "tiered".to_string();
+    }
+    price.get("amount").and_then(number).map(|a| a.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+/// Whether a subscription had started and not yet ended or been cancelled at `at`
+fn active_at(sub: &Subscription, at: DateTime<Utc>) -> bool {
+    let parse = |s: &Option<String>| s.as_deref().and_then(|s| time::parse_instant(s).ok());
+    let started = parse(&sub.start_date).or(parse(&sub.created_at)).is_none_or(|start| start <= at);
+    let ended = parse(&sub.cancelled_at)
+        .into_iter()
+        .chain(parse(&sub.end_date))
+        .any(|end| end <= at);
+    started && !ended
+}
+
+/// Fetch usage for every subscription of a customer and attribute cost per meter
+async fn usage_breakdown(client: &ApiClient, customer_id: &str, period: &Period) -> Result<Vec<UsageRow>> {
+    let subs: ListResponse<Subscription> = client
+        .get_with_query("/v1/subscriptions", &[("customer_id", customer_id)])
+        .await?;
+
+    let mut plan_prices: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+    // Keyed by (meter, unit price, currency) so identical charges across subscriptions merge
+    let mut merged: BTreeMap<(String, String, String), (f64, f64)> = BTreeMap::new();
+
+    for sub in &subs.items {
+        let mut body = serde_json::json!({ "subscription_id": sub.id });
+        if let Some(start) = period.start {
+            body["start_time"] = start.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+        }
+        if let Some(end) = period.end {
+            body["end_time"] = end.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+        }
+        let usage: SubscriptionUsage = client.post("/v1/subscriptions/usage", &body).await?;
+
+        let plan_id = sub.plan_id.clone().unwrap_or_default();
+        if !plan_prices.contains_key(&plan_id) && !plan_id.is_empty() {
+            let prices: ListResponse<serde_json::Value> = client
+                .get_with_query("/v1/prices", &[("plan_ids", &plan_id)])
+                .await?;
+            plan_prices.insert(plan_id.clone(), prices.items);
+        }
+        let prices = plan_prices.get(&plan_id).map(Vec::as_slice).unwrap_or_default();
+
+        let currency = usage.currency.unwrap_or_default().to_uppercase();
+        for charge in usage.charges {
+            let key = (
+                charge.meter_display_name.clone().or(charge.meter_id.clone()).unwrap_or_default(),
+                unit_price(&charge, prices),
+                currency.clone(),
+            );
+            let entry = merged.entry(key).or_default();
+            entry.0 += charge.quantity.unwrap_or_default();
+            entry.1 += charge.amount.unwrap_or_default();
+        }
+    }
+
+    Ok(merged
+        .into_iter()
+        .map(|((meter, unit_price, currency), (quantity, cost))| UsageRow {
+            meter,
+            quantity,
+            unit_price,
+            cost,
+            currency,
+        })
+        .collect())
+}
+
+fn print_usage(rows: Vec<UsageRow>) {
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    for row in &rows {
+        *totals.entry(row.currency.clone()).or_default() += row.cost;
+    }
+
+    // `--query` sees what `-o json` prints, whatever the format
+    match (output::format(), globals::get().query.is_some()) {
+        (Format::Json | Format::Yaml, _) | (_, true) => {
+            let out = serde_json::json!({ "charges": rows, "totals": totals });
+            println!("{}", output::print_detail(&out));
+            return;
+        }
+        (Format::Csv | Format::Ndjson, false) => {
+            println!("{}", output::print_table(&rows));
+            return;
+        }
+        (Format::Table, false) => {}
+    }
+
+    println!("{}", output::print_table(&rows));
+    if !rows.is_empty() {
+        println!();
+        for (currency, total) in totals {
+            output::success(&format!("Total: {:.2} {}", total, currency));
+        }
+    }
+}
+
+/// Existing customers indexed by external ID and lowercased email
+#[derive(Default)]
+struct CustomerIndex {
+    by_external_id: HashMap<String, String>,
+    by_email: HashMap<String, String>,
+}
+
+impl CustomerIndex {
+    fn insert(&mut self, id: &str, external_id: Option<&str>, email: Option<&str>) {
+        if let Some(ext) = external_id.filter(|e| !e.is_empty()) {
+            self.by_external_id.insert(ext.to_string(), id.to_string());
+        }
+        if let Some(email) = email.filter(|e| !e.is_empty()) {
+            self.by_email.insert(email.to_lowercase(), id.to_string());
+        }
+    }
+
+    /// Find the existing customer a row duplicates, and which field matched
+    fn find(&self, row: &serde_json::Value) -> Option<(String, &'static str)> {
+        let field = |k: &str| row.get(k).and_then(|v| v.as_str());
+        field("external_id")
+            .and_then(|ext| self.by_external_id.get(ext))
+            .map(|id| (id.clone(), "external_id"))
+            .or_else(|| {
+                field("email")
+                    .and_then(|email| self.by_email.get(&email.to_lowercase()))
+                    .map(|id| (id.clone(), "email"))
+            })
+    }
+}
+
+async fn import(client: &ApiClient, file: &str, on_duplicate: OnDuplicate, opts: &BatchArgs) -> Result<()> {
+    let rows = batch::read_rows(file)?;
+
+    let sp = spinner::create_spinner("Fetching existing customers...");
+    let existing: Vec<Customer> = client.get_all("/v1/customers").await?;
+    sp.finish_and_clear();
+
+    let mut index = CustomerIndex::default();
+    for c in &existing {
+        index.insert(&c.id, c.external_id.as_deref(), c.email.as_deref());
+    }
+    output::info(&format!("{} existing customers, {} rows to import", existing.len(), rows.len()));
+
+    let index = RefCell::new(index);
+    let counts: RefCell<BTreeMap<&'static str, usize>> = RefCell::new(BTreeMap::new());
+    let result = batch::run(rows, Some(file), 1, opts, "Customer", |row| {
+        let (index, counts) = (&index, &counts);
+        async move {
+            let duplicate = index.borrow().find(&row);
+            let (category, message) = match (duplicate, on_duplicate) {
+                (None, _) => {
+                    let customer: Customer = client.post("/v1/customers", &row).await?;
+                    index.borrow_mut().insert(&customer.id, customer.external_id.as_deref(), customer.email.as_deref());
+                    ("created", format!("created {}", customer.id))
+                }
+                (Some((id, field)), OnDuplicate::Skip) => {
+                    ("skipped", format!("skipped, duplicate of {} by {}", id, field))
+                }
+                (Some((id, _)), OnDuplicate::Update) => {
+                    let customer: Customer = client.put(&format!("/v1/customers/{}", id), &row).await?;
+                    ("updated", format!("updated {}", customer.id))
+                }
+                (Some((id, field)), OnDuplicate::Fail) => {
+                    *counts.borrow_mut().entry("duplicate").or_default() += 1;
+                    anyhow::bail!("duplicate of existing customer {} by {}", id, field);
+                }
+            };
+            *counts.borrow_mut().entry(category).or_default() += 1;
+            Ok(message)
+        }
+    }).await;
+
+    let summary: Vec<String> = counts.into_inner().iter().map(|(k, v)| format!("{} {}", v, k)).collect();
+    if !summary.is_empty() {
+        output::info(&format!("Import: {}", summary.join(", ")));
+    }
+    result
+}
+
+/// A change `sync` makes to bring one customer in line with the source
+enum SyncChange {
+    Create(serde_json::Value),
+    Update { id: String, body: serde_json::Value },
+    Delete { id: String },
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct SyncRow {
+    #[tabled(rename = "Action")]
+    action: String,
+    #[tabled(rename = "Key")]
+    key: String,
+    #[tabled(rename = "Customer")]
+    customer: String,
+    #[tabled(rename = "Changes")]
+    changes: String,
+    #[tabled(rename = "Result")]
+    result: String,
+}
+
+impl SyncRow {
+    fn new(action: &str, key: &str, customer: &str, changes: String) -> Self {
+        Self {
+            action: action.to_string(),
+            key: key.to_string(),
+            customer: customer.to_string(),
+            changes,
+            result: String::new(),
         }
     }
+}
+
+/// How many rows create, update, and delete a customer
+fn action_counts<'a>(rows: impl IntoIterator<Item = &'a SyncRow>) -> (usize, usize, usize) {
+    rows.into_iter().fold((0, 0, 0), |(c, u, d), row| match row.action.as_str() {
+        "create" => (c + 1, u, d),
+        "update" => (c, u + 1, d),
+        _ => (c, u, d + 1),
+    })
+}
+
+/// A customer field as text, lowercased for emails so they match case-insensitively
+fn key_of(value: &serde_json::Value, key: &str) -> Option<String> {
+    let v = match value.get(key)? {
+        serde_json::Value::String(s) if s.is_empty() => return None,
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => return None,
+        other => other.to_string(),
+    };
+    Some(if key == "email" { v.to_lowercase() } else { v })
+}
+
+/// Fields of `row` that differ from `existing`, as an update body and a
+/// readable `field: old → new` list. Metadata is compared key by key, so keys
+/// only present in FlexPrice are left alone.
+fn diff(row: &serde_json::Value, existing: &serde_json::Value) -> (serde_json::Map<String, serde_json::Value>, Vec<String>) {
+    let show = |v: Option<&serde_json::Value>| match v {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Null) | None => "∅".to_string(),
+        Some(other) => other.to_string(),
+    };
+    let mut body = serde_json::Map::new();
+    let mut changes = vec![];
+    for (field, new) in row.as_object().into_iter().flatten() {
+        if field == "metadata" {
+            let current = existing.get("metadata");
+            let changed: Vec<&String> = new
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(k, v)| current.and_then(|m| m.get(k.as_str())) != Some(*v))
+                .map(|(k, _)| k)
+                .collect();
+            if !changed.is_empty() {
+                let mut merged = current.and_then(|m| m.as_object()).cloned().unwrap_or_default();
+                for k in changed {
+                    changes.push(format!("metadata.{}: {} → {}", k, show(merged.get(k)), show(new.get(k))));
+                    merged.insert(k.clone(), new[k].clone());
+                }
+                body.insert(field.clone(), merged.into());
+            }
+        } else if existing.get(field) != Some(new) {
+            changes.push(format!("{}: {} → {}", field, show(existing.get(field)), show(Some(new))));
+            body.insert(field.clone(), new.clone());
+        }
+    }
+    (body, changes)
+}
+
+/// Work out the creates, updates, and deletes that make FlexPrice match
+/// `source`, then apply them unless `dry_run`
+/// Make the customers match `source`. With `max_deletes`, customers missing
+/// from it are deleted too, unless there are more of them than that.
+async fn sync(client: &ApiClient, source: &str, key: &str, max_deletes: Option<usize>, dry_run: bool) -> Result<()> {
+    let rows = batch::read_rows(source)?;
+    if max_deletes.is_some() && rows.is_empty() {
+        anyhow::bail!("{} has no rows; refusing to delete every customer with --delete-missing", source);
+    }
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        let k = key_of(row, key).with_context(|| format!("Row {} of {} has no {}", i + 1, source, key))?;
+        if let Some(first) = seen.insert(k.clone(), i) {
+            anyhow::bail!("Rows {} and {} of {} share {} '{}'", first + 1, i + 1, source, key, k);
+        }
+    }
+
+    let sp = spinner::create_spinner("Fetching existing customers...");
+    let existing: Vec<serde_json::Value> = client.get_all("/v1/customers").await?;
+    sp.finish_and_clear();
+    let by_key: BTreeMap<String, &serde_json::Value> = existing
+        .iter()
+        .filter_map(|c| Some((key_of(c, key)?, c)))
+        .collect();
+
+    let mut plan: Vec<(SyncChange, SyncRow)> = vec![];
+    for row in rows {
+        let k = key_of(&row, key).unwrap_or_default();
+        match by_key.get(&k) {
+            None => {
+                let fields = row.as_object().map(|o| o.keys().cloned().collect::<Vec<_>>().join(", ")).unwrap_or_default();
+                plan.push((SyncChange::Create(row), SyncRow::new("create", &k, "", fields)));
+            }
+            Some(current) => {
+                let (body, changes) = diff(&row, current);
+                if body.is_empty() {
+                    continue;
+                }
+                let id = current.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                let row = SyncRow::new("update", &k, &id, changes.join("; "));
+                plan.push((SyncChange::Update { id, body: body.into() }, row));
+            }
+        }
+    }
+    if max_deletes.is_some() {
+        for (k, current) in &by_key {
+            if seen.contains_key(k) {
+                continue;
+            }
+            let id = current.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            let row = SyncRow::new("delete", k, &id, String::new());
+            plan.push((SyncChange::Delete { id }, row));
+        }
+    }
+
+    if plan.is_empty() {
+        output::success(&format!("{} customers already match {}", seen.len(), source));
+        return Ok(());
+    }
+    if dry_run {
+        let rows: Vec<SyncRow> = plan.into_iter().map(|(_, row)| row).collect();
+        println!("{}", output::print_table(&rows));
+        if output::human() {
+            println!();
+            let (create, update, delete) = action_counts(&rows);
+            output::info(&format!(
+                "Dry run: {} to create, {} to update, {} to delete. Nothing was changed.",
+                create, update, delete
+            ));
+            if let Some(max) = max_deletes.filter(|max| delete > *max) {
+                output::warning(&format!("{} deletes is more than --max-deletes {}; without --dry-run, sync would refuse.", delete, max));
+            }
+        }
+        return Ok(());
+    }
+    let deletes = plan.iter().filter(|(change, _)| matches!(change, SyncChange::Delete { .. })).count();
+    if let Some(max) = max_deletes.filter(|max| deletes > *max) {
+        anyhow::bail!(
+            "Refusing to delete {} customers missing from {}, more than --max-deletes {}; check the source, or raise --max-deletes",
+            deletes,
+            source,
+            max
+        );
+    }
+
+    let bar = spinner::create_progress(plan.len() as u64, "Syncing", "sync");
+    let mut results: Vec<(usize, SyncRow, bool)> = stream::iter(plan.into_iter().enumerate())
+        .map(|(i, (change, mut row))| {
+            let bar = bar.clone();
+            async move {
+                let result = match change {
+                    SyncChange::Create(body) => client.post::<_, Customer>("/v1/customers", &body).await.map(|c| row.customer = c.id),
+                    SyncChange::Update { id, body } => client.put::<_, Customer>(&format!("/v1/customers/{}", id), &body).await.map(|_| ()),
+                    SyncChange::Delete { id } => client.delete_empty(&format!("/v1/customers/{}", id)).await,
+                };
+                bar.inc(1);
+                let ok = result.is_ok();
+                row.result = match result {
+                    Ok(()) => "ok".green().to_string(),
+                    Err(e) => format!("{}: {:#}", "failed".red(), e),
+                };
+                (i, row, ok)
+            }
+        })
+        .buffer_unordered(8)
+        .collect()
+        .await;
+    bar.finish_and_clear();
+    results.sort_by_key(|(i, _, _)| *i);
+
+    let failed = results.iter().filter(|(_, _, ok)| !ok).count();
+    let (created, updated, deleted) = action_counts(results.iter().filter(|(_, _, ok)| *ok).map(|(_, row, _)| row));
+    let rows: Vec<SyncRow> = results.into_iter().map(|(_, row, _)| row).collect();
+    println!("{}", output::print_table(&rows));
+    if output::human() {
+        println!();
+        output::info(&format!(
+            "Sync: {} created, {} updated, {} deleted, {} failed",
+            created, updated, deleted, failed
+        ));
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} changes failed", failed, rows.len());
+    }
     Ok(())
 }
@@ -0,0 +1,224 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use serde_json::{Map, Value};
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::cli::apply::{satisfies, show};
+use crate::cli::auth::require_profile_auth;
+use crate::cli::export::Resource;
+use crate::cli::import::{create_body, fetch_target, is_active, key, keys_by_id, singular, REFERENCES};
+use crate::utils::{output, spinner};
+
+/// Compare the catalogs of two profiles' environments
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Profile whose environment is the reference, e.g. prod
+    #[arg(long, value_name = "PROFILE")]
+    from: String,
+    /// Profile whose environment is checked against it, e.g. staging
+    #[arg(long, value_name = "PROFILE")]
+    to: String,
+    /// Compare only these resources (comma-separated; default: meters,
+    /// features, plans, prices, entitlements)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    resources: Vec<Resource>,
+    /// Field names to leave out of the comparison (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    ignore_fields: Vec<String>,
+}
+
+/// The pricing catalog, compared when `--resources` isn't given
+const CATALOG: &[Resource] = &[
+    Resource::Meters,
+    Resource::Features,
+    Resource::Plans,
+    Resource::Prices,
+    Resource::Entitlements,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    /// In `--from` but not in `--to`
+    Missing,
+    /// In `--to` but not in `--from`
+    Extra,
+    /// In both under the same key, with different values
+    Differs,
+}
+
+/// One resource that differs between the environments
+#[derive(serde::Serialize)]
+struct Drift {
+    resource: &'static str,
+    key: String,
+    status: Status,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<FieldDrift>,
+    #[serde(skip)]
+    kind: Resource,
+}
+
+#[derive(serde::Serialize)]
+struct FieldDrift {
+    field: String,
+    from: Value,
+    to: Value,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct DriftRow {
+    #[tabled(rename = "")]
+    status: String,
+    #[tabled(rename = "Resource")]
+    resource: String,
+    #[tabled(rename = "Key")]
+    key: String,
+    #[tabled(rename = "Detail")]
+    detail: String,
+}
+
+pub async fn handle(args: DiffArgs) -> Result<()> {
+    if args.from == args.to {
+        anyhow::bail!("--from and --to are both profile '{}'; pass two different profiles", args.from);
+    }
+    let from_client = ApiClient::new(require_profile_auth(&args.from)?)?;
+    let to_client = ApiClient::new(require_profile_auth(&args.to)?)?;
+    let requested = if args.resources.is_empty() { CATALOG } else { &args.resources };
+    let mut kinds: Vec<Resource> = vec![];
+    for &kind in requested {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+
+    let sp = spinner::create_spinner(&format!("Reading profiles '{}' and '{}'...", args.from, args.to));
+    let fetched = tokio::try_join!(fetch_target(&from_client, &kinds), fetch_target(&to_client, &kinds));
+    sp.finish_and_clear();
+    let (from, to) = fetched?;
+
+    let ignore: BTreeSet<&str> = args.ignore_fields.iter().map(|f| f.trim()).collect();
+    let (drift, matching) = compare(&kinds, &from, &to, &ignore);
+
+    if !output::human() {
+        println!("{}", output::print_detail(&drift));
+    } else {
+        if !drift.is_empty() {
+            let rows: Vec<DriftRow> = drift.iter().map(|d| drift_row(d, &args)).collect();
+            println!("{}", output::print_table(&rows));
+            println!();
+        }
+        let count = |s: Status| drift.iter().filter(|d| d.status == s).count();
+        output::info(&format!(
+            "'{}' → '{}': {} missing, {} extra, {} differing, {} matching.",
+            args.from,
+            args.to,
+            count(Status::Missing),
+            count(Status::Extra),
+            count(Status::Differs),
+            matching
+        ));
+    }
+
+    if drift.is_empty() {
+        if output::human() {
+            output::success(&format!("'{}' matches '{}'.", args.to, args.from));
+        }
+        return Ok(());
+    }
+    anyhow::bail!("{} resource(s) drifted between '{}' and '{}'", drift.len(), args.from, args.to)
+}
+
+/// Match each side's active resources by key. Returns the drift and how many
+/// resources matched exactly.
+fn compare(
+    kinds: &[Resource],
+    from: &HashMap<Resource, Vec<Value>>,
+    to: &HashMap<Resource, Vec<Value>>,
+    ignore: &BTreeSet<&str>,
+) -> (Vec<Drift>, usize) {
+    let from_keys = keys_by_id(from);
+    let to_keys = keys_by_id(to);
+    let mut drift = vec![];
+    let mut matching = 0;
+
+    for &kind in kinds {
+        let keyed = |items: &HashMap<Resource, Vec<Value>>, keys: &HashMap<String, String>| -> Vec<(String, Map<String, Value>)> {
+            let mut keyed: Vec<_> = items
+                .get(&kind)
+                .into_iter()
+                .flatten()
+                .filter(|item| is_active(item))
+                .filter_map(|item| Some((key(kind, item, keys)?, comparable(item, keys))))
+                .collect();
+            keyed.sort_by(|a, b| a.0.cmp(&b.0));
+            keyed
+        };
+        let from_items = keyed(from, &from_keys);
+        let to_items: HashMap<String, Map<String, Value>> = keyed(to, &to_keys).into_iter().collect();
+        let drifted = |key: &String, status, fields| Drift { resource: kind.name(), key: key.clone(), status, fields, kind };
+
+        for (key, body) in &from_items {
+            let Some(other) = to_items.get(key) else {
+                drift.push(drifted(key, Status::Missing, vec![]));
+                continue;
+            };
+            let fields: BTreeSet<&String> = body.keys().chain(other.keys()).collect();
+            let fields: Vec<FieldDrift> = fields
+                .into_iter()
+                .filter(|f| !ignore.contains(f.as_str()) && !same(body.get(*f), other.get(*f)))
+                .map(|f| FieldDrift {
+                    field: f.clone(),
+                    from: body.get(f).cloned().unwrap_or(Value::Null),
+                    to: other.get(f).cloned().unwrap_or(Value::Null),
+                })
+                .collect();
+            match fields.is_empty() {
+                true => matching += 1,
+                false => drift.push(drifted(key, Status::Differs, fields)),
+            }
+        }
+        let mut extra: Vec<&String> = to_items.keys().filter(|k| !from_items.iter().any(|(key, _)| key == *k)).collect();
+        extra.sort();
+        drift.extend(extra.into_iter().map(|key| drifted(key, Status::Extra, vec![])));
+    }
+    (drift, matching)
+}
+
+/// A resource's fields without server-assigned ones, with references to
+/// other resources replaced by their keys so they compare across environments
+fn comparable(item: &Value, keys: &HashMap<String, String>) -> Map<String, Value> {
+    let mut body = create_body(item);
+    for field in REFERENCES {
+        if let Some(key) = body.get(*field).and_then(|v| v.as_str()).and_then(|id| keys.get(id)) {
+            body.insert(field.to_string(), key.clone().into());
+        }
+    }
+    body
+}
+
+/// Whether two values are equal, treating a missing field as null and
+/// numbers as equal to their string forms
+fn same(a: Option<&Value>, b: Option<&Value>) -> bool {
+    match (a.filter(|v| !v.is_null()), b.filter(|v| !v.is_null())) {
+        (None, None) => true,
+        (Some(a), Some(b)) => satisfies(Some(a), b) && satisfies(Some(b), a),
+        _ => false,
+    }
+}
+
+fn drift_row(drift: &Drift, args: &DiffArgs) -> DriftRow {
+    let (status, detail) = match drift.status {
+        Status::Missing => ("-".red().bold().to_string(), format!("not in '{}'", args.to)),
+        Status::Extra => ("+".green().bold().to_string(), format!("only in '{}'", args.to)),
+        Status::Differs => {
+            let fields = drift.fields.iter().map(|f| format!("{}: {} → {}", f.field, show(Some(&f.from)), show(Some(&f.to))));
+            ("~".yellow().bold().to_string(), fields.collect::<Vec<_>>().join("\n"))
+        }
+    };
+    DriftRow { status, resource: singular(drift.kind).to_string(), key: drift.key.clone(), detail }
+}
@@ -0,0 +1,413 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use colored::Colorize;
+use futures::future::join_all;
+use tabled::Tabled;
+
+use crate::api::capabilities::{self, parse_version, Known, Requirement, MIN_SERVER_VERSION, REQUIREMENTS};
+use crate::api::client::{ApiClient, ServerInfo};
+use crate::api::error::ApiClientError;
+use crate::cli::auth;
+use crate::config::{ConfigFile, Credentials};
+use crate::utils::{output, spinner};
+
+/// Clock skew past which a check warns, and past which it fails
+const SKEW_WARN_SECS: i64 = 30;
+const SKEW_FAIL_SECS: i64 = 300;
+
+/// Read endpoints probed for the permissions check, by the resource they list
+const READ_PROBES: &[(&str, &str)] = &[
+    ("customers", "/v1/customers?limit=1"),
+    ("plans", "/v1/plans?limit=1"),
+    ("prices", "/v1/prices?limit=1"),
+    ("meters", "/v1/meters?limit=1"),
+    ("features", "/v1/features?limit=1"),
+    ("subscriptions", "/v1/subscriptions?limit=1"),
+    ("invoices", "/v1/invoices?limit=1"),
+    ("wallets", "/v1/wallets?limit=1"),
+    ("events", "/v1/events?page_size=1"),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl Status {
+    fn name(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+            Status::Skip => "skip",
+        }
+    }
+
+    fn mark(self) -> String {
+        match self {
+            Status::Pass => "✓".green().bold().to_string(),
+            Status::Warn => "⚠".yellow().bold().to_string(),
+            Status::Fail => "✗".red().bold().to_string(),
+            Status::Skip => "-".dimmed().to_string(),
+        }
+    }
+}
+
+/// One check's outcome, and what to do about it when it didn't pass
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Pass, detail: detail.into(), fix: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Skip, detail: detail.into(), fix: None }
+    }
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct CheckRow {
+    #[tabled(rename = "Check")]
+    check: String,
+    #[tabled(rename = "Result")]
+    status: String,
+    #[tabled(rename = "Detail")]
+    detail: String,
+    #[tabled(rename = "Fix")]
+    fix: String,
+}
+
+impl From<&Check> for CheckRow {
+    fn from(c: &Check) -> Self {
+        Self {
+            check: c.name.to_string(),
+            status: c.status.name().to_string(),
+            detail: c.detail.clone(),
+            fix: c.fix.clone().unwrap_or_default(),
+        }
+    }
+}
+
+pub async fn handle() -> Result<()> {
+    let sp = spinner::create_spinner("Running checks...");
+    let checks = run_checks().await;
+    sp.finish_and_clear();
+
+    if output::human() {
+        let width = checks.iter().map(|c| c.name.len()).max().unwrap_or_default();
+        for check in &checks {
+            println!("  {} {:<width$}  {}", check.status.mark(), check.name, check.detail, width = width);
+            if let Some(fix) = &check.fix {
+                println!("    {} {}", "→".dimmed(), fix);
+            }
+        }
+    } else {
+        let rows: Vec<CheckRow> = checks.iter().map(Into::into).collect();
+        println!("{}", output::print_table(&rows));
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} checks failed", failed, checks.len());
+    }
+    if output::human() {
+        println!();
+        output::success("Everything needed to use the CLI checks out.");
+    }
+    Ok(())
+}
+
+/// Run the checks in order, skipping those that depend on one that failed
+async fn run_checks() -> Vec<Check> {
+    let mut checks = vec![];
+
+    // `--env NAME` is looked up here rather than before the command runs, so
+    // a name that doesn't resolve is reported like any other failed check
+    let resolved = auth::resolve_environment().await;
+    let creds = match Credentials::load() {
+        // Keep an unresolved name out of the requests below
+        Ok(creds) if resolved.is_err() => Credentials { environment_id: None, ..creds },
+        Ok(creds) => creds,
+        Err(e) => {
+            checks.push(Check::fail("Configuration", format!("{:#}", e), "Fix or remove ~/.flexprice/config.toml, or check it with `flexprice config`"));
+            return checks;
+        }
+    };
+    let credentials = credentials_check(&creds);
+    let authenticated = credentials.status == Status::Pass;
+    checks.push(credentials);
+
+    let client = match ApiClient::new(creds.clone()) {
+        Ok(client) => client.without_cache().without_login_prompt(),
+        Err(e) => {
+            checks.push(Check::fail("Connection", format!("{:#}", e), "Fix the client certificate or TLS options named above"));
+            return checks;
+        }
+    };
+
+    let started = Instant::now();
+    let server = client.server_info().await;
+    let latency = started.elapsed();
+    let server = match server {
+        Ok(server) => {
+            checks.push(Check::pass("API reachable", format!("{} ({}ms)", creds.api_url, latency.as_millis())));
+            server
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                "API reachable",
+                format!("{}: {:#}", creds.api_url, e),
+                "Check the API URL (`flexprice config`), your network and --proxy, and --ca-cert for a private certificate authority",
+            ));
+            for name in ["Clock", "Server version", "Command support", "Authentication", "Environment", "Permissions"] {
+                checks.push(Check::skip(name, "API unreachable"));
+            }
+            return checks;
+        }
+    };
+    checks.push(clock_check(&server, latency));
+    checks.push(version_check(&server));
+    checks.push(command_support_check(&capabilities::remember(client.base_url(), &server)));
+
+    if !authenticated {
+        for name in ["Authentication", "Environment", "Permissions"] {
+            checks.push(Check::skip(name, "no usable credentials"));
+        }
+        return checks;
+    }
+    let authentication = authentication_check(&client, &creds).await;
+    let rejected = authentication.status == Status::Fail;
+    checks.push(authentication);
+    if rejected {
+        for name in ["Environment", "Permissions"] {
+            checks.push(Check::skip(name, "credentials rejected"));
+        }
+        return checks;
+    }
+    checks.push(environment_check(&creds, resolved).await);
+    checks.push(permissions_check(&client).await);
+    checks
+}
+
+fn credentials_check(creds: &Credentials) -> Check {
+    let profile = ConfigFile::load().map(|c| c.selected_profile()).unwrap_or_default();
+    if !creds.is_authenticated() {
+        return Check::fail(
+            "Credentials",
+            format!("none for profile '{}'", profile),
+            "Run `flexprice auth login`, or `flexprice auth set-api-key` for an API key",
+        );
+    }
+    if creds.api_key.is_some() {
+        return Check::pass("Credentials", format!("API key {} (profile '{}')", creds.masked_api_key(), profile));
+    }
+    match creds.session_expires_at() {
+        Some(at) if creds.session_expired() => Check::fail(
+            "Credentials",
+            format!("login session expired {}", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")),
+            "Run `flexprice auth login` to sign in again",
+        ),
+        Some(at) => Check::pass(
+            "Credentials",
+            format!("login session until {} (profile '{}')", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"), profile),
+        ),
+        None => Check::pass("Credentials", format!("login session (profile '{}')", profile)),
+    }
+}
+
+/// The server's `Date` has whole seconds and left it at some point during the
+/// request, so skews within the round trip plus a second aren't counted
+fn clock_check(server: &ServerInfo, latency: std::time::Duration) -> Check {
+    let Some(server_time) = server.date else {
+        return Check::skip("Clock", "the server didn't send its time");
+    };
+    let skew = chrono::Utc::now() - server_time;
+    let slack = latency.as_secs() as i64 + 1;
+    let seconds = skew.num_seconds();
+    let off = seconds.abs().saturating_sub(slack);
+    if off <= SKEW_WARN_SECS {
+        return Check::pass("Clock", "in sync with the server");
+    }
+    let detail = format!("{} {} the server", describe_skew(seconds.abs()), if seconds > 0 { "ahead of" } else { "behind" });
+    let fix = "Sync the system clock, e.g. turn on automatic time (NTP); login sessions and event timestamps rely on it";
+    match off > SKEW_FAIL_SECS {
+        true => Check::fail("Clock", detail, fix),
+        false => Check::warn("Clock", detail, fix),
+    }
+}
+
+/// `7m 12s`, `45s`, `2h 3m`
+fn describe_skew(seconds: i64) -> String {
+    match seconds {
+        s if s >= 3600 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s if s >= 60 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+fn version_check(server: &ServerInfo) -> Check {
+    let cli = env!("CARGO_PKG_VERSION");
+    let Some(version) = server.version.as_deref() else {
+        return Check::skip("Server version", format!("not reported by the server (CLI {})", cli));
+    };
+    let (min_major, min_minor) = MIN_SERVER_VERSION;
+    match parse_version(version) {
+        Some(found) if found < MIN_SERVER_VERSION => Check::fail(
+            "Server version",
+            format!("{} is older than {}.{}, the oldest CLI {} supports", version, min_major, min_minor, cli),
+            format!("Upgrade the FlexPrice server to {}.{} or later, or use an older CLI release", min_major, min_minor),
+        ),
+        Some(_) => Check::pass("Server version", format!("{} (CLI {})", version, cli)),
+        None => Check::warn(
+            "Server version",
+            format!("can't tell whether {} is supported (CLI {})", version, cli),
+            format!("Make sure the server runs FlexPrice {}.{} or later", min_major, min_minor),
+        ),
+    }
+}
+
+/// Commands this server lacks the endpoints for, which the CLI refuses to run
+fn command_support_check(known: &Known) -> Check {
+    if known.capabilities.is_none() && known.version.is_none() {
+        return Check::skip("Command support", "the server reports neither its version nor its capabilities");
+    }
+    let missing: Vec<&Requirement> = REQUIREMENTS.iter().filter(|r| !known.supports(r)).collect();
+    if missing.is_empty() {
+        return Check::pass("Command support", "every command is available");
+    }
+    let commands: Vec<String> = missing.iter().map(|r| format!("`{}`", r.command.join(" "))).collect();
+    // A server that lists capabilities may be new enough and just not offer them
+    let fix = if known.capabilities.is_some() {
+        let mut capabilities: Vec<&str> = missing.iter().map(|r| r.capability).collect();
+        capabilities.sort_unstable();
+        capabilities.dedup();
+        format!("Turn on these server capabilities, or upgrade the server, to use them: {}", capabilities.join(", "))
+    } else {
+        let (major, minor) = missing.iter().map(|r| r.since).max().unwrap_or(MIN_SERVER_VERSION);
+        format!("Upgrade the FlexPrice server to {}.{} or later to use them", major, minor)
+    };
+    Check::warn("Command support", format!("not available on this server: {}", commands.join(", ")), fix)
+}
+
+async fn authentication_check(client: &ApiClient, creds: &Credentials) -> Check {
+    match client.get::<serde_json::Value>("/v1/users/me").await {
+        Ok(user) => {
+            let who = ["email", "id"].iter().find_map(|k| user.get(*k).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).map(str::to_string));
+            let tenant = user.get("tenant_id").and_then(|v| v.as_str()).or(creds.tenant_id.as_deref()).map(|t| format!(" in tenant {}", t));
+            Check::pass("Authentication", format!("accepted as {}{}", who.unwrap_or_else(|| "this user".to_string()), tenant.unwrap_or_default()))
+        }
+        Err(e) => match api_error(&e) {
+            Some(ApiClientError::Auth(_)) if creds.api_key.is_some() => Check::fail(
+                "Authentication",
+                format!("API key rejected: {}", e),
+                "Create a new API key in the FlexPrice dashboard and run `flexprice auth set-api-key`",
+            ),
+            Some(ApiClientError::Auth(_)) => {
+                Check::fail("Authentication", format!("login session rejected: {}", e), "Run `flexprice auth login` to sign in again")
+            }
+            _ => Check::warn(
+                "Authentication",
+                format!("couldn't confirm the credentials: {}", e),
+                "Try `flexprice auth whoami`; if it fails the same way, share `flexprice support bundle` with support",
+            ),
+        },
+    }
+}
+
+/// Whether the environment sent in `x-environment-id` belongs to the tenant
+async fn environment_check(creds: &Credentials, resolved: Result<()>) -> Check {
+    let fix = "Pick an environment with --env NAME, or correct FLEXPRICE_ENVIRONMENT_ID or environment_id in .flexprice.toml";
+    if let Err(e) = resolved {
+        return Check::fail("Environment", format!("{:#}", e), fix);
+    }
+    let Some(wanted) = creds.environment_id.clone() else {
+        return Check::pass("Environment", "none selected; requests go to the credentials' default environment");
+    };
+    match auth::environments(creds.clone()).await {
+        Ok(environments) => match environments.iter().find(|e| e.id == wanted) {
+            Some(env) => {
+                let kind = env.environment_type.as_deref().map(|t| format!(", {}", t)).unwrap_or_default();
+                Check::pass("Environment", format!("{} ({}{})", env.id, env.name.as_deref().unwrap_or("unnamed"), kind))
+            }
+            None => {
+                let names: Vec<String> = environments.iter().map(|e| e.name.clone().unwrap_or_else(|| e.id.clone())).collect();
+                Check::fail(
+                    "Environment",
+                    format!("{} isn't one of this tenant's environments ({})", wanted, names.join(", ")),
+                    fix,
+                )
+            }
+        },
+        Err(e) => Check::warn(
+            "Environment",
+            format!("{} couldn't be looked up: {}", wanted, e),
+            "The permissions check below shows whether the server accepts it",
+        ),
+    }
+}
+
+/// Whether the credentials can read each core resource. Write access isn't
+/// probed, since that would change billing data.
+async fn permissions_check(client: &ApiClient) -> Check {
+    let probes = READ_PROBES.iter().map(|(resource, path)| async move {
+        (*resource, client.get::<serde_json::Value>(path).await)
+    });
+    let results = join_all(probes).await;
+    let mut denied = vec![];
+    let mut rejected = vec![];
+    let mut failed = vec![];
+    for (resource, result) in &results {
+        let Err(e) = result else { continue };
+        match api_error(e) {
+            Some(ApiClientError::Auth(_)) => denied.push(*resource),
+            Some(ApiClientError::Validation(_)) => rejected.push((*resource, e.to_string())),
+            _ => failed.push((*resource, e.to_string())),
+        }
+    }
+    if !rejected.is_empty() {
+        let (resource, error) = &rejected[0];
+        return Check::fail(
+            "Permissions",
+            format!("the server rejected requests for {}: {}", resource, error),
+            "Check the selected environment; the server may not accept it for these credentials",
+        );
+    }
+    if !denied.is_empty() {
+        return Check::fail(
+            "Permissions",
+            format!("no read access to {}", denied.join(", ")),
+            "Use an API key with read access to these resources, or ask a tenant admin to grant it",
+        );
+    }
+    if !failed.is_empty() {
+        let (resource, error) = &failed[0];
+        return Check::warn(
+            "Permissions",
+            format!("{} of {} resources couldn't be checked ({}: {})", failed.len(), results.len(), resource, error),
+            "Retry in a moment; if it persists, share `flexprice support bundle` with support",
+        );
+    }
+    Check::pass("Permissions", format!("can read all {} core resources (write access isn't checked)", results.len()))
+}
+
+fn api_error(e: &anyhow::Error) -> Option<&ApiClientError> {
+    e.chain().find_map(|cause| cause.downcast_ref::<ApiClientError>())
+}
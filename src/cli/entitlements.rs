@@ -1,34 +1,133 @@
-use anyhow::Result;
+use std::io::IsTerminal;
+
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use dialoguer::Confirm;
 use tabled::Tabled;
 
 use crate::api::client::ApiClient;
-use crate::api::models::{Entitlement, ListResponse};
-use crate::cli::auth::require_auth;
-use crate::utils::{output, spinner};
+use crate::api::query::QueryBuilder;
+use crate::api::models::{CustomerUsageSummary, Entitlement, FeatureUsage, ListResponse};
+use crate::config::globals;
+use crate::cli::completions;
+use crate::cli::customers;
+use crate::cli::features;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::fanout::{self, DeleteManyArgs, IdsArgs};
+use crate::utils::{chart, output, spinner};
 
 #[derive(Subcommand)]
 pub enum EntitlementCommands {
-    /// List all entitlements
+    /// List entitlements, a page at a time
     List {
-        #[arg(long)]
-        json: bool,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get an entitlement by ID
+    /// Get an entitlement by ID, or many with --ids-file
     Get {
+        #[arg(add = completions::ids("/v1/entitlements"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Create entitlements from a JSON file (object or array of objects)
+    Create {
+        #[arg(long, required_unless_present = "edit")]
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Update an entitlement from flags, a JSON file, or in $EDITOR
+    Update {
+        #[arg(add = completions::ids("/v1/entitlements"))]
         id: String,
+        #[arg(long, required_unless_present_any = ["edit", "usage_limit", "enabled"])]
+        json: Option<String>,
+        /// Edit the entitlement's current state in $EDITOR
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        /// New usage limit for metered features
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        usage_limit: Option<f64>,
+        /// Enable or disable the entitlement
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        enabled: Option<bool>,
+    },
+    /// Delete an entitlement by ID, or many from --ids-file or --filter
+    Delete {
+        #[arg(
+            add = completions::ids("/v1/entitlements"),
+            required_unless_present_any = ["ids_file", "filters"],
+            conflicts_with_all = ["ids_file", "filters"]
+        )]
+        id: Option<String>,
+        #[command(flatten)]
+        many: DeleteManyArgs,
+    },
+    /// Apply the same change to a feature's entitlement on many plans
+    ///
+    /// With --dry-run, shows the changes without applying them.
+    BulkUpdate {
+        /// Feature lookup key
         #[arg(long)]
-        json: bool,
+        feature: String,
+        /// Field to change, e.g. usage_limit=5000 (repeatable)
+        #[arg(long = "set", value_name = "FIELD=VALUE", required = true)]
+        set: Vec<String>,
+        /// Comma-separated plan IDs
+        #[arg(long, value_delimiter = ',', required_unless_present = "all_plans")]
+        plans: Vec<String>,
+        /// Every plan the feature is attached to
+        #[arg(long, conflicts_with = "plans")]
+        all_plans: bool,
+        /// Apply without asking for confirmation
+        #[arg(long, short)]
+        yes: bool,
     },
-    /// Create a new entitlement from a JSON file
-    Create {
+    /// Enable a feature on a plan, creating the entitlement if needed
+    Enable {
+        /// Plan ID
+        #[arg(add = completions::ids("/v1/plans"))]
+        plan_id: String,
+        /// Feature lookup key
+        feature: String,
+    },
+    /// Disable a feature on a plan
+    Disable {
+        /// Plan ID
+        #[arg(add = completions::ids("/v1/plans"))]
+        plan_id: String,
+        /// Feature lookup key
+        feature: String,
+    },
+    /// Check whether a customer is entitled to a feature
+    ///
+    /// Prints the customer's usage, limit, and what remains, and exits 0 when
+    /// the customer is entitled and 1 when not. A metered feature past a hard
+    /// limit counts as not entitled; past a soft limit it still counts.
+    Check {
+        /// Customer ID, ext:<external_id>, or email:<address>
+        #[arg(long, add = completions::ids("/v1/customers"))]
+        customer: String,
+        /// Feature lookup key or ID
         #[arg(long)]
-        json: String,
+        feature: String,
     },
-    /// Delete an entitlement by ID
-    Delete { id: String },
 }
 
+/// Starting point for `create --edit`
+const CREATE_TEMPLATE: &str = r#"{
+  "plan_id": "",
+  "feature_id": "",
+  "feature_type": "boolean",
+  "is_enabled": true
+}"#;
+
 #[derive(Tabled, serde::Serialize)]
 struct EntitlementRow {
     #[tabled(rename = "ID")]
@@ -58,39 +157,351 @@ impl From<Entitlement> for EntitlementRow {
     }
 }
 
-pub async fn handle(cmd: EntitlementCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
-
+pub async fn handle(cmd: EntitlementCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        EntitlementCommands::List { json } => {
+        EntitlementCommands::List { page } => {
             let sp = spinner::create_spinner("Fetching entitlements...");
-            let resp: ListResponse<Entitlement> = client.get("/v1/entitlements").await?;
+            let (items, pagination) = page.fetch::<Entitlement>(&client, "/v1/entitlements").await?;
             sp.finish_and_clear();
-            let rows: Vec<EntitlementRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            let rows: Vec<EntitlementRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "entitlement"));
         }
-        EntitlementCommands::Get { id, json } => {
+        EntitlementCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/entitlements", &many).await };
             let sp = spinner::create_spinner("Fetching entitlement...");
             let ent: Entitlement = client.get(&format!("/v1/entitlements/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&ent, json));
+            println!("{}", output::print_detail(&ent));
         }
-        EntitlementCommands::Create { json: file } => {
-            let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Creating entitlement...");
-            let ent: Entitlement = client.post("/v1/entitlements", &body).await?;
-            sp.finish_and_clear();
-            output::success(&format!("Entitlement created: {}", ent.id));
-            println!("{}", output::print_detail(&ent, false));
+        EntitlementCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<Entitlement>(file.as_deref(), edit, CREATE_TEMPLATE, None)? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                let sp = spinner::create_spinner("Creating entitlement...");
+                let ent: Entitlement = client.post("/v1/entitlements", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Entitlement created: {}", ent.id));
+                println!("{}", output::print_detail(&ent));
+            }
+            Some(Payload::Many(items)) => {
+                batch::create_many(&client, items, file.as_deref(), &opts, "Entitlement created", "/v1/entitlements").await?;
+            }
+        },
+        EntitlementCommands::Update { id, json: file, usage_limit, enabled, .. } => {
+            let path = format!("/v1/entitlements/{}", id);
+            let fields = editor::field_updates(&[
+                ("usage_limit", usage_limit.map(Into::into)),
+                ("is_enabled", enabled.map(Into::into)),
+            ]);
+            let body = match fields {
+                Some(body) => Some(body),
+                None => editor::update_body::<Entitlement>(&client, &path, file.as_deref()).await?,
+            };
+            if let Some(body) = body {
+                let sp = spinner::create_spinner("Updating entitlement...");
+                let ent: Entitlement = client.put(&path, &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Entitlement updated: {}", ent.id));
+                println!("{}", output::print_detail(&ent));
+            }
         }
-        EntitlementCommands::Delete { id } => {
+        EntitlementCommands::Delete { id, many } => {
+            let Some(id) = id else { return fanout::delete_many(&client, "/v1/entitlements", "entitlements", &many).await };
             let sp = spinner::create_spinner("Deleting entitlement...");
             client.delete_empty(&format!("/v1/entitlements/{}", id)).await?;
             sp.finish_and_clear();
             output::success(&format!("Entitlement {} deleted.", id));
         }
+        EntitlementCommands::BulkUpdate { feature, set, plans, all_plans, yes } => {
+            let changes = set.iter().map(|s| parse_assignment(s)).collect::<Result<Vec<_>>>()?;
+            let plans = (!all_plans).then_some(plans);
+            bulk_update(&client, &feature, &changes, plans.as_deref(), globals::get().dry_run, yes).await?;
+        }
+        EntitlementCommands::Enable { plan_id, feature } => {
+            set_enabled(&client, &plan_id, &feature, true).await?;
+        }
+        EntitlementCommands::Disable { plan_id, feature } => {
+            set_enabled(&client, &plan_id, &feature, false).await?;
+        }
+        EntitlementCommands::Check { customer, feature } => {
+            check(&client, &customer, &feature).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Find the entitlement linking a plan to a feature, if any
+pub async fn find_for_plan(client: &ApiClient, plan_id: &str, feature_id: &str) -> Result<Option<Entitlement>> {
+    let resp: ListResponse<Entitlement> = client
+        .get_with_query("/v1/entitlements", &[("plan_ids", plan_id), ("feature_ids", feature_id)])
+        .await?;
+    Ok(resp.items.into_iter().find(|e| {
+        e.plan_id.as_deref() == Some(plan_id) && e.feature_id.as_deref() == Some(feature_id)
+    }))
+}
+
+/// Parse `field=value`; the value is read as JSON when it parses (numbers,
+/// booleans, `null`) and as a string otherwise
+fn parse_assignment(s: &str) -> Result<(String, serde_json::Value)> {
+    let (field, value) = s
+        .split_once('=')
+        .with_context(|| format!("Invalid --set '{}': expected FIELD=VALUE", s))?;
+    let value = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+    Ok((field.trim().to_string(), value))
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct BulkChangeRow {
+    #[tabled(rename = "Plan")]
+    plan_id: String,
+    #[tabled(rename = "Entitlement")]
+    entitlement_id: String,
+    #[tabled(rename = "Field")]
+    field: String,
+    #[tabled(rename = "Current")]
+    current: String,
+    #[tabled(rename = "New")]
+    new: String,
+}
+
+/// Update the entitlement for `lookup_key` on each plan in `plans` (or every
+/// plan it is attached to), previewing the changes and reporting per plan
+async fn bulk_update(
+    client: &ApiClient,
+    lookup_key: &str,
+    changes: &[(String, serde_json::Value)],
+    plans: Option<&[String]>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let sp = spinner::create_spinner(&format!("Finding entitlements for {}...", lookup_key));
+    let feature = features::find_by_lookup_key(client, lookup_key).await?;
+    let all: Vec<serde_json::Value> = client
+        .get_all(&QueryBuilder::new().param("feature_ids", &feature.id).to_path("/v1/entitlements"))
+        .await?;
+    sp.finish_and_clear();
+
+    let plan_of = |e: &serde_json::Value| e.get("plan_id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let targets: Vec<serde_json::Value> = match plans {
+        None => all,
+        Some(plans) => {
+            for plan in plans {
+                if !all.iter().any(|e| &plan_of(e) == plan) {
+                    output::warning(&format!("{} is not attached to plan {}; skipping.", lookup_key, plan));
+                }
+            }
+            all.into_iter().filter(|e| plans.contains(&plan_of(e))).collect()
+        }
+    };
+    if targets.is_empty() {
+        output::info(&format!("No entitlements for {} to update.", lookup_key));
+        return Ok(());
+    }
+
+    let show = |v: Option<&serde_json::Value>| match v {
+        None | Some(serde_json::Value::Null) => "-".to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+    };
+    let rows: Vec<BulkChangeRow> = targets
+        .iter()
+        .flat_map(|e| {
+            changes.iter().map(move |(field, value)| BulkChangeRow {
+                plan_id: plan_of(e),
+                entitlement_id: e.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                field: field.clone(),
+                current: show(e.get(field)),
+                new: show(Some(value)),
+            })
+        })
+        .collect();
+    println!("{}", output::print_table(&rows));
+    println!();
+
+    if dry_run {
+        output::info(&format!("Dry run: {} plan(s) would be updated.", targets.len()));
+        return Ok(());
+    }
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Refusing to update {} plans without confirmation; pass --yes", targets.len());
+        }
+        let confirmed = Confirm::new()
+            .with_prompt(format!("  Update {} on {} plan(s)?", lookup_key, targets.len()))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            output::info("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let body = serde_json::Value::Object(changes.iter().cloned().collect());
+    let mut failed = 0;
+    for ent in &targets {
+        let id = ent.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+        match client.put::<_, Entitlement>(&format!("/v1/entitlements/{}", id), &body).await {
+            Ok(_) => output::success(&format!("{}: updated ({})", plan_of(ent), id)),
+            Err(e) => {
+                failed += 1;
+                output::error(&format!("{}: {:#}", plan_of(ent), e));
+            }
+        }
+    }
+    println!();
+    output::info(&format!("{} updated, {} failed", targets.len() - failed, failed));
+    if failed > 0 {
+        anyhow::bail!("{} of {} plans failed to update", failed, targets.len());
+    }
+    Ok(())
+}
+
+/// Answer to `entitlements check`
+#[derive(serde::Serialize)]
+struct CheckResult {
+    customer_id: String,
+    feature: String,
+    entitled: bool,
+    enabled: bool,
+    /// `None` for features that aren't metered
+    usage: Option<f64>,
+    /// `None` for unlimited usage
+    limit: Option<f64>,
+    remaining: Option<f64>,
+    soft_limit: bool,
+}
+
+impl CheckResult {
+    fn new(customer_id: String, feature: &str, usage: Option<&FeatureUsage>) -> Self {
+        let Some(usage) = usage else {
+            return Self {
+                customer_id,
+                feature: feature.to_string(),
+                entitled: false,
+                enabled: false,
+                usage: None,
+                limit: None,
+                remaining: None,
+                soft_limit: false,
+            };
+        };
+        let enabled = usage.is_enabled != Some(false);
+        let metered = usage.feature.feature_type.as_deref() == Some("METERED");
+        let used = metered.then(|| usage.current_usage.unwrap_or_default());
+        let limit = usage.total_limit.filter(|_| metered);
+        let remaining = limit.map(|l| (l - used.unwrap_or_default()).max(0.0));
+        let soft_limit = usage.is_soft_limit == Some(true);
+        let exhausted = remaining == Some(0.0) && !soft_limit;
+        Self {
+            customer_id,
+            feature: feature.to_string(),
+            entitled: enabled && !exhausted,
+            enabled,
+            usage: used,
+            limit,
+            remaining,
+            soft_limit,
+        }
+    }
+}
+
+/// Report whether `reference` is entitled to `feature`, failing when not so
+/// scripts can branch on the exit code
+async fn check(client: &ApiClient, reference: &str, feature: &str) -> Result<()> {
+    let sp = spinner::create_spinner(&format!("Checking {} for {}...", feature, reference));
+    let id = customers::resolve(client, reference).await?;
+    let summary: CustomerUsageSummary = client.get(&format!("/v1/customers/{}/usage", id)).await?;
+    sp.finish_and_clear();
+
+    let usage = summary
+        .features
+        .iter()
+        .find(|f| f.feature.lookup_key.as_deref() == Some(feature) || f.feature.id == feature);
+    let result = CheckResult::new(id, feature, usage);
+
+    if !output::human() {
+        println!("{}", output::print_record(&result));
+    } else if usage.is_some() {
+        let counts = match (result.usage, result.limit) {
+            (Some(used), Some(limit)) => format!(
+                "{} of {} used, {} remaining{}",
+                chart::format_value(used),
+                chart::format_value(limit),
+                chart::format_value(result.remaining.unwrap_or_default()),
+                if result.soft_limit { " (soft limit)" } else { "" }
+            ),
+            (Some(used), None) => format!("{} used, unlimited", chart::format_value(used)),
+            _ => String::new(),
+        };
+        if !counts.is_empty() {
+            output::info(&counts);
+        }
+    }
+
+    if result.entitled {
+        if result.soft_limit && result.remaining == Some(0.0) {
+            output::warning(&format!("{} has reached its soft limit for {}.", reference, feature));
+        }
+        output::success(&format!("{} is entitled to {}.", reference, feature));
+        return Ok(());
+    }
+    match usage {
+        None => anyhow::bail!("{} is not entitled to {}: no active plan grants it", reference, feature),
+        Some(_) if !result.enabled => anyhow::bail!("{} is not entitled to {}: the feature is disabled", reference, feature),
+        Some(_) => anyhow::bail!("{} is not entitled to {}: the usage limit is reached", reference, feature),
+    }
+}
+
+async fn set_enabled(client: &ApiClient, plan_id: &str, lookup_key: &str, enabled: bool) -> Result<()> {
+    let verb = if enabled { "Enabling" } else { "Disabling" };
+    let sp = spinner::create_spinner(&format!("{} {} on {}...", verb, lookup_key, plan_id));
+    let feature = features::find_by_lookup_key(client, lookup_key).await?;
+    let existing = find_for_plan(client, plan_id, &feature.id).await?;
+
+    let ent: Entitlement = match existing {
+        Some(ent) if ent.is_enabled == Some(enabled) => {
+            sp.finish_and_clear();
+            output::info(&format!(
+                "{} is already {} on plan {}.",
+                lookup_key,
+                if enabled { "enabled" } else { "disabled" },
+                plan_id
+            ));
+            return Ok(());
+        }
+        Some(ent) => {
+            let body = serde_json::json!({ "is_enabled": enabled });
+            client.put(&format!("/v1/entitlements/{}", ent.id), &body).await?
+        }
+        None if enabled => {
+            let body = serde_json::json!({
+                "plan_id": plan_id,
+                "feature_id": feature.id,
+                "feature_type": feature.feature_type.as_deref().unwrap_or("boolean"),
+                "is_enabled": true,
+            });
+            client.post("/v1/entitlements", &body).await?
+        }
+        None => {
+            sp.finish_and_clear();
+            output::info(&format!("{} is not attached to plan {}; nothing to disable.", lookup_key, plan_id));
+            return Ok(());
+        }
+    };
+    sp.finish_and_clear();
+
+    if feature.feature_type.as_deref().is_some_and(|t| t != "boolean") {
+        output::warning(&format!(
+            "{} is a {} feature; only its enabled flag was changed.",
+            lookup_key,
+            feature.feature_type.as_deref().unwrap_or_default()
+        ));
     }
+    output::success(&format!(
+        "{} {} on plan {} (entitlement {}).",
+        lookup_key,
+        if enabled { "enabled" } else { "disabled" },
+        plan_id,
+        ent.id
+    ));
     Ok(())
 }
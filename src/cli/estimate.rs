@@ -0,0 +1,421 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use futures::future::try_join_all;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::query::QueryBuilder;
+use crate::api::models::{Meter, Plan, Price, PriceTier};
+use crate::cli::completions;
+use crate::utils::{output, spinner};
+
+#[derive(Subcommand)]
+pub enum EstimateCommands {
+    /// Price a usage profile under several plans side by side
+    Compare {
+        /// Comma-separated plan IDs; the first is the baseline for deltas
+        #[arg(long, value_delimiter = ',', num_args = 1.., required = true, add = completions::ids("/v1/plans"))]
+        plans: Vec<String>,
+        /// JSON file of quantities per meter, keyed by meter ID, name, or event name
+        #[arg(long)]
+        usage: String,
+    },
+}
+
+/// Flags for `estimate` without a subcommand: one plan, with usage given inline
+#[derive(Args)]
+pub struct PlanEstimateArgs {
+    /// Plan to price
+    #[arg(long, required = true, add = completions::ids("/v1/plans"))]
+    plan: Option<String>,
+    /// Quantity for one meter, by ID, name, or event name, e.g. api_call=10000 (repeatable)
+    #[arg(long = "usage", value_name = "METER=QUANTITY")]
+    usage: Vec<String>,
+}
+
+/// One plan's cost for the usage profile
+#[derive(serde::Serialize)]
+struct Estimate {
+    plan_id: String,
+    plan_name: String,
+    charges: Vec<Charge>,
+    /// One per currency and billing period, since those don't add up
+    totals: Vec<Total>,
+}
+
+#[derive(serde::Serialize)]
+struct Total {
+    currency: String,
+    billing_period: Option<String>,
+    amount: f64,
+}
+
+#[derive(serde::Serialize)]
+struct Charge {
+    price_id: String,
+    name: String,
+    quantity: Option<f64>,
+    /// How the amount was worked out, e.g. `1000 × 0.01 + 500 × 0.005`
+    calculation: String,
+    amount: f64,
+    currency: String,
+    billing_period: Option<String>,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ChargeRow {
+    #[tabled(rename = "Plan")]
+    plan: String,
+    #[tabled(rename = "Charge")]
+    charge: String,
+    #[tabled(rename = "Quantity")]
+    quantity: String,
+    #[tabled(rename = "Period")]
+    period: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct BreakdownRow {
+    #[tabled(rename = "Charge")]
+    charge: String,
+    #[tabled(rename = "Quantity")]
+    quantity: String,
+    #[tabled(rename = "Calculation")]
+    calculation: String,
+    #[tabled(rename = "Period")]
+    period: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ComparisonRow {
+    #[tabled(rename = "Plan")]
+    plan: String,
+    #[tabled(rename = "Period")]
+    period: String,
+    #[tabled(rename = "Fixed")]
+    fixed: String,
+    #[tabled(rename = "Usage")]
+    usage: String,
+    #[tabled(rename = "Total")]
+    total: String,
+    #[tabled(rename = "Δ vs baseline")]
+    delta: String,
+    #[tabled(rename = "Δ %")]
+    delta_pct: String,
+}
+
+pub async fn handle(cmd: Option<EstimateCommands>, args: PlanEstimateArgs, client: ApiClient) -> Result<()> {
+    let Some(cmd) = cmd else {
+        let quantities = parse_usage(&args.usage)?;
+        let plan = args.plan.unwrap_or_default();
+        let sp = spinner::create_spinner("Fetching plan and prices...");
+        let fetched = tokio::try_join!(client.get_all::<Meter>("/v1/meters"), fetch_plan(&client, &plan));
+        sp.finish_and_clear();
+        let (meters, (plan, prices)) = fetched?;
+
+        let by_meter = resolve_usage(&quantities, &meters);
+        let estimate = estimate(plan, &prices, &by_meter, &meters);
+        if !output::human() {
+            println!("{}", output::print_detail(&estimate));
+            return Ok(());
+        }
+        print_breakdown(&estimate);
+        return Ok(());
+    };
+
+    match cmd {
+        EstimateCommands::Compare { plans, usage } => {
+            let data = std::fs::read_to_string(&usage).with_context(|| format!("Failed to read {}", usage))?;
+            let quantities: BTreeMap<String, f64> =
+                serde_json::from_str(&data).with_context(|| format!("Expected {{\"<meter>\": quantity, ...}} in {}", usage))?;
+
+            let sp = spinner::create_spinner("Fetching plans and prices...");
+            let fetched = tokio::try_join!(
+                client.get_all::<Meter>("/v1/meters"),
+                try_join_all(plans.iter().map(|id| fetch_plan(&client, id))),
+            );
+            sp.finish_and_clear();
+            let (meters, plans) = fetched?;
+
+            let by_meter = resolve_usage(&quantities, &meters);
+            let estimates: Vec<Estimate> = plans
+                .into_iter()
+                .map(|(plan, prices)| estimate(plan, &prices, &by_meter, &meters))
+                .collect();
+
+            if !output::human() {
+                println!("{}", output::print_detail(&estimates));
+                return Ok(());
+            }
+            print_comparison(&estimates);
+        }
+    }
+    Ok(())
+}
+
+async fn fetch_plan(client: &ApiClient, id: &str) -> Result<(Plan, Vec<Price>)> {
+    let (plan_path, prices_path) = (format!("/v1/plans/{}", id), QueryBuilder::new().param("plan_ids", id).to_path("/v1/prices"));
+    let (plan, prices) = tokio::try_join!(client.get::<Plan>(&plan_path), client.get_all::<Price>(&prices_path))
+        .with_context(|| format!("Failed to fetch plan {}", id))?;
+    Ok((plan, prices))
+}
+
+/// `METER=QUANTITY` flags as quantities by meter; a meter given twice adds up
+fn parse_usage(flags: &[String]) -> Result<BTreeMap<String, f64>> {
+    let mut quantities = BTreeMap::new();
+    for flag in flags {
+        let parsed = flag.split_once('=').and_then(|(meter, qty)| Some((meter.trim(), qty.trim().parse::<f64>().ok()?)));
+        match parsed {
+            Some((meter, qty)) if !meter.is_empty() && qty >= 0.0 => *quantities.entry(meter.to_string()).or_insert(0.0) += qty,
+            _ => anyhow::bail!("Invalid --usage '{}'; expected METER=QUANTITY, e.g. api_call=10000", flag),
+        }
+    }
+    Ok(quantities)
+}
+
+/// Quantities keyed by meter ID; usage keys may name a meter by ID, name, or event name
+fn resolve_usage(quantities: &BTreeMap<String, f64>, meters: &[Meter]) -> BTreeMap<String, f64> {
+    let mut by_meter = BTreeMap::new();
+    for (key, qty) in quantities {
+        let meter = meters.iter().find(|m| {
+            m.id == *key || m.name.as_deref() == Some(key.as_str()) || m.event_name.as_deref() == Some(key.as_str())
+        });
+        match meter {
+            Some(m) => *by_meter.entry(m.id.clone()).or_insert(0.0) += qty,
+            None => output::warning(&format!("Usage for '{}' doesn't match any meter; ignoring it", key)),
+        }
+    }
+    by_meter
+}
+
+fn estimate(plan: Plan, prices: &[Price], usage: &BTreeMap<String, f64>, meters: &[Meter]) -> Estimate {
+    // Archived prices no longer bill
+    let charges: Vec<Charge> = prices
+        .iter()
+        .filter(|p| !p.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("archived") || s.eq_ignore_ascii_case("deleted")))
+        .map(|price| {
+            let quantity = price.meter_id.as_ref().map(|id| usage.get(id).copied().unwrap_or(0.0));
+            let name = match &price.meter_id {
+                Some(id) => meters
+                    .iter()
+                    .find(|m| m.id == *id)
+                    .and_then(|m| m.name.clone())
+                    .unwrap_or_else(|| id.clone()),
+                None => price.description.clone().or(price.lookup_key.clone()).unwrap_or_else(|| "Fixed fee".into()),
+            };
+            let (amount, calculation) = price_cost(price, quantity);
+            Charge {
+                price_id: price.id.clone(),
+                name,
+                amount,
+                calculation,
+                quantity,
+                currency: price.currency.clone().unwrap_or_default().to_uppercase(),
+                billing_period: price.billing_period.clone(),
+            }
+        })
+        .collect();
+    let mut totals: BTreeMap<(String, Option<String>), f64> = BTreeMap::new();
+    for charge in &charges {
+        *totals.entry((charge.currency.clone(), charge.billing_period.clone())).or_default() += charge.amount;
+    }
+    Estimate {
+        plan_name: plan.name.unwrap_or_else(|| plan.id.clone()),
+        plan_id: plan.id,
+        charges,
+        totals: totals
+            .into_iter()
+            .map(|((currency, billing_period), amount)| Total { currency, billing_period, amount })
+            .collect(),
+    }
+}
+
+impl Estimate {
+    /// The total billed in `total`'s currency and period, if this plan has one
+    fn total_like(&self, total: &Total) -> Option<f64> {
+        self.totals
+            .iter()
+            .find(|t| t.currency == total.currency && t.billing_period == total.billing_period)
+            .map(|t| t.amount)
+    }
+
+    /// What the fixed fees in `total`'s currency and period add up to
+    fn fixed(&self, total: &Total) -> f64 {
+        self.charges
+            .iter()
+            .filter(|c| c.quantity.is_none() && c.currency == total.currency && c.billing_period == total.billing_period)
+            .fold(0.0, |sum, c| sum + c.amount)
+    }
+}
+
+impl Total {
+    /// e.g. `120.00 USD (MONTHLY)`
+    fn describe(&self) -> String {
+        let amount = format!("{:.2} {}", self.amount, self.currency).trim_end().to_string();
+        match &self.billing_period {
+            Some(period) => format!("{} ({})", amount, period),
+            None => amount,
+        }
+    }
+}
+
+/// What one price charges for `quantity` units (`None` for fixed prices),
+/// and how that was worked out
+fn price_cost(price: &Price, quantity: Option<f64>) -> (f64, String) {
+    let amount = parse_amount(price.amount.as_deref());
+    let unit = price.amount.clone().unwrap_or_else(|| "0".into());
+    let Some(qty) = quantity else { return (amount, "fixed fee".into()) };
+    if qty <= 0.0 {
+        return (0.0, "no usage".into());
+    }
+    match price.billing_model.as_deref() {
+        Some("PACKAGE") => {
+            let transform = price.transform_quantity.as_ref();
+            let divide_by = transform.and_then(|t| t.get("divide_by")).and_then(|v| v.as_f64()).filter(|d| *d > 0.0).unwrap_or(1.0);
+            let packages = match transform.and_then(|t| t.get("round")).and_then(|v| v.as_str()) {
+                Some("down") => (qty / divide_by).floor(),
+                _ => (qty / divide_by).ceil(),
+            };
+            (packages * amount, format!("{} packages of {} × {}", packages, divide_by, unit))
+        }
+        Some("TIERED") => {
+            let tiers = price.tiers.as_deref().unwrap_or_default();
+            if price.tier_mode.as_deref() == Some("SLAB") {
+                slab_cost(tiers, qty)
+            } else {
+                let Some(i) = tiers.iter().position(|t| t.up_to.is_none_or(|up| qty <= up as f64)) else {
+                    return (0.0, "no matching tier".into());
+                };
+                let tier = &tiers[i];
+                let cost = qty * parse_amount(tier.unit_amount.as_deref()) + parse_amount(tier.flat_amount.as_deref());
+                let bound = tier.up_to.map_or("∞".to_string(), |up| up.to_string());
+                (cost, format!("tier {} (up to {}): {}", i + 1, bound, tier_charge(tier, qty)))
+            }
+        }
+        _ => (qty * amount, format!("{} × {}", qty, unit)),
+    }
+}
+
+/// Each tier charges only for the units that fall inside it
+fn slab_cost(tiers: &[PriceTier], qty: f64) -> (f64, String) {
+    let mut floor = 0.0;
+    let mut cost = 0.0;
+    let mut parts = vec![];
+    for tier in tiers {
+        let ceiling = tier.up_to.map(|up| up as f64).unwrap_or(f64::INFINITY);
+        let units = qty.min(ceiling) - floor;
+        if units <= 0.0 {
+            break;
+        }
+        cost += units * parse_amount(tier.unit_amount.as_deref()) + parse_amount(tier.flat_amount.as_deref());
+        parts.push(tier_charge(tier, units));
+        floor = ceiling;
+    }
+    (cost, parts.join(" + "))
+}
+
+/// `units × unit amount`, plus the tier's flat amount when it has one
+fn tier_charge(tier: &PriceTier, units: f64) -> String {
+    let unit = tier.unit_amount.as_deref().unwrap_or("0");
+    match tier.flat_amount.as_deref().filter(|flat| parse_amount(Some(flat)) != 0.0) {
+        Some(flat) => format!("{} × {} + {}", units, unit, flat),
+        None => format!("{} × {}", units, unit),
+    }
+}
+
+fn parse_amount(amount: Option<&str>) -> f64 {
+    amount.and_then(|a| a.parse().ok()).unwrap_or_default()
+}
+
+fn print_breakdown(estimate: &Estimate) {
+    let rows: Vec<BreakdownRow> = estimate
+        .charges
+        .iter()
+        .map(|c| BreakdownRow {
+            charge: c.name.clone(),
+            quantity: c.quantity.map(|q| q.to_string()).unwrap_or_else(|| "—".into()),
+            calculation: c.calculation.clone(),
+            period: c.billing_period.clone().unwrap_or_default(),
+            amount: format!("{:.2}", c.amount),
+        })
+        .collect();
+    println!("{}", output::print_table(&rows));
+    println!();
+    for total in &estimate.totals {
+        output::info(&format!("{}: {}", estimate.plan_name, total.describe().bold()));
+    }
+    if estimate.totals.len() > 1 {
+        output::warning("Prices bill in different currencies or over different periods, so there is a total for each.");
+    }
+}
+
+fn print_comparison(estimates: &[Estimate]) {
+    let charges: Vec<ChargeRow> = estimates
+        .iter()
+        .flat_map(|e| {
+            e.charges.iter().map(|c| ChargeRow {
+                plan: e.plan_name.clone(),
+                charge: c.name.clone(),
+                quantity: c.quantity.map(|q| q.to_string()).unwrap_or_else(|| "—".into()),
+                period: c.billing_period.clone().unwrap_or_default(),
+                amount: format!("{:.2}", c.amount),
+            })
+        })
+        .collect();
+    println!("{}", output::print_table(&charges));
+    println!();
+
+    // Each total is compared with the baseline's total in the same currency and period
+    let baseline = estimates.first();
+    let rows: Vec<ComparisonRow> = estimates
+        .iter()
+        .enumerate()
+        .flat_map(|(i, e)| {
+            e.totals.iter().map(move |total| {
+                let fixed = e.fixed(total);
+                let base = baseline.and_then(|b| b.total_like(total));
+                ComparisonRow {
+                    plan: e.plan_name.clone(),
+                    period: total.billing_period.clone().unwrap_or_default(),
+                    fixed: format!("{:.2}", fixed),
+                    usage: format!("{:.2}", total.amount - fixed),
+                    total: format!("{:.2} {}", total.amount, total.currency).bold().to_string(),
+                    delta: match (i, base) {
+                        (0, _) => "baseline".dimmed().to_string(),
+                        (_, Some(base)) => signed(total.amount - base),
+                        (_, None) => "—".into(),
+                    },
+                    delta_pct: match (i, base) {
+                        (0, _) => String::new(),
+                        (_, Some(base)) if base != 0.0 => format!("{:+.1}%", (total.amount - base) / base * 100.0),
+                        _ => "—".into(),
+                    },
+                }
+            })
+        })
+        .collect();
+    println!("{}", output::print_table(&rows));
+
+    if estimates.iter().any(|e| e.totals.len() > 1) {
+        output::warning("Prices bill in different currencies or over different periods, so there is a total for each.");
+    }
+}
+
+/// A delta coloured by direction: cheaper is green, dearer is red
+fn signed(delta: f64) -> String {
+    let text = format!("{:+.2}", delta);
+    if delta < 0.0 {
+        text.green().to_string()
+    } else if delta > 0.0 {
+        text.red().to_string()
+    } else {
+        text
+    }
+}
@@ -1,84 +1,1532 @@
-use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use colored::Colorize;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use serde::Deserialize;
+use tabled::Tabled;
+use tokio::io::AsyncBufReadExt;
 
-use crate::api::client::ApiClient;
-use crate::cli::auth::require_auth;
-use crate::utils::{output, spinner};
+use crate::api::client::{ApiClient, DryRun};
+use crate::api::error::ApiClientError;
+use crate::api::models::Event;
+use crate::api::query::QueryBuilder;
+use crate::config::globals;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::fanout::{self, IdsArgs};
+use crate::utils::wait::{self, WaitArgs};
+use crate::utils::{ids, output, spinner, time};
 
 #[derive(Subcommand)]
 pub enum EventCommands {
-    /// Ingest a single event from a JSON file
+    /// Ingest a single event from a JSON file, or a stream of events from stdin
+    ///
+    /// With --stdin, each line is one JSON event. Events are sent to the bulk
+    /// endpoint in batches until stdin closes or Ctrl-C.
     Ingest {
-        #[arg(long)]
-        json: String,
+        #[arg(long, required_unless_present = "stdin")]
+        json: Option<String>,
+        /// Read events from stdin, one JSON object per line
+        #[arg(long, conflicts_with = "json")]
+        stdin: bool,
+        /// Number of events sent per request with --stdin
+        #[arg(long, default_value_t = 100)]
+        batch_size: usize,
+        /// Seconds to wait before sending a partial batch with --stdin
+        #[arg(long, default_value_t = 5)]
+        flush_interval: u64,
+        #[command(flatten)]
+        ids: EventIdArgs,
     },
-    /// Ingest events in bulk from a JSON file
+    /// Ingest events in bulk from a JSON or NDJSON file, or NDJSON on stdin
     IngestBulk {
+        /// JSON file with an array of events (or `{"events": [...]}`)
+        #[arg(long, required_unless_present = "ndjson")]
+        json: Option<String>,
+        /// NDJSON file with one event per line, or `-` to stream stdin
+        #[arg(long, value_name = "FILE", conflicts_with = "json")]
+        ndjson: Option<String>,
+        /// Number of events sent per request
+        #[arg(long, default_value_t = 100)]
+        batch_size: usize,
+        #[command(flatten)]
+        ids: EventIdArgs,
+        #[command(flatten)]
+        batch: BatchArgs,
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
+    /// Ingest events from a CSV file, mapping columns to event fields
+    IngestCsv {
+        /// CSV file with a header row
         #[arg(long)]
-        json: String,
+        file: String,
+        /// JSON file mapping event fields and properties to CSV columns
+        #[arg(long)]
+        mapping: Option<String>,
+        /// Number of events sent per request
+        #[arg(long, default_value_t = 100)]
+        batch_size: usize,
+        /// Number of requests in flight at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+    /// Follow a log stream on stdin and ingest the events extracted from it
+    ///
+    /// With --dry-run, prints the extracted events as JSON lines instead.
+    IngestLogs {
+        /// Regex applied to each line; lines that don't match are skipped.
+        /// Without it, each line is parsed as a JSON object
+        #[arg(long)]
+        regex: Option<String>,
+        /// FIELD=VALUE rule (repeatable), e.g. `name=$1`, `customer=${cust}`,
+        /// `tokens:number=$.usage.tokens`; unknown fields become properties
+        #[arg(long = "map", value_name = "FIELD=VALUE")]
+        maps: Vec<String>,
+        /// Number of events sent per request
+        #[arg(long, default_value_t = 100)]
+        batch_size: usize,
+        /// Seconds to wait before sending a partial batch
+        #[arg(long, default_value_t = 5)]
+        flush_interval: u64,
+    },
+    /// Generate randomized events and ingest them, to seed usage for testing
+    ///
+    /// With --dry-run, prints the generated events as JSON lines instead.
+    Simulate {
+        /// Event name for every generated event
+        #[arg(long)]
+        event_name: String,
+        /// Number of distinct customers to spread events across
+        #[arg(long, default_value_t = 10)]
+        customers: usize,
+        /// Prefix for generated external customer IDs (`sim-customer-001`, …)
+        #[arg(long, default_value = "sim-customer-")]
+        customer_prefix: String,
+        /// Events per second, minute, or hour, e.g. `100/s` or `500/m`
+        #[arg(long, default_value = "10/s")]
+        rate: String,
+        /// How long to generate events for, e.g. `60s` or `5m`
+        #[arg(long, default_value = "60s")]
+        duration: String,
+        /// JSON file describing each property's value or distribution
+        #[arg(long)]
+        properties_template: Option<String>,
+        /// Spread timestamps over this window before now and send without pacing, e.g. `7d`
+        #[arg(long)]
+        backfill: Option<String>,
+        /// Number of events sent per request
+        #[arg(long, default_value_t = 100)]
+        batch_size: usize,
+        /// Number of requests in flight at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+        /// Seed for generating the same events on every run
+        #[arg(long)]
+        seed: Option<u64>,
     },
     /// List recent events
-    List {
+    List,
+    /// Export a time range of events to NDJSON, one file per day, fetching
+    /// days concurrently. Re-running resumes, skipping days already exported.
+    Export {
+        /// Start of the range: a date (`2026-01-01`) or RFC 3339 timestamp
+        #[arg(long)]
+        from: String,
+        /// End of the range, exclusive (default: now)
+        #[arg(long)]
+        to: Option<String>,
+        /// Only events with this name
+        #[arg(long)]
+        event_name: Option<String>,
+        /// Only events for this external customer ID
         #[arg(long)]
-        json: bool,
+        customer: Option<String>,
+        /// Directory for the per-day files
+        #[arg(long, default_value = "events-export")]
+        out: String,
+        /// Also merge the days, oldest first, into this file
+        #[arg(long, value_name = "FILE")]
+        merge: Option<String>,
+        /// Days fetched at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+        /// Events requested per page
+        #[arg(long, default_value_t = 500)]
+        page_size: usize,
     },
-    /// Get an event by ID
-    Get {
-        id: String,
+    /// Show the most recent events, optionally polling for new ones
+    Tail {
+        /// Keep polling and print new events as they arrive (Ctrl-C to stop)
+        #[arg(long, short)]
+        follow: bool,
+        /// Only events with this name
+        #[arg(long)]
+        event_name: Option<String>,
+        /// Only events for this external customer ID
         #[arg(long)]
-        json: bool,
+        customer: Option<String>,
+        /// Number of recent events to show first
+        #[arg(long, short = 'n', default_value_t = 10)]
+        lines: usize,
+        /// Seconds between polls with --follow
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+    /// Get an event by ID, or many with --ids-file
+    Get {
+        #[arg(required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
     },
     /// Query event usage
     Usage {
         /// JSON body for usage query
         #[arg(long)]
         json: String,
+        /// Cap the query's end_time at this date or RFC 3339 timestamp
+        #[arg(long)]
+        as_of: Option<String>,
     },
 }
 
-pub async fn handle(cmd: EventCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
-
+pub async fn handle(cmd: EventCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        EventCommands::Ingest { json: file } => {
+        EventCommands::Ingest { stdin: true, batch_size, flush_interval, ids, .. } => {
+            let flush_every = Duration::from_secs(flush_interval.max(1));
+            ingest_logs(&client, &LogMapping::events(ids), batch_size.max(1), flush_every, globals::get().dry_run).await?;
+        }
+        EventCommands::Ingest { json, ids, .. } => {
+            let file = json.unwrap_or_default();
             let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
+            let mut body: serde_json::Value = serde_json::from_str(&data)?;
+            let key = ids.assign(&mut body).with_context(|| format!("Invalid event in {}", file))?;
             let sp = spinner::create_spinner("Ingesting event...");
-            let resp: serde_json::Value = client.post("/v1/events", &body).await?;
+            let resp: serde_json::Value = client.post_idempotent("/v1/events", &body, &key).await?;
             sp.finish_and_clear();
             output::success("Event ingested successfully!");
-            println!("{}", output::print_detail(&resp, false));
+            println!("{}", output::print_detail(&resp));
         }
-        EventCommands::IngestBulk { json: file } => {
-            let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Ingesting events in bulk...");
-            let resp: serde_json::Value = client.post("/v1/events/bulk", &body).await?;
-            sp.finish_and_clear();
-            output::success("Bulk events ingested successfully!");
-            println!("{}", output::print_detail(&resp, false));
+        EventCommands::IngestBulk { ndjson: Some(file), batch_size, ids, batch: opts, wait, .. } if file == "-" => {
+            if opts.checkpoint.is_some() || opts.resume.is_some() {
+                anyhow::bail!("--checkpoint and --resume need a file; a stream from stdin can't be resumed");
+            }
+            if wait.wait {
+                anyhow::bail!("--wait needs a file; a stream from stdin has no end to wait for");
+            }
+            let flush_every = Duration::from_secs(5);
+            ingest_logs(&client, &LogMapping::events(ids), batch_size.max(1), flush_every, globals::get().dry_run).await?;
+        }
+        EventCommands::IngestBulk { json, ndjson, batch_size, ids, batch: opts, wait } => {
+            let timeout = wait.timeout()?;
+            let (file, mut events) = match (json, ndjson) {
+                (_, Some(file)) => {
+                    let events = read_ndjson(&file)?;
+                    (file, events)
+                }
+                (file, None) => {
+                    let file = file.unwrap_or_default();
+                    let events = match batch::read_payload(&file)? {
+                        Payload::Many(events) => events,
+                        Payload::Single(mut body) => match body.get_mut("events").map(serde_json::Value::take) {
+                            Some(serde_json::Value::Array(events)) => events,
+                            _ => anyhow::bail!("Expected an array of events or an object with an `events` array"),
+                        },
+                    };
+                    (file, events)
+                }
+            };
+            let mut event_ids = vec![];
+            for (i, event) in events.iter_mut().enumerate() {
+                event_ids.push(ids.assign(event).with_context(|| format!("Event {} in {}", i + 1, file))?);
+            }
+            let batch_size = batch_size.max(1);
+            let chunks: Vec<serde_json::Value> = events
+                .chunks(batch_size)
+                .map(|c| serde_json::Value::Array(c.to_vec()))
+                .collect();
+            batch::run(chunks, Some(&file), batch_size, &opts, "Events ingested", |chunk| {
+                let client = &client;
+                async move {
+                    let events = chunk.as_array().map(Vec::as_slice).unwrap_or_default();
+                    post_bulk(client, events).await?;
+                    Ok(events.len().to_string())
+                }
+            }).await?;
+            if wait.wait && !globals::get().dry_run {
+                wait_processed(&client, event_ids, timeout).await?;
+            }
+        }
+        EventCommands::IngestCsv { file, mapping, batch_size, concurrency } => {
+            let mapping = match mapping {
+                Some(path) => {
+                    let data = std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path))?;
+                    serde_json::from_str(&data).with_context(|| format!("Invalid mapping in {}", path))?
+                }
+                None => CsvMapping::default(),
+            };
+            ingest_csv(&client, &file, &mapping, batch_size.max(1), concurrency.max(1)).await?;
+        }
+        EventCommands::IngestLogs { regex, maps, batch_size, flush_interval } => {
+            let mapping = LogMapping::new(regex.as_deref(), &maps)?;
+            let flush_every = Duration::from_secs(flush_interval.max(1));
+            ingest_logs(&client, &mapping, batch_size.max(1), flush_every, globals::get().dry_run).await?;
         }
-        EventCommands::List { json } => {
+        EventCommands::Simulate {
+            event_name,
+            customers,
+            customer_prefix,
+            rate,
+            duration,
+            properties_template,
+            backfill,
+            batch_size,
+            concurrency,
+            seed,
+        } => {
+            let properties = match properties_template {
+                Some(path) => {
+                    let data = std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path))?;
+                    let template: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&data)
+                        .with_context(|| format!("Expected a JSON object of properties in {}", path))?;
+                    template
+                        .into_iter()
+                        .map(|(name, spec)| {
+                            let dist = Distribution::parse(&spec).with_context(|| format!("property {} in {}", name, path))?;
+                            Ok((name, dist))
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                }
+                None => vec![],
+            };
+            let per_second = parse_rate(&rate)?;
+            let duration = time::parse_duration(&duration)?;
+            let start = chrono::Utc::now();
+            // Events are stamped across the run, or the backfill window before it
+            time::check_span(start, duration)?;
+            let backfill = match backfill.as_deref().map(time::parse_duration).transpose()? {
+                Some(window) => {
+                    time::check_span(start, window)?;
+                    Some(window.to_std().unwrap_or_default())
+                }
+                None => None,
+            };
+            let total = ((per_second * duration.num_milliseconds() as f64 / 1000.0).round() as usize).max(1);
+            let sim = Simulation {
+                event_name,
+                customers: (1..=customers.max(1)).map(|i| format!("{}{:03}", customer_prefix, i)).collect(),
+                properties,
+                per_second,
+                backfill,
+                start,
+            };
+            let mut rng = seed.map(fastrand::Rng::with_seed).unwrap_or_default();
+            if globals::get().dry_run {
+                for i in 0..total {
+                    println!("{}", sim.event(i, &mut rng));
+                }
+                return Ok(());
+            }
+            simulate(&client, &sim, total, &mut rng, batch_size.max(1), concurrency.max(1)).await?;
+        }
+        EventCommands::List => {
             let sp = spinner::create_spinner("Fetching events...");
             let resp: serde_json::Value = client.get("/v1/events").await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&resp, json));
+            println!("{}", output::print_detail(&resp));
+        }
+        EventCommands::Export { from, to, event_name, customer, out, merge, concurrency, page_size } => {
+            let from = time::parse_instant(&from)?;
+            let to = to.as_deref().map(time::parse_instant).transpose()?.unwrap_or_else(chrono::Utc::now);
+            if from >= to {
+                anyhow::bail!("--from must be before --to");
+            }
+            let filter = EventFilter { event_name, customer };
+            let opts = ExportOptions { dir: out, merge, concurrency: concurrency.max(1), page_size: page_size.max(1) };
+            export(&client, &filter, from, to, &opts).await?;
+        }
+        EventCommands::Tail { follow, event_name, customer, lines, interval } => {
+            let filter = EventFilter { event_name, customer };
+            tail(&client, &filter, lines, follow.then_some(interval)).await?;
         }
-        EventCommands::Get { id, json } => {
+        EventCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/events", &many).await };
             let sp = spinner::create_spinner("Fetching event...");
             let event: serde_json::Value = client.get(&format!("/v1/events/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&event, json));
+            println!("{}", output::print_detail(&event));
         }
-        EventCommands::Usage { json: file } => {
+        EventCommands::Usage { json: file, as_of } => {
             let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
+            let mut body: serde_json::Value = serde_json::from_str(&data)?;
+            if let Some(at) = as_of.as_deref().map(time::parse_instant).transpose()? {
+                time::cap_end_time(&mut body, at);
+            }
             let sp = spinner::create_spinner("Fetching usage...");
             let usage: serde_json::Value = client.post("/v1/events/usage", &body).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&usage, false));
+            println!("{}", output::print_detail(&usage));
+        }
+    }
+    Ok(())
+}
+
+struct EventFilter {
+    event_name: Option<String>,
+    customer: Option<String>,
+}
+
+impl EventFilter {
+    fn path(&self, page_size: usize, since: Option<&str>) -> String {
+        self.query(page_size, since).to_path("/v1/events")
+    }
+
+    /// A page of events in `[start, end)`, continuing after `key` from the previous page
+    fn range_path(&self, page_size: usize, start: &str, end: &str, key: Option<&str>) -> String {
+        self.query(page_size, Some(start))
+            .param("end_time", end)
+            .opt("iter_last_key", key)
+            .to_path("/v1/events")
+    }
+
+    fn query(&self, page_size: usize, since: Option<&str>) -> QueryBuilder {
+        QueryBuilder::new()
+            .param("page_size", page_size)
+            .opt("event_name", self.event_name.as_deref())
+            .opt("external_customer_id", self.customer.as_deref())
+            .opt("start_time", since)
+    }
+}
+
+/// Events page; newest first
+#[derive(serde::Deserialize)]
+struct EventPage<T = Event> {
+    #[serde(default, alias = "items")]
+    events: Vec<T>,
+    #[serde(default)]
+    has_more: Option<bool>,
+    /// Cursor for the next (older) page
+    #[serde(default)]
+    iter_last_key: Option<String>,
+}
+
+/// Position of the newest printed event, so polls from that instant don't repeat it
+#[derive(Default)]
+struct Cursor {
+    since: Option<String>,
+    /// IDs already printed with timestamp `since`
+    seen: HashSet<String>,
+}
+
+impl Cursor {
+    fn is_new(&self, e: &Event) -> bool {
+        e.timestamp > self.since
+            || (e.timestamp == self.since && !self.seen.contains(e.id.as_deref().unwrap_or_default()))
+    }
+
+    fn advance(&mut self, e: &Event) {
+        if e.timestamp > self.since {
+            self.since = e.timestamp.clone();
+            self.seen.clear();
+        }
+        self.seen.insert(e.id.clone().unwrap_or_default());
+    }
+}
+
+/// Print the last `lines` events, then poll every `follow` seconds for new ones
+async fn tail(client: &ApiClient, filter: &EventFilter, lines: usize, follow: Option<u64>) -> Result<()> {
+    let page: EventPage = client.get(&filter.path(lines.max(1), None)).await?;
+    let mut cursor = Cursor::default();
+
+    if output::human() {
+        println!("{}", format_header().bold());
+    }
+    for event in page.events.iter().take(lines).rev() {
+        print_event(event);
+        cursor.advance(event);
+    }
+
+    let Some(interval) = follow else { return Ok(()) };
+    if output::human() {
+        output::info("Waiting for new events (Ctrl-C to stop)...");
+    }
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(Duration::from_secs(interval.max(1))) => {}
+        }
+        let page: EventPage = match client.get(&filter.path(100, cursor.since.as_deref())).await {
+            Ok(page) => page,
+            Err(e) => {
+                output::warning(&format!("Poll failed: {:#}", e));
+                continue;
+            }
+        };
+        let new: Vec<&Event> = page.events.iter().filter(|e| cursor.is_new(e)).collect();
+        for event in new.into_iter().rev() {
+            print_event(event);
+            cursor.advance(event);
         }
     }
+}
+
+struct ExportOptions {
+    dir: String,
+    merge: Option<String>,
+    concurrency: usize,
+    page_size: usize,
+}
+
+/// One day of an export, clipped to the requested range
+struct ExportDay {
+    date: chrono::NaiveDate,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+}
+
+impl ExportDay {
+    /// Days cut short by the ends of the range go to a `.partial` file, which
+    /// is fetched again on every run; only whole days are resumed
+    fn is_whole(&self) -> bool {
+        self.end - self.start == chrono::Duration::days(1)
+    }
+
+    fn path(&self, dir: &str) -> std::path::PathBuf {
+        let suffix = if self.is_whole() { "" } else { ".partial" };
+        std::path::Path::new(dir).join(format!("{}{}.ndjson", self.date, suffix))
+    }
+}
+
+/// Split `[from, to)` at UTC midnights
+fn export_days(from: chrono::DateTime<chrono::Utc>, to: chrono::DateTime<chrono::Utc>) -> Vec<ExportDay> {
+    let mut days = vec![];
+    let mut date = from.date_naive();
+    loop {
+        let midnight = |d: chrono::NaiveDate| d.and_hms_opt(0, 0, 0).expect("valid time").and_utc();
+        let Some(next) = date.succ_opt() else { break };
+        let (start, end) = (midnight(date).max(from), midnight(next).min(to));
+        if start >= to {
+            break;
+        }
+        days.push(ExportDay { date, start, end });
+        date = next;
+    }
+    days
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DayOutcome {
+    Exported,
+    Skipped,
+    Failed,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ExportRow {
+    #[tabled(rename = "Day")]
+    day: String,
+    #[tabled(rename = "Events")]
+    events: usize,
+    #[tabled(rename = "File")]
+    file: String,
+    #[tabled(rename = "Result")]
+    result: String,
+    #[tabled(skip)]
+    #[serde(skip)]
+    outcome: DayOutcome,
+}
+
+/// Export every day in `[from, to)` to its own file, `concurrency` days at a
+/// time, then optionally merge them in order
+async fn export(
+    client: &ApiClient,
+    filter: &EventFilter,
+    from: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+    opts: &ExportOptions,
+) -> Result<()> {
+    std::fs::create_dir_all(&opts.dir).with_context(|| format!("Failed to create {}", opts.dir))?;
+    let days = export_days(from, to);
+
+    let progress = spinner::create_progress(days.len() as u64, "Exporting", "export");
+    let rows: Vec<ExportRow> = stream::iter(&days)
+        .map(|day| {
+            let progress = &progress;
+            async move {
+                let path = day.path(&opts.dir);
+                let (events, outcome, result) = if day.is_whole() && path.exists() {
+                    (count_lines(&path), DayOutcome::Skipped, "skipped (already exported)".dimmed().to_string())
+                } else {
+                    match export_day(client, filter, day, &path, opts.page_size).await {
+                        Ok(n) => (n, DayOutcome::Exported, "ok".green().to_string()),
+                        Err(e) => {
+                            progress.suspend(|| output::error(&format!("{}: {:#}", day.date, e)));
+                            (0, DayOutcome::Failed, format!("{}: {:#}", "failed".red(), e))
+                        }
+                    }
+                };
+                progress.inc(1);
+                ExportRow { day: day.date.to_string(), events, file: path.display().to_string(), result, outcome }
+            }
+        })
+        .buffered(opts.concurrency)
+        .collect()
+        .await;
+    progress.finish_and_clear();
+
+    let count = |outcome| rows.iter().filter(|r| r.outcome == outcome).count();
+    let (failed, skipped) = (count(DayOutcome::Failed), count(DayOutcome::Skipped));
+    let total: usize = rows.iter().map(|r| r.events).sum();
+    if output::human() {
+        output::success(&format!("{} events over {} days in {}", total, rows.len(), opts.dir));
+        if skipped > 0 {
+            output::info(&format!("{} days were already exported and skipped", skipped));
+        }
+    } else {
+        println!("{}", output::print_table(&rows));
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} days failed; run the same command again to resume", failed, rows.len());
+    }
+
+    if let Some(merge) = &opts.merge {
+        let mut out = std::fs::File::create(merge).with_context(|| format!("Failed to create {}", merge))?;
+        for row in &rows {
+            let mut day = std::fs::File::open(&row.file).with_context(|| format!("Failed to read {}", row.file))?;
+            std::io::copy(&mut day, &mut out).with_context(|| format!("Failed to write {}", merge))?;
+        }
+        if output::human() {
+            output::success(&format!("Merged into {}", merge));
+        }
+    }
+    Ok(())
+}
+
+/// Fetch every page of one day and write it oldest first via a `.part` file,
+/// so an interrupted day is never mistaken for a finished one
+async fn export_day(client: &ApiClient, filter: &EventFilter, day: &ExportDay, path: &std::path::Path, page_size: usize) -> Result<usize> {
+    let start = day.start.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let end = day.end.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let mut events: Vec<serde_json::Value> = vec![];
+    let mut key: Option<String> = None;
+    loop {
+        let page: EventPage<serde_json::Value> = client.get(&filter.range_path(page_size, &start, &end, key.as_deref())).await?;
+        // Servers that don't report has_more are done on a short page
+        let more = page.has_more.unwrap_or(page.events.len() >= page_size);
+        let advanced = page.iter_last_key.is_some() && page.iter_last_key != key;
+        let empty = page.events.is_empty();
+        events.extend(page.events);
+        if empty || !more || !advanced {
+            break;
+        }
+        key = page.iter_last_key;
+    }
+    events.sort_by_cached_key(|e| {
+        e.get("timestamp").and_then(|t| t.as_str()).and_then(|t| time::parse_instant(t).ok())
+    });
+
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let lines: String = events.iter().map(|e| format!("{}\n", e)).collect();
+    std::fs::write(&part, lines).with_context(|| format!("Failed to write {}", path.display()))?;
+    std::fs::rename(&part, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(events.len())
+}
+
+fn count_lines(path: &std::path::Path) -> usize {
+    std::fs::read_to_string(path).map(|s| s.lines().count()).unwrap_or_default()
+}
+
+fn print_event(event: &Event) {
+    if output::human() {
+        println!("{}", format_row(event));
+    } else {
+        println!("{}", output::print_record(event));
+    }
+}
+
+/// An event's columns, as `events tail` prints them and the dashboard's Events tab lists them
+#[derive(Tabled, serde::Serialize)]
+pub struct EventRow {
+    #[tabled(rename = "Timestamp")]
+    timestamp: String,
+    #[tabled(rename = "Event")]
+    event_name: String,
+    #[tabled(rename = "Customer")]
+    customer: String,
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Properties")]
+    properties: String,
+}
+
+impl From<Event> for EventRow {
+    fn from(e: Event) -> Self {
+        Self {
+            timestamp: e.timestamp.unwrap_or_default(),
+            event_name: e.event_name.unwrap_or_default(),
+            customer: e.external_customer_id.unwrap_or_default(),
+            id: e.id.unwrap_or_default(),
+            properties: e.properties.map(|p| p.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+fn format_header() -> String {
+    let headers = EventRow::headers();
+    format!(
+        "{:<24}  {:<24}  {:<20}  {:<28}  {}",
+        headers[0].to_uppercase(),
+        headers[1].to_uppercase(),
+        headers[2].to_uppercase(),
+        headers[3].to_uppercase(),
+        headers[4].to_uppercase(),
+    )
+}
+
+fn format_row(e: &Event) -> String {
+    let row = EventRow::from(e.clone());
+    format!(
+        "{:<24}  {:<24}  {:<20}  {:<28}  {}",
+        row.timestamp,
+        row.event_name.cyan(),
+        row.customer,
+        row.id.dimmed(),
+        row.properties.dimmed(),
+    )
+}
+
+/// How CSV columns map onto events (`--mapping`).
+///
+/// Field values name a column, or give a literal prefixed with `=` (e.g.
+/// `"=api_call"`). Without `properties`, every unmapped column becomes a
+/// string property.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CsvMapping {
+    #[serde(default = "default_column::event_name")]
+    event_name: String,
+    #[serde(default = "default_column::external_customer_id")]
+    external_customer_id: String,
+    #[serde(default)]
+    timestamp: Option<String>,
+    #[serde(default)]
+    event_id: Option<String>,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    properties: Option<BTreeMap<String, PropertyColumn>>,
+}
+
+mod default_column {
+    pub fn event_name() -> String {
+        "event_name".into()
+    }
+    pub fn external_customer_id() -> String {
+        "external_customer_id".into()
+    }
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        Self {
+            event_name: default_column::event_name(),
+            external_customer_id: default_column::external_customer_id(),
+            timestamp: Some("timestamp".into()),
+            event_id: Some("event_id".into()),
+            source: None,
+            properties: None,
+        }
+    }
+}
+
+/// A property's column, optionally converted to a number or boolean
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PropertyColumn {
+    Column(String),
+    Typed {
+        column: String,
+        #[serde(rename = "type")]
+        kind: PropertyType,
+    },
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum PropertyType {
+    String,
+    Number,
+    Boolean,
+}
+
+impl CsvMapping {
+    /// Columns consumed by top-level fields (not copied into properties)
+    fn field_columns(&self) -> HashSet<&str> {
+        [Some(&self.event_name), Some(&self.external_customer_id), self.timestamp.as_ref(), self.event_id.as_ref(), self.source.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Build one event from a CSV row (`row` maps header → cell)
+    fn event(&self, row: &HashMap<&str, &str>) -> Result<serde_json::Value> {
+        let lookup = |spec: &str| -> Option<String> {
+            match spec.strip_prefix('=') {
+                Some(literal) => Some(literal.to_string()),
+                None => row.get(spec).filter(|v| !v.is_empty()).map(|v| v.to_string()),
+            }
+        };
+        let mut event = serde_json::Map::new();
+        for (field, spec, required) in [
+            ("event_name", Some(&self.event_name), true),
+            ("external_customer_id", Some(&self.external_customer_id), true),
+            ("timestamp", self.timestamp.as_ref(), false),
+            ("event_id", self.event_id.as_ref(), false),
+            ("source", self.source.as_ref(), false),
+        ] {
+            match spec.and_then(|s| lookup(s)) {
+                Some(value) if field == "timestamp" => {
+                    event.insert(field.into(), normalize_timestamp(value).into());
+                }
+                Some(value) => {
+                    event.insert(field.into(), value.into());
+                }
+                None if required => anyhow::bail!("missing {}", field),
+                None => {}
+            }
+        }
+
+        let mut props = serde_json::Map::new();
+        match &self.properties {
+            Some(map) => {
+                for (name, column) in map {
+                    let (column, kind) = match column {
+                        PropertyColumn::Column(c) => (c.as_str(), PropertyType::String),
+                        PropertyColumn::Typed { column, kind } => (column.as_str(), *kind),
+                    };
+                    let Some(raw) = lookup(column) else { continue };
+                    let value = match kind {
+                        PropertyType::String => raw.into(),
+                        PropertyType::Number => raw
+                            .parse::<f64>()
+                            .map(serde_json::Value::from)
+                            .map_err(|_| anyhow::anyhow!("property {}: '{}' is not a number", name, raw))?,
+                        PropertyType::Boolean => raw
+                            .parse::<bool>()
+                            .map(serde_json::Value::from)
+                            .map_err(|_| anyhow::anyhow!("property {}: '{}' is not true/false", name, raw))?,
+                    };
+                    props.insert(name.clone(), value);
+                }
+            }
+            None => {
+                let fields = self.field_columns();
+                for (column, value) in row {
+                    if !fields.contains(column) && !value.is_empty() {
+                        props.insert(column.to_string(), value.to_string().into());
+                    }
+                }
+            }
+        }
+        if !props.is_empty() {
+            event.insert("properties".into(), props.into());
+        }
+        Ok(event.into())
+    }
+}
+
+/// RFC 3339 with milliseconds when the value parses as a date or time; unchanged otherwise
+fn normalize_timestamp(value: String) -> String {
+    time::parse_instant(&value)
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or(value)
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct CsvBatchRow {
+    #[tabled(rename = "Batch")]
+    batch: usize,
+    #[tabled(rename = "Rows")]
+    rows: String,
+    #[tabled(rename = "Events")]
+    events: usize,
+    #[tabled(rename = "Result")]
+    result: String,
+}
+
+/// An event and the CSV line it came from
+type NumberedEvent = (usize, serde_json::Value);
+
+async fn ingest_csv(client: &ApiClient, file: &str, mapping: &CsvMapping, batch_size: usize, concurrency: usize) -> Result<()> {
+    let mut reader = csv::Reader::from_path(file).with_context(|| format!("Failed to read {}", file))?;
+    let headers = reader.headers()?.clone();
+
+    // Valid rows; mapping errors are reported up front
+    let mut events: Vec<NumberedEvent> = vec![];
+    let mut invalid = 0;
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = record.with_context(|| format!("Invalid CSV in {}", file))?;
+        let row: HashMap<&str, &str> = headers.iter().zip(record.iter()).collect();
+        match mapping.event(&row) {
+            Ok(event) => events.push((line, event)),
+            Err(e) => {
+                invalid += 1;
+                output::warning(&format!("line {}: {:#}", line, e));
+            }
+        }
+    }
+    if events.is_empty() {
+        anyhow::bail!("No valid events in {}", file);
+    }
+
+    let batches: Vec<&[NumberedEvent]> = events.chunks(batch_size).collect();
+    let bar = spinner::create_progress(events.len() as u64, "Ingesting", "ingest");
+    let mut results: Vec<(usize, &[NumberedEvent], Result<()>)> = stream::iter(batches.into_iter().enumerate())
+        .map(|(i, batch)| {
+            let bar = bar.clone();
+            async move {
+                let events: Vec<serde_json::Value> = batch.iter().map(|(_, e)| e.clone()).collect();
+                let result = post_bulk(client, &events).await;
+                bar.inc(batch.len() as u64);
+                (i, batch, result)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    bar.finish_and_clear();
+    results.sort_by_key(|(i, _, _)| *i);
+
+    let sent: usize = results.iter().filter(|(_, _, r)| r.is_ok()).map(|(_, b, _)| b.len()).sum();
+    let failed_batches = results.iter().filter(|(_, _, r)| r.as_ref().is_err_and(|e| !e.is::<DryRun>())).count();
+    let rows: Vec<CsvBatchRow> = results
+        .into_iter()
+        .map(|(i, batch, result)| CsvBatchRow {
+            batch: i + 1,
+            rows: format!("{}-{}", batch[0].0, batch[batch.len() - 1].0),
+            events: batch.len(),
+            result: match result {
+                Ok(()) => "ok".green().to_string(),
+                Err(e) if e.is::<DryRun>() => "not sent (dry run)".dimmed().to_string(),
+                Err(e) => format!("{}: {:#}", "failed".red(), e),
+            },
+        })
+        .collect();
+    println!("{}", output::print_table(&rows));
+    println!();
+    output::info(&format!(
+        "{} events ingested, {} failed in {} batch(es), {} invalid rows skipped",
+        sent,
+        events.len() - sent,
+        failed_batches,
+        invalid
+    ));
+    if failed_batches > 0 {
+        anyhow::bail!("{} of {} batches failed", failed_batches, rows.len());
+    }
+    Ok(())
+}
+
+/// How `ingest-logs` turns a log line into an event.
+///
+/// With `--regex`, rule values are capture templates (`$1`, `${name}`);
+/// otherwise lines are JSON and values starting with `$` are paths into
+/// the line (`$.user.id`). Anything else is a literal. Without a regex or
+/// rules, each JSON line is sent as the event itself.
+struct LogMapping {
+    regex: Option<Regex>,
+    rules: Vec<LogRule>,
+    /// Report lines that aren't JSON objects as invalid instead of skipping
+    /// them, for input that should be nothing but events
+    strict: bool,
+    /// Give each event an `event_id`, for input that should be nothing but events
+    ids: Option<EventIdArgs>,
+}
+
+/// One `--map FIELD=VALUE` rule
+struct LogRule {
+    target: LogTarget,
+    value: String,
+}
+
+enum LogTarget {
+    Field(&'static str),
+    /// A property, converted when a type was given (`tokens:number`)
+    Property(String, Option<PropertyType>),
+}
+
+impl LogRule {
+    fn parse(spec: &str) -> Result<Self> {
+        let (field, value) = spec
+            .split_once('=')
+            .with_context(|| format!("Invalid --map '{}': expected FIELD=VALUE", spec))?;
+        let (name, kind) = match field.split_once(':') {
+            Some((name, kind)) => (name, Some(kind)),
+            None => (field, None),
+        };
+        let target = match name {
+            "name" | "event_name" => Some("event_name"),
+            "customer" | "external_customer_id" => Some("external_customer_id"),
+            "timestamp" | "time" => Some("timestamp"),
+            "id" | "event_id" => Some("event_id"),
+            "source" => Some("source"),
+            _ => None,
+        };
+        let target = match (target, kind) {
+            (Some(field), None) => LogTarget::Field(field),
+            (Some(_), Some(_)) => anyhow::bail!("Invalid --map '{}': only properties take a type", spec),
+            (None, kind) => {
+                let name = name.strip_prefix("properties.").unwrap_or(name);
+                if name.is_empty() {
+                    anyhow::bail!("Invalid --map '{}': missing field name", spec);
+                }
+                let kind = match kind {
+                    None => None,
+                    Some("string") => Some(PropertyType::String),
+                    Some("number") => Some(PropertyType::Number),
+                    Some("boolean" | "bool") => Some(PropertyType::Boolean),
+                    Some(other) => anyhow::bail!("Invalid --map '{}': unknown type '{}' (string, number, boolean)", spec, other),
+                };
+                LogTarget::Property(name.to_string(), kind)
+            }
+        };
+        Ok(Self { target, value: value.to_string() })
+    }
+}
+
+/// What a line's rule values are resolved against
+enum LogLine<'h> {
+    Captures(regex::Captures<'h>),
+    Json(serde_json::Value),
+}
+
+impl LogLine<'_> {
+    /// A rule's value for this line; `None` when it resolves to nothing
+    fn resolve(&self, spec: &str) -> Option<serde_json::Value> {
+        match self {
+            LogLine::Captures(caps) => {
+                let mut out = String::new();
+                caps.expand(spec, &mut out);
+                (!out.is_empty()).then(|| out.into())
+            }
+            LogLine::Json(value) => match spec.strip_prefix('$') {
+                Some(path) => json_path(value, path).filter(|v| !v.is_null()).cloned(),
+                None => Some(spec.into()),
+            },
+        }
+    }
+}
+
+/// Look up a dotted path (`.usage.tokens`, `.items.0.id`) in a JSON value
+fn json_path<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.').filter(|s| !s.is_empty()).try_fold(value, |v, key| match v {
+        serde_json::Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => v.get(key),
+    })
+}
+
+impl LogMapping {
+    fn new(regex: Option<&str>, maps: &[String]) -> Result<Self> {
+        let regex = regex.map(Regex::new).transpose().context("Invalid --regex")?;
+        let rules = maps.iter().map(|m| LogRule::parse(m)).collect::<Result<Vec<_>>>()?;
+        let has = |field: &str| rules.iter().any(|r| matches!(r.target, LogTarget::Field(f) if f == field));
+        if (regex.is_some() || !rules.is_empty()) && !(has("event_name") && has("external_customer_id")) {
+            anyhow::bail!("--map needs both a name and a customer rule (e.g. --map 'name=$1' --map 'customer=$2')");
+        }
+        Ok(Self { regex, rules, strict: false, ids: None })
+    }
+
+    /// Lines that are events as they are, e.g. piped from another tool
+    fn events(ids: EventIdArgs) -> Self {
+        Self { regex: None, rules: vec![], strict: true, ids: Some(ids) }
+    }
+
+    /// The event for one line; `None` if the line doesn't match the regex or
+    /// isn't a JSON object (unless strict), or is blank
+    fn event(&self, line: &str) -> Option<Result<serde_json::Value>> {
+        let line = match &self.regex {
+            Some(re) => LogLine::Captures(re.captures(line)?),
+            None if line.trim().is_empty() => return None,
+            None => {
+                let value = match serde_json::from_str::<serde_json::Value>(line) {
+                    Ok(value) if value.is_object() => value,
+                    Ok(_) if self.strict => return Some(Err(anyhow::anyhow!("not a JSON object"))),
+                    Err(e) if self.strict => return Some(Err(anyhow::anyhow!("invalid JSON: {}", e))),
+                    _ => return None,
+                };
+                if self.rules.is_empty() {
+                    let mut value = value;
+                    return Some(match (value.get("event_name"), &self.ids) {
+                        (None, _) => Err(anyhow::anyhow!("missing event_name")),
+                        (Some(_), Some(ids)) => ids.assign(&mut value).map(|_| value),
+                        (Some(_), None) => Ok(value),
+                    });
+                }
+                LogLine::Json(value)
+            }
+        };
+        Some(self.build(&line))
+    }
+
+    fn build(&self, line: &LogLine) -> Result<serde_json::Value> {
+        let mut event = serde_json::Map::new();
+        let mut props = serde_json::Map::new();
+        for rule in &self.rules {
+            let Some(value) = line.resolve(&rule.value) else { continue };
+            match &rule.target {
+                LogTarget::Field(field) => {
+                    let text = match value {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    let text = if *field == "timestamp" { normalize_timestamp(text) } else { text };
+                    event.insert(field.to_string(), text.into());
+                }
+                LogTarget::Property(name, kind) => {
+                    let value = match (kind, value) {
+                        (None, value) => value,
+                        (Some(PropertyType::String), serde_json::Value::String(s)) => s.into(),
+                        (Some(PropertyType::String), other) => other.to_string().into(),
+                        (Some(PropertyType::Number), v @ serde_json::Value::Number(_)) => v,
+                        (Some(PropertyType::Number), v) => v
+                            .as_str()
+                            .and_then(|s| s.parse::<f64>().ok())
+                            .map(serde_json::Value::from)
+                            .ok_or_else(|| anyhow::anyhow!("property {}: {} is not a number", name, v))?,
+                        (Some(PropertyType::Boolean), v @ serde_json::Value::Bool(_)) => v,
+                        (Some(PropertyType::Boolean), v) => v
+                            .as_str()
+                            .and_then(|s| s.parse::<bool>().ok())
+                            .map(serde_json::Value::from)
+                            .ok_or_else(|| anyhow::anyhow!("property {}: {} is not true/false", name, v))?,
+                    };
+                    props.insert(name.clone(), value);
+                }
+            }
+        }
+        for field in ["event_name", "external_customer_id"] {
+            if !event.contains_key(field) {
+                anyhow::bail!("missing {}", field);
+            }
+        }
+        if !props.is_empty() {
+            event.insert("properties".into(), props.into());
+        }
+        Ok(event.into())
+    }
+}
+
+/// How events get an `event_id` before they're sent. The server ignores an
+/// event whose ID it has already seen, so a retried batch isn't counted twice.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct EventIdArgs {
+    /// Use this property (or top-level field) as each event's event_id instead
+    /// of generating a UUIDv7; events that already have an event_id keep it
+    #[arg(long, value_name = "PROP")]
+    pub idempotency_key_field: Option<String>,
+}
+
+impl EventIdArgs {
+    /// Fill in the event's `event_id` if it has none, returning the ID
+    fn assign(&self, event: &mut serde_json::Value) -> Result<String> {
+        let Some(fields) = event.as_object_mut() else {
+            anyhow::bail!("not a JSON object");
+        };
+        if let Some(id) = fields.get("event_id").and_then(id_text) {
+            return Ok(id);
+        }
+        let id = match &self.idempotency_key_field {
+            Some(field) => fields
+                .get("properties")
+                .and_then(|props| props.get(field))
+                .or_else(|| fields.get(field))
+                .and_then(id_text)
+                .ok_or_else(|| anyhow::anyhow!("missing {} for the event_id", field))?,
+            None => ids::uuid_v7(),
+        };
+        fields.insert("event_id".into(), id.clone().into());
+        Ok(id)
+    }
+}
+
+/// A JSON value as an event ID; blank strings and null don't count
+fn id_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) if s.trim().is_empty() => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Send events to the bulk endpoint. When every event has an ID, the batch
+/// carries an idempotency key derived from them, so it can be safely retried.
+async fn post_bulk(client: &ApiClient, events: &[serde_json::Value]) -> Result<()> {
+    let body = serde_json::json!({ "events": events });
+    let event_ids: Option<Vec<String>> = events.iter().map(|e| e.get("event_id").and_then(id_text)).collect();
+    let _: serde_json::Value = match event_ids {
+        Some(event_ids) if !event_ids.is_empty() => {
+            let key = ids::stable_hash(event_ids.iter().map(String::as_str));
+            client.post_idempotent("/v1/events/bulk", &body, &key).await?
+        }
+        _ => client.post("/v1/events/bulk", &body).await?,
+    };
+    Ok(())
+}
+
+/// Poll until every event in `event_ids` can be fetched. Events are queued
+/// when ingested, so they appear one by one as the server processes them.
+async fn wait_processed(client: &ApiClient, event_ids: Vec<String>, timeout: Duration) -> Result<()> {
+    let total = event_ids.len();
+    let unseen = std::cell::RefCell::new(event_ids);
+    let settled = wait::poll("ingested events", timeout, || async {
+        let checking = unseen.borrow().clone();
+        let found: Vec<(String, Result<serde_json::Value>)> = stream::iter(checking)
+            .map(|id| async move {
+                let event = client.get(&format!("/v1/events/{}", id)).await;
+                (id, event)
+            })
+            .buffer_unordered(8)
+            .collect()
+            .await;
+        let mut missing = vec![];
+        for (id, event) in found {
+            match event {
+                Ok(_) => {}
+                Err(e) if is_not_found(&e) => missing.push(id),
+                Err(e) => return Err(e),
+            }
+        }
+        let state = format!("{} of {} processed", total - missing.len(), total);
+        let done = missing.is_empty();
+        *unseen.borrow_mut() = missing;
+        Ok(match done {
+            true => wait::Status::Settled(state, ()),
+            false => wait::Status::Pending(state),
+        })
+    })
+    .await?;
+    match settled {
+        Some(()) => output::success(&format!("All {} event(s) processed.", total)),
+        None => output::info("Stopped waiting; the remaining events are still queued on the server."),
+    }
+    Ok(())
+}
+
+fn is_not_found(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| matches!(cause.downcast_ref::<ApiClientError>(), Some(ApiClientError::NotFound(_))))
+}
+
+/// Read an NDJSON file of events, one JSON object per line; blank lines are ignored
+fn read_ndjson(path: &str) -> Result<Vec<serde_json::Value>> {
+    let data = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let event: serde_json::Value = serde_json::from_str(line)
+                .with_context(|| format!("Invalid JSON on line {} of {}", i + 1, path))?;
+            if !event.is_object() {
+                anyhow::bail!("Line {} of {} is not a JSON object", i + 1, path);
+            }
+            Ok(event)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct LogStats {
+    lines: usize,
+    skipped: usize,
+    invalid: usize,
+    ingested: usize,
+    failed: usize,
+}
+
+/// Read stdin until EOF or Ctrl-C, sending events once `batch_size` are
+/// pending or `flush_every` has passed since the oldest pending one
+async fn ingest_logs(client: &ApiClient, mapping: &LogMapping, batch_size: usize, flush_every: Duration, dry_run: bool) -> Result<()> {
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut pending: Vec<serde_json::Value> = vec![];
+    let mut deadline: Option<tokio::time::Instant> = None;
+    let mut stats = LogStats::default();
+
+    if output::human() && !dry_run {
+        let what = if mapping.strict { "events" } else { "log lines" };
+        output::info(&format!("Reading {} from stdin (Ctrl-C to stop)...", what));
+    }
+    loop {
+        let flush_at = deadline.unwrap_or_else(tokio::time::Instant::now);
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line.context("Failed to read stdin")? else { break };
+                stats.lines += 1;
+                match mapping.event(&line) {
+                    None => stats.skipped += 1,
+                    Some(Err(e)) => {
+                        stats.invalid += 1;
+                        output::warning(&format!("line {}: {:#}", stats.lines, e));
+                    }
+                    Some(Ok(event)) if dry_run => println!("{}", event),
+                    Some(Ok(event)) => {
+                        pending.push(event);
+                        deadline.get_or_insert_with(|| tokio::time::Instant::now() + flush_every);
+                    }
+                }
+                if pending.len() >= batch_size {
+                    flush_logs(client, &mut pending, &mut stats).await;
+                    deadline = None;
+                }
+            }
+            _ = tokio::time::sleep_until(flush_at), if deadline.is_some() => {
+                flush_logs(client, &mut pending, &mut stats).await;
+                deadline = None;
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    flush_logs(client, &mut pending, &mut stats).await;
+
+    if dry_run {
+        if stats.skipped > 0 {
+            output::warning(&format!("{} of {} lines didn't match", stats.skipped, stats.lines));
+        }
+        return Ok(());
+    }
+    output::info(&format!(
+        "{} lines read: {} events ingested, {} failed, {} invalid, {} skipped",
+        stats.lines, stats.ingested, stats.failed, stats.invalid, stats.skipped
+    ));
+    if stats.failed > 0 {
+        anyhow::bail!("{} events could not be ingested", stats.failed);
+    }
+    Ok(())
+}
+
+/// Send the pending events as one bulk request; failures are reported and dropped
+async fn flush_logs(client: &ApiClient, pending: &mut Vec<serde_json::Value>, stats: &mut LogStats) {
+    if pending.is_empty() {
+        return;
+    }
+    let events = std::mem::take(pending);
+    let n = events.len();
+    match post_bulk(client, &events).await {
+        Ok(_) => {
+            stats.ingested += n;
+            if output::human() {
+                output::success(&format!("{} events ingested ({} total)", n, stats.ingested));
+            }
+        }
+        Err(e) => {
+            stats.failed += n;
+            output::warning(&format!("{} events dropped: {:#}", n, e));
+        }
+    }
+    spinner::emit(serde_json::json!({ "phase": "ingest", "done": stats.ingested, "total": null, "failed": stats.failed }));
+}
+
+/// How one simulated property gets its value (`--properties-template`).
+///
+/// A plain JSON value is used as-is; `{"int": [1, 500]}` and
+/// `{"float": [0.5, 2.0]}` draw uniformly from a range, `{"choice": ["a", "b"]}`
+/// or `{"choice": {"a": 3, "b": 1}}` pick a value (optionally weighted), and
+/// `{"bool": 0.2}` is true with that probability.
+enum Distribution {
+    Constant(serde_json::Value),
+    Int(i64, i64),
+    Float(f64, f64),
+    Choice(Vec<(serde_json::Value, f64)>),
+    Bool(f64),
+}
+
+impl Distribution {
+    fn parse(spec: &serde_json::Value) -> Result<Self> {
+        let Some((kind, arg)) = spec.as_object().filter(|o| o.len() == 1).and_then(|o| o.iter().next()) else {
+            return Ok(Self::Constant(spec.clone()));
+        };
+        let range = |arg: &serde_json::Value| -> Result<(serde_json::Value, serde_json::Value)> {
+            match arg.as_array().map(Vec::as_slice) {
+                Some([lo, hi]) => Ok((lo.clone(), hi.clone())),
+                _ => anyhow::bail!("`{}` takes [min, max]", kind),
+            }
+        };
+        Ok(match kind.as_str() {
+            "int" => {
+                let (lo, hi) = range(arg)?;
+                match (lo.as_i64(), hi.as_i64()) {
+                    (Some(lo), Some(hi)) if lo <= hi => Self::Int(lo, hi),
+                    _ => anyhow::bail!("`int` takes [min, max] integers with min <= max"),
+                }
+            }
+            "float" => {
+                let (lo, hi) = range(arg)?;
+                match (lo.as_f64(), hi.as_f64()) {
+                    (Some(lo), Some(hi)) if lo <= hi => Self::Float(lo, hi),
+                    _ => anyhow::bail!("`float` takes [min, max] numbers with min <= max"),
+                }
+            }
+            "choice" => {
+                let options: Vec<(serde_json::Value, f64)> = match arg {
+                    serde_json::Value::Array(values) => values.iter().map(|v| (v.clone(), 1.0)).collect(),
+                    serde_json::Value::Object(weights) => weights
+                        .iter()
+                        .map(|(v, w)| match w.as_f64() {
+                            Some(w) if w >= 0.0 => Ok((v.clone().into(), w)),
+                            _ => anyhow::bail!("`choice` weight for '{}' must be a non-negative number", v),
+                        })
+                        .collect::<Result<_>>()?,
+                    _ => anyhow::bail!("`choice` takes an array of values or an object of value weights"),
+                };
+                if options.iter().map(|(_, w)| w).sum::<f64>() <= 0.0 {
+                    anyhow::bail!("`choice` needs at least one option with a positive weight");
+                }
+                Self::Choice(options)
+            }
+            "bool" => match arg.as_f64() {
+                Some(p) if (0.0..=1.0).contains(&p) => Self::Bool(p),
+                _ => anyhow::bail!("`bool` takes a probability between 0 and 1"),
+            },
+            _ => Self::Constant(spec.clone()),
+        })
+    }
+
+    fn sample(&self, rng: &mut fastrand::Rng) -> serde_json::Value {
+        match self {
+            Self::Constant(v) => v.clone(),
+            Self::Int(lo, hi) => rng.i64(*lo..=*hi).into(),
+            Self::Float(lo, hi) => (lo + rng.f64() * (hi - lo)).into(),
+            Self::Choice(options) => {
+                let total: f64 = options.iter().map(|(_, w)| w).sum();
+                let mut pick = rng.f64() * total;
+                for (value, weight) in options {
+                    if pick < *weight {
+                        return value.clone();
+                    }
+                    pick -= weight;
+                }
+                options.last().map(|(v, _)| v.clone()).unwrap_or_default()
+            }
+            Self::Bool(p) => (rng.f64() < *p).into(),
+        }
+    }
+}
+
+/// Events per second from `100/s`, `500/m`, `1000/h`, or a bare number (per second)
+fn parse_rate(rate: &str) -> Result<f64> {
+    let (count, unit) = rate.trim().split_once('/').unwrap_or((rate.trim(), "s"));
+    let per: f64 = match unit {
+        "s" | "sec" => 1.0,
+        "m" | "min" => 60.0,
+        "h" | "hr" => 3600.0,
+        _ => anyhow::bail!("Invalid rate unit '{}' in '{}': use /s, /m, or /h", unit, rate),
+    };
+    match count.parse::<f64>() {
+        Ok(n) if n > 0.0 => Ok(n / per),
+        _ => anyhow::bail!("Invalid rate '{}': expected e.g. 100/s", rate),
+    }
+}
+
+struct Simulation {
+    event_name: String,
+    customers: Vec<String>,
+    properties: Vec<(String, Distribution)>,
+    per_second: f64,
+    /// Window before now that timestamps are spread over, instead of pacing
+    backfill: Option<Duration>,
+    start: chrono::DateTime<chrono::Utc>,
+}
+
+impl Simulation {
+    /// The `i`th event. Paced events are stamped at a random instant within
+    /// their slot of the run; backfilled ones anywhere in the window.
+    fn event(&self, i: usize, rng: &mut fastrand::Rng) -> serde_json::Value {
+        let at = match self.backfill {
+            Some(window) => self.start - window.mul_f64(rng.f64()),
+            None => self.start + Duration::from_secs_f64((i as f64 + rng.f64()) / self.per_second),
+        };
+        let mut props = serde_json::Map::new();
+        for (name, dist) in &self.properties {
+            props.insert(name.clone(), dist.sample(rng));
+        }
+        serde_json::json!({
+            "event_name": self.event_name,
+            "external_customer_id": self.customers[rng.usize(..self.customers.len())],
+            "timestamp": at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "source": "simulate",
+            "properties": props,
+        })
+    }
+
+    /// When the batch starting at event `first` should be sent; `None` to send right away
+    fn due(&self, first: usize) -> Option<tokio::time::Instant> {
+        self.backfill.is_none().then(|| {
+            let offset = Duration::from_secs_f64(first as f64 / self.per_second);
+            tokio::time::Instant::now() + (self.start + offset - chrono::Utc::now()).to_std().unwrap_or_default()
+        })
+    }
+}
+
+/// Generate `total` events and send them in batches, paced to the simulation's
+/// rate unless backfilling
+async fn simulate(
+    client: &ApiClient,
+    sim: &Simulation,
+    total: usize,
+    rng: &mut fastrand::Rng,
+    batch_size: usize,
+    concurrency: usize,
+) -> Result<()> {
+    let bar = spinner::create_progress(total as u64, "Simulating", "ingest");
+    let results: Vec<(usize, Result<()>)> = stream::iter((0..total).step_by(batch_size))
+        .then(|first| {
+            let events: Vec<serde_json::Value> = (first..total.min(first + batch_size)).map(|i| sim.event(i, rng)).collect();
+            let due = sim.due(first);
+            async move {
+                if let Some(due) = due {
+                    tokio::time::sleep_until(due).await;
+                }
+                events
+            }
+        })
+        .map(|events| {
+            let bar = bar.clone();
+            async move {
+                let n = events.len();
+                let result = client
+                    .post::<_, serde_json::Value>("/v1/events/bulk", &serde_json::json!({ "events": events }))
+                    .await
+                    .map(|_| ());
+                bar.inc(n as u64);
+                (n, result)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    bar.finish_and_clear();
+
+    let sent: usize = results.iter().filter(|(_, r)| r.is_ok()).map(|(n, _)| n).sum();
+    let failed: Vec<&anyhow::Error> = results.iter().filter_map(|(_, r)| r.as_ref().err()).collect();
+    let elapsed = (chrono::Utc::now() - sim.start).num_milliseconds() as f64 / 1000.0;
+    output::success(&format!(
+        "{} '{}' events ingested for {} customers in {:.1}s",
+        sent,
+        sim.event_name,
+        sim.customers.len(),
+        elapsed
+    ));
+    if let Some(e) = failed.first() {
+        output::warning(&format!("{} events in {} batch(es) failed; first error: {:#}", total - sent, failed.len(), e));
+        anyhow::bail!("{} of {} batches failed", failed.len(), results.len());
+    }
     Ok(())
 }
@@ -0,0 +1,177 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use futures::future::try_join_all;
+use serde_json::Value;
+use tabled::Tabled;
+use zip::write::SimpleFileOptions;
+
+use crate::api::client::ApiClient;
+use crate::utils::{output, spinner};
+
+/// Snapshot the tenant's configuration for backups or moving it between environments
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Directory to write to, or a path ending in .zip for a single archive
+    /// (default: flexprice-export-<timestamp>)
+    #[arg(long)]
+    out: Option<String>,
+    /// Format of each resource file
+    #[arg(long, value_enum, default_value_t = FileFormat::Json)]
+    format: FileFormat,
+    /// Export only these resources (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    only: Vec<Resource>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum FileFormat {
+    Json,
+    Yaml,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum Resource {
+    Customers,
+    Plans,
+    Prices,
+    Features,
+    Meters,
+    Entitlements,
+    Subscriptions,
+}
+
+const RESOURCES: &[Resource] = &[
+    Resource::Customers,
+    Resource::Plans,
+    Resource::Prices,
+    Resource::Features,
+    Resource::Meters,
+    Resource::Entitlements,
+    Resource::Subscriptions,
+];
+
+impl Resource {
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::Customers => "customers",
+            Resource::Plans => "plans",
+            Resource::Prices => "prices",
+            Resource::Features => "features",
+            Resource::Meters => "meters",
+            Resource::Entitlements => "entitlements",
+            Resource::Subscriptions => "subscriptions",
+        }
+    }
+
+    pub fn endpoint(self) -> String {
+        format!("/v1/{}", self.name())
+    }
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ExportRow {
+    #[tabled(rename = "Resource")]
+    resource: String,
+    #[tabled(rename = "Count")]
+    count: usize,
+    #[tabled(rename = "File")]
+    file: String,
+}
+
+pub async fn handle(args: ExportArgs, client: ApiClient) -> Result<()> {
+    let creds = client.credentials();
+
+    let out = args
+        .out
+        .unwrap_or_else(|| format!("flexprice-export-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+    let resources: Vec<Resource> = if args.only.is_empty() {
+        RESOURCES.to_vec()
+    } else {
+        RESOURCES.iter().copied().filter(|r| args.only.contains(r)).collect()
+    };
+
+    let progress = spinner::create_progress(resources.len() as u64, "Exporting", "export");
+    let fetched = try_join_all(resources.iter().map(|&res| {
+        let (client, progress) = (&client, &progress);
+        async move {
+            let items: Vec<Value> = client
+                .get_all(&res.endpoint())
+                .await
+                .with_context(|| format!("Failed to export {}", res.name()))?;
+            progress.inc(1);
+            Ok::<_, anyhow::Error>((res, items))
+        }
+    }))
+    .await;
+    match fetched {
+        Ok(_) => progress.finish_and_clear(),
+        Err(_) => progress.abandon(),
+    }
+    let fetched = fetched?;
+
+    let ext = match args.format {
+        FileFormat::Json => "json",
+        FileFormat::Yaml => "yaml",
+    };
+    let render = |value: &Value| -> Result<String> {
+        Ok(match args.format {
+            FileFormat::Json => serde_json::to_string_pretty(value)? + "\n",
+            FileFormat::Yaml => serde_yaml::to_string(value)?,
+        })
+    };
+
+    let mut files = vec![];
+    let mut rows = vec![];
+    let mut counts = serde_json::Map::new();
+    for (res, items) in fetched {
+        let file = format!("{}.{}", res.name(), ext);
+        rows.push(ExportRow { resource: res.name().to_string(), count: items.len(), file: file.clone() });
+        counts.insert(res.name().into(), items.len().into());
+        files.push((file, render(&Value::Array(items))?));
+    }
+    // What was exported and from where, for whoever restores it
+    let manifest = serde_json::json!({
+        "exported_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "api_url": creds.api_url,
+        "environment_id": creds.environment_id,
+        "cli_version": env!("CARGO_PKG_VERSION"),
+        "counts": counts,
+    });
+    files.push((format!("export.{}", ext), render(&manifest)?));
+
+    if out.to_lowercase().ends_with(".zip") {
+        write_archive(&out, &files)?;
+    } else {
+        write_dir(&out, &files)?;
+    }
+
+    println!("{}", output::print_table(&rows));
+    if output::human() {
+        let total: usize = rows.iter().map(|r| r.count).sum();
+        output::success(&format!("Exported {} resources to {}", total, out));
+    }
+    Ok(())
+}
+
+fn write_dir(dir: &str, files: &[(String, String)]) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir))?;
+    for (name, content) in files {
+        let path = Path::new(dir).join(name);
+        std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn write_archive(path: &str, files: &[(String, String)]) -> Result<()> {
+    let file = std::fs::File::create(path).with_context(|| format!("Failed to create {}", path))?;
+    let mut zip = zip::ZipWriter::new(file);
+    for (name, content) in files {
+        zip.start_file(name.as_str(), SimpleFileOptions::default())?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
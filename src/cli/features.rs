@@ -4,33 +4,90 @@ use tabled::Tabled;
 
 use crate::api::client::ApiClient;
 use crate::api::models::{Feature, ListResponse};
-use crate::cli::auth::require_auth;
+use crate::cli::completions;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::fanout::{self, DeleteManyArgs, IdsArgs};
 use crate::utils::{output, spinner};
 
 #[derive(Subcommand)]
 pub enum FeatureCommands {
-    /// List all features
+    /// List features, a page at a time
     List {
-        #[arg(long)]
-        json: bool,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get a feature by ID
+    /// Get a feature by ID, or many with --ids-file
     Get {
-        id: String,
-        #[arg(long)]
-        json: bool,
+        #[arg(add = completions::ids("/v1/features"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
     },
-    /// Create a new feature from a JSON file
+    /// Create features interactively or from a JSON file (object or array of objects)
     Create {
         #[arg(long)]
-        json: String,
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Update a feature from flags, a JSON file, or in $EDITOR
+    Update {
+        #[arg(add = completions::ids("/v1/features"))]
+        id: String,
+        #[arg(long, required_unless_present_any = ["edit", "name", "description"])]
+        json: Option<String>,
+        /// Edit the feature's current state in $EDITOR
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        /// New name
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        name: Option<String>,
+        /// New description
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        description: Option<String>,
+    },
+    /// Delete a feature by ID, or many from --ids-file or --filter
+    Delete {
+        #[arg(
+            add = completions::ids("/v1/features"),
+            required_unless_present_any = ["ids_file", "filters"],
+            conflicts_with_all = ["ids_file", "filters"]
+        )]
+        id: Option<String>,
+        #[command(flatten)]
+        many: DeleteManyArgs,
     },
-    /// Delete a feature by ID
-    Delete { id: String },
 }
 
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "feature",
+    fields: &[
+        Field::text("name", "Name").required(),
+        Field::text("lookup_key", "Lookup key").required(),
+        Field::choice("type", "Type", &["boolean", "metered", "static"]),
+        Field::text("meter_id", "Meter ID (metered features only)"),
+        Field::text("description", "Description"),
+    ],
+    check: None,
+};
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "name": "",
+  "lookup_key": "",
+  "type": "boolean",
+  "description": ""
+}"#;
+
 #[derive(Tabled, serde::Serialize)]
-struct FeatureRow {
+pub struct FeatureRow {
     #[tabled(rename = "ID")]
     id: String,
     #[tabled(rename = "Name")]
@@ -55,34 +112,55 @@ impl From<Feature> for FeatureRow {
     }
 }
 
-pub async fn handle(cmd: FeatureCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
-
+pub async fn handle(cmd: FeatureCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        FeatureCommands::List { json } => {
+        FeatureCommands::List { page } => {
             let sp = spinner::create_spinner("Fetching features...");
-            let resp: ListResponse<Feature> = client.get("/v1/features").await?;
+            let (items, pagination) = page.fetch::<Feature>(&client, "/v1/features").await?;
             sp.finish_and_clear();
-            let rows: Vec<FeatureRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            let rows: Vec<FeatureRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "feature"));
         }
-        FeatureCommands::Get { id, json } => {
+        FeatureCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/features", &many).await };
             let sp = spinner::create_spinner("Fetching feature...");
             let feature: Feature = client.get(&format!("/v1/features/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&feature, json));
+            println!("{}", output::print_detail(&feature));
         }
-        FeatureCommands::Create { json: file } => {
-            let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Creating feature...");
-            let feature: Feature = client.post("/v1/features", &body).await?;
-            sp.finish_and_clear();
-            output::success(&format!("Feature created: {}", feature.id));
-            println!("{}", output::print_detail(&feature, false));
+        FeatureCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<Feature>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                let sp = spinner::create_spinner("Creating feature...");
+                let feature: Feature = client.post("/v1/features", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Feature created: {}", feature.id));
+                println!("{}", output::print_detail(&feature));
+            }
+            Some(Payload::Many(items)) => {
+                batch::create_many(&client, items, file.as_deref(), &opts, "Feature created", "/v1/features").await?;
+            }
+        },
+        FeatureCommands::Update { id, json: file, name, description, .. } => {
+            let path = format!("/v1/features/{}", id);
+            let fields = editor::field_updates(&[
+                ("name", name.map(Into::into)),
+                ("description", description.map(Into::into)),
+            ]);
+            let body = match fields {
+                Some(body) => Some(body),
+                None => editor::update_body::<Feature>(&client, &path, file.as_deref()).await?,
+            };
+            if let Some(body) = body {
+                let sp = spinner::create_spinner("Updating feature...");
+                let feature: Feature = client.put(&path, &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Feature updated: {}", feature.id));
+                println!("{}", output::print_detail(&feature));
+            }
         }
-        FeatureCommands::Delete { id } => {
+        FeatureCommands::Delete { id, many } => {
+            let Some(id) = id else { return fanout::delete_many(&client, "/v1/features", "features", &many).await };
             let sp = spinner::create_spinner("Deleting feature...");
             client.delete_empty(&format!("/v1/features/{}", id)).await?;
             sp.finish_and_clear();
@@ -91,3 +169,12 @@ pub async fn handle(cmd: FeatureCommands) -> Result<()> {
     }
     Ok(())
 }
+
+/// Resolve a feature by its lookup key
+pub async fn find_by_lookup_key(client: &ApiClient, lookup_key: &str) -> Result<Feature> {
+    let resp: ListResponse<Feature> = client.get_with_query("/v1/features", &[("lookup_key", lookup_key)]).await?;
+    resp.items
+        .into_iter()
+        .find(|f| f.lookup_key.as_deref() == Some(lookup_key))
+        .ok_or_else(|| anyhow::anyhow!("No feature found with lookup key '{}'", lookup_key))
+}
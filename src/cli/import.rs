@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::io::{IsTerminal, Read};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use dialoguer::Confirm;
+use serde_json::{Map, Value};
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::cli::apply::satisfies;
+use crate::cli::auth::{require_auth, require_profile_auth};
+use crate::cli::export::Resource;
+use crate::config::globals;
+use crate::utils::{output, spinner};
+
+/// Recreate an `export` snapshot in this or another environment
+#[derive(Args)]
+pub struct ImportArgs {
+    /// Snapshot directory or .zip written by `flexprice export`
+    #[arg(long, value_name = "DIR|ZIP")]
+    dir: String,
+    /// Import into this profile's environment instead of the current one
+    #[arg(long, value_name = "PROFILE")]
+    target_profile: Option<String>,
+    /// Import only these resources (comma-separated). Subscriptions are only
+    /// imported when listed here.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    only: Vec<Resource>,
+    /// Import without asking for confirmation
+    #[arg(long, short)]
+    yes: bool,
+}
+
+/// Dependencies first, so every reference can be remapped when its resource is created
+const ORDER: &[Resource] = &[
+    Resource::Meters,
+    Resource::Features,
+    Resource::Plans,
+    Resource::Prices,
+    Resource::Entitlements,
+    Resource::Customers,
+    Resource::Subscriptions,
+];
+
+/// Fields the server assigns, dropped before creating a copy
+const SERVER_FIELDS: &[&str] = &[
+    "id", "tenant_id", "environment_id", "status", "created_at", "updated_at", "created_by", "updated_by",
+];
+
+/// Fields holding another resource's ID, remapped to the target's ID for it
+pub const REFERENCES: &[&str] = &["meter_id", "feature_id", "plan_id", "customer_id"];
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    /// Not in the target; will be created
+    Create,
+    /// Already in the target and matches the snapshot
+    Exists,
+    /// In the target under the same key but with different values; left alone
+    Conflict,
+    /// Can't be imported, e.g. no key to match on or a missing dependency
+    Skip,
+}
+
+/// What `import` will do with one snapshot resource
+#[derive(serde::Serialize)]
+struct Step {
+    resource: &'static str,
+    key: String,
+    source_id: String,
+    outcome: Outcome,
+    /// The matching resource in the target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_id: Option<String>,
+    /// Why a resource conflicts or is skipped
+    #[serde(skip_serializing_if = "String::is_empty")]
+    detail: String,
+    #[serde(skip)]
+    kind: Resource,
+    #[serde(skip)]
+    body: Map<String, Value>,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct StepRow {
+    #[tabled(rename = "")]
+    outcome: String,
+    #[tabled(rename = "Resource")]
+    resource: String,
+    #[tabled(rename = "Key")]
+    key: String,
+    #[tabled(rename = "Detail")]
+    detail: String,
+}
+
+pub async fn handle(args: ImportArgs) -> Result<()> {
+    let creds = match &args.target_profile {
+        Some(profile) => require_profile_auth(profile)?,
+        None => require_auth()?,
+    };
+    let client = ApiClient::new(creds.clone())?;
+
+    let kinds: Vec<Resource> = ORDER
+        .iter()
+        .copied()
+        .filter(|r| match args.only.is_empty() {
+            true => *r != Resource::Subscriptions,
+            false => args.only.contains(r),
+        })
+        .collect();
+    let snapshot = read_snapshot(&args.dir, &kinds)?;
+    if snapshot.values().all(Vec::is_empty) {
+        anyhow::bail!("Nothing to import from {}", args.dir);
+    }
+
+    let sp = spinner::create_spinner("Reading the target environment...");
+    let target = fetch_target(&client, &kinds).await;
+    sp.finish_and_clear();
+    let target = target?;
+
+    let (mut steps, mut ids) = plan(&kinds, &snapshot, &target);
+    let count = |o: Outcome| steps.iter().filter(|s| s.outcome == o).count();
+    let creates = count(Outcome::Create);
+    let summary = format!(
+        "{} to create, {} already present, {} conflicts, {} skipped",
+        creates,
+        count(Outcome::Exists),
+        count(Outcome::Conflict),
+        count(Outcome::Skip)
+    );
+
+    let shown: Vec<&Step> = steps.iter().filter(|s| s.outcome != Outcome::Exists).collect();
+    if !output::human() {
+        println!("{}", output::print_detail(&steps));
+    } else {
+        if !shown.is_empty() {
+            let rows: Vec<StepRow> = shown.iter().map(|s| step_row(s)).collect();
+            println!("{}", output::print_table(&rows));
+            println!();
+        }
+        output::info(&format!("Import into {}: {}.", target_name(&args, &creds.api_url), summary));
+    }
+
+    if creates == 0 || globals::get().dry_run {
+        if output::human() && globals::get().dry_run {
+            output::info("Dry run: nothing was changed.");
+        }
+        return Ok(());
+    }
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Refusing to create {} resource(s) without confirmation; pass --yes", creates);
+        }
+        let confirmed = Confirm::new()
+            .with_prompt(format!("  Create {} resource(s)?", creates))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            output::info("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let mut failed = 0;
+    for step in steps.iter_mut().filter(|s| s.outcome == Outcome::Create) {
+        let label = format!("{} {}", singular(step.kind), step.key);
+        match create(&client, step, &ids).await {
+            Ok(id) => {
+                output::success(&format!("{}: created ({})", label, id));
+                ids.insert(step.source_id.clone(), id);
+            }
+            Err(e) => {
+                failed += 1;
+                output::error(&format!("{}: {:#}", label, e));
+            }
+        }
+    }
+    println!();
+    output::info(&format!("{} created, {} failed", creates - failed, failed));
+    if failed > 0 {
+        anyhow::bail!("{} of {} resources failed to import", failed, creates);
+    }
+    Ok(())
+}
+
+fn target_name(args: &ImportArgs, api_url: &str) -> String {
+    match &args.target_profile {
+        Some(profile) => format!("profile '{}' ({})", profile, api_url),
+        None => api_url.to_string(),
+    }
+}
+
+/// Each resource's items from `<name>.json` or `<name>.yaml` in a directory or
+/// zip; resources missing from the snapshot are empty
+fn read_snapshot(path: &str, kinds: &[Resource]) -> Result<HashMap<Resource, Vec<Value>>> {
+    let mut archive = match path.to_lowercase().ends_with(".zip") {
+        true => {
+            let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+            Some(zip::ZipArchive::new(file).with_context(|| format!("{} is not a zip archive", path))?)
+        }
+        false if Path::new(path).is_dir() => None,
+        false => anyhow::bail!("{} is not a directory or .zip archive", path),
+    };
+
+    let mut snapshot = HashMap::new();
+    for &kind in kinds {
+        let mut items = vec![];
+        for ext in ["json", "yaml"] {
+            let name = format!("{}.{}", kind.name(), ext);
+            let content = match archive.as_mut() {
+                Some(zip) => match zip.by_name(&name) {
+                    Ok(mut entry) => {
+                        let mut content = String::new();
+                        entry.read_to_string(&mut content)?;
+                        Some(content)
+                    }
+                    Err(_) => None,
+                },
+                None => std::fs::read_to_string(Path::new(path).join(&name)).ok(),
+            };
+            let Some(content) = content else { continue };
+            items = match ext {
+                "json" => serde_json::from_str(&content),
+                _ => serde_yaml::from_str(&content).map_err(serde::de::Error::custom),
+            }
+            .with_context(|| format!("Invalid {} in {}", name, path))?;
+            break;
+        }
+        snapshot.insert(kind, items);
+    }
+    Ok(snapshot)
+}
+
+pub async fn fetch_target(client: &ApiClient, kinds: &[Resource]) -> Result<HashMap<Resource, Vec<Value>>> {
+    // Prices and entitlements are keyed by their plan, meter, and feature
+    let mut needed: Vec<Resource> = kinds.to_vec();
+    for dep in [Resource::Meters, Resource::Features, Resource::Plans, Resource::Customers] {
+        if !needed.contains(&dep) {
+            needed.push(dep);
+        }
+    }
+    let lists = futures::future::try_join_all(needed.iter().map(|r| async move {
+        let items: Vec<Value> = client.get_all(&r.endpoint()).await?;
+        Ok::<_, anyhow::Error>((*r, items))
+    }))
+    .await?;
+    Ok(lists.into_iter().collect())
+}
+
+/// Match every snapshot resource against the target by key. Returns the
+/// steps and a map from snapshot IDs to the IDs of matching target resources.
+fn plan(kinds: &[Resource], snapshot: &HashMap<Resource, Vec<Value>>, target: &HashMap<Resource, Vec<Value>>) -> (Vec<Step>, HashMap<String, String>) {
+    let source_keys = keys_by_id(snapshot);
+    let target_keys = keys_by_id(target);
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut steps = vec![];
+
+    for &kind in kinds {
+        let live: HashMap<String, &Value> = target
+            .get(&kind)
+            .into_iter()
+            .flatten()
+            .filter(|item| is_active(item))
+            .filter_map(|item| Some((key(kind, item, &target_keys)?, item)))
+            .collect();
+
+        for item in snapshot.get(&kind).into_iter().flatten() {
+            let source_id = str_field(item, "id");
+            let body = create_body(item);
+            let mut step = Step {
+                resource: kind.name(),
+                key: String::new(),
+                source_id: source_id.clone(),
+                outcome: Outcome::Skip,
+                target_id: None,
+                detail: String::new(),
+                kind,
+                body,
+            };
+            let Some(key) = key(kind, item, &source_keys) else {
+                step.key = source_id;
+                step.detail = format!("no {} to match on", key_field(kind));
+                steps.push(step);
+                continue;
+            };
+            step.key = key.clone();
+
+            if let Some(existing) = live.get(&key) {
+                let target_id = str_field(existing, "id");
+                ids.insert(source_id, target_id.clone());
+                let differs: Vec<&str> = step
+                    .body
+                    .iter()
+                    .filter(|(k, v)| !REFERENCES.contains(&k.as_str()) && !satisfies(existing.get(*k), v))
+                    .map(|(k, _)| k.as_str())
+                    .collect();
+                step.outcome = if differs.is_empty() { Outcome::Exists } else { Outcome::Conflict };
+                step.detail = differs.join(", ");
+                step.target_id = Some(target_id);
+                steps.push(step);
+                continue;
+            }
+
+            // Every reference must be to something that exists or will be created first
+            let missing = REFERENCES.iter().find_map(|field| {
+                let id = step.body.get(*field)?.as_str()?;
+                let known = ids.contains_key(id) || steps.iter().any(|s| s.source_id == id && s.outcome == Outcome::Create);
+                (!known).then(|| format!("{} {} is not in the snapshot or the target", field, id))
+            });
+            match missing {
+                Some(detail) => step.detail = detail,
+                None => step.outcome = Outcome::Create,
+            }
+            steps.push(step);
+        }
+    }
+    (steps, ids)
+}
+
+/// The key a resource is matched on. Resources owned by another (prices,
+/// entitlements, subscriptions) include their owner's key, looked up in
+/// `keys` by ID, so they match across environments.
+pub fn key(kind: Resource, item: &Value, keys: &HashMap<String, String>) -> Option<String> {
+    let field = |name: &str| Some(str_field(item, name)).filter(|v| !v.is_empty());
+    let owner = |name: &str| field(name).and_then(|id| keys.get(&id).cloned());
+    match kind {
+        Resource::Meters => field("event_name"),
+        Resource::Features | Resource::Plans => field("lookup_key"),
+        Resource::Customers => field("external_id"),
+        Resource::Prices => field("lookup_key").or_else(|| {
+            // Without a lookup key, a price is identified by what it charges for
+            let parts = [
+                owner("plan_id")?,
+                owner("meter_id").unwrap_or_default(),
+                field("currency").unwrap_or_default().to_lowercase(),
+                field("billing_period").unwrap_or_default(),
+                item.get("amount").and_then(number).map(|a| a.to_string()).unwrap_or_default(),
+            ];
+            Some(parts.join("/"))
+        }),
+        Resource::Entitlements => Some(format!("{}/{}", owner("plan_id")?, owner("feature_id")?)),
+        Resource::Subscriptions => Some(format!("{}/{}", owner("customer_id")?, owner("plan_id")?)),
+    }
+}
+
+fn key_field(kind: Resource) -> &'static str {
+    match kind {
+        Resource::Meters => "event_name",
+        Resource::Customers => "external_id",
+        Resource::Entitlements => "plan and feature",
+        Resource::Subscriptions => "customer and plan",
+        Resource::Prices => "lookup_key or plan",
+        _ => "lookup_key",
+    }
+}
+
+/// Keys of the resources other keys are built from, by ID
+pub fn keys_by_id(resources: &HashMap<Resource, Vec<Value>>) -> HashMap<String, String> {
+    let empty = HashMap::new();
+    let mut keys = HashMap::new();
+    for kind in [Resource::Meters, Resource::Features, Resource::Plans, Resource::Customers] {
+        for item in resources.get(&kind).into_iter().flatten() {
+            if let Some(key) = key(kind, item, &empty) {
+                keys.insert(str_field(item, "id"), key);
+            }
+        }
+    }
+    keys
+}
+
+/// Archived resources and ended subscriptions don't count as matches
+pub fn is_active(item: &Value) -> bool {
+    let status = item
+        .get("subscription_status")
+        .or_else(|| item.get("status"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    !matches!(status.as_str(), "archived" | "deleted" | "cancelled" | "canceled")
+}
+
+/// A snapshot item without server-assigned fields or expanded relations
+pub fn create_body(item: &Value) -> Map<String, Value> {
+    let Some(obj) = item.as_object() else { return Map::new() };
+    obj.iter()
+        .filter(|(k, v)| !SERVER_FIELDS.contains(&k.as_str()) && !v.is_null())
+        .filter(|(k, v)| !(matches!(k.as_str(), "plan" | "feature" | "meter" | "customer" | "prices" | "entitlements") && (v.is_object() || v.is_array())))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// POST a step's body with its references remapped to target IDs
+async fn create(client: &ApiClient, step: &Step, ids: &HashMap<String, String>) -> Result<String> {
+    let mut body = step.body.clone();
+    for field in REFERENCES {
+        let Some(source) = body.get(*field).and_then(|v| v.as_str()).map(String::from) else { continue };
+        let target = ids
+            .get(&source)
+            .with_context(|| format!("skipped: {} {} was not imported", field, source))?;
+        body.insert(field.to_string(), target.clone().into());
+    }
+    let created: Value = client.post(&step.kind.endpoint(), &Value::Object(body)).await?;
+    Ok(str_field(&created, "id"))
+}
+
+fn step_row(step: &Step) -> StepRow {
+    let (outcome, detail) = match step.outcome {
+        Outcome::Create => ("+".green().bold().to_string(), String::new()),
+        Outcome::Exists => ("=".dimmed().to_string(), String::new()),
+        Outcome::Conflict => (
+            "!".yellow().bold().to_string(),
+            format!("differs in {} (target {} kept)", step.detail, step.target_id.as_deref().unwrap_or_default()),
+        ),
+        Outcome::Skip => ("-".red().bold().to_string(), step.detail.clone()),
+    };
+    StepRow { outcome, resource: singular(step.kind).to_string(), key: step.key.clone(), detail }
+}
+
+pub fn singular(kind: Resource) -> &'static str {
+    kind.name().trim_end_matches('s')
+}
+
+pub fn str_field(item: &Value, field: &str) -> String {
+    item.get(field).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+}
+
+fn number(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
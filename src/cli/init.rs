@@ -0,0 +1,190 @@
+use std::io::IsTerminal;
+use std::path::Path;
+
+use anyhow::Result;
+use clap::Args;
+use dialoguer::{Confirm, Input, Password, Select};
+
+use crate::api::client::ApiClient;
+use crate::api::models::Environment;
+use crate::cli::auth;
+use crate::config::file::DEFAULT_PROFILE;
+use crate::config::project::{self, ProjectFile};
+use crate::config::{globals, ConfigFile, Credentials};
+use crate::utils::{output, spinner};
+
+/// Set up auth and an environment, and write a project file and example payloads
+#[derive(Args)]
+pub struct InitArgs {
+    /// Overwrite files that already exist
+    #[arg(long)]
+    force: bool,
+}
+
+/// Example `customers create --json` payload
+const CUSTOMER_EXAMPLE: &str = r#"{
+  "external_id": "acme-001",
+  "name": "Acme Corp",
+  "email": "billing@acme.com",
+  "metadata": {}
+}
+"#;
+
+/// Example `plans create --json` payload
+const PLAN_EXAMPLE: &str = r#"{
+  "name": "Starter",
+  "lookup_key": "starter",
+  "description": "Example plan"
+}
+"#;
+
+/// Example `events ingest --json` payload, for the example customer
+const EVENT_EXAMPLE: &str = r#"{
+  "event_name": "api_call",
+  "external_customer_id": "acme-001",
+  "properties": {
+    "tokens": 100
+  }
+}
+"#;
+
+pub async fn handle(args: InitArgs) -> Result<()> {
+    let interactive = std::io::stdin().is_terminal();
+    output::print_banner();
+    let creds = authenticate(interactive).await?;
+    let environment = choose_environment(creds, interactive).await?;
+
+    let profile = ConfigFile::load()?.selected_profile();
+    let settings = ProjectFile {
+        profile: (profile != DEFAULT_PROFILE).then_some(profile),
+        environment_id: environment.map(|env| env.id),
+        ..Default::default()
+    };
+
+    println!();
+    if writable(Path::new(project::FILE_NAME), args.force) {
+        settings.save(Path::new(project::FILE_NAME))?;
+        output::success(&format!("Wrote {}", project::FILE_NAME));
+    }
+    for (file, content) in [("customer.json", CUSTOMER_EXAMPLE), ("plan.json", PLAN_EXAMPLE), ("event.json", EVENT_EXAMPLE)] {
+        if writable(Path::new(file), args.force) {
+            std::fs::write(file, content)?;
+            output::success(&format!("Wrote {}", file));
+        }
+    }
+
+    println!();
+    output::info("Next steps:");
+    for step in [
+        "flexprice customers create --json customer.json",
+        "flexprice plans create --json plan.json",
+        "flexprice events ingest --json event.json",
+        "flexprice customers summary ext:acme-001",
+    ] {
+        output::info(&format!("  {}", step));
+    }
+    println!();
+    Ok(())
+}
+
+/// Whether `path` may be written, warning when an existing file is kept
+fn writable(path: &Path, force: bool) -> bool {
+    if path.exists() && !force {
+        output::warning(&format!("{} already exists; kept it (pass --force to overwrite)", path.display()));
+        return false;
+    }
+    true
+}
+
+/// Reuse the profile's credentials, or log in or store an API key
+async fn authenticate(interactive: bool) -> Result<Credentials> {
+    let creds = Credentials::load()?;
+    if creds.is_authenticated() {
+        let profile = ConfigFile::load()?.selected_profile();
+        let reuse = !interactive
+            || Confirm::new()
+                .with_prompt(format!("  Use the saved credentials for profile '{}' ({})?", profile, creds.api_url))
+                .default(true)
+                .interact()?;
+        if reuse {
+            return Ok(creds);
+        }
+    } else if !interactive {
+        anyhow::bail!("Not authenticated and stdin is not a terminal. Pipe an API key to `flexprice auth set-api-key` first.");
+    }
+
+    let methods = ["Log in with email and password", "Use an API key"];
+    let method = Select::new()
+        .with_prompt("  How do you want to authenticate?")
+        .items(&methods)
+        .default(0)
+        .interact()?;
+    if method == 0 {
+        auth::login(None).await?;
+    } else {
+        let api_url: String = Input::new()
+            .with_prompt("  API Endpoint")
+            .default("http://localhost:8080".to_string())
+            .interact_text()?;
+        let key = Password::new().with_prompt("  API Key").interact()?;
+        auth::set_api_key(key, api_url).await?;
+    }
+    Credentials::load()
+}
+
+/// The environment the project works in: `--env` if given, otherwise one
+/// picked from the tenant's or created. `None` leaves it to the profile.
+async fn choose_environment(creds: Credentials, interactive: bool) -> Result<Option<Environment>> {
+    if let Some(id) = globals::environment() {
+        return Ok(Some(Environment { id: id.to_string(), ..Default::default() }));
+    }
+    if !interactive {
+        return Ok(None);
+    }
+
+    let sp = spinner::create_spinner("Fetching environments...");
+    let environments = auth::environments(creds.clone()).await;
+    sp.finish_and_clear();
+    let environments = match environments {
+        Ok(environments) => environments,
+        Err(e) => {
+            output::warning(&format!("Can't list environments, so the profile's will be used: {:#}", e));
+            return Ok(None);
+        }
+    };
+
+    let mut items: Vec<String> = environments
+        .iter()
+        .map(|e| {
+            let kind = e.environment_type.as_deref().unwrap_or("unknown");
+            format!("{} ({}, {})", e.name.as_deref().unwrap_or_default(), kind, e.id)
+        })
+        .collect();
+    items.push("Create a new environment".to_string());
+    let current = environments.iter().position(|e| creds.environment_id.as_deref() == Some(e.id.as_str()));
+    let choice = Select::new()
+        .with_prompt("  Environment for this project")
+        .items(&items)
+        .default(current.unwrap_or(0))
+        .interact()?;
+    if let Some(env) = environments.get(choice) {
+        return Ok(Some(env.clone()));
+    }
+
+    let name: String = Input::new()
+        .with_prompt("  Environment name")
+        .default("development".to_string())
+        .interact_text()?;
+    let kinds = ["development", "production"];
+    let kind = Select::new().with_prompt("  Type").items(&kinds).default(0).interact()?;
+    let mut creds = creds;
+    creds.environment_id = None;
+    let client = ApiClient::new(creds)?;
+    let sp = spinner::create_spinner("Creating environment...");
+    let env: Environment = client
+        .post("/v1/environments", &serde_json::json!({ "name": name, "type": kinds[kind] }))
+        .await?;
+    sp.finish_and_clear();
+    output::success(&format!("Environment created: {} ({})", name, env.id));
+    Ok(Some(env))
+}
@@ -1,40 +1,159 @@
-use anyhow::Result;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use colored::Colorize;
+use dialoguer::Confirm;
+use futures::stream::{self, StreamExt};
 use tabled::Tabled;
 
 use crate::api::client::ApiClient;
-use crate::api::models::{Invoice, ListResponse};
-use crate::cli::auth::require_auth;
-use crate::utils::{output, spinner};
+use crate::api::models::{Invoice, InvoiceComment, InvoiceLineItem, InvoiceTax, TaxRate};
+use crate::cli::completions;
+use crate::config::globals;
+use crate::utils::spinner::Progress;
+use crate::utils::time::{self, Period};
+use crate::utils::fanout::{self, IdsArgs};
+use crate::utils::output::{self, Format};
+use crate::utils::paging::PageArgs;
+use crate::utils::spinner;
+use crate::utils::wait::{self, WaitArgs};
 
 #[derive(Subcommand)]
 pub enum InvoiceCommands {
-    /// List all invoices
+    /// List invoices, a page at a time
     List {
+        /// Append per-currency totals (count, sum, average)
         #[arg(long)]
-        json: bool,
+        summary: bool,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get an invoice by ID
+    /// Get an invoice by ID, or many with --ids-file
     Get {
+        #[arg(add = completions::ids("/v1/invoices"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Show an invoice's line items with its subtotal, tax, and total
+    Lines {
+        #[arg(add = completions::ids("/v1/invoices"))]
         id: String,
-        #[arg(long)]
-        json: bool,
     },
     /// Finalize an invoice
-    Finalize { id: String },
+    Finalize {
+        #[arg(add = completions::ids("/v1/invoices"))]
+        id: String,
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
     /// Void an invoice
-    Void { id: String },
-    /// Download invoice PDF
-    Pdf {
+    Void {
+        #[arg(add = completions::ids("/v1/invoices"))]
+        id: String,
+    },
+    /// Collect the amount due on an invoice, after confirming it
+    Pay {
+        #[arg(add = completions::ids("/v1/invoices"))]
+        id: String,
+        /// How to charge; without it the server's default collection runs
+        #[arg(long, value_enum)]
+        method: Option<PayMethod>,
+        /// Saved card, bank account, or wallet to charge
+        #[arg(long, required_if_eq_any = [("method", "card"), ("method", "ach")])]
+        payment_method_id: Option<String>,
+        /// Collect without asking for confirmation
+        #[arg(long, short)]
+        yes: bool,
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
+    /// Mark an invoice as paid or failed, e.g. after collecting outside FlexPrice
+    UpdatePaymentStatus {
+        #[arg(add = completions::ids("/v1/invoices"))]
         id: String,
-        /// Output file path
-        #[arg(long, short, default_value = "invoice.pdf")]
-        output: String,
+        #[arg(long, value_enum)]
+        status: PaymentStatus,
+    },
+    /// Attach a note to an invoice, e.g. collection context or a dispute
+    Comment {
+        #[arg(add = completions::ids("/v1/invoices"))]
+        id: String,
+        /// Note text
+        #[arg(long, short)]
+        message: String,
+    },
+    /// List an invoice's notes, oldest first
+    Comments {
+        #[arg(add = completions::ids("/v1/invoices"))]
+        id: String,
+    },
+    /// Download invoice PDF, or every matching invoice's PDF with --all
+    Pdf {
+        #[arg(add = completions::ids("/v1/invoices"), required_unless_present = "all", conflicts_with = "all")]
+        id: Option<String>,
+        /// File to write the PDF to
+        #[arg(long, default_value = "invoice.pdf", conflicts_with = "all")]
+        out: String,
+        /// Download the PDF of every invoice matching --filter and --period
+        #[arg(long)]
+        all: bool,
+        /// Directory to write --all downloads into (created if missing)
+        #[arg(long, default_value = "invoices", requires = "all")]
+        dir: String,
+        /// Only invoices whose FIELD equals VALUE, e.g. `status=finalized` (repeatable)
+        #[arg(long, value_name = "FIELD=VALUE", requires = "all")]
+        filter: Vec<String>,
+        /// Only invoices whose period starts in this range: `YYYY-MM` or `START..END`
+        #[arg(long, requires = "all")]
+        period: Option<String>,
     },
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PayMethod {
+    /// Charge a saved card
+    Card,
+    /// Debit a saved bank account
+    Ach,
+    /// Draw on the customer's wallet credits
+    Credits,
+    /// Record a payment made outside FlexPrice
+    Offline,
+}
+
+impl PayMethod {
+    fn api_name(self) -> &'static str {
+        match self {
+            PayMethod::Card => "CARD",
+            PayMethod::Ach => "ACH",
+            PayMethod::Credits => "CREDITS",
+            PayMethod::Offline => "OFFLINE",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PaymentStatus {
+    /// The amount due was collected
+    Paid,
+    /// Collection was attempted and failed
+    Failed,
+}
+
+impl PaymentStatus {
+    fn api_name(self) -> &'static str {
+        match self {
+            PaymentStatus::Paid => "SUCCEEDED",
+            PaymentStatus::Failed => "FAILED",
+        }
+    }
+}
+
 #[derive(Tabled, serde::Serialize)]
-struct InvoiceRow {
+pub struct InvoiceRow {
     #[tabled(rename = "ID")]
     id: String,
     #[tabled(rename = "Customer")]
@@ -62,45 +181,624 @@ impl From<Invoice> for InvoiceRow {
     }
 }
 
-pub async fn handle(cmd: InvoiceCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
+/// An invoice with its billing period written out for people
+#[derive(Tabled, serde::Serialize)]
+struct ReadableInvoiceRow {
+    #[tabled(inline)]
+    #[serde(flatten)]
+    invoice: InvoiceRow,
+    #[tabled(rename = "Period")]
+    period: String,
+}
+
+impl From<Invoice> for ReadableInvoiceRow {
+    fn from(i: Invoice) -> Self {
+        Self {
+            period: billing_period(&i).unwrap_or_default(),
+            invoice: i.into(),
+        }
+    }
+}
+
+/// An invoice with its tax split out, for CSV exports
+#[derive(Tabled, serde::Serialize)]
+struct TaxedInvoiceRow {
+    #[tabled(inline)]
+    #[serde(flatten)]
+    invoice: InvoiceRow,
+    #[tabled(rename = "Subtotal")]
+    subtotal: String,
+    #[tabled(rename = "Tax")]
+    tax: String,
+    /// Every applied tax, e.g. `CA sales tax (US-CA) 7.25%: 7.25; …`
+    #[tabled(rename = "Taxes")]
+    taxes: String,
+}
+
+impl From<Invoice> for TaxedInvoiceRow {
+    fn from(i: Invoice) -> Self {
+        let amount = |a: Option<f64>| a.map(|a| format!("{:.2}", a)).unwrap_or_default();
+        let taxes: Vec<String> = i
+            .taxes
+            .iter()
+            .map(|t| {
+                let mut label = tax_name(t);
+                if let Some(j) = t.jurisdiction.as_deref().filter(|j| !j.is_empty()) {
+                    label = format!("{} ({})", label, j);
+                }
+                if let Some(rate) = t.rate {
+                    label = format!("{} {}%", label, rate);
+                }
+                format!("{}: {}", label, amount(t.tax_amount))
+            })
+            .collect();
+        Self {
+            subtotal: amount(i.subtotal),
+            tax: amount(total_tax(&i)),
+            taxes: taxes.join("; "),
+            invoice: i.into(),
+        }
+    }
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct TaxRow {
+    #[tabled(rename = "Tax")]
+    name: String,
+    #[tabled(rename = "Jurisdiction")]
+    jurisdiction: String,
+    #[tabled(rename = "Rate")]
+    rate: String,
+    #[tabled(rename = "Taxable")]
+    taxable_amount: String,
+    #[tabled(rename = "Amount")]
+    tax_amount: String,
+}
+
+impl From<&InvoiceTax> for TaxRow {
+    fn from(t: &InvoiceTax) -> Self {
+        let amount = |a: Option<f64>| a.map(|a| format!("{:.2}", a)).unwrap_or_default();
+        Self {
+            name: tax_name(t),
+            jurisdiction: t.jurisdiction.clone().unwrap_or_default(),
+            rate: t.rate.map(|r| format!("{}%", r)).unwrap_or_default(),
+            taxable_amount: amount(t.taxable_amount),
+            tax_amount: amount(t.tax_amount),
+        }
+    }
+}
+
+/// A tax's name, falling back to its code or tax rate ID
+fn tax_name(t: &InvoiceTax) -> String {
+    [&t.name, &t.code, &t.tax_rate_id]
+        .into_iter()
+        .flatten()
+        .find(|s| !s.is_empty())
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct LineRow {
+    #[tabled(rename = "Description")]
+    description: String,
+    #[tabled(rename = "Quantity")]
+    quantity: String,
+    #[tabled(rename = "Unit Price")]
+    unit_price: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+    #[tabled(rename = "Meter")]
+    meter: String,
+}
+
+impl From<&InvoiceLineItem> for LineRow {
+    fn from(l: &InvoiceLineItem) -> Self {
+        Self {
+            description: line_description(l),
+            quantity: l.quantity.map(|q| q.to_string()).unwrap_or_default(),
+            unit_price: unit_price(l).map(format_rate).unwrap_or_default(),
+            amount: l.amount.map(|a| format!("{:.2}", a)).unwrap_or_default(),
+            meter: l.meter_display_name.clone().or(l.meter_id.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// What a line item charges for: its display name, else its plan's, else its price ID
+pub fn line_description(l: &InvoiceLineItem) -> String {
+    [&l.display_name, &l.plan_display_name, &l.price_id]
+        .into_iter()
+        .flatten()
+        .find(|s| !s.is_empty())
+        .cloned()
+        .unwrap_or_else(|| l.id.clone())
+}
+
+/// Amount per unit, which the API doesn't return, from the line's amount and quantity
+pub fn unit_price(l: &InvoiceLineItem) -> Option<f64> {
+    let quantity = l.quantity.filter(|q| *q != 0.0)?;
+    Some(l.amount? / quantity)
+}
+
+/// A per-unit price to four decimals without trailing zeros, e.g. `0.005`
+pub fn format_rate(p: f64) -> String {
+    let text = format!("{:.4}", p);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// `(Subtotal, Tax, Total)` for an invoice's footer, falling back to the sum
+/// of its lines and to the amount due where the server leaves them out
+pub fn totals(i: &Invoice) -> (f64, f64, f64) {
+    let subtotal = i.subtotal.unwrap_or_else(|| i.line_items.iter().filter_map(|l| l.amount).sum());
+    let tax = total_tax(i).unwrap_or_default();
+    let total = i.total.or(i.amount_due).unwrap_or(subtotal + tax);
+    (subtotal, tax, total)
+}
+
+/// The invoice's total tax, summing its tax lines when the server doesn't
+fn total_tax(i: &Invoice) -> Option<f64> {
+    i.total_tax.or_else(|| {
+        (!i.taxes.is_empty()).then(|| i.taxes.iter().fold(0.0, |sum, t| sum + t.tax_amount.unwrap_or_default()))
+    })
+}
 
+/// The invoice's billing period, e.g. `Mar 1 → Apr 1 (monthly, ended 3 days ago)`
+pub fn billing_period(i: &Invoice) -> Option<String> {
+    time::describe_period(
+        i.period_start.as_deref()?,
+        i.period_end.as_deref()?,
+        i.billing_period.as_deref(),
+        chrono::Utc::now(),
+    )
+}
+
+pub async fn handle(cmd: InvoiceCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        InvoiceCommands::List { json } => {
+        InvoiceCommands::List { summary, page } => {
             let sp = spinner::create_spinner("Fetching invoices...");
-            let resp: ListResponse<Invoice> = client.get("/v1/invoices").await?;
+            let (items, pagination) = page.fetch::<Invoice>(&client, "/v1/invoices").await?;
+            sp.finish_and_clear();
+            let totals = summary.then(|| {
+                output::summarize(items.iter().map(|i| {
+                    (i.currency.as_deref().unwrap_or_default(), i.amount_due.unwrap_or_default())
+                }))
+            });
+            if output::human() && !globals::get().raw {
+                let rows: Vec<ReadableInvoiceRow> = items.into_iter().map(Into::into).collect();
+                println!("{}", output::print_page(&rows, &pagination, totals.as_deref(), "invoice"));
+            } else if output::format() == Format::Csv && globals::get().query.is_none() {
+                let rows: Vec<TaxedInvoiceRow> = items.into_iter().map(Into::into).collect();
+                println!("{}", output::print_table(&rows));
+            } else {
+                let rows: Vec<InvoiceRow> = items.into_iter().map(Into::into).collect();
+                println!("{}", output::print_page(&rows, &pagination, totals.as_deref(), "invoice"));
+            }
+        }
+        InvoiceCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/invoices", &many).await };
+            let sp = spinner::create_spinner("Fetching invoice...");
+            let path = format!("/v1/invoices/{}", id);
+            let (inv, comments) = tokio::join!(client.get::<Invoice>(&path), comments(&client, &id));
+            let inv = inv?;
             sp.finish_and_clear();
-            let rows: Vec<InvoiceRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            let mut detail = match billing_period(&inv).filter(|_| output::human() && !globals::get().raw) {
+                Some(period) => time::replace_period(&inv, "period_start", "period_end", "period", period),
+                None => serde_json::to_value(&inv)?,
+            };
+            if output::human() {
+                // Taxes get their own table below, and line items their own command
+                if let Some(obj) = detail.as_object_mut() {
+                    obj.remove("taxes");
+                    obj.remove("line_items");
+                }
+            }
+            println!("{}", output::print_detail(&detail));
+            if output::human() && !inv.taxes.is_empty() {
+                let taxes = named_taxes(&client, &inv.taxes).await;
+                let rows: Vec<TaxRow> = taxes.iter().map(Into::into).collect();
+                println!("  {}", "Taxes".bold());
+                println!("{}", output::print_table(&rows));
+                let currency = inv.currency.as_deref().unwrap_or_default();
+                output::info(&format!("Total tax: {:.2} {}", total_tax(&inv).unwrap_or_default(), currency));
+            }
+            if output::human() && !inv.line_items.is_empty() {
+                output::info(&format!(
+                    "{} line items; run `flexprice invoices lines {}` for the breakdown.",
+                    inv.line_items.len(),
+                    inv.id
+                ));
+            }
+            // Servers without invoice notes just don't show one
+            if let Some(latest) = comments.ok().and_then(|c| c.into_iter().last()).filter(|_| output::human()) {
+                println!();
+                output::info(&format!("Latest note: {}", describe_comment(&latest)));
+            }
         }
-        InvoiceCommands::Get { id, json } => {
+        InvoiceCommands::Lines { id } => {
             let sp = spinner::create_spinner("Fetching invoice...");
             let inv: Invoice = client.get(&format!("/v1/invoices/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&inv, json));
+            let rows: Vec<LineRow> = inv.line_items.iter().map(Into::into).collect();
+            println!("{}", output::print_table(&rows));
+            if output::human() {
+                let (subtotal, tax, total) = totals(&inv);
+                let currency = inv.currency.as_deref().unwrap_or_default();
+                println!();
+                println!("  {:<10}{:>14.2} {}", "Subtotal", subtotal, currency);
+                println!("  {:<10}{:>14.2} {}", "Tax", tax, currency);
+                println!("  {:<10}{:>14} {}", "Total".bold(), format!("{:.2}", total).bold(), currency);
+            }
+        }
+        InvoiceCommands::Pay { id, method, payment_method_id, yes, wait } => {
+            pay(&client, &id, method, payment_method_id.as_deref(), yes, &wait).await?;
+        }
+        InvoiceCommands::UpdatePaymentStatus { id, status } => {
+            let sp = spinner::create_spinner("Updating payment status...");
+            let inv: Invoice = client
+                .put(&format!("/v1/invoices/{}/payment", id), &serde_json::json!({ "payment_status": status.api_name() }))
+                .await?;
+            sp.finish_and_clear();
+            let label = if status == PaymentStatus::Paid { "paid" } else { "failed" };
+            output::success(&format!("Invoice {} marked {}.", id, label));
+            println!("{}", output::print_detail(&InvoiceRow::from(inv)));
+        }
+        InvoiceCommands::Comment { id, message } => {
+            let sp = spinner::create_spinner("Adding note...");
+            let comment: InvoiceComment = client
+                .post(&format!("/v1/invoices/{}/comments", id), &serde_json::json!({ "message": message }))
+                .await
+                .context("Failed to add note (this FlexPrice server may not support invoice comments)")?;
+            sp.finish_and_clear();
+            output::success(&format!("Note added to invoice {}.", id));
+            println!("{}", output::print_detail(&comment));
         }
-        InvoiceCommands::Finalize { id } => {
+        InvoiceCommands::Comments { id } => {
+            let sp = spinner::create_spinner("Fetching notes...");
+            let comments = comments(&client, &id)
+                .await
+                .context("Failed to fetch notes (this FlexPrice server may not support invoice comments)")?;
+            sp.finish_and_clear();
+            let rows: Vec<CommentRow> = comments.into_iter().map(Into::into).collect();
+            println!("{}", output::print_table(&rows));
+        }
+        InvoiceCommands::Finalize { id, wait } => {
+            let timeout = wait.timeout()?;
             let sp = spinner::create_spinner("Finalizing invoice...");
-            let inv: serde_json::Value = client.post_empty(&format!("/v1/invoices/{}/finalize", id)).await?;
+            let mut inv: serde_json::Value = client.post_empty(&format!("/v1/invoices/{}/finalize", id)).await?;
             sp.finish_and_clear();
+            if wait.wait {
+                let path = format!("/v1/invoices/{}", id);
+                let what = format!("invoice {}", id);
+                let Some(settled) = wait::resource(&client, &path, &what, "invoice_status", &["FINALIZED", "VOIDED"], timeout).await? else {
+                    output::info("Stopped waiting; finalization continues on the server.");
+                    return Ok(());
+                };
+                if settled.get("invoice_status").and_then(|s| s.as_str()).is_some_and(|s| s.eq_ignore_ascii_case("VOIDED")) {
+                    println!("{}", output::print_detail(&settled));
+                    anyhow::bail!("Invoice {} was voided instead of finalized", id);
+                }
+                inv = settled;
+            }
             output::success(&format!("Invoice {} finalized.", id));
-            println!("{}", output::print_detail(&inv, false));
+            println!("{}", output::print_detail(&inv));
         }
         InvoiceCommands::Void { id } => {
             let sp = spinner::create_spinner("Voiding invoice...");
             let inv: serde_json::Value = client.post_empty(&format!("/v1/invoices/{}/void", id)).await?;
             sp.finish_and_clear();
             output::success(&format!("Invoice {} voided.", id));
-            println!("{}", output::print_detail(&inv, false));
+            println!("{}", output::print_detail(&inv));
         }
-        InvoiceCommands::Pdf { id, output: out_path } => {
-            let sp = spinner::create_spinner("Downloading PDF...");
-            let pdf_content = client.get_text(&format!("/v1/invoices/{}/pdf", id)).await?;
-            std::fs::write(&out_path, pdf_content)?;
-            sp.finish_and_clear();
-            output::success(&format!("Invoice PDF saved to {}", out_path));
+        InvoiceCommands::Pdf { id: Some(id), out, .. } => {
+            let bar = spinner::create_transfer("Downloading PDF", "download");
+            let size = download_pdf(&client, &id, Path::new(&out), Some(&bar)).await;
+            bar.finish_and_clear();
+            output::success(&format!("Invoice PDF saved to {} ({} bytes)", out, size?));
+        }
+        InvoiceCommands::Pdf { dir, filter, period, .. } => {
+            export_pdfs(&client, &dir, &filter, period.as_deref()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Show what an invoice owes, confirm, then collect it: with the server's
+/// default collection, or as a payment by `method`
+async fn pay(client: &ApiClient, id: &str, method: Option<PayMethod>, payment_method_id: Option<&str>, yes: bool, wait: &WaitArgs) -> Result<()> {
+    let timeout = wait.timeout()?;
+    let sp = spinner::create_spinner("Fetching invoice...");
+    let inv: Invoice = client.get(&format!("/v1/invoices/{}", id)).await?;
+    sp.finish_and_clear();
+    if inv.invoice_status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("VOIDED")) {
+        anyhow::bail!("Invoice {} is voided; there is nothing to collect.", id);
+    }
+    let due = inv.amount_due.unwrap_or_default();
+    if due <= 0.0 {
+        anyhow::bail!("Invoice {} has nothing due.", id);
+    }
+    let currency = inv.currency.clone().unwrap_or_default();
+    let number = inv.invoice_number.clone().unwrap_or_else(|| inv.id.clone());
+    let via = method.map(|m| format!(" via {}", m.api_name().to_lowercase())).unwrap_or_default();
+
+    if !yes && !globals::get().dry_run {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Refusing to collect {:.2} {} without confirmation; pass --yes", due, currency.to_uppercase());
+        }
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "  Collect {:.2} {} for invoice {} (customer {}){}?",
+                due,
+                currency.to_uppercase(),
+                number,
+                inv.customer_id.as_deref().unwrap_or("-"),
+                via
+            ))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            output::info("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let sp = spinner::create_spinner("Collecting payment...");
+    let mut resp: serde_json::Value = match method {
+        None => client.post_empty(&format!("/v1/invoices/{}/payment/attempt", id)).await?,
+        Some(method) => {
+            let mut body = serde_json::json!({
+                "destination_type": "INVOICE",
+                "destination_id": id,
+                "amount": format!("{:.2}", due),
+                "currency": currency,
+                "payment_method_type": method.api_name(),
+                "process_payment": true,
+            });
+            if let Some(pm) = payment_method_id {
+                body["payment_method_id"] = pm.into();
+            }
+            client.post("/v1/payments", &body).await?
+        }
+    };
+    sp.finish_and_clear();
+    if wait.wait && !is_settled(&resp) {
+        // A payment is followed by its own status; default collection by the invoice's
+        let (path, what) = match resp.get("id").and_then(|v| v.as_str()).filter(|_| method.is_some()) {
+            Some(payment) => (format!("/v1/payments/{}", payment), format!("payment {}", payment)),
+            None => (format!("/v1/invoices/{}", id), format!("payment for invoice {}", number)),
+        };
+        match wait::resource(client, &path, &what, "payment_status", PAYMENT_SETTLED, timeout).await? {
+            Some(settled) => resp = settled,
+            None => output::info("Stopped waiting; collection continues on the server."),
+        }
+    }
+    let amount = format!("{:.2} {}", due, currency.to_uppercase());
+    match resp.get("payment_status").and_then(|s| s.as_str()).map(str::to_uppercase).as_deref() {
+        Some("FAILED") => {
+            println!("{}", output::print_detail(&resp));
+            let reason = resp.get("error_message").and_then(|m| m.as_str()).unwrap_or("no reason given");
+            anyhow::bail!("Payment for invoice {} failed: {}", number, reason);
+        }
+        Some(status @ ("PENDING" | "PROCESSING")) => output::info(&format!(
+            "Payment of {} for invoice {}{} is {}.",
+            amount,
+            number,
+            via,
+            status.to_lowercase()
+        )),
+        _ => output::success(&format!("Payment of {} collected for invoice {}{}.", amount, number, via)),
+    }
+    println!("{}", output::print_detail(&resp));
+    Ok(())
+}
+
+/// Payment statuses that won't change without another attempt or a refund
+pub const PAYMENT_SETTLED: &[&str] = &["SUCCEEDED", "FAILED", "REFUNDED", "PARTIALLY_REFUNDED"];
+
+/// Whether a payment or invoice has a settled payment status
+pub fn is_settled(resource: &serde_json::Value) -> bool {
+    let status = resource.get("payment_status").and_then(|s| s.as_str()).unwrap_or_default().to_uppercase();
+    PAYMENT_SETTLED.contains(&status.as_str())
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct CommentRow {
+    #[tabled(rename = "Created")]
+    created_at: String,
+    #[tabled(rename = "Author")]
+    created_by: String,
+    #[tabled(rename = "Message")]
+    message: String,
+}
+
+impl From<InvoiceComment> for CommentRow {
+    fn from(c: InvoiceComment) -> Self {
+        Self {
+            created_at: c.created_at.unwrap_or_default(),
+            created_by: c.created_by.unwrap_or_default(),
+            message: c.message.unwrap_or_default(),
         }
     }
+}
+
+/// An invoice's notes, oldest first
+async fn comments(client: &ApiClient, invoice_id: &str) -> Result<Vec<InvoiceComment>> {
+    let mut comments: Vec<InvoiceComment> = client
+        .get_all(&format!("/v1/invoices/{}/comments", invoice_id))
+        .await?;
+    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(comments)
+}
+
+/// An invoice's taxes, with names and rates filled in from their tax rates
+/// where the invoice only carries the rate's ID. Rates that can't be fetched
+/// are left as they are.
+async fn named_taxes(client: &ApiClient, taxes: &[InvoiceTax]) -> Vec<InvoiceTax> {
+    stream::iter(taxes.iter().cloned())
+        .map(|mut t| async move {
+            let unnamed = t.name.as_deref().unwrap_or_default().is_empty();
+            if let Some(id) = t.tax_rate_id.clone().filter(|_| unnamed) {
+                if let Ok(rate) = client.get::<TaxRate>(&format!("/v1/taxes/rates/{}", id)).await {
+                    t.name = rate.name.or(t.name);
+                    t.code = t.code.or(rate.code);
+                    t.rate = t.rate.or_else(|| rate.percentage_value.and_then(|p| p.parse().ok()));
+                }
+            }
+            t
+        })
+        .buffered(4)
+        .collect()
+        .await
+}
+
+/// `message (author, date)` for a one-line summary
+fn describe_comment(c: &InvoiceComment) -> String {
+    let by: Vec<&str> = [c.created_by.as_deref(), c.created_at.as_deref().map(|t| t.get(..10).unwrap_or(t))]
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty())
+        .collect();
+    let message = c.message.as_deref().unwrap_or_default();
+    if by.is_empty() {
+        message.to_string()
+    } else {
+        format!("{} ({})", message, by.join(", "))
+    }
+}
+
+/// Download an invoice's PDF to `path` via a `.part` file, so an interrupted
+/// download never leaves a truncated PDF behind
+async fn download_pdf(client: &ApiClient, id: &str, path: &Path, progress: Option<&Progress>) -> Result<u64> {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = Path::new(&part);
+    let mut file = std::fs::File::create(part).with_context(|| format!("Failed to create {}", part.display()))?;
+    let result = client.get_bytes(&format!("/v1/invoices/{}/pdf", id), &mut file, progress).await;
+    drop(file);
+    match result {
+        Ok(size) => {
+            std::fs::rename(part, path).with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(size)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(part);
+            Err(e)
+        }
+    }
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ExportRow {
+    #[tabled(rename = "Invoice")]
+    id: String,
+    #[tabled(rename = "File")]
+    file: String,
+    #[tabled(rename = "Bytes")]
+    bytes: u64,
+    #[tabled(rename = "Result")]
+    result: String,
+}
+
+/// Download the PDFs of every invoice matching `filters` and `period` into `dir`
+async fn export_pdfs(client: &ApiClient, dir: &str, filters: &[String], period: Option<&str>) -> Result<()> {
+    let filters = filters.iter().map(|f| parse_filter(f)).collect::<Result<Vec<_>>>()?;
+    let period = period.map(time::parse_period).transpose()?.unwrap_or_default();
+
+    let sp = spinner::create_spinner("Fetching invoices...");
+    let invoices: Vec<Invoice> = client.get_all("/v1/invoices").await?;
+    sp.finish_and_clear();
+    let invoices: Vec<Invoice> = invoices
+        .into_iter()
+        .filter(|inv| in_period(inv, &period) && filters.iter().all(|(field, value)| matches(inv, field, value)))
+        .collect();
+    if invoices.is_empty() {
+        output::info("No invoices match.");
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir))?;
+    let bar = spinner::create_progress(invoices.len() as u64, "Downloading", "export");
+    let mut rows: Vec<(usize, ExportRow, bool)> = stream::iter(invoices.iter().enumerate())
+        .map(|(i, inv)| {
+            let bar = bar.clone();
+            async move {
+                let name = inv.invoice_number.as_deref().filter(|n| !n.is_empty()).unwrap_or(&inv.id);
+                let path = Path::new(dir).join(format!("{}.pdf", file_name(name)));
+                let result = download_pdf(client, &inv.id, &path, None).await;
+                bar.inc(1);
+                let ok = result.is_ok();
+                let row = ExportRow {
+                    id: inv.id.clone(),
+                    file: path.display().to_string(),
+                    bytes: *result.as_ref().unwrap_or(&0),
+                    result: match result {
+                        Ok(_) => "ok".green().to_string(),
+                        Err(e) => format!("{}: {:#}", "failed".red(), e),
+                    },
+                };
+                (i, row, ok)
+            }
+        })
+        .buffer_unordered(4)
+        .collect()
+        .await;
+    bar.finish_and_clear();
+    rows.sort_by_key(|(i, _, _)| *i);
+
+    let failed = rows.iter().filter(|(_, _, ok)| !ok).count();
+    let rows: Vec<ExportRow> = rows.into_iter().map(|(_, row, _)| row).collect();
+    println!("{}", output::print_table(&rows));
+    if output::human() {
+        println!();
+        output::info(&format!("{} PDFs saved to {}, {} failed", rows.len() - failed, dir, failed));
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} downloads failed", failed, rows.len());
+    }
     Ok(())
 }
+
+/// Parse a `--filter FIELD=VALUE`, accepting `status` for `invoice_status`
+fn parse_filter(s: &str) -> Result<(String, String)> {
+    let (field, value) = s
+        .split_once('=')
+        .with_context(|| format!("Invalid --filter '{}': expected FIELD=VALUE", s))?;
+    let field = match field.trim() {
+        "status" => "invoice_status",
+        other => other,
+    };
+    let known = serde_json::to_value(Invoice::default())?;
+    if known.get(field).is_none() {
+        let fields: Vec<&str> = known.as_object().into_iter().flat_map(|o| o.keys()).map(String::as_str).collect();
+        anyhow::bail!("Unknown --filter field '{}'; use one of: status, {}", field, fields.join(", "));
+    }
+    Ok((field.to_string(), value.trim().to_string()))
+}
+
+/// Case-insensitive match of an invoice field against a filter value
+fn matches(inv: &Invoice, field: &str, value: &str) -> bool {
+    let json = serde_json::to_value(inv).unwrap_or_default();
+    match json.get(field) {
+        Some(serde_json::Value::String(s)) => s.eq_ignore_ascii_case(value),
+        Some(serde_json::Value::Null) | None => false,
+        Some(other) => serde_json::from_str::<serde_json::Value>(value).is_ok_and(|v| v == *other),
+    }
+}
+
+/// Whether an invoice's billing period starts within `period`; invoices
+/// without a period only match an unbounded one
+fn in_period(inv: &Invoice, period: &Period) -> bool {
+    if period.start.is_none() && period.end.is_none() {
+        return true;
+    }
+    let Some(start) = inv.period_start.as_deref().and_then(|s| time::parse_instant(s).ok()) else {
+        return false;
+    };
+    period.start.is_none_or(|from| start >= from) && period.end.is_none_or(|to| start < to)
+}
+
+/// Make an invoice number safe to use as a file name
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,202 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clap::Subcommand;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::query::QueryBuilder;
+use crate::api::models::Task;
+use crate::cli::completions;
+use crate::utils::fanout::{self, IdsArgs};
+use crate::utils::paging::PageArgs;
+use crate::utils::{output, spinner, time};
+
+/// Statuses after which a task won't change again
+const TERMINAL_STATUSES: &[&str] = &["COMPLETED", "FAILED", "CANCELLED"];
+
+#[derive(Subcommand)]
+pub enum JobCommands {
+    /// List background tasks (imports, exports, recomputations)
+    List {
+        /// Only show tasks in this status, e.g. PROCESSING or FAILED
+        #[arg(long)]
+        status: Option<String>,
+        /// Only show tasks of this type, e.g. IMPORT
+        #[arg(long = "type")]
+        task_type: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Get a task by ID, or many with --ids-file
+    Get {
+        #[arg(add = completions::ids("/v1/tasks"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Wait for a task to finish, exiting non-zero if it fails
+    Wait {
+        #[arg(add = completions::ids("/v1/tasks"))]
+        id: String,
+        /// Give up after this long, e.g. 90s, 15m, 2h (default: wait indefinitely)
+        #[arg(long)]
+        timeout: Option<String>,
+        /// Seconds between status checks
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct TaskRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Type")]
+    task_type: String,
+    #[tabled(rename = "Entity")]
+    entity_type: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Progress")]
+    progress: String,
+    #[tabled(rename = "Created")]
+    created_at: String,
+}
+
+impl From<Task> for TaskRow {
+    fn from(t: Task) -> Self {
+        Self {
+            progress: progress(&t),
+            status: output::status_badge(&status(&t)),
+            id: t.id,
+            task_type: t.task_type.unwrap_or_default(),
+            entity_type: t.entity_type.unwrap_or_default(),
+            created_at: t.created_at.unwrap_or_default(),
+        }
+    }
+}
+
+pub async fn handle(cmd: JobCommands, client: ApiClient) -> Result<()> {
+    match cmd {
+        JobCommands::List { status, task_type, page } => {
+            let path = QueryBuilder::new()
+                .opt("task_status", status.map(|s| s.to_uppercase()))
+                .opt("task_type", task_type.map(|t| t.to_uppercase()))
+                .to_path("/v1/tasks");
+            let sp = spinner::create_spinner("Fetching tasks...");
+            let (items, pagination) = page.fetch::<Task>(&client, &path).await?;
+            sp.finish_and_clear();
+            let rows: Vec<TaskRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "task"));
+        }
+        JobCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/tasks", &many).await };
+            let sp = spinner::create_spinner("Fetching task...");
+            let task: Task = client.get(&format!("/v1/tasks/{}", id)).await?;
+            sp.finish_and_clear();
+            println!("{}", output::print_detail(&task));
+        }
+        JobCommands::Wait { id, timeout, interval } => {
+            let timeout = timeout
+                .as_deref()
+                .map(time::parse_duration)
+                .transpose()?
+                .map(|d| d.to_std())
+                .transpose()?;
+            let sp = spinner::create_spinner("Fetching task...");
+            let task: Task = client.get(&format!("/v1/tasks/{}", id)).await?;
+            sp.finish_and_clear();
+
+            let Some(task) = wait(&client, task, interval, timeout).await? else {
+                output::info(&format!("Stopped waiting; task {} continues on the server.", id));
+                return Ok(());
+            };
+            if !output::human() {
+                println!("{}", output::print_detail(&task));
+            }
+            match status(&task).as_str() {
+                "COMPLETED" => output::success(&format!(
+                    "Task {} completed: {} record(s) processed.",
+                    task.id,
+                    task.processed_records.unwrap_or_default()
+                )),
+                s => anyhow::bail!(
+                    "Task {} {}: {}",
+                    task.id,
+                    s.to_lowercase(),
+                    task.error_summary.as_deref().unwrap_or("no details from server")
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A task's status in upper case, `PENDING` if the server didn't say
+pub fn status(task: &Task) -> String {
+    task.task_status.as_deref().unwrap_or("PENDING").to_uppercase()
+}
+
+fn progress(task: &Task) -> String {
+    match (task.processed_records, task.total_records) {
+        (Some(done), Some(total)) if total > 0 => format!("{}/{} ({:.0}%)", done, total, done as f64 * 100.0 / total as f64),
+        (Some(done), _) => done.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Poll a task until it reaches a terminal status, showing a progress bar once
+/// the server reports a record count. Returns `None` if interrupted with
+/// Ctrl-C and fails if `timeout` elapses first.
+pub async fn wait(client: &ApiClient, mut task: Task, interval: u64, timeout: Option<Duration>) -> Result<Option<Task>> {
+    let started = Instant::now();
+    let sp = spinner::create_spinner(&format!("Waiting for task {}...", task.id));
+    let mut bar = None;
+    loop {
+        if let Some(total) = task.total_records.filter(|t| *t > 0) {
+            let bar = bar.get_or_insert_with(|| {
+                sp.finish_and_clear();
+                spinner::create_progress(total, &format!("Task {}", task.id), "task")
+            });
+            bar.set_length(total);
+            bar.set_position(task.processed_records.unwrap_or_default());
+        }
+        if TERMINAL_STATUSES.contains(&status(&task).as_str()) {
+            break;
+        }
+        let mut sleep = Duration::from_secs(interval.max(1));
+        if let Some(timeout) = timeout {
+            let remaining = timeout.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                sp.finish_and_clear();
+                if let Some(bar) = &bar {
+                    bar.abandon();
+                }
+                anyhow::bail!(
+                    "Timed out after {}s; task {} is still {}",
+                    timeout.as_secs(),
+                    task.id,
+                    status(&task).to_lowercase()
+                );
+            }
+            sleep = sleep.min(remaining);
+        }
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                sp.finish_and_clear();
+                if let Some(bar) = &bar {
+                    bar.abandon();
+                }
+                return Ok(None);
+            }
+            _ = tokio::time::sleep(sleep) => {}
+        }
+        task = client.get(&format!("/v1/tasks/{}", task.id)).await?;
+    }
+    sp.finish_and_clear();
+    if let Some(bar) = bar {
+        bar.finish_and_clear();
+    }
+    Ok(Some(task))
+}
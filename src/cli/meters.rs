@@ -1,36 +1,274 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
 use clap::Subcommand;
 use tabled::Tabled;
 
 use crate::api::client::ApiClient;
-use crate::api::models::{Meter, ListResponse};
-use crate::cli::auth::require_auth;
-use crate::utils::{output, spinner};
+use crate::api::models::{Meter, MeterUsage, Task};
+use crate::cli::completions;
+use crate::cli::jobs;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::wizard::{self, Field, Wizard};
+use crate::utils::fanout::{self, DeleteManyArgs, IdsArgs};
+use crate::utils::{chart, output, spinner, time};
 
 #[derive(Subcommand)]
 pub enum MeterCommands {
-    /// List all meters
+    /// List meters, a page at a time
     List {
-        #[arg(long)]
-        json: bool,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get a meter by ID
+    /// Get a meter by ID, or many with --ids-file
     Get {
+        #[arg(add = completions::ids("/v1/meters"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Create meters from flags, interactively, or from a JSON file (object or array of objects)
+    ///
+    /// The aggregation and filters are checked before anything is sent.
+    Create {
+        #[arg(long)]
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        /// Name (default: the event name)
+        #[arg(long, requires = "event_name")]
+        name: Option<String>,
+        /// Event name to aggregate, e.g. `api_call`
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        event_name: Option<String>,
+        /// How matching events are combined (default: count)
+        #[arg(long, value_enum, requires = "event_name")]
+        aggregation: Option<Aggregation>,
+        /// Property to aggregate; needed for everything but count
+        #[arg(long, value_name = "PROP", requires = "event_name")]
+        aggregation_field: Option<String>,
+        /// Only aggregate events whose property has one of these values,
+        /// e.g. `region=us|eu` (repeatable)
+        #[arg(long = "filter", value_name = "KEY=VALUE[|VALUE]", value_parser = wizard::parse_filter, requires = "event_name")]
+        filters: Vec<serde_json::Value>,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Update a meter from flags, a JSON file, or in $EDITOR
+    Update {
+        #[arg(add = completions::ids("/v1/meters"))]
         id: String,
+        #[arg(long, required_unless_present_any = ["edit", "name"])]
+        json: Option<String>,
+        /// Edit the meter's current state in $EDITOR
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        /// New name
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        name: Option<String>,
+    },
+    /// Delete a meter by ID, or many from --ids-file or --filter
+    Delete {
+        #[arg(
+            add = completions::ids("/v1/meters"),
+            required_unless_present_any = ["ids_file", "filters"],
+            conflicts_with_all = ["ids_file", "filters"]
+        )]
+        id: Option<String>,
+        #[command(flatten)]
+        many: DeleteManyArgs,
+    },
+    /// Reprocess historical events for a meter and wait for the job to finish
+    Recompute {
+        #[arg(add = completions::ids("/v1/meters"))]
+        id: String,
+        /// Start of the range to recompute (date or RFC 3339 timestamp)
+        #[arg(long)]
+        from: String,
+        /// End of the range to recompute (default: now)
+        #[arg(long)]
+        to: Option<String>,
+        /// Seconds between job status checks
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Start the job and print its ID without waiting
         #[arg(long)]
-        json: bool,
+        no_wait: bool,
     },
-    /// Create a new meter from a JSON file
-    Create {
+    /// Show a meter's usage over time, as a table or a terminal chart
+    Usage {
+        #[arg(add = completions::ids("/v1/meters"))]
+        id: String,
+        /// Only usage from this external customer ID
+        #[arg(long)]
+        customer: Option<String>,
+        /// How far back to look, e.g. 24h, 7d, 4w
+        #[arg(long, default_value = "7d")]
+        window: String,
+        /// Size of each bucket
+        #[arg(long, value_enum, default_value_t = Granularity::Hour)]
+        granularity: Granularity,
+        /// Draw a bar chart instead of a table
         #[arg(long)]
-        json: String,
+        chart: bool,
     },
-    /// Delete a meter by ID
-    Delete { id: String },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum Granularity {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl Granularity {
+    pub fn seconds(self) -> i64 {
+        match self {
+            Granularity::Minute => 60,
+            Granularity::Hour => 3600,
+            Granularity::Day => 86400,
+        }
+    }
+
+    /// The API's `window_size`
+    pub fn window_size(self) -> &'static str {
+        match self {
+            Granularity::Minute => "MINUTE",
+            Granularity::Hour => "HOUR",
+            Granularity::Day => "DAY",
+        }
+    }
+
+    pub fn label(self, at: DateTime<Utc>) -> String {
+        match self {
+            Granularity::Day => at.format("%b %-d").to_string(),
+            _ => at.format("%b %-d %H:%M").to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum Aggregation {
+    /// Number of events
+    Count,
+    /// Total of a property
+    Sum,
+    /// Average of a property
+    Avg,
+    /// Largest value of a property
+    Max,
+    /// Number of distinct values of a property
+    Unique,
+    /// Most recent value of a property
+    Latest,
+}
+
+impl Aggregation {
+    /// The API's `aggregation.type`
+    pub fn api_name(self) -> &'static str {
+        match self {
+            Aggregation::Count => "COUNT",
+            Aggregation::Sum => "SUM",
+            Aggregation::Avg => "AVG",
+            Aggregation::Max => "MAX",
+            Aggregation::Unique => "COUNT_UNIQUE",
+            Aggregation::Latest => "LATEST",
+        }
+    }
+}
+
+/// Aggregation types the API accepts
+const AGGREGATIONS: &[&str] = &["COUNT", "SUM", "AVG", "MAX", "COUNT_UNIQUE", "LATEST"];
+
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "meter",
+    fields: &[
+        Field::text("name", "Name").required(),
+        Field::text("event_name", "Event name").required(),
+        Field::choice("aggregation.type", "Aggregation", AGGREGATIONS),
+        Field::text("aggregation.field", "Property to aggregate (blank for COUNT)"),
+        Field::filters("filters", "Filters"),
+        Field::choice("reset_usage", "Reset usage", &["BILLING_PERIOD", "NEVER"]),
+    ],
+    check: Some(validate),
+};
+
+/// Check a create payload's aggregation and filters, so mistakes get a clear
+/// message instead of a rejected request
+pub fn validate(body: &serde_json::Value) -> Result<()> {
+    fn text(value: Option<&serde_json::Value>) -> &str {
+        value.and_then(|v| v.as_str()).map(str::trim).unwrap_or_default()
+    }
+    if text(body.get("event_name")).is_empty() {
+        anyhow::bail!("event_name is required: the name of the events to aggregate");
+    }
+    let aggregation = body.get("aggregation");
+    let field = text(aggregation.and_then(|a| a.get("field")));
+    match text(aggregation.and_then(|a| a.get("type"))) {
+        "" => anyhow::bail!("aggregation.type is required; use one of: {}", AGGREGATIONS.join(", ")),
+        "COUNT" if !field.is_empty() => anyhow::bail!(
+            "COUNT counts events and takes no property; to aggregate '{}', use SUM, AVG, MAX, COUNT_UNIQUE, or LATEST",
+            field
+        ),
+        kind if !AGGREGATIONS.contains(&kind) => {
+            anyhow::bail!("Unknown aggregation '{}'; use one of: {}", kind, AGGREGATIONS.join(", "))
+        }
+        kind if kind != "COUNT" && field.is_empty() => {
+            anyhow::bail!("{} needs a property to aggregate (aggregation.field, or --aggregation-field)", kind)
+        }
+        _ => {}
+    }
+
+    let filters = match body.get("filters") {
+        None | Some(serde_json::Value::Null) => return Ok(()),
+        Some(serde_json::Value::Array(filters)) => filters,
+        Some(_) => anyhow::bail!(r#"filters must be an array of {{"key": ..., "values": [...]}} objects"#),
+    };
+    let mut keys = std::collections::HashSet::new();
+    for filter in filters {
+        let key = text(filter.get("key"));
+        if key.is_empty() {
+            anyhow::bail!("Filter {} has no key; name the event property to match", filter);
+        }
+        let values = filter.get("values").and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or_default();
+        if values.is_empty() {
+            anyhow::bail!("Filter on '{}' has no values; list the values to keep, e.g. {}=us|eu", key, key);
+        }
+        if let Some(value) = values.iter().find(|v| !v.is_string()) {
+            anyhow::bail!("Filter on '{}' has a non-string value {}; event properties are matched as strings", key, value);
+        }
+        if !keys.insert(key) {
+            anyhow::bail!("Filter on '{}' is given twice; list every value in one filter, e.g. {}=us|eu", key, key);
+        }
+    }
+    Ok(())
+}
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "name": "",
+  "event_name": "",
+  "aggregation": {
+    "type": "COUNT",
+    "field": ""
+  },
+  "filters": [],
+  "reset_usage": "BILLING_PERIOD"
+}"#;
+
+#[derive(Tabled, serde::Serialize)]
+struct UsageRow {
+    #[tabled(rename = "Window")]
+    window: String,
+    #[tabled(rename = "Value")]
+    value: f64,
 }
 
 #[derive(Tabled, serde::Serialize)]
-struct MeterRow {
+pub struct MeterRow {
     #[tabled(rename = "ID")]
     id: String,
     #[tabled(rename = "Name")]
@@ -49,45 +287,236 @@ impl From<Meter> for MeterRow {
             id: m.id,
             name: m.name.unwrap_or_default(),
             event_name: m.event_name.unwrap_or_default(),
-            aggregation: m.aggregation.unwrap_or_default(),
+            aggregation: m
+                .aggregation
+                .map(|a| match a.get("type").and_then(|t| t.as_str()) {
+                    Some(kind) => kind.to_string(),
+                    None => a.as_str().unwrap_or_default().to_string(),
+                })
+                .unwrap_or_default(),
             status: m.status.map(|s| output::status_badge(&s)).unwrap_or_default(),
         }
     }
 }
 
-pub async fn handle(cmd: MeterCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
-
+pub async fn handle(cmd: MeterCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        MeterCommands::List { json } => {
+        MeterCommands::List { page } => {
             let sp = spinner::create_spinner("Fetching meters...");
-            let resp: ListResponse<Meter> = client.get("/v1/meters").await?;
+            let (items, pagination) = page.fetch::<Meter>(&client, "/v1/meters").await?;
             sp.finish_and_clear();
-            let rows: Vec<MeterRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            let rows: Vec<MeterRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "meter"));
         }
-        MeterCommands::Get { id, json } => {
+        MeterCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/meters", &many).await };
             let sp = spinner::create_spinner("Fetching meter...");
             let meter: Meter = client.get(&format!("/v1/meters/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&meter, json));
+            println!("{}", output::print_detail(&meter));
         }
-        MeterCommands::Create { json: file } => {
-            let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Creating meter...");
-            let meter: Meter = client.post("/v1/meters", &body).await?;
-            sp.finish_and_clear();
-            output::success(&format!("Meter created: {}", meter.id));
-            println!("{}", output::print_detail(&meter, false));
+        MeterCommands::Create { json: file, edit, name, event_name, aggregation, aggregation_field, filters, batch: opts } => {
+            let payload = match event_name {
+                Some(event_name) => {
+                    let kind = aggregation.unwrap_or(Aggregation::Count).api_name();
+                    let mut body = serde_json::json!({
+                        "name": name.unwrap_or_else(|| event_name.clone()),
+                        "event_name": event_name,
+                        "aggregation": { "type": kind },
+                        "reset_usage": "BILLING_PERIOD",
+                    });
+                    if let Some(field) = aggregation_field {
+                        body["aggregation"]["field"] = field.into();
+                    }
+                    if !filters.is_empty() {
+                        body["filters"] = filters.into();
+                    }
+                    Some(Payload::Single(body))
+                }
+                None => editor::load_or_edit::<Meter>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))?,
+            };
+            match &payload {
+                Some(Payload::Single(body)) => validate(body)?,
+                Some(Payload::Many(items)) => {
+                    for (i, body) in items.iter().enumerate() {
+                        validate(body).with_context(|| format!("Meter {} in {}", i + 1, file.as_deref().unwrap_or("the payload")))?;
+                    }
+                }
+                None => {}
+            }
+            match payload {
+                None => {}
+                Some(Payload::Single(body)) => {
+                    let sp = spinner::create_spinner("Creating meter...");
+                    let meter: Meter = client.post("/v1/meters", &body).await?;
+                    sp.finish_and_clear();
+                    output::success(&format!("Meter created: {}", meter.id));
+                    println!("{}", output::print_detail(&meter));
+                }
+                Some(Payload::Many(items)) => {
+                    batch::create_many(&client, items, file.as_deref(), &opts, "Meter created", "/v1/meters").await?;
+                }
+            }
         }
-        MeterCommands::Delete { id } => {
+        MeterCommands::Update { id, json: file, name, .. } => {
+            let path = format!("/v1/meters/{}", id);
+            let body = match editor::field_updates(&[("name", name.map(Into::into))]) {
+                Some(body) => Some(body),
+                None => editor::update_body::<Meter>(&client, &path, file.as_deref()).await?,
+            };
+            if let Some(body) = body {
+                let sp = spinner::create_spinner("Updating meter...");
+                let meter: Meter = client.put(&path, &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Meter updated: {}", meter.id));
+                println!("{}", output::print_detail(&meter));
+            }
+        }
+        MeterCommands::Delete { id, many } => {
+            let Some(id) = id else { return fanout::delete_many(&client, "/v1/meters", "meters", &many).await };
             let sp = spinner::create_spinner("Deleting meter...");
             client.delete_empty(&format!("/v1/meters/{}", id)).await?;
             sp.finish_and_clear();
             output::success(&format!("Meter {} deleted.", id));
         }
+        MeterCommands::Recompute { id, from, to, interval, no_wait } => {
+            let start = time::parse_instant(&from)?;
+            let end = to.as_deref().map(time::parse_instant).transpose()?.unwrap_or_else(chrono::Utc::now);
+            if start >= end {
+                anyhow::bail!("--from must be before --to");
+            }
+
+            let sp = spinner::create_spinner("Starting recompute...");
+            let meter: Meter = client.get(&format!("/v1/meters/{}", id)).await?;
+            let body = serde_json::json!({
+                "start_time": start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                "end_time": end.to_rfc3339_opts(SecondsFormat::Secs, true),
+            });
+            let task: Task = client
+                .post(&format!("/v1/meters/{}/recompute", id), &body)
+                .await
+                .context("Failed to start recompute (this FlexPrice server may not support meter recomputation)")?;
+            sp.finish_and_clear();
+            if task.id.is_empty() {
+                anyhow::bail!("The server did not return a recompute task; meter recomputation may not be supported");
+            }
+
+            let name = meter.name.unwrap_or(meter.id);
+            if no_wait {
+                output::success(&format!("Recompute of {} started: task {}", name, task.id));
+                return Ok(());
+            }
+            output::info(&format!(
+                "Recomputing {} from {} to {} (task {})",
+                name,
+                start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                end.to_rfc3339_opts(SecondsFormat::Secs, true),
+                task.id
+            ));
+            let task = jobs::wait(&client, task, interval, None).await?;
+            let Some(task) = task else {
+                output::info("Stopped waiting; the recompute continues on the server.");
+                return Ok(());
+            };
+            if !output::human() {
+                println!("{}", output::print_detail(&task));
+            }
+            match jobs::status(&task).as_str() {
+                "COMPLETED" => output::success(&format!(
+                    "Recompute finished: {} event(s) processed.",
+                    task.processed_records.unwrap_or_default()
+                )),
+                status => anyhow::bail!(
+                    "Recompute {}: {}",
+                    status.to_lowercase(),
+                    task.error_summary.as_deref().unwrap_or("no details from server")
+                ),
+            }
+        }
+        MeterCommands::Usage { id, customer, window, granularity, chart } => {
+            let end = Utc::now();
+            let start = time::before(end, time::parse_duration(&window)?)?;
+            let mut body = serde_json::json!({
+                "meter_id": id,
+                "start_time": start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                "end_time": end.to_rfc3339_opts(SecondsFormat::Secs, true),
+                "window_size": granularity.window_size(),
+            });
+            if let Some(ref customer) = customer {
+                body["external_customer_id"] = customer.clone().into();
+            }
+
+            let meter_path = format!("/v1/meters/{}", id);
+            let sp = spinner::create_spinner("Fetching usage...");
+            let fetched = tokio::try_join!(
+                client.get::<Meter>(&meter_path),
+                client.post::<_, MeterUsage>("/v1/events/usage/meter", &body),
+            );
+            sp.finish_and_clear();
+            let (meter, usage) = fetched?;
+            let buckets = fill_buckets(&usage, start, end, granularity);
+
+            if !(chart && output::human()) {
+                let rows: Vec<UsageRow> = buckets
+                    .iter()
+                    .map(|(at, value)| UsageRow { window: at.to_rfc3339_opts(SecondsFormat::Secs, true), value: *value })
+                    .collect();
+                println!("{}", output::print_table(&rows));
+                return Ok(());
+            }
+            print_usage_chart(&meter, customer.as_deref(), &window, granularity, &buckets);
+        }
     }
     Ok(())
 }
+
+/// One value per bucket from `start` to `end`, with zeros where the server
+/// returned nothing
+pub fn fill_buckets(usage: &MeterUsage, start: DateTime<Utc>, end: DateTime<Utc>, granularity: Granularity) -> Vec<(DateTime<Utc>, f64)> {
+    let step = granularity.seconds();
+    let first = start.timestamp() - start.timestamp().rem_euclid(step);
+    let count = ((end.timestamp() - first) / step + 1).max(1) as usize;
+    let mut values = vec![0.0; count];
+    for bucket in &usage.results {
+        let Some(at) = bucket.window_size.as_deref().and_then(|w| time::parse_instant(w).ok()) else { continue };
+        let offset = at.timestamp() - first;
+        if offset >= 0 && ((offset / step) as usize) < count {
+            values[(offset / step) as usize] += bucket.value.unwrap_or_default();
+        }
+    }
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| (DateTime::from_timestamp(first + i as i64 * step, 0).unwrap_or_default(), v))
+        .collect()
+}
+
+fn print_usage_chart(meter: &Meter, customer: Option<&str>, window: &str, granularity: Granularity, buckets: &[(DateTime<Utc>, f64)]) {
+    let name = meter.name.as_deref().unwrap_or(&meter.id);
+    let unit = match granularity {
+        Granularity::Minute => "minute",
+        Granularity::Hour => "hour",
+        Granularity::Day => "day",
+    };
+    let scope = customer.map(|c| format!(" for {}", c)).unwrap_or_default();
+    output::info(&format!("{}{}, last {} by {}", name, scope, window, unit));
+    println!();
+
+    let values: Vec<f64> = buckets.iter().map(|(_, v)| *v).collect();
+    let width = console::Term::stdout().size().1 as usize;
+    let (columns, per) = chart::fit(&values, width.saturating_sub(16).max(10));
+    let (Some((first, _)), Some((last, _))) = (buckets.first(), buckets.last()) else { return };
+    println!("{}", chart::bars(&columns, 10, &granularity.label(*first), &granularity.label(*last)));
+    println!();
+
+    let total: f64 = values.iter().sum();
+    let peak = buckets.iter().max_by(|a, b| a.1.total_cmp(&b.1));
+    let mut summary = format!("Total {}", chart::format_value(total));
+    if let Some((at, value)) = peak.filter(|(_, v)| *v > 0.0) {
+        summary.push_str(&format!(", peak {} at {}", chart::format_value(*value), granularity.label(*at)));
+    }
+    if per > 1 {
+        summary.push_str(&format!(" (each column sums {} {}s)", per, unit));
+    }
+    output::info(&summary);
+}
@@ -1,10 +1,42 @@
+pub mod addons;
+pub mod api;
+pub mod apply;
+pub mod assert;
+pub mod audit;
 pub mod auth;
+pub mod completions;
+pub mod config;
 pub mod customers;
+pub mod diff;
+pub mod doctor;
 pub mod plans;
+pub mod prices;
+pub mod query;
+pub mod estimate;
 pub mod subscriptions;
+pub mod support;
 pub mod invoices;
+pub mod payments;
+pub mod taxes;
+pub mod jobs;
 pub mod meters;
 pub mod events;
+pub mod export;
+pub mod import;
+pub mod init;
 pub mod wallets;
 pub mod features;
 pub mod entitlements;
+pub mod tenant;
+pub mod webhooks;
+
+/// The subcommands invoked, e.g. `["invoices", "comment"]`
+pub fn command_path(matches: &clap::ArgMatches) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut matches = matches;
+    while let Some((name, sub)) = matches.subcommand() {
+        path.push(name.to_string());
+        matches = sub;
+    }
+    path
+}
@@ -0,0 +1,217 @@
+use anyhow::Result;
+use clap::Subcommand;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::query::QueryBuilder;
+use crate::api::models::Payment;
+use crate::cli::{completions, invoices};
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::fanout::{self, IdsArgs};
+use crate::utils::wait::{self, WaitArgs};
+use crate::utils::{output, spinner};
+
+#[derive(Subcommand)]
+pub enum PaymentCommands {
+    /// List payments
+    List {
+        /// Only payments for this invoice
+        #[arg(long, add = completions::ids("/v1/invoices"))]
+        invoice_id: Option<String>,
+        /// Only payments with this status, e.g. succeeded or failed
+        #[arg(long)]
+        status: Option<String>,
+        /// Append per-currency totals (count, sum, average)
+        #[arg(long)]
+        summary: bool,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Get a payment by ID, or many with --ids-file
+    Get {
+        #[arg(add = completions::ids("/v1/payments"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Record payments interactively or from a JSON file (object or array of objects)
+    Create {
+        #[arg(long)]
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Update a payment from flags, a JSON file, or in $EDITOR
+    Update {
+        #[arg(add = completions::ids("/v1/payments"))]
+        id: String,
+        #[arg(long, required_unless_present_any = ["edit", "status"])]
+        json: Option<String>,
+        /// Edit the payment's current state in $EDITOR
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        /// New payment status, e.g. SUCCEEDED
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        status: Option<String>,
+    },
+    /// Attempt to collect payment for an invoice
+    Attempt {
+        /// Invoice ID
+        #[arg(add = completions::ids("/v1/invoices"))]
+        invoice_id: String,
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
+}
+
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "payment",
+    fields: &[
+        Field::text("destination_id", "Invoice ID").required(),
+        Field::choice("destination_type", "Destination type", &["INVOICE"]),
+        Field::text("amount", "Amount").required(),
+        Field::text("currency", "Currency").default("usd"),
+        Field::choice("payment_method_type", "Payment method", &["OFFLINE", "CREDITS", "CARD", "ACH"]),
+        Field::text("payment_method_id", "Payment method ID (card, ACH, and credits only)"),
+    ],
+    check: None,
+};
+
+/// Starting point for `create --edit`
+const CREATE_TEMPLATE: &str = r#"{
+  "destination_type": "INVOICE",
+  "destination_id": "",
+  "amount": "0.00",
+  "currency": "usd",
+  "payment_method_type": "OFFLINE",
+  "payment_method_id": "",
+  "process_payment": true
+}"#;
+
+#[derive(Tabled, serde::Serialize)]
+struct PaymentRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Invoice")]
+    invoice_id: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+    #[tabled(rename = "Currency")]
+    currency: String,
+    #[tabled(rename = "Method")]
+    method: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Created")]
+    created_at: String,
+}
+
+impl From<Payment> for PaymentRow {
+    fn from(p: Payment) -> Self {
+        let invoice_id = match p.destination_type.as_deref() {
+            Some("INVOICE") | None => p.destination_id.unwrap_or_default(),
+            Some(other) => format!("{} {}", other.to_lowercase(), p.destination_id.unwrap_or_default()),
+        };
+        Self {
+            id: p.id,
+            invoice_id,
+            amount: p.amount.unwrap_or_default(),
+            currency: p.currency.unwrap_or_default().to_uppercase(),
+            method: p.payment_method_type.unwrap_or_default(),
+            status: p.payment_status.map(|s| output::status_badge(&s)).unwrap_or_default(),
+            created_at: p.created_at.unwrap_or_default(),
+        }
+    }
+}
+
+pub async fn handle(cmd: PaymentCommands, client: ApiClient) -> Result<()> {
+    match cmd {
+        PaymentCommands::List { invoice_id, status, summary, page } => {
+            let path = QueryBuilder::new()
+                .opt("destination_id", invoice_id)
+                .opt("payment_status", status.map(|s| s.to_uppercase()))
+                .to_path("/v1/payments");
+            let sp = spinner::create_spinner("Fetching payments...");
+            let (items, pagination) = page.fetch::<Payment>(&client, &path).await?;
+            sp.finish_and_clear();
+            let totals = summary.then(|| {
+                output::summarize(items.iter().map(|p| {
+                    let amount = p.amount.as_deref().and_then(|a| a.parse().ok()).unwrap_or_default();
+                    (p.currency.as_deref().unwrap_or_default(), amount)
+                }))
+            });
+            let rows: Vec<PaymentRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, totals.as_deref(), "payment"));
+        }
+        PaymentCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/payments", &many).await };
+            let sp = spinner::create_spinner("Fetching payment...");
+            let payment: Payment = client.get(&format!("/v1/payments/{}", id)).await?;
+            sp.finish_and_clear();
+            println!("{}", output::print_detail(&payment));
+        }
+        PaymentCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<Payment>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                let sp = spinner::create_spinner("Recording payment...");
+                let payment: Payment = client.post("/v1/payments", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Payment created: {}", payment.id));
+                println!("{}", output::print_detail(&payment));
+            }
+            Some(Payload::Many(items)) => {
+                batch::create_many(&client, items, file.as_deref(), &opts, "Payment created", "/v1/payments").await?;
+            }
+        },
+        PaymentCommands::Update { id, json: file, status, .. } => {
+            let path = format!("/v1/payments/{}", id);
+            let fields = editor::field_updates(&[
+                ("payment_status", status.map(|s| s.to_uppercase().into())),
+            ]);
+            let body = match fields {
+                Some(body) => Some(body),
+                None => editor::update_body::<Payment>(&client, &path, file.as_deref()).await?,
+            };
+            if let Some(body) = body {
+                let sp = spinner::create_spinner("Updating payment...");
+                let payment: Payment = client.put(&path, &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Payment updated: {}", payment.id));
+                println!("{}", output::print_detail(&payment));
+            }
+        }
+        PaymentCommands::Attempt { invoice_id, wait } => {
+            let timeout = wait.timeout()?;
+            let sp = spinner::create_spinner("Attempting payment...");
+            let resp: serde_json::Value = client
+                .post_empty(&format!("/v1/invoices/{}/payment/attempt", invoice_id))
+                .await?;
+            sp.finish_and_clear();
+            if !wait.wait || invoices::is_settled(&resp) {
+                output::success(&format!("Payment attempted for invoice {}.", invoice_id));
+                println!("{}", output::print_detail(&resp));
+                return Ok(());
+            }
+            let path = format!("/v1/invoices/{}", invoice_id);
+            let what = format!("payment for invoice {}", invoice_id);
+            let Some(inv) = wait::resource(&client, &path, &what, "payment_status", invoices::PAYMENT_SETTLED, timeout).await? else {
+                output::info("Stopped waiting; collection continues on the server.");
+                return Ok(());
+            };
+            println!("{}", output::print_detail(&inv));
+            match inv.get("payment_status").and_then(|s| s.as_str()).unwrap_or_default().to_uppercase().as_str() {
+                "FAILED" => anyhow::bail!("Payment for invoice {} failed", invoice_id),
+                "SUCCEEDED" => output::success(&format!("Payment collected for invoice {}.", invoice_id)),
+                status => output::info(&format!("Payment for invoice {} is {}.", invoice_id, status.to_lowercase())),
+            }
+        }
+    }
+    Ok(())
+}
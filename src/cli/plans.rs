@@ -1,36 +1,106 @@
-use anyhow::Result;
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use colored::Colorize;
 use tabled::Tabled;
 
 use crate::api::client::ApiClient;
-use crate::api::models::{Plan, ListResponse};
-use crate::cli::auth::require_auth;
+use crate::api::query::QueryBuilder;
+use crate::api::models::{Entitlement, Feature, Meter, Plan, Price};
+use crate::cli::completions;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::fanout::{self, DeleteManyArgs, IdsArgs};
 use crate::utils::{output, spinner};
 
 #[derive(Subcommand)]
 pub enum PlanCommands {
-    /// List all plans
+    /// List plans, a page at a time
     List {
-        #[arg(long)]
-        json: bool,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get a plan by ID
+    /// Get a plan by ID, or many with --ids-file
     Get {
-        id: String,
-        #[arg(long)]
-        json: bool,
+        #[arg(add = completions::ids("/v1/plans"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
     },
-    /// Create a new plan from a JSON file
+    /// Create plans interactively or from a JSON file (object or array of objects)
     Create {
         #[arg(long)]
-        json: String,
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Update a plan from flags, a JSON file, or in $EDITOR
+    Update {
+        #[arg(add = completions::ids("/v1/plans"))]
+        id: String,
+        #[arg(long, required_unless_present_any = ["edit", "name", "description"])]
+        json: Option<String>,
+        /// Edit the plan's current state in $EDITOR
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        /// New name
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        name: Option<String>,
+        /// New description
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        description: Option<String>,
+    },
+    /// Delete a plan by ID, or many from --ids-file or --filter
+    Delete {
+        #[arg(
+            add = completions::ids("/v1/plans"),
+            required_unless_present_any = ["ids_file", "filters"],
+            conflicts_with_all = ["ids_file", "filters"]
+        )]
+        id: Option<String>,
+        #[command(flatten)]
+        many: DeleteManyArgs,
+    },
+    /// Compare two plans' prices and entitlements side by side
+    Diff {
+        /// Plan to compare from, e.g. the current one
+        #[arg(add = completions::ids("/v1/plans"))]
+        from: String,
+        /// Plan to compare to, e.g. the upgrade
+        #[arg(add = completions::ids("/v1/plans"))]
+        to: String,
+        /// Also list what the plans have in common
+        #[arg(long)]
+        all: bool,
     },
-    /// Delete a plan by ID
-    Delete { id: String },
 }
 
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "plan",
+    fields: &[
+        Field::text("name", "Name").required(),
+        Field::text("lookup_key", "Lookup key"),
+        Field::text("description", "Description"),
+    ],
+    check: None,
+};
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "name": "",
+  "lookup_key": "",
+  "description": ""
+}"#;
+
 #[derive(Tabled, serde::Serialize)]
-struct PlanRow {
+pub struct PlanRow {
     #[tabled(rename = "ID")]
     id: String,
     #[tabled(rename = "Name")]
@@ -52,39 +122,297 @@ impl From<Plan> for PlanRow {
     }
 }
 
-pub async fn handle(cmd: PlanCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
-
+pub async fn handle(cmd: PlanCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        PlanCommands::List { json } => {
+        PlanCommands::List { page } => {
             let sp = spinner::create_spinner("Fetching plans...");
-            let resp: ListResponse<Plan> = client.get("/v1/plans").await?;
+            let (items, pagination) = page.fetch::<Plan>(&client, "/v1/plans").await?;
             sp.finish_and_clear();
-            let rows: Vec<PlanRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            let rows: Vec<PlanRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "plan"));
         }
-        PlanCommands::Get { id, json } => {
+        PlanCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/plans", &many).await };
             let sp = spinner::create_spinner("Fetching plan...");
             let plan: Plan = client.get(&format!("/v1/plans/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&plan, json));
+            println!("{}", output::print_detail(&plan));
         }
-        PlanCommands::Create { json: file } => {
-            let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Creating plan...");
-            let plan: Plan = client.post("/v1/plans", &body).await?;
-            sp.finish_and_clear();
-            output::success(&format!("Plan created: {}", plan.id));
-            println!("{}", output::print_detail(&plan, false));
+        PlanCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<Plan>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                let sp = spinner::create_spinner("Creating plan...");
+                let plan: Plan = client.post("/v1/plans", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Plan created: {}", plan.id));
+                println!("{}", output::print_detail(&plan));
+            }
+            Some(Payload::Many(items)) => {
+                batch::create_many(&client, items, file.as_deref(), &opts, "Plan created", "/v1/plans").await?;
+            }
+        },
+        PlanCommands::Update { id, json: file, name, description, .. } => {
+            let path = format!("/v1/plans/{}", id);
+            let fields = editor::field_updates(&[
+                ("name", name.map(Into::into)),
+                ("description", description.map(Into::into)),
+            ]);
+            let body = match fields {
+                Some(body) => Some(body),
+                None => editor::update_body::<Plan>(&client, &path, file.as_deref()).await?,
+            };
+            if let Some(body) = body {
+                let sp = spinner::create_spinner("Updating plan...");
+                let plan: Plan = client.put(&path, &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Plan updated: {}", plan.id));
+                println!("{}", output::print_detail(&plan));
+            }
         }
-        PlanCommands::Delete { id } => {
+        PlanCommands::Delete { id, many } => {
+            let Some(id) = id else { return fanout::delete_many(&client, "/v1/plans", "plans", &many).await };
             let sp = spinner::create_spinner("Deleting plan...");
             client.delete_empty(&format!("/v1/plans/{}", id)).await?;
             sp.finish_and_clear();
             output::success(&format!("Plan {} deleted.", id));
         }
+        PlanCommands::Diff { from, to, all } => {
+            let sp = spinner::create_spinner("Fetching plans...");
+            let fetched = tokio::try_join!(
+                fetch_terms(&client, &from),
+                fetch_terms(&client, &to),
+                client.get_all::<Meter>("/v1/meters"),
+                client.get_all::<Feature>("/v1/features"),
+            );
+            sp.finish_and_clear();
+            let (from, to, meters, features) = fetched?;
+
+            let mut names: HashMap<String, String> = HashMap::new();
+            names.extend(meters.into_iter().filter_map(|m| Some((m.id, m.name?))));
+            names.extend(features.into_iter().filter_map(|f| Some((f.id, f.name?))));
+            let differences: Vec<Difference> = [
+                compare("Plan", plan_terms(&from.plan), plan_terms(&to.plan)),
+                compare("Price", price_terms(&from.prices, &names), price_terms(&to.prices, &names)),
+                compare("Entitlement", entitlement_terms(&from.entitlements, &names), entitlement_terms(&to.entitlements, &names)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            print_diff(&from.plan, &to.plan, differences, all);
+        }
     }
     Ok(())
 }
+
+/// A plan with what it charges and grants
+struct PlanTerms {
+    plan: Plan,
+    prices: Vec<Price>,
+    entitlements: Vec<Entitlement>,
+}
+
+async fn fetch_terms(client: &ApiClient, id: &str) -> Result<PlanTerms> {
+    let plan_path = format!("/v1/plans/{}", id);
+    let (prices_path, entitlements_path) = (
+        QueryBuilder::new().param("plan_ids", id).to_path("/v1/prices"),
+        QueryBuilder::new().param("plan_ids", id).to_path("/v1/entitlements"),
+    );
+    let (plan, prices, entitlements) = tokio::try_join!(
+        client.get::<Plan>(&plan_path),
+        client.get_all::<Price>(&prices_path),
+        client.get_all::<Entitlement>(&entitlements_path),
+    )
+    .with_context(|| format!("Failed to fetch plan {}", id))?;
+    // Archived prices no longer bill
+    let prices = prices
+        .into_iter()
+        .filter(|p| !p.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("archived") || s.eq_ignore_ascii_case("deleted")))
+        .collect();
+    Ok(PlanTerms { plan, prices, entitlements })
+}
+
+/// One line of a plan's terms: a key to match it across plans, a label, and its value
+struct Term {
+    key: String,
+    label: String,
+    value: String,
+}
+
+/// How one term differs between the plans; `None` where a plan lacks it
+#[derive(serde::Serialize)]
+struct Difference {
+    section: &'static str,
+    item: String,
+    change: &'static str,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct DiffRow {
+    #[tabled(rename = "")]
+    change: String,
+    #[tabled(rename = "Section")]
+    section: String,
+    #[tabled(rename = "Item")]
+    item: String,
+    #[tabled(rename = "From")]
+    from: String,
+    #[tabled(rename = "To")]
+    to: String,
+}
+
+fn plan_terms(plan: &Plan) -> Vec<Term> {
+    let term = |key: &str, value: &Option<String>| Term { key: key.into(), label: key.into(), value: value.clone().unwrap_or_default() };
+    vec![term("Name", &plan.name), term("Description", &plan.description)]
+}
+
+/// Usage prices match across plans by meter, fixed fees by position; both
+/// only within the same billing period and currency
+fn price_terms(prices: &[Price], names: &HashMap<String, String>) -> Vec<Term> {
+    let mut fees: HashMap<String, usize> = HashMap::new();
+    prices
+        .iter()
+        .map(|p| {
+            let scope = format!("{}:{}", period(p), p.currency.as_deref().unwrap_or_default().to_uppercase());
+            let (key, label) = match &p.meter_id {
+                Some(meter) => (format!("usage:{}:{}", meter, scope), names.get(meter).unwrap_or(meter).clone()),
+                None => {
+                    let n = fees.entry(scope.clone()).or_default();
+                    *n += 1;
+                    let label = p.description.clone().or_else(|| p.lookup_key.clone()).unwrap_or_else(|| "Fixed fee".into());
+                    (format!("fixed:{}:{}", scope, n), label)
+                }
+            };
+            Term { key, label, value: describe_price(p) }
+        })
+        .collect()
+}
+
+fn entitlement_terms(entitlements: &[Entitlement], names: &HashMap<String, String>) -> Vec<Term> {
+    entitlements
+        .iter()
+        .map(|e| {
+            let feature = e.feature_id.clone().unwrap_or_default();
+            let value = if e.is_enabled == Some(false) {
+                "disabled".to_string()
+            } else if e.feature_type.as_deref() == Some("METERED") {
+                e.usage_limit.map_or("unlimited".to_string(), |l| format!("up to {}", l))
+            } else {
+                "enabled".to_string()
+            };
+            Term { label: names.get(&feature).unwrap_or(&feature).clone(), key: feature, value }
+        })
+        .collect()
+}
+
+/// Billing period, e.g. `monthly` or `3 × monthly`
+fn period(p: &Price) -> String {
+    let period = p.billing_period.as_deref().unwrap_or_default().to_lowercase();
+    match p.billing_period_count {
+        Some(n) if n > 1 => format!("{} × {}", n, period),
+        _ => period,
+    }
+}
+
+/// What a price charges, detailed enough that any change to it shows
+fn describe_price(p: &Price) -> String {
+    let currency = p.currency.as_deref().unwrap_or_default().to_uppercase();
+    let amount = p.amount.as_deref().unwrap_or("0");
+    let rate = match p.billing_model.as_deref() {
+        Some("TIERED") => {
+            let tiers: Vec<String> = p
+                .tiers
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .map(|t| {
+                    let bound = t.up_to.map_or("rest".to_string(), |up| format!("≤{}", up));
+                    let flat = t.flat_amount.as_deref().filter(|f| f.parse::<f64>().is_ok_and(|f| f != 0.0));
+                    match flat {
+                        Some(flat) => format!("{} @ {} + {}", bound, t.unit_amount.as_deref().unwrap_or("0"), flat),
+                        None => format!("{} @ {}", bound, t.unit_amount.as_deref().unwrap_or("0")),
+                    }
+                })
+                .collect();
+            format!("{} {}: {}", p.tier_mode.as_deref().unwrap_or("VOLUME").to_lowercase(), currency, tiers.join(", "))
+        }
+        Some("PACKAGE") => {
+            let size = p.transform_quantity.as_ref().and_then(|t| t.get("divide_by")).and_then(|v| v.as_f64()).unwrap_or(1.0);
+            format!("{} {} per {} units", amount, currency, size)
+        }
+        _ if p.meter_id.is_none() => format!("{} {}", amount, currency),
+        _ => format!("{} {} per unit", amount, currency),
+    };
+    match period(p) {
+        period if period.is_empty() => rate,
+        period => format!("{} / {}", rate, period),
+    }
+}
+
+/// Terms of both plans in order: `from`'s, then those only `to` has
+fn compare(section: &'static str, from: Vec<Term>, to: Vec<Term>) -> Vec<Difference> {
+    let mut to: Vec<Option<Term>> = to.into_iter().map(Some).collect();
+    let mut differences = vec![];
+    for term in from {
+        let matched = to.iter_mut().find(|t| t.as_ref().is_some_and(|t| t.key == term.key)).and_then(Option::take);
+        let change = match &matched {
+            None => "removed",
+            Some(other) if other.value != term.value => "changed",
+            Some(_) => "unchanged",
+        };
+        differences.push(Difference {
+            section,
+            item: match &matched {
+                Some(other) if other.label != term.label => format!("{} → {}", term.label, other.label),
+                _ => term.label.clone(),
+            },
+            change,
+            from: Some(term.value),
+            to: matched.map(|t| t.value),
+        });
+    }
+    differences.extend(to.into_iter().flatten().map(|term| Difference {
+        section,
+        item: term.label,
+        change: "added",
+        from: None,
+        to: Some(term.value),
+    }));
+    differences
+}
+
+fn print_diff(from: &Plan, to: &Plan, differences: Vec<Difference>, all: bool) {
+    let differences: Vec<Difference> = differences.into_iter().filter(|d| all || d.change != "unchanged").collect();
+    if !output::human() {
+        println!("{}", output::print_detail(&differences));
+        return;
+    }
+    let plan_name = |p: &Plan| format!("{} ({})", p.name.as_deref().unwrap_or(&p.id), p.id);
+    output::info(&format!("From: {}", plan_name(from)));
+    output::info(&format!("To:   {}", plan_name(to)));
+    println!();
+    let changed = differences.iter().filter(|d| d.change != "unchanged").count();
+    if differences.is_empty() {
+        output::success("The plans have the same prices and entitlements.");
+        return;
+    }
+    let rows: Vec<DiffRow> = differences
+        .into_iter()
+        .map(|d| DiffRow {
+            change: match d.change {
+                "added" => "+".green().bold().to_string(),
+                "removed" => "-".red().bold().to_string(),
+                "changed" => "~".yellow().bold().to_string(),
+                _ => "=".dimmed().to_string(),
+            },
+            section: d.section.to_string(),
+            item: d.item,
+            from: d.from.unwrap_or_else(|| "—".into()),
+            to: d.to.unwrap_or_else(|| "—".into()),
+        })
+        .collect();
+    println!("{}", output::print_table(&rows));
+    println!();
+    output::info(&format!("{} difference(s)", changed));
+}
@@ -0,0 +1,212 @@
+use anyhow::Result;
+use clap::Subcommand;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::query::QueryBuilder;
+use crate::api::models::Price;
+use crate::cli::completions;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::PageArgs;
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::fanout::{self, DeleteManyArgs, IdsArgs};
+use crate::utils::{output, spinner};
+
+#[derive(Subcommand)]
+pub enum PriceCommands {
+    /// List prices, optionally for one plan
+    List {
+        /// Only prices attached to this plan
+        #[arg(long, add = completions::ids("/v1/plans"))]
+        plan_id: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Get a price by ID, including its tiers, or many with --ids-file
+    Get {
+        #[arg(add = completions::ids("/v1/prices"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Create prices interactively or from a JSON file (object or array of objects)
+    Create {
+        #[arg(long)]
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Update a price from flags, a JSON file, or in $EDITOR
+    Update {
+        #[arg(add = completions::ids("/v1/prices"))]
+        id: String,
+        #[arg(long, required_unless_present_any = ["edit", "lookup_key", "description"])]
+        json: Option<String>,
+        /// Edit the price's current state in $EDITOR
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        /// New lookup key
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        lookup_key: Option<String>,
+        /// New description
+        #[arg(long, conflicts_with_all = ["json", "edit"])]
+        description: Option<String>,
+    },
+    /// Delete a price by ID, or many from --ids-file or --filter
+    Delete {
+        #[arg(
+            add = completions::ids("/v1/prices"),
+            required_unless_present_any = ["ids_file", "filters"],
+            conflicts_with_all = ["ids_file", "filters"]
+        )]
+        id: Option<String>,
+        #[command(flatten)]
+        many: DeleteManyArgs,
+    },
+}
+
+/// Prompts for `create` without `--json` or `--edit`. Tiered prices need
+/// `tiers`, so they are created with `--edit` or `--json`.
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "price",
+    fields: &[
+        Field::text("plan_id", "Plan ID").required(),
+        Field::choice("type", "Type", &["USAGE", "FIXED"]),
+        Field::choice("billing_model", "Billing model", &["FLAT_FEE", "PACKAGE"]),
+        Field::text("amount", "Amount").required(),
+        Field::text("currency", "Currency").default("usd"),
+        Field::choice("billing_period", "Billing period", &["MONTHLY", "ANNUAL", "QUARTERLY", "WEEKLY", "DAILY"]),
+        Field::text("meter_id", "Meter ID (usage prices only)"),
+        Field::text("lookup_key", "Lookup key"),
+        Field::text("description", "Description"),
+    ],
+    check: None,
+};
+
+/// Starting point for `create --edit`
+const CREATE_TEMPLATE: &str = r#"{
+  "plan_id": "",
+  "type": "USAGE",
+  "billing_model": "TIERED",
+  "tier_mode": "VOLUME",
+  "currency": "usd",
+  "billing_period": "MONTHLY",
+  "billing_period_count": 1,
+  "billing_cadence": "RECURRING",
+  "invoice_cadence": "ARREAR",
+  "meter_id": "",
+  "tiers": [
+    { "up_to": 1000, "unit_amount": "0.01" },
+    { "up_to": null, "unit_amount": "0.005" }
+  ],
+  "lookup_key": "",
+  "description": ""
+}"#;
+
+#[derive(Tabled, serde::Serialize)]
+struct PriceRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Plan")]
+    plan_id: String,
+    #[tabled(rename = "Type")]
+    price_type: String,
+    #[tabled(rename = "Model")]
+    billing_model: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+    #[tabled(rename = "Currency")]
+    currency: String,
+    #[tabled(rename = "Period")]
+    period: String,
+    #[tabled(rename = "Meter")]
+    meter_id: String,
+}
+
+impl From<Price> for PriceRow {
+    fn from(p: Price) -> Self {
+        let amount = match &p.tiers {
+            Some(tiers) if p.billing_model.as_deref() == Some("TIERED") => {
+                format!("{} tiers ({})", tiers.len(), p.tier_mode.as_deref().unwrap_or("VOLUME").to_lowercase())
+            }
+            _ => p.amount.clone().unwrap_or_default(),
+        };
+        let period = match (p.billing_period, p.billing_period_count) {
+            (Some(period), Some(n)) if n > 1 => format!("{} × {}", n, period),
+            (period, _) => period.unwrap_or_default(),
+        };
+        Self {
+            id: p.id,
+            plan_id: p.plan_id.unwrap_or_default(),
+            price_type: p.price_type.unwrap_or_default(),
+            billing_model: p.billing_model.unwrap_or_default(),
+            amount,
+            currency: p.currency.unwrap_or_default().to_uppercase(),
+            period,
+            meter_id: p.meter_id.unwrap_or_default(),
+        }
+    }
+}
+
+pub async fn handle(cmd: PriceCommands, client: ApiClient) -> Result<()> {
+    match cmd {
+        PriceCommands::List { plan_id, page } => {
+            let path = QueryBuilder::new().opt("plan_ids", plan_id).to_path("/v1/prices");
+            let sp = spinner::create_spinner("Fetching prices...");
+            let (items, pagination) = page.fetch::<Price>(&client, &path).await?;
+            sp.finish_and_clear();
+            let rows: Vec<PriceRow> = items.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, None, "price"));
+        }
+        PriceCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/prices", &many).await };
+            let sp = spinner::create_spinner("Fetching price...");
+            let price: Price = client.get(&format!("/v1/prices/{}", id)).await?;
+            sp.finish_and_clear();
+            println!("{}", output::print_detail(&price));
+        }
+        PriceCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<Price>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                let sp = spinner::create_spinner("Creating price...");
+                let price: Price = client.post("/v1/prices", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Price created: {}", price.id));
+                println!("{}", output::print_detail(&price));
+            }
+            Some(Payload::Many(items)) => {
+                batch::create_many(&client, items, file.as_deref(), &opts, "Price created", "/v1/prices").await?;
+            }
+        },
+        PriceCommands::Update { id, json: file, lookup_key, description, .. } => {
+            let path = format!("/v1/prices/{}", id);
+            let fields = editor::field_updates(&[
+                ("lookup_key", lookup_key.map(Into::into)),
+                ("description", description.map(Into::into)),
+            ]);
+            let body = match fields {
+                Some(body) => Some(body),
+                None => editor::update_body::<Price>(&client, &path, file.as_deref()).await?,
+            };
+            if let Some(body) = body {
+                let sp = spinner::create_spinner("Updating price...");
+                let price: Price = client.put(&path, &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Price updated: {}", price.id));
+                println!("{}", output::print_detail(&price));
+            }
+        }
+        PriceCommands::Delete { id, many } => {
+            let Some(id) = id else { return fanout::delete_many(&client, "/v1/prices", "prices", &many).await };
+            let sp = spinner::create_spinner("Deleting price...");
+            client.delete_empty(&format!("/v1/prices/{}", id)).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Price {} deleted.", id));
+        }
+    }
+    Ok(())
+}
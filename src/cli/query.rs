@@ -0,0 +1,138 @@
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use tabled::Tabled;
+
+use crate::cli::assert::split_command;
+use crate::config::ConfigFile;
+use crate::utils::output;
+
+#[derive(Subcommand)]
+pub enum QueryCommands {
+    /// Save a command under a name for the current profile
+    Save {
+        /// Name to run it by, e.g. unpaid-eu
+        name: String,
+        /// Command to save, quoted, e.g. "invoices list --summary -o csv"
+        command: String,
+        /// Replace a query already saved under this name
+        #[arg(long)]
+        force: bool,
+    },
+    /// Run a saved query, appending any extra arguments
+    Run {
+        name: String,
+        /// Arguments added after the saved command
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// List the current profile's saved queries
+    List,
+    /// Delete a saved query
+    Delete { name: String },
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct QueryRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Command")]
+    command: String,
+}
+
+pub fn handle(cmd: QueryCommands, cli: clap::Command) -> Result<()> {
+    let mut config = ConfigFile::load()?;
+    let profile = config.selected_profile();
+
+    match cmd {
+        QueryCommands::Save { name, command, force } => {
+            let words = parse(&command, cli)?;
+            let queries = config.queries.entry(profile.clone()).or_default();
+            if queries.contains_key(&name) && !force {
+                anyhow::bail!("Query '{}' already exists for profile '{}'; pass --force to replace it.", name, profile);
+            }
+            queries.insert(name.clone(), words.join(" "));
+            config.save()?;
+            output::success(&format!("Saved '{}' for profile '{}'.", name, profile));
+            output::info(&format!("Run it with `flexprice query run {}`.", name));
+        }
+        QueryCommands::Run { name, .. } => {
+            let command = lookup(&config, &profile, &name)?;
+            let (before, after) = surrounding_args(&name);
+            let mut argv = before;
+            argv.extend(split_command(command)?);
+            argv.extend(after);
+            let exe = std::env::current_exe().context("Cannot locate the flexprice binary")?;
+            let status = Command::new(exe)
+                .args(&argv)
+                .status()
+                .with_context(|| format!("Failed to run query '{}'", name))?;
+            // The query has already reported its own error
+            if !status.success() {
+                std::process::exit(status.code().unwrap_or(1));
+            }
+        }
+        QueryCommands::List => {
+            let rows: Vec<QueryRow> = config
+                .queries
+                .remove(&profile)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, command)| QueryRow { name, command })
+                .collect();
+            println!("{}", output::print_table(&rows));
+        }
+        QueryCommands::Delete { name } => {
+            lookup(&config, &profile, &name)?;
+            if let Some(queries) = config.queries.get_mut(&profile) {
+                queries.remove(&name);
+                if queries.is_empty() {
+                    config.queries.remove(&profile);
+                }
+            }
+            config.save()?;
+            output::success(&format!("Query '{}' deleted.", name));
+        }
+    }
+    Ok(())
+}
+
+/// Split a command to save and check that it parses, so typos surface now
+/// rather than on the first run
+fn parse(command: &str, cli: clap::Command) -> Result<Vec<String>> {
+    let mut words = split_command(command)?;
+    if words.first().is_some_and(|w| w == "flexprice") {
+        words.remove(0);
+    }
+    let matches = cli
+        .try_get_matches_from(std::iter::once("flexprice".to_string()).chain(words.iter().cloned()))
+        .map_err(|e| anyhow::anyhow!("Invalid command: {}", e.render().to_string().trim()))?;
+    if matches.subcommand_name() == Some("query") {
+        anyhow::bail!("A saved query can't run another query.");
+    }
+    // Keep quoted arguments together when the words are joined for storage
+    Ok(words
+        .into_iter()
+        .map(|w| if w.is_empty() || w.contains(char::is_whitespace) { format!("\"{}\"", w) } else { w })
+        .collect())
+}
+
+fn lookup<'a>(config: &'a ConfigFile, profile: &str, name: &str) -> Result<&'a String> {
+    config.queries.get(profile).and_then(|q| q.get(name)).with_context(|| {
+        format!("No query '{}' for profile '{}'. Run `flexprice query list` to see saved queries.", name, profile)
+    })
+}
+
+/// The arguments this invocation was given around `query run NAME`, passed
+/// on as typed: global flags before it (e.g. `--profile`) stay in front, where
+/// flags inside the saved command take precedence over them, and everything
+/// after the name is appended to the saved command.
+fn surrounding_args(name: &str) -> (Vec<String>, Vec<String>) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(start) = args.windows(2).position(|w| w[0] == "query" && w[1] == "run") else {
+        return (vec![], vec![]);
+    };
+    let end = args[start + 2..].iter().position(|a| a == name).map_or(args.len(), |i| start + 3 + i);
+    (args[..start].to_vec(), args[end..].to_vec())
+}
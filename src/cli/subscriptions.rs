@@ -1,42 +1,158 @@
 use anyhow::Result;
+use chrono::{SecondsFormat, Utc};
 use clap::Subcommand;
+use dialoguer::{FuzzySelect, Input, Select};
+use futures::stream::{self, StreamExt};
 use tabled::Tabled;
 
 use crate::api::client::ApiClient;
-use crate::api::models::{Subscription, ListResponse};
-use crate::cli::auth::require_auth;
-use crate::utils::{output, spinner};
+use crate::api::query::QueryBuilder;
+use crate::api::models::{Customer, Invoice, ListResponse, Plan, Price, Subscription, SubscriptionPause};
+use crate::cli::{completions, customers};
+use crate::config::globals;
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::fanout::{self, IdsArgs};
+use crate::utils::paging::PageArgs;
+use crate::utils::{output, spinner, time, wizard};
 
 #[derive(Subcommand)]
 pub enum SubscriptionCommands {
-    /// List all subscriptions
+    /// List subscriptions, a page at a time
     List {
+        /// Only paused subscriptions, with resume dates and the revenue on hold
         #[arg(long)]
-        json: bool,
+        paused: bool,
+        /// Only this customer's subscriptions (ID, ext:<external_id>, or email:<address>)
+        #[arg(long, add = completions::ids("/v1/customers"))]
+        customer: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get a subscription by ID
+    /// Get a subscription by ID, or many with --ids-file
     Get {
-        id: String,
-        #[arg(long)]
-        json: bool,
+        #[arg(add = completions::ids("/v1/subscriptions"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
     },
-    /// Create a new subscription from a JSON file
+    /// Create subscriptions from a JSON file (object or array of objects)
+    ///
+    /// Without --json or --edit, prompts for a customer and plan from the
+    /// tenant, then the start date, billing period, and trial.
     Create {
         #[arg(long)]
-        json: String,
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
     },
     /// Cancel a subscription
-    Cancel { id: String },
+    Cancel {
+        #[arg(add = completions::ids("/v1/subscriptions"))]
+        id: String,
+    },
+    /// Pause a subscription now or from a date, optionally until a set end
+    Pause {
+        #[arg(add = completions::ids("/v1/subscriptions"))]
+        id: String,
+        /// Start the pause on this date (YYYY-MM-DD or RFC 3339) instead of now
+        #[arg(long)]
+        from: Option<String>,
+        /// Resume automatically on this date
+        #[arg(long, conflicts_with = "days")]
+        until: Option<String>,
+        /// Resume automatically after this many days
+        #[arg(long)]
+        days: Option<u32>,
+        /// Why the subscription is paused
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Resume a paused subscription now
+    Resume {
+        #[arg(add = completions::ids("/v1/subscriptions"))]
+        id: String,
+    },
+    /// Activate a draft subscription
+    Activate {
+        #[arg(add = completions::ids("/v1/subscriptions"))]
+        id: String,
+        /// Start date (YYYY-MM-DD or RFC 3339); defaults to now
+        #[arg(long)]
+        start_date: Option<String>,
+    },
+    /// Move a subscription to another plan
+    ChangePlan {
+        #[arg(add = completions::ids("/v1/subscriptions"))]
+        id: String,
+        /// Plan to move to
+        #[arg(long, add = completions::ids("/v1/plans"))]
+        plan: String,
+        /// How to bill the unused part of the current period
+        #[arg(long, value_enum, default_value_t = ProrationBehavior::CreateProrations)]
+        proration_behavior: ProrationBehavior,
+        /// Show the resulting charges without changing the subscription
+        #[arg(long)]
+        preview: bool,
+    },
+    /// Attach an add-on to a subscription
+    AddAddon {
+        #[arg(add = completions::ids("/v1/subscriptions"))]
+        id: String,
+        /// Add-on to attach
+        #[arg(long, add = completions::ids("/v1/addons"))]
+        addon: String,
+        /// How many instances, for add-ons of type multiple_instance
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        quantity: u32,
+    },
+    /// Schedule a paused subscription to resume on a date
+    ResumeAt {
+        #[arg(add = completions::ids("/v1/subscriptions"))]
+        id: String,
+        /// Resume date (YYYY-MM-DD) or RFC 3339 timestamp
+        #[arg(long)]
+        date: String,
+    },
     /// Get usage for a subscription
     Usage {
         /// JSON body for usage query
         #[arg(long)]
         json: String,
+        /// Cap the query's end_time at this date or RFC 3339 timestamp
+        #[arg(long)]
+        as_of: Option<String>,
     },
 }
 
+#[derive(Clone, Copy, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProrationBehavior {
+    /// Credit unused time on the old plan and charge for the new one
+    CreateProrations,
+    /// Switch without prorated charges or credits
+    None,
+}
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "customer_id": "",
+  "plan_id": "",
+  "currency": "usd",
+  "billing_cadence": "RECURRING",
+  "billing_period": "MONTHLY",
+  "billing_period_count": 1,
+  "start_date": ""
+}"#;
+
+/// Billing periods offered when the plan's prices don't name any
+const BILLING_PERIODS: &[&str] = &["MONTHLY", "ANNUAL", "QUARTERLY", "HALF_YEARLY", "WEEKLY", "DAILY"];
+
 #[derive(Tabled, serde::Serialize)]
-struct SubscriptionRow {
+pub struct SubscriptionRow {
     #[tabled(rename = "ID")]
     id: String,
     #[tabled(rename = "Customer")]
@@ -64,48 +180,391 @@ impl From<Subscription> for SubscriptionRow {
     }
 }
 
-pub async fn handle(cmd: SubscriptionCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
+/// A subscription with its current period written out for people
+#[derive(Tabled, serde::Serialize)]
+struct ReadableSubscriptionRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Customer")]
+    customer_id: String,
+    #[tabled(rename = "Plan")]
+    plan_id: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Current Period")]
+    period: String,
+}
 
+impl From<Subscription> for ReadableSubscriptionRow {
+    fn from(s: Subscription) -> Self {
+        let period = current_period(&s);
+        let row = SubscriptionRow::from(s);
+        Self {
+            id: row.id,
+            customer_id: row.customer_id,
+            plan_id: row.plan_id,
+            status: row.status,
+            period: period.unwrap_or_else(|| format!("{} → {}", row.period_start, row.period_end)),
+        }
+    }
+}
+
+/// The subscription's current period, e.g. `Mar 1 → Apr 1 (monthly, 12 days remaining)`
+pub fn current_period(s: &Subscription) -> Option<String> {
+    let cadence = match (s.billing_period.as_deref(), s.billing_period_count) {
+        (Some(period), Some(n)) if n > 1 => Some(format!("{} × {}", n, period)),
+        (period, _) => period.map(String::from),
+    };
+    time::describe_period(
+        s.current_period_start.as_deref()?,
+        s.current_period_end.as_deref()?,
+        cadence.as_deref(),
+        Utc::now(),
+    )
+}
+
+/// Prompts for `create` without `--json` or `--edit`: a customer and plan
+/// picked from the tenant, then the terms the plan's prices allow
+async fn create_wizard(client: &ApiClient) -> Result<Option<serde_json::Value>> {
+    wizard::start("subscription")?;
+    let sp = spinner::create_spinner("Fetching customers and plans...");
+    let fetched = tokio::try_join!(client.get_all::<Customer>("/v1/customers"), client.get_all::<Plan>("/v1/plans"));
+    sp.finish_and_clear();
+    let (customers, plans) = fetched?;
+    let plans: Vec<Plan> = plans.into_iter().filter(|p| !matches!(p.status.as_deref(), Some("archived" | "deleted"))).collect();
+    if customers.is_empty() {
+        anyhow::bail!("There are no customers to subscribe. Create one with `flexprice customers create` first.");
+    }
+    if plans.is_empty() {
+        anyhow::bail!("There are no plans to subscribe to. Create one with `flexprice plans create` first.");
+    }
+
+    let labels: Vec<String> = customers.iter().map(customer_label).collect();
+    let customer = &customers[pick("Customer (type to search)", &labels)?];
+    let labels: Vec<String> = plans.iter().map(|p| format!("{} ({})", p.name.as_deref().unwrap_or("Unnamed"), p.id)).collect();
+    let plan = &plans[pick("Plan (type to search)", &labels)?];
+
+    let sp = spinner::create_spinner("Fetching the plan's prices...");
+    let prices = client.get_all::<Price>(&QueryBuilder::new().param("plan_ids", &plan.id).to_path("/v1/prices")).await;
+    sp.finish_and_clear();
+    let prices: Vec<Price> = prices?.into_iter().filter(|p| p.status.as_deref() != Some("archived")).collect();
+    if prices.is_empty() {
+        output::warning("The plan has no prices, so the subscription won't charge anything.");
+    }
+
+    // Offer only the currencies and periods the plan is priced in
+    let mut currencies: Vec<String> = prices.iter().filter_map(|p| p.currency.as_deref()).map(str::to_lowercase).collect();
+    currencies.sort();
+    currencies.dedup();
+    if currencies.is_empty() {
+        currencies.push("usd".into());
+    }
+    let currency = choose("Currency", &currencies)?;
+    let mut periods: Vec<String> = vec![];
+    for period in prices.iter().filter_map(|p| p.billing_period.as_deref()).map(str::to_uppercase) {
+        if !periods.contains(&period) {
+            periods.push(period);
+        }
+    }
+    if periods.is_empty() {
+        periods = BILLING_PERIODS.iter().map(|p| p.to_string()).collect();
+    }
+    let billing_period = choose("Billing period", &periods)?;
+
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let start: String = Input::new()
+        .with_prompt("  Start date (YYYY-MM-DD or RFC 3339)")
+        .default(today)
+        .validate_with(|s: &String| time::parse_instant(s).map(|_| ()).map_err(|e| e.to_string()))
+        .interact_text()?;
+    let start = time::parse_instant(&start)?;
+    let trial_days: u32 = Input::new().with_prompt("  Trial days (0 for none)").default(0).interact_text()?;
+
+    let mut payload = serde_json::json!({
+        "customer_id": customer.id,
+        "plan_id": plan.id,
+        "currency": currency,
+        "billing_cadence": "RECURRING",
+        "billing_period": billing_period,
+        "billing_period_count": 1,
+        "start_date": start.to_rfc3339_opts(SecondsFormat::Secs, true),
+    });
+    if trial_days > 0 {
+        let trial_end = start + chrono::Duration::days(trial_days.into());
+        payload["trial_start"] = start.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+        payload["trial_end"] = trial_end.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+    }
+    Ok(wizard::confirm("subscription", &payload)?.then_some(payload))
+}
+
+/// A customer as shown in the picker, e.g. `Acme Corp · acme-001 · billing@acme.com`
+fn customer_label(c: &Customer) -> String {
+    let parts: Vec<&str> = [c.name.as_deref(), c.external_id.as_deref(), c.email.as_deref()]
+        .into_iter()
+        .flatten()
+        .filter(|p| !p.is_empty())
+        .collect();
+    match parts.is_empty() {
+        true => c.id.clone(),
+        false => parts.join(" · "),
+    }
+}
+
+/// Index of the item picked from a list filtered as you type
+fn pick(prompt: &str, items: &[String]) -> Result<usize> {
+    Ok(FuzzySelect::new().with_prompt(format!("  {}", prompt)).items(items).default(0).interact()?)
+}
+
+/// One of `options`, without a prompt when there is only one
+fn choose(prompt: &str, options: &[String]) -> Result<String> {
+    if let [only] = options {
+        return Ok(only.clone());
+    }
+    let i = Select::new().with_prompt(format!("  {}", prompt)).items(options).default(0).interact()?;
+    Ok(options[i].clone())
+}
+
+pub async fn handle(cmd: SubscriptionCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        SubscriptionCommands::List { json } => {
+        SubscriptionCommands::List { paused: true, customer, .. } => {
+            let customer = match customer {
+                Some(c) => Some(customers::resolve(&client, &c).await?),
+                None => None,
+            };
+            let sp = spinner::create_spinner("Fetching paused subscriptions...");
+            let rows = paused_subscriptions(&client, customer.as_deref()).await?;
+            sp.finish_and_clear();
+            let totals = output::summarize(rows.iter().map(|r| (r.currency.as_str(), r.last_invoice)));
+            println!("{}", output::print_table_with_summary(&rows, Some(&totals), "paused subscription"));
+        }
+        SubscriptionCommands::List { paused: false, customer, page } => {
+            let customer_id = match customer {
+                Some(c) => Some(customers::resolve(&client, &c).await?),
+                None => None,
+            };
+            let path = QueryBuilder::new().opt("customer_id", customer_id).to_path("/v1/subscriptions");
             let sp = spinner::create_spinner("Fetching subscriptions...");
-            let resp: ListResponse<Subscription> = client.get("/v1/subscriptions").await?;
+            let (items, pagination) = page.fetch::<Subscription>(&client, &path).await?;
             sp.finish_and_clear();
-            let rows: Vec<SubscriptionRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            if output::human() && !globals::get().raw {
+                let rows: Vec<ReadableSubscriptionRow> = items.into_iter().map(Into::into).collect();
+                println!("{}", output::print_page(&rows, &pagination, None, "subscription"));
+            } else {
+                let rows: Vec<SubscriptionRow> = items.into_iter().map(Into::into).collect();
+                println!("{}", output::print_page(&rows, &pagination, None, "subscription"));
+            }
         }
-        SubscriptionCommands::Get { id, json } => {
+        SubscriptionCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/subscriptions", &many).await };
             let sp = spinner::create_spinner("Fetching subscription...");
             let sub: Subscription = client.get(&format!("/v1/subscriptions/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&sub, json));
+            match current_period(&sub).filter(|_| output::human() && !globals::get().raw) {
+                Some(period) => {
+                    let detail = time::replace_period(&sub, "current_period_start", "current_period_end", "current_period", period);
+                    println!("{}", output::print_detail(&detail));
+                }
+                None => println!("{}", output::print_detail(&sub)),
+            }
         }
-        SubscriptionCommands::Create { json: file } => {
-            let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Creating subscription...");
-            let sub: Subscription = client.post("/v1/subscriptions", &body).await?;
-            sp.finish_and_clear();
-            output::success(&format!("Subscription created: {}", sub.id));
-            println!("{}", output::print_detail(&sub, false));
+        SubscriptionCommands::Create { json: file, edit, batch: opts } => {
+            let payload = match file.is_none() && !edit {
+                true => create_wizard(&client).await?.map(Payload::Single),
+                false => editor::load_or_edit::<Subscription>(file.as_deref(), edit, CREATE_TEMPLATE, None)?,
+            };
+            match payload {
+                None => {}
+                Some(Payload::Single(body)) => {
+                    let sp = spinner::create_spinner("Creating subscription...");
+                    let sub: Subscription = client.post("/v1/subscriptions", &body).await?;
+                    sp.finish_and_clear();
+                    output::success(&format!("Subscription created: {}", sub.id));
+                    println!("{}", output::print_detail(&sub));
+                }
+                Some(Payload::Many(items)) => {
+                    batch::create_many(&client, items, file.as_deref(), &opts, "Subscription created", "/v1/subscriptions").await?;
+                }
+            }
         }
         SubscriptionCommands::Cancel { id } => {
             let sp = spinner::create_spinner("Cancelling subscription...");
             let sub: serde_json::Value = client.post_empty(&format!("/v1/subscriptions/{}/cancel", id)).await?;
             sp.finish_and_clear();
             output::success(&format!("Subscription {} cancelled.", id));
-            println!("{}", output::print_detail(&sub, false));
+            println!("{}", output::print_detail(&sub));
+        }
+        SubscriptionCommands::Pause { id, from, until, days, reason } => {
+            let start = from.as_deref().map(time::parse_instant).transpose()?;
+            let end = until.as_deref().map(time::parse_instant).transpose()?;
+            if let (Some(end), start) = (end, start) {
+                if end <= start.unwrap_or_else(Utc::now) {
+                    anyhow::bail!("--until must be after the pause starts");
+                }
+            }
+            let mut body = serde_json::json!({
+                "pause_mode": if start.is_some() { "scheduled" } else { "immediate" },
+            });
+            if let Some(start) = start {
+                body["pause_start"] = start.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+            }
+            if let Some(end) = end {
+                body["pause_end"] = end.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+            }
+            if let Some(days) = days {
+                body["pause_days"] = days.into();
+            }
+            if let Some(reason) = reason {
+                body["reason"] = reason.into();
+            }
+            let sp = spinner::create_spinner("Pausing subscription...");
+            let pause: SubscriptionPause = client.post(&format!("/v1/subscriptions/{}/pause", id), &body).await?;
+            sp.finish_and_clear();
+            match start {
+                Some(start) => output::success(&format!("Subscription {} will pause on {}.", id, start.format("%Y-%m-%d"))),
+                None => output::success(&format!("Subscription {} paused.", id)),
+            }
+            println!("{}", output::print_detail(&pause));
+        }
+        SubscriptionCommands::Resume { id } => {
+            let body = serde_json::json!({ "resume_mode": "immediate" });
+            let sp = spinner::create_spinner("Resuming subscription...");
+            let resp: serde_json::Value = client.post(&format!("/v1/subscriptions/{}/resume", id), &body).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Subscription {} resumed.", id));
+            println!("{}", output::print_detail(&resp));
+        }
+        SubscriptionCommands::Activate { id, start_date } => {
+            let start = start_date.as_deref().map(time::parse_instant).transpose()?.unwrap_or_else(Utc::now);
+            let body = serde_json::json!({ "start_date": start.to_rfc3339_opts(SecondsFormat::Secs, true) });
+            let sp = spinner::create_spinner("Activating subscription...");
+            let sub: Subscription = client.post(&format!("/v1/subscriptions/{}/activate", id), &body).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Subscription {} activated.", id));
+            println!("{}", output::print_detail(&sub));
+        }
+        SubscriptionCommands::ChangePlan { id, plan, proration_behavior, preview } => {
+            let body = serde_json::json!({
+                "target_plan_id": plan,
+                "proration_behavior": proration_behavior,
+            });
+            let (path, message) = if preview {
+                (format!("/v1/subscriptions/{}/change/preview", id), "Previewing plan change...")
+            } else {
+                (format!("/v1/subscriptions/{}/change/execute", id), "Changing plan...")
+            };
+            let sp = spinner::create_spinner(message);
+            let resp: serde_json::Value = client.post(&path, &body).await?;
+            sp.finish_and_clear();
+            if !preview {
+                output::success(&format!("Subscription {} moved to plan {}.", id, plan));
+            }
+            println!("{}", output::print_detail(&resp));
+        }
+        SubscriptionCommands::AddAddon { id, addon, quantity } => {
+            let body = serde_json::json!({ "subscription_id": id, "addon_id": addon, "quantity": quantity });
+            let sp = spinner::create_spinner("Adding add-on...");
+            let added: serde_json::Value = client.post("/v1/subscriptions/addon", &body).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Add-on {} (× {}) added to subscription {}.", addon, quantity, id));
+            println!("{}", output::print_detail(&added));
         }
-        SubscriptionCommands::Usage { json: file } => {
+        SubscriptionCommands::ResumeAt { id, date } => {
+            let at = time::parse_instant(&date)?;
+            if at <= Utc::now() {
+                anyhow::bail!("Resume date {} is in the past; use a future date", date);
+            }
+            let body = serde_json::json!({
+                "resume_mode": "scheduled",
+                "resume_date": at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            });
+            let sp = spinner::create_spinner("Scheduling resume...");
+            let resp: serde_json::Value = client.post(&format!("/v1/subscriptions/{}/resume", id), &body).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Subscription {} will resume on {}.", id, at.format("%Y-%m-%d")));
+            println!("{}", output::print_detail(&resp));
+        }
+        SubscriptionCommands::Usage { json: file, as_of } => {
             let data = std::fs::read_to_string(&file)?;
-            let body: serde_json::Value = serde_json::from_str(&data)?;
+            let mut body: serde_json::Value = serde_json::from_str(&data)?;
+            if let Some(at) = as_of.as_deref().map(time::parse_instant).transpose()? {
+                time::cap_end_time(&mut body, at);
+            }
             let sp = spinner::create_spinner("Fetching usage...");
             let usage: serde_json::Value = client.post("/v1/subscriptions/usage", &body).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&usage, false));
+            println!("{}", output::print_detail(&usage));
         }
     }
     Ok(())
 }
+
+#[derive(Tabled, serde::Serialize)]
+struct PausedRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Customer")]
+    customer_id: String,
+    #[tabled(rename = "Plan")]
+    plan_id: String,
+    #[tabled(rename = "Paused Since")]
+    paused_since: String,
+    #[tabled(rename = "Resumes")]
+    resumes_at: String,
+    #[tabled(rename = "Last Invoice", display_with = "money")]
+    last_invoice: f64,
+    #[tabled(rename = "Currency")]
+    currency: String,
+}
+
+fn money(v: &f64) -> String {
+    format!("{:.2}", v)
+}
+
+/// Paused subscriptions with their active pause and most recent invoice
+/// amount, which stands in for the revenue on hold. Resumes soonest first,
+/// open-ended pauses last.
+async fn paused_subscriptions(client: &ApiClient, customer_id: Option<&str>) -> Result<Vec<PausedRow>> {
+    let path = QueryBuilder::new()
+        .param("subscription_status", "paused")
+        .opt("customer_id", customer_id)
+        .to_path("/v1/subscriptions");
+    let subs: Vec<Subscription> = client.get_all(&path).await?;
+
+    let results = stream::iter(subs)
+        .map(|sub| async move {
+            let pauses: Vec<SubscriptionPause> = client
+                .get(&format!("/v1/subscriptions/{}/pauses", sub.id))
+                .await?;
+            let invoices: ListResponse<Invoice> = client
+                .get_with_query("/v1/invoices", &[("subscription_id", &sub.id), ("limit", "1")])
+                .await?;
+            Ok::<_, anyhow::Error>((sub, pauses, invoices.items.into_iter().next()))
+        })
+        .buffer_unordered(8)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut rows = vec![];
+    for result in results {
+        let (sub, pauses, invoice) = result?;
+        let pause = pauses
+            .into_iter()
+            .find(|p| p.pause_status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("active")));
+        let date = |s: Option<String>| s.map(|s| s.chars().take(10).collect::<String>());
+        let (paused_since, resumes_at) = pause
+            .map(|p| (date(p.pause_start), date(p.pause_end)))
+            .unwrap_or_default();
+        let currency = invoice.as_ref().and_then(|i| i.currency.clone()).or(sub.currency);
+        rows.push(PausedRow {
+            id: sub.id,
+            customer_id: sub.customer_id.unwrap_or_default(),
+            plan_id: sub.plan_id.unwrap_or_default(),
+            paused_since: paused_since.unwrap_or_default(),
+            resumes_at: resumes_at.unwrap_or_else(|| "-".to_string()),
+            last_invoice: invoice.and_then(|i| i.amount_due).unwrap_or_default(),
+            currency: currency.unwrap_or_default().to_uppercase(),
+        });
+    }
+    rows.sort_by(|a, b| (a.resumes_at == "-", &a.resumes_at).cmp(&(b.resumes_at == "-", &b.resumes_at)));
+    Ok(rows)
+}
@@ -0,0 +1,114 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use zip::write::SimpleFileOptions;
+
+use crate::config::{ConfigFile, Credentials};
+use crate::utils::{log, output};
+
+/// Most recent lines of each log included in a bundle
+const HISTORY_LINES: usize = 200;
+const REQUEST_LOG_LINES: usize = 1000;
+
+/// Keys whose values are masked wherever they appear in config.toml
+const SECRET_KEYS: &[&str] = &["api_key", "auth_token", "password", "token"];
+
+#[derive(Subcommand)]
+pub enum SupportCommands {
+    /// Package recent history, request logs, and redacted config into a zip
+    Bundle {
+        /// Where to write the zip (default: flexprice-support-<timestamp>.zip)
+        #[arg(long)]
+        out: Option<String>,
+    },
+}
+
+pub fn handle(cmd: SupportCommands) -> Result<()> {
+    let SupportCommands::Bundle { out: out_path } = cmd;
+    let out_path = out_path.unwrap_or_else(|| {
+        format!("flexprice-support-{}.zip", chrono::Utc::now().format("%Y%m%d-%H%M%S"))
+    });
+
+    let config = ConfigFile::load().unwrap_or_default();
+    let profile = config.selected_profile();
+
+    let mut files: Vec<(&str, String)> = vec![("environment.txt", environment(&profile))];
+    files.push(("history.log", tail(&log::history_path(), HISTORY_LINES)));
+    files.push(("cli.log", tail(&log::requests_path(), REQUEST_LOG_LINES)));
+    files.push(("config.toml", redacted_config(config)?));
+    if let Ok(creds) = Credentials::load_from_file() {
+        files.push(("credentials.json", serde_json::to_string_pretty(&creds.redacted())?));
+    }
+
+    let file = std::fs::File::create(&out_path)
+        .with_context(|| format!("Failed to create {}", out_path))?;
+    let mut zip = zip::ZipWriter::new(file);
+    for (name, content) in &files {
+        zip.start_file(*name, SimpleFileOptions::default())?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+
+    output::success(&format!("Support bundle written to {}", out_path));
+    output::info("API keys, tokens, and proxy credentials are masked. Review the contents before sharing.");
+    Ok(())
+}
+
+fn environment(profile: &str) -> String {
+    let api_url = Credentials::load().map(|c| c.api_url).unwrap_or_default();
+    format!(
+        "flexprice {}\nos: {} ({})\nprofile: {}\napi_url: {}\ngenerated: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        profile,
+        if api_url.is_empty() { "(not set)" } else { &api_url },
+        chrono::Utc::now().to_rfc3339(),
+    )
+}
+
+fn redacted_config(mut config: ConfigFile) -> Result<String> {
+    for creds in config.profiles.values_mut() {
+        *creds = creds.redacted();
+    }
+    for queries in config.queries.values_mut() {
+        for command in queries.values_mut() {
+            let words: Vec<String> = command.split_whitespace().map(str::to_string).collect();
+            *command = log::redact_args(&words).join(" ");
+        }
+    }
+    let mut table = toml::Table::try_from(&config)?;
+    // Profiles were masked above, keeping the ends of their API keys
+    for (_, value) in table.iter_mut().filter(|(key, _)| *key != "profiles") {
+        if let toml::Value::Table(inner) = value {
+            redact_table(inner);
+        }
+    }
+    Ok(toml::to_string_pretty(&table)?)
+}
+
+/// Mask secret-valued keys and proxy credentials at any depth, e.g. an
+/// `api_key` under `[defaults]`
+fn redact_table(table: &mut toml::Table) {
+    for (key, value) in table.iter_mut() {
+        let key = key.replace('-', "_");
+        match value {
+            toml::Value::Table(inner) => redact_table(inner),
+            toml::Value::String(s) if key == "proxy" => *s = log::strip_userinfo(s),
+            toml::Value::String(s) if SECRET_KEYS.contains(&key.as_str()) => *s = "***".to_string(),
+            _ => {}
+        }
+    }
+}
+
+/// The last `n` lines of a file, or a note if it doesn't exist
+fn tail(path: &std::path::Path, n: usize) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            let lines: Vec<&str> = content.lines().collect();
+            lines[lines.len().saturating_sub(n)..].join("\n") + "\n"
+        }
+        Err(_) => format!("({} not found)\n", path.display()),
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::models::{ListResponse, TaxAssociation, TaxRate};
+use crate::cli::{completions, customers};
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::fanout::{self, IdsArgs};
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::{output, spinner};
+
+#[derive(Subcommand)]
+pub enum TaxCommands {
+    /// List tax rates, or the rates assigned to a customer
+    List {
+        /// Only the rates assigned to this customer (ID, ext:<external_id>, or email:<address>)
+        #[arg(long, add = completions::ids("/v1/customers"))]
+        customer: Option<String>,
+    },
+    /// Get a tax rate by ID, or many with --ids-file
+    Get {
+        #[arg(add = completions::ids("/v1/taxes/rates"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
+    },
+    /// Create tax rates interactively or from a JSON file (object or array of objects)
+    Create {
+        #[arg(long)]
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// Apply a tax rate to a customer's invoices
+    Assign {
+        #[arg(add = completions::ids("/v1/taxes/rates"))]
+        tax_rate_id: String,
+        /// Customer to tax (ID, ext:<external_id>, or email:<address>)
+        #[arg(long, add = completions::ids("/v1/customers"))]
+        customer: String,
+        /// Order among the customer's rates, lowest applied first
+        #[arg(long)]
+        priority: Option<i64>,
+        /// Assign without adding the rate to new invoices automatically
+        #[arg(long)]
+        no_auto_apply: bool,
+    },
+    /// Stop applying a tax rate to a customer's invoices
+    Unassign {
+        #[arg(add = completions::ids("/v1/taxes/rates"))]
+        tax_rate_id: String,
+        /// Customer (ID, ext:<external_id>, or email:<address>)
+        #[arg(long, add = completions::ids("/v1/customers"))]
+        customer: String,
+    },
+}
+
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "tax rate",
+    fields: &[
+        Field::text("name", "Name").required(),
+        Field::text("code", "Code, e.g. US-CA-SALES").required(),
+        Field::choice("tax_rate_type", "Type", &["percentage", "fixed"]),
+        Field::text("percentage_value", "Percentage (percentage rates only)"),
+        Field::text("fixed_value", "Amount per invoice (fixed rates only)"),
+        Field::text("currency", "Currency (fixed rates only)"),
+        Field::text("description", "Description"),
+    ],
+    check: Some(validate),
+};
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "name": "",
+  "code": "",
+  "tax_rate_type": "percentage",
+  "percentage_value": "0",
+  "description": ""
+}"#;
+
+#[derive(Tabled, serde::Serialize)]
+pub struct TaxRateRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Code")]
+    code: String,
+    #[tabled(rename = "Rate")]
+    rate: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+impl From<TaxRate> for TaxRateRow {
+    fn from(t: TaxRate) -> Self {
+        Self {
+            rate: describe_rate(&t),
+            id: t.id,
+            name: t.name.unwrap_or_default(),
+            code: t.code.unwrap_or_default(),
+            status: t.tax_rate_status.map(|s| output::status_badge(&s)).unwrap_or_default(),
+        }
+    }
+}
+
+/// A customer's tax rate, with the rate's details
+#[derive(Tabled, serde::Serialize)]
+struct AssignmentRow {
+    #[tabled(rename = "Tax Rate")]
+    tax_rate_id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Rate")]
+    rate: String,
+    #[tabled(rename = "Priority")]
+    priority: String,
+    #[tabled(rename = "Auto Apply")]
+    auto_apply: String,
+}
+
+pub async fn handle(cmd: TaxCommands, client: ApiClient) -> Result<()> {
+    match cmd {
+        TaxCommands::List { customer: None } => {
+            let sp = spinner::create_spinner("Fetching tax rates...");
+            let rates: Vec<TaxRate> = client.get_all("/v1/taxes/rates").await?;
+            sp.finish_and_clear();
+            let rows: Vec<TaxRateRow> = rates.into_iter().map(Into::into).collect();
+            println!("{}", output::print_table(&rows));
+        }
+        TaxCommands::List { customer: Some(customer) } => {
+            let customer = customers::resolve(&client, &customer).await?;
+            let sp = spinner::create_spinner("Fetching the customer's tax rates...");
+            let fetched = tokio::try_join!(assignments(&client, &customer), client.get_all::<TaxRate>("/v1/taxes/rates"));
+            sp.finish_and_clear();
+            let (assigned, rates) = fetched?;
+            let rates: HashMap<String, TaxRate> = rates.into_iter().map(|r| (r.id.clone(), r)).collect();
+            let rows: Vec<AssignmentRow> = assigned
+                .into_iter()
+                .map(|a| {
+                    let rate = rates.get(&a.tax_rate_id);
+                    AssignmentRow {
+                        name: rate.and_then(|r| r.name.clone()).unwrap_or_default(),
+                        rate: rate.map(describe_rate).unwrap_or_default(),
+                        priority: a.priority.map(|p| p.to_string()).unwrap_or_default(),
+                        auto_apply: if a.auto_apply.unwrap_or(true) { "yes" } else { "no" }.to_string(),
+                        tax_rate_id: a.tax_rate_id,
+                    }
+                })
+                .collect();
+            println!("{}", output::print_table(&rows));
+        }
+        TaxCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/taxes/rates", &many).await };
+            let sp = spinner::create_spinner("Fetching tax rate...");
+            let rate: TaxRate = client.get(&format!("/v1/taxes/rates/{}", id)).await?;
+            sp.finish_and_clear();
+            println!("{}", output::print_detail(&rate));
+        }
+        TaxCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<TaxRate>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                validate(&body)?;
+                let sp = spinner::create_spinner("Creating tax rate...");
+                let rate: TaxRate = client.post("/v1/taxes/rates", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Tax rate created: {}", rate.id));
+                println!("{}", output::print_detail(&rate));
+            }
+            Some(Payload::Many(items)) => {
+                for (i, body) in items.iter().enumerate() {
+                    validate(body).with_context(|| format!("Tax rate {} in {}", i + 1, file.as_deref().unwrap_or("the payload")))?;
+                }
+                batch::create_many(&client, items, file.as_deref(), &opts, "Tax rate created", "/v1/taxes/rates").await?;
+            }
+        },
+        TaxCommands::Assign { tax_rate_id, customer, priority, no_auto_apply } => {
+            let customer = customers::resolve(&client, &customer).await?;
+            let mut body = serde_json::json!({
+                "tax_rate_id": tax_rate_id,
+                "entity_type": "customer",
+                "entity_id": customer,
+                "auto_apply": !no_auto_apply,
+            });
+            if let Some(priority) = priority {
+                body["priority"] = priority.into();
+            }
+            let sp = spinner::create_spinner("Assigning tax rate...");
+            let association: TaxAssociation = client.post("/v1/taxes/associations", &body).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Tax rate {} assigned to customer {}.", tax_rate_id, customer));
+            println!("{}", output::print_detail(&association));
+        }
+        TaxCommands::Unassign { tax_rate_id, customer } => {
+            let customer = customers::resolve(&client, &customer).await?;
+            let sp = spinner::create_spinner("Unassigning tax rate...");
+            let assigned = assignments(&client, &customer).await;
+            let Some(association) = assigned?.into_iter().find(|a| a.tax_rate_id == tax_rate_id) else {
+                sp.finish_and_clear();
+                anyhow::bail!("Tax rate {} is not assigned to customer {}", tax_rate_id, customer);
+            };
+            client.delete_empty(&format!("/v1/taxes/associations/{}", association.id)).await?;
+            sp.finish_and_clear();
+            output::success(&format!("Tax rate {} no longer applies to customer {}.", tax_rate_id, customer));
+        }
+    }
+    Ok(())
+}
+
+/// The tax rates assigned to a customer
+async fn assignments(client: &ApiClient, customer_id: &str) -> Result<Vec<TaxAssociation>> {
+    let resp: ListResponse<TaxAssociation> = client
+        .get_with_query("/v1/taxes/associations", &[("entity_type", "customer"), ("entity_id", customer_id)])
+        .await?;
+    Ok(resp.items)
+}
+
+/// A rate as charged, e.g. `8.25%` or `2.00 USD`
+pub fn describe_rate(t: &TaxRate) -> String {
+    match (t.tax_rate_type.as_deref().map(str::to_lowercase).as_deref(), &t.percentage_value, &t.fixed_value) {
+        (Some("fixed"), _, Some(amount)) => format!("{} {}", amount, t.currency.as_deref().unwrap_or_default().to_uppercase()).trim().to_string(),
+        (_, Some(percentage), _) => format!("{}%", percentage),
+        _ => String::new(),
+    }
+}
+
+/// A percentage rate needs a percentage, and a fixed rate an amount and currency
+fn validate(body: &serde_json::Value) -> Result<()> {
+    let field = |name: &str| body.get(name).filter(|v| !v.is_null() && v.as_str() != Some(""));
+    match body.get("tax_rate_type").and_then(|t| t.as_str()).unwrap_or("percentage").to_lowercase().as_str() {
+        "percentage" if field("percentage_value").is_none() => anyhow::bail!("A percentage tax rate needs a percentage_value"),
+        "fixed" if field("fixed_value").is_none() || field("currency").is_none() => {
+            anyhow::bail!("A fixed tax rate needs a fixed_value and a currency")
+        }
+        "percentage" | "fixed" => Ok(()),
+        other => anyhow::bail!("Unknown tax_rate_type '{}': expected percentage or fixed", other),
+    }
+}
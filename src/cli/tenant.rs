@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::models::{Tenant, TenantBillingUsage, UsageCharge};
+use crate::config::ConfigFile;
+use crate::utils::{output, spinner};
+
+#[derive(Subcommand)]
+pub enum TenantCommands {
+    /// Show your tenant's own FlexPrice consumption and current bill
+    Usage,
+    /// List the tenants your login can access
+    List,
+    /// Send future requests as another tenant
+    Switch { id: String },
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct TenantRow {
+    #[tabled(rename = "")]
+    active: String,
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Created")]
+    created_at: String,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ChargeRow {
+    #[tabled(rename = "Metric")]
+    metric: String,
+    #[tabled(rename = "Quantity")]
+    quantity: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+}
+
+impl From<UsageCharge> for ChargeRow {
+    fn from(c: UsageCharge) -> Self {
+        Self {
+            metric: c.meter_display_name.or(c.meter_id).unwrap_or_default(),
+            quantity: c.quantity.map(|q| format!("{}", q)).unwrap_or_default(),
+            amount: c.display_amount
+                .or_else(|| c.amount.map(|a| format!("{:.2}", a)))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+pub async fn handle(cmd: TenantCommands, client: ApiClient) -> Result<()> {
+    let creds = client.credentials();
+
+    match cmd {
+        TenantCommands::Usage => {
+            let sp = spinner::create_spinner("Fetching tenant billing...");
+            let billing: TenantBillingUsage = client.get("/v1/tenants/billing").await?;
+            sp.finish_and_clear();
+
+            if !output::human() {
+                println!("{}", output::print_detail(&billing));
+                return Ok(());
+            }
+
+            println!();
+            for sub in &billing.subscriptions {
+                output::info(&format!(
+                    "Subscription: {}  {}",
+                    sub.id,
+                    sub.subscription_status.as_deref().map(output::status_badge).unwrap_or_default()
+                ));
+            }
+
+            let Some(usage) = billing.usage else {
+                output::info("No usage recorded for the current billing period.");
+                return Ok(());
+            };
+            if let (Some(start), Some(end)) = (&usage.start_time, &usage.end_time) {
+                output::info(&format!("Period:       {} → {}", start, end));
+            }
+            println!();
+
+            let rows: Vec<ChargeRow> = usage.charges.into_iter().map(Into::into).collect();
+            println!("{}", output::print_table(&rows));
+
+            let total = usage.display_amount
+                .or_else(|| usage.amount.map(|a| format!("{:.2}", a)))
+                .unwrap_or_else(|| "0.00".to_string());
+            println!();
+            output::success(&format!(
+                "Current bill: {} {}",
+                total,
+                usage.currency.unwrap_or_default().to_uppercase()
+            ));
+            println!();
+        }
+        TenantCommands::List => {
+            let tenants = list(&client).await?;
+            if !output::human() {
+                println!("{}", output::print_detail(&tenants));
+                return Ok(());
+            }
+            let current = creds.tenant_id.as_deref();
+            let rows: Vec<TenantRow> = tenants
+                .into_iter()
+                .map(|t| TenantRow {
+                    active: if current == Some(t.id.as_str()) { "*".green().bold().to_string() } else { String::new() },
+                    name: t.name.unwrap_or_default(),
+                    status: t.status.as_deref().map(output::status_badge).unwrap_or_default(),
+                    created_at: t.created_at.unwrap_or_default(),
+                    id: t.id,
+                })
+                .collect();
+            println!("{}", output::print_table(&rows));
+        }
+        TenantCommands::Switch { id } => {
+            if creds.api_key.is_some() {
+                anyhow::bail!(
+                    "API keys belong to a single tenant. Log in with `flexprice auth login` to switch tenants."
+                );
+            }
+            let sp = spinner::create_spinner("Checking tenant access...");
+            let tenants = list(&client).await;
+            sp.finish_and_clear();
+            let tenant = tenants?
+                .into_iter()
+                .find(|t| t.id == id)
+                .with_context(|| format!("Tenant '{}' isn't available to this login. Run `flexprice tenant list` to see yours.", id))?;
+
+            let mut config = ConfigFile::load()?;
+            let profile = config.selected_profile();
+            let mut stored = config.stored_credentials(&profile)?;
+            stored.tenant_id = Some(tenant.id.clone());
+            config.write_credentials(&profile, stored)?;
+            match tenant.name {
+                Some(name) => output::success(&format!("Now using tenant {} ({}).", name, tenant.id)),
+                None => output::success(&format!("Now using tenant {}.", tenant.id)),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Tenants the current login can access
+async fn list(client: &ApiClient) -> Result<Vec<Tenant>> {
+    client
+        .get_all("/v1/tenants")
+        .await
+        .context("Failed to list tenants (this FlexPrice server may not support multiple tenants per user)")
+}
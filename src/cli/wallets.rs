@@ -1,48 +1,116 @@
-use anyhow::Result;
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Subcommand;
+use futures::stream::{self, StreamExt};
 use tabled::Tabled;
 
 use crate::api::client::ApiClient;
-use crate::api::models::{Wallet, WalletBalance, ListResponse};
-use crate::cli::auth::require_auth;
-use crate::utils::{output, spinner};
+use crate::api::models::{Wallet, WalletBalance, WalletTransaction};
+use crate::cli::{completions, customers};
+use crate::utils::batch::{self, BatchArgs, Payload};
+use crate::utils::editor;
+use crate::utils::paging::{PageArgs, Pagination};
+use crate::utils::wizard::{Field, Wizard};
+use crate::utils::fanout::{self, IdsArgs};
+use crate::utils::{output, spinner, time};
 
 #[derive(Subcommand)]
 pub enum WalletCommands {
-    /// List all wallets
+    /// List wallets, a page at a time
     List {
+        /// Append per-currency totals (count, sum, average)
+        #[arg(long)]
+        summary: bool,
+        /// Only wallets in this currency, e.g. usd
         #[arg(long)]
-        json: bool,
+        currency: Option<String>,
+        /// Only this customer's wallets (ID, ext:<external_id>, or email:<address>)
+        #[arg(long, add = completions::ids("/v1/customers"))]
+        customer: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
     },
-    /// Get a wallet by ID
+    /// Get a wallet by ID, or many with --ids-file
     Get {
-        id: String,
-        #[arg(long)]
-        json: bool,
+        #[arg(add = completions::ids("/v1/wallets"), required_unless_present = "ids_file", conflicts_with = "ids_file")]
+        id: Option<String>,
+        #[command(flatten)]
+        many: IdsArgs,
     },
-    /// Create a new wallet from a JSON file
+    /// Create wallets interactively or from a JSON file (object or array of objects)
     Create {
         #[arg(long)]
-        json: String,
+        json: Option<String>,
+        /// Write the payload in $EDITOR, starting from a template
+        #[arg(long, conflicts_with = "json")]
+        edit: bool,
+        #[command(flatten)]
+        batch: BatchArgs,
     },
     /// Top up a wallet
     TopUp {
         /// Wallet ID
+        #[arg(add = completions::ids("/v1/wallets"))]
         id: String,
         /// JSON body with top-up details
         #[arg(long)]
         json: String,
+        /// Currency of the amount; refused if it isn't the wallet's
+        #[arg(long)]
+        currency: Option<String>,
+    },
+    /// Debit credits from a wallet
+    Debit {
+        /// Wallet ID
+        #[arg(add = completions::ids("/v1/wallets"))]
+        id: String,
+        /// JSON body with debit details
+        #[arg(long)]
+        json: String,
+        /// Currency of the amount; refused if it isn't the wallet's
+        #[arg(long)]
+        currency: Option<String>,
     },
     /// Get real-time wallet balance
     Balance {
+        #[arg(add = completions::ids("/v1/wallets"))]
         id: String,
+    },
+    /// List credit grants with balances expiring soon
+    ExpiringCredits {
+        /// Look-ahead window, e.g. 7d, 30d, 12w
+        #[arg(long, default_value = "30d")]
+        within: String,
+        /// Only wallets in this currency, e.g. usd
         #[arg(long)]
-        json: bool,
+        currency: Option<String>,
     },
 }
 
+/// Prompts for `create` without `--json` or `--edit`
+const CREATE_WIZARD: Wizard = Wizard {
+    kind: "wallet",
+    fields: &[
+        Field::text("customer_id", "Customer ID").required(),
+        Field::text("name", "Name"),
+        Field::text("currency", "Currency").default("usd").required(),
+        Field::number("initial_credits_to_load", "Initial credits", 0.0),
+    ],
+    check: None,
+};
+
+/// Starting point for `create --edit`
+pub const CREATE_TEMPLATE: &str = r#"{
+  "customer_id": "",
+  "name": "",
+  "currency": "usd",
+  "initial_credits_to_load": 0
+}"#;
+
 #[derive(Tabled, serde::Serialize)]
-struct WalletRow {
+pub struct WalletRow {
     #[tabled(rename = "ID")]
     id: String,
     #[tabled(rename = "Customer")]
@@ -67,48 +135,201 @@ impl From<Wallet> for WalletRow {
     }
 }
 
-pub async fn handle(cmd: WalletCommands) -> Result<()> {
-    let creds = require_auth()?;
-    let client = ApiClient::new(creds)?;
-
+pub async fn handle(cmd: WalletCommands, client: ApiClient) -> Result<()> {
     match cmd {
-        WalletCommands::List { json } => {
+        WalletCommands::List { summary, currency, customer, page } => {
+            let customer = match customer {
+                Some(c) => Some(customers::resolve(&client, &c).await?),
+                None => None,
+            };
             let sp = spinner::create_spinner("Fetching wallets...");
-            let resp: ListResponse<Wallet> = client.get("/v1/wallets").await?;
+            // The filters run here rather than on the server, so they need every wallet
+            let filtered = currency.is_some() || customer.is_some();
+            let page = PageArgs { all: page.all || filtered, ..page };
+            let (items, pagination) = page.fetch::<Wallet>(&client, "/v1/wallets").await?;
             sp.finish_and_clear();
-            let rows: Vec<WalletRow> = resp.items.into_iter().map(Into::into).collect();
-            println!("{}", output::print_table(&rows, json));
+            let wallets: Vec<Wallet> = items
+                .into_iter()
+                .filter(|w| in_currency(w, currency.as_deref()))
+                .filter(|w| customer.is_none() || w.customer_id == customer)
+                .collect();
+            let pagination = if filtered { Pagination::complete(wallets.len()) } else { pagination };
+            let totals = summary.then(|| {
+                output::summarize(wallets.iter().map(|w| {
+                    (w.currency.as_deref().unwrap_or_default(), w.balance.unwrap_or_default())
+                }))
+            });
+            let rows: Vec<WalletRow> = wallets.into_iter().map(Into::into).collect();
+            println!("{}", output::print_page(&rows, &pagination, totals.as_deref(), "wallet"));
         }
-        WalletCommands::Get { id, json } => {
+        WalletCommands::Get { id, many } => {
+            let Some(id) = id else { return fanout::get_many(&client, "/v1/wallets", &many).await };
             let sp = spinner::create_spinner("Fetching wallet...");
             let wallet: Wallet = client.get(&format!("/v1/wallets/{}", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&wallet, json));
+            println!("{}", output::print_detail(&wallet));
         }
-        WalletCommands::Create { json: file } => {
+        WalletCommands::Create { json: file, edit, batch: opts } => match editor::load_or_edit::<Wallet>(file.as_deref(), edit, CREATE_TEMPLATE, Some(&CREATE_WIZARD))? {
+            None => {}
+            Some(Payload::Single(body)) => {
+                let sp = spinner::create_spinner("Creating wallet...");
+                let wallet: Wallet = client.post("/v1/wallets", &body).await?;
+                sp.finish_and_clear();
+                output::success(&format!("Wallet created: {}", wallet.id));
+                println!("{}", output::print_detail(&wallet));
+            }
+            Some(Payload::Many(items)) => {
+                batch::create_many(&client, items, file.as_deref(), &opts, "Wallet created", "/v1/wallets").await?;
+            }
+        },
+        WalletCommands::TopUp { id, json: file, currency } => {
             let data = std::fs::read_to_string(&file)?;
             let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Creating wallet...");
-            let wallet: Wallet = client.post("/v1/wallets", &body).await?;
+            check_currency(&client, &id, &body, currency.as_deref()).await?;
+            let sp = spinner::create_spinner("Topping up wallet...");
+            let resp: serde_json::Value = client.post(&format!("/v1/wallets/{}/top-up", id), &body).await?;
             sp.finish_and_clear();
-            output::success(&format!("Wallet created: {}", wallet.id));
-            println!("{}", output::print_detail(&wallet, false));
+            output::success(&format!("Wallet {} topped up.", id));
+            println!("{}", output::print_detail(&resp));
         }
-        WalletCommands::TopUp { id, json: file } => {
+        WalletCommands::Debit { id, json: file, currency } => {
             let data = std::fs::read_to_string(&file)?;
             let body: serde_json::Value = serde_json::from_str(&data)?;
-            let sp = spinner::create_spinner("Topping up wallet...");
-            let resp: serde_json::Value = client.post(&format!("/v1/wallets/{}/top-up", id), &body).await?;
+            check_currency(&client, &id, &body, currency.as_deref()).await?;
+            let sp = spinner::create_spinner("Debiting wallet...");
+            let resp: serde_json::Value = client
+                .post(&format!("/v1/wallets/{}/debit", id), &body)
+                .await
+                .context("Failed to debit wallet (this FlexPrice server may not support wallet debits)")?;
             sp.finish_and_clear();
-            output::success(&format!("Wallet {} topped up.", id));
-            println!("{}", output::print_detail(&resp, false));
+            output::success(&format!("Wallet {} debited.", id));
+            println!("{}", output::print_detail(&resp));
         }
-        WalletCommands::Balance { id, json } => {
+        WalletCommands::Balance { id } => {
             let sp = spinner::create_spinner("Fetching balance...");
             let balance: WalletBalance = client.get(&format!("/v1/wallets/{}/balance/real-time", id)).await?;
             sp.finish_and_clear();
-            println!("{}", output::print_detail(&balance, json));
+            println!("{}", output::print_detail(&balance));
+        }
+        WalletCommands::ExpiringCredits { within, currency } => {
+            let cutoff = time::after(Utc::now(), time::parse_duration(&within)?)?;
+            let sp = spinner::create_spinner("Scanning wallets for expiring credits...");
+            let rows = expiring_credits(&client, cutoff, currency.as_deref()).await?;
+            sp.finish_and_clear();
+
+            println!("{}", output::print_table(&rows));
+            if output::human() && !rows.is_empty() {
+                let mut totals: BTreeMap<&str, f64> = BTreeMap::new();
+                for row in &rows {
+                    *totals.entry(row.currency.as_str()).or_default() += row.amount;
+                }
+                println!();
+                for (currency, total) in totals {
+                    output::warning(&format!("{:.2} {} expiring within {}", total, currency, within));
+                }
+            }
         }
     }
     Ok(())
 }
+
+fn in_currency(wallet: &Wallet, currency: Option<&str>) -> bool {
+    currency.is_none_or(|c| wallet.currency.as_deref().is_some_and(|w| w.eq_ignore_ascii_case(c)))
+}
+
+/// Refuse a top-up or debit whose currency (from `--currency` or the body's
+/// `currency`) differs from the wallet's, since amounts aren't converted
+async fn check_currency(client: &ApiClient, id: &str, body: &serde_json::Value, flag: Option<&str>) -> Result<()> {
+    let claimed: Vec<(&str, &str)> = [
+        flag.map(|c| ("--currency", c)),
+        body.get("currency").and_then(|c| c.as_str()).map(|c| ("the JSON's `currency`", c)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if claimed.is_empty() {
+        return Ok(());
+    }
+    if let [(a, x), (b, y)] = claimed.as_slice() {
+        if !x.eq_ignore_ascii_case(y) {
+            anyhow::bail!("{} is {} but {} is {}", a, x.to_uppercase(), b, y.to_uppercase());
+        }
+    }
+
+    let wallet: Wallet = client.get(&format!("/v1/wallets/{}", id)).await?;
+    let Some(held) = wallet.currency.as_deref() else { return Ok(()) };
+    for (source, currency) in claimed {
+        if !currency.eq_ignore_ascii_case(held) {
+            anyhow::bail!(
+                "Wallet {} holds {} but {} is {}; amounts aren't converted between currencies",
+                id,
+                held.to_uppercase(),
+                source,
+                currency.to_uppercase()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct ExpiringCreditRow {
+    #[tabled(rename = "Customer")]
+    customer_id: String,
+    #[tabled(rename = "Wallet")]
+    wallet_id: String,
+    #[tabled(rename = "Amount", display_with = "money")]
+    amount: f64,
+    #[tabled(rename = "Currency")]
+    currency: String,
+    #[tabled(rename = "Expires")]
+    expires_at: String,
+    #[tabled(rename = "In")]
+    expires_in: String,
+}
+
+fn money(v: &f64) -> String {
+    format!("{:.2}", v)
+}
+
+/// Credit grants with remaining balance that expire before `cutoff`, soonest first
+async fn expiring_credits(client: &ApiClient, cutoff: DateTime<Utc>, currency: Option<&str>) -> Result<Vec<ExpiringCreditRow>> {
+    let mut wallets: Vec<Wallet> = client.get_all("/v1/wallets").await?;
+    wallets.retain(|w| in_currency(w, currency));
+    let now = Utc::now();
+
+    let per_wallet = stream::iter(wallets)
+        .map(|wallet| async move {
+            let txns: Vec<WalletTransaction> = client
+                .get_all(&format!("/v1/wallets/{}/transactions", wallet.id))
+                .await?;
+            Ok::<_, anyhow::Error>((wallet, txns))
+        })
+        .buffer_unordered(8)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut rows = vec![];
+    for result in per_wallet {
+        let (wallet, txns) = result?;
+        for txn in txns {
+            let available = txn.credits_available.unwrap_or_default();
+            let Some(expiry) = txn.expiry_date.as_deref().and_then(|d| time::parse_instant(d).ok()) else {
+                continue;
+            };
+            if available <= 0.0 || expiry < now || expiry > cutoff {
+                continue;
+            }
+            rows.push((expiry, ExpiringCreditRow {
+                customer_id: wallet.customer_id.clone().unwrap_or_default(),
+                wallet_id: wallet.id.clone(),
+                amount: available,
+                currency: wallet.currency.clone().unwrap_or_default().to_uppercase(),
+                expires_at: expiry.format("%Y-%m-%d").to_string(),
+                expires_in: format!("{}d", (expiry - now).num_days()),
+            }));
+        }
+    }
+    rows.sort_by_key(|(expiry, _)| *expiry);
+    Ok(rows.into_iter().map(|(_, row)| row).collect())
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::json;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::models::ListResponse;
+use crate::utils::{output, spinner};
+
+#[derive(Subcommand)]
+pub enum WebhookCommands {
+    /// Browse webhook event types and sample payloads
+    Events {
+        #[command(subcommand)]
+        command: WebhookEventCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WebhookEventCommands {
+    /// List all webhook event types
+    List,
+    /// Print a representative payload for an event type
+    Sample {
+        /// Event type, e.g. invoice.update.finalized
+        event_type: String,
+    },
+}
+
+/// Event types bundled with the CLI, used when the server doesn't expose a catalog
+const BUNDLED_EVENTS: &[(&str, &str)] = &[
+    ("customer.created", "A customer was created"),
+    ("customer.updated", "A customer's details changed"),
+    ("customer.deleted", "A customer was deleted"),
+    ("subscription.created", "A subscription was created"),
+    ("subscription.updated", "A subscription changed plan, status, or period"),
+    ("subscription.paused", "A subscription was paused"),
+    ("subscription.resumed", "A paused subscription was resumed"),
+    ("subscription.cancelled", "A subscription was cancelled"),
+    ("invoice.create.drafted", "A draft invoice was generated"),
+    ("invoice.update.finalized", "An invoice was finalized"),
+    ("invoice.update.payment", "An invoice's payment status changed"),
+    ("invoice.update.voided", "An invoice was voided"),
+    ("payment.created", "A payment was recorded"),
+    ("payment.success", "A payment succeeded"),
+    ("payment.failed", "A payment attempt failed"),
+    ("wallet.created", "A wallet was created"),
+    ("wallet.updated", "A wallet's settings changed"),
+    ("wallet.terminated", "A wallet was terminated"),
+    ("wallet.transaction.created", "Credits were added to or consumed from a wallet"),
+    ("feature.created", "A feature was created"),
+    ("feature.updated", "A feature changed"),
+    ("feature.deleted", "A feature was deleted"),
+    ("entitlement.created", "A plan entitlement was created"),
+    ("entitlement.updated", "A plan entitlement changed"),
+    ("entitlement.deleted", "A plan entitlement was removed"),
+];
+
+#[derive(Tabled, serde::Serialize, serde::Deserialize)]
+struct WebhookEventRow {
+    #[tabled(rename = "Event Type")]
+    #[serde(alias = "event_type", alias = "name")]
+    event_type: String,
+    #[tabled(rename = "Description")]
+    #[serde(default)]
+    description: String,
+}
+
+pub async fn handle(cmd: WebhookCommands, client: ApiClient) -> Result<()> {
+    match cmd {
+        WebhookCommands::Events { command: WebhookEventCommands::List } => {
+            let sp = spinner::create_spinner("Fetching webhook events...");
+            let rows = match client.get::<ListResponse<WebhookEventRow>>("/v1/webhooks/events").await {
+                Ok(resp) if !resp.items.is_empty() => resp.items,
+                _ => bundled_rows(),
+            };
+            sp.finish_and_clear();
+            println!("{}", output::print_table(&rows));
+        }
+        WebhookCommands::Events { command: WebhookEventCommands::Sample { event_type } } => {
+            let sp = spinner::create_spinner("Fetching sample payload...");
+            let remote: Result<serde_json::Value> = client
+                .get(&format!("/v1/webhooks/events/{}/sample", event_type))
+                .await;
+            sp.finish_and_clear();
+            let payload = match remote {
+                Ok(payload) if payload.get("event_type").is_some() => payload,
+                _ => bundled_sample(&event_type)?,
+            };
+            println!("{}", output::print_raw(&payload));
+        }
+    }
+    Ok(())
+}
+
+fn bundled_rows() -> Vec<WebhookEventRow> {
+    BUNDLED_EVENTS
+        .iter()
+        .map(|(event_type, description)| WebhookEventRow {
+            event_type: event_type.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+/// Build a representative payload for a bundled event type
+fn bundled_sample(event_type: &str) -> Result<serde_json::Value> {
+    if !BUNDLED_EVENTS.iter().any(|(t, _)| *t == event_type) {
+        anyhow::bail!(
+            "Unknown webhook event type '{}'. Run `flexprice webhooks events list` to see all types.",
+            event_type
+        );
+    }
+    let now = "2024-03-01T12:00:00Z";
+    let resource = event_type.split('.').next().unwrap_or_default();
+    let data = match resource {
+        "customer" => json!({
+            "id": "cus_01HQ8Z4N1W",
+            "external_id": "acme-001",
+            "name": "Acme Corp",
+            "email": "billing@acme.com",
+            "status": "published",
+            "created_at": now,
+        }),
+        "subscription" => json!({
+            "id": "subs_01HQ8Z6K2M",
+            "customer_id": "cus_01HQ8Z4N1W",
+            "plan_id": "plan_01HQ8Y9R7T",
+            "subscription_status": event_type.rsplit('.').next().map(|s| match s {
+                "cancelled" => "cancelled",
+                "paused" => "paused",
+                _ => "active",
+            }),
+            "currency": "usd",
+            "current_period_start": "2024-03-01T00:00:00Z",
+            "current_period_end": "2024-04-01T00:00:00Z",
+            "created_at": now,
+        }),
+        "invoice" => json!({
+            "id": "inv_01HQ9A1B2C",
+            "customer_id": "cus_01HQ8Z4N1W",
+            "subscription_id": "subs_01HQ8Z6K2M",
+            "invoice_status": if event_type.ends_with("voided") { "voided" } else if event_type.ends_with("drafted") { "draft" } else { "finalized" },
+            "payment_status": if event_type.ends_with("payment") { "succeeded" } else { "pending" },
+            "amount_due": "149.00",
+            "amount_paid": if event_type.ends_with("payment") { "149.00" } else { "0" },
+            "currency": "usd",
+            "period_start": "2024-02-01T00:00:00Z",
+            "period_end": "2024-03-01T00:00:00Z",
+            "created_at": now,
+        }),
+        "payment" => json!({
+            "id": "pay_01HQ9C3D4E",
+            "destination_type": "INVOICE",
+            "destination_id": "inv_01HQ9A1B2C",
+            "amount": "149.00",
+            "currency": "usd",
+            "payment_method_type": "CARD",
+            "payment_status": if event_type.ends_with("failed") { "FAILED" } else if event_type.ends_with("success") { "SUCCEEDED" } else { "PENDING" },
+            "created_at": now,
+        }),
+        "wallet" => json!({
+            "id": "wallet_01HQ9E5F6G",
+            "customer_id": "cus_01HQ8Z4N1W",
+            "currency": "usd",
+            "balance": "500.00",
+            "credit_balance": "500.00",
+            "wallet_status": if event_type.ends_with("terminated") { "closed" } else { "active" },
+            "created_at": now,
+        }),
+        "feature" => json!({
+            "id": "feat_01HQ9G7H8J",
+            "name": "API Calls",
+            "lookup_key": "api_calls",
+            "type": "metered",
+            "meter_id": "meter_01HQ9H9K0L",
+            "status": "published",
+            "created_at": now,
+        }),
+        _ => json!({
+            "id": "ent_01HQ9J1M2N",
+            "plan_id": "plan_01HQ8Y9R7T",
+            "feature_id": "feat_01HQ9G7H8J",
+            "feature_type": "metered",
+            "is_enabled": true,
+            "usage_limit": 10000,
+            "usage_reset_period": "MONTHLY",
+            "created_at": now,
+        }),
+    };
+    Ok(json!({
+        "event_type": event_type,
+        "timestamp": now,
+        "data": data,
+    }))
+}
@@ -0,0 +1,135 @@
+use std::ffi::OsString;
+
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, Command};
+
+use super::file::ConfigFile;
+use super::project::ProjectFile;
+use crate::utils::output;
+
+/// Layer `[defaults]` from config.toml, and then `output` and `[defaults]`
+/// from the project's .flexprice.toml, underneath the command line.
+///
+/// Defaults are resolved from the least to the most specific table along the
+/// subcommand path — `[defaults]`, then `[defaults.invoices]`, then
+/// `[defaults.invoices.list]` — and appended to `args` for every flag the
+/// invoked command accepts but that wasn't given on the command line or via
+/// an environment variable.
+pub fn apply(args: Vec<OsString>, cmd: Command) -> Vec<OsString> {
+    let mut defaults = match ConfigFile::load() {
+        Ok(c) => c.defaults,
+        Err(e) => {
+            output::warning(&format!("{:#}", e));
+            return args;
+        }
+    };
+    if let Some(project) = ProjectFile::current() {
+        if let Some(format) = &project.output {
+            defaults.insert("output".into(), format.clone().into());
+        }
+        merge(&mut defaults, project.defaults.clone());
+    }
+    if defaults.is_empty() {
+        return args;
+    }
+
+    let mut cmd = cmd;
+    cmd.build();
+    // Let clap report parse errors against the untouched arguments
+    let Ok(matches) = cmd.clone().try_get_matches_from(&args) else {
+        return args;
+    };
+
+    // Walk down to the invoked subcommand, merging defaults tables on the way
+    let mut resolved = scalars(&defaults);
+    let mut table = Some(&defaults);
+    let mut leaf_cmd = &cmd;
+    let mut leaf_matches = &matches;
+    while let Some((name, sub_matches)) = leaf_matches.subcommand() {
+        let Some(sub_cmd) = leaf_cmd.find_subcommand(name) else { break };
+        table = table.and_then(|t| t.get(name)).and_then(|v| v.as_table());
+        if let Some(t) = table {
+            resolved.extend(scalars(t));
+        }
+        leaf_cmd = sub_cmd;
+        leaf_matches = sub_matches;
+    }
+
+    let mut args = args;
+    for (key, value) in resolved {
+        args.extend(default_args(leaf_cmd, leaf_matches, &key, &value));
+    }
+    args
+}
+
+/// Overlay `over` onto `base`, merging tables key by key
+fn merge(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Non-table entries of a defaults table, i.e. flag values at this level
+fn scalars(table: &toml::Table) -> Vec<(String, toml::Value)> {
+    table
+        .iter()
+        .filter(|(_, v)| !v.is_table())
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// Render a single default as command-line arguments, or nothing if the flag
+/// doesn't apply to `cmd` or was set explicitly.
+fn default_args(cmd: &Command, matches: &ArgMatches, key: &str, value: &toml::Value) -> Vec<OsString> {
+    let long = key.replace('_', "-");
+    let Some(arg) = cmd.get_arguments().find(|a| a.get_long() == Some(long.as_str())) else {
+        return vec![];
+    };
+    let explicit = |id: &str| {
+        matches!(
+            matches.value_source(id),
+            Some(ValueSource::CommandLine) | Some(ValueSource::EnvVariable)
+        )
+    };
+    // Conflicts are declared on one side only, so check both directions
+    let conflicts = |a: &clap::Arg, b: &clap::Arg| {
+        cmd.get_arg_conflicts_with(a).iter().any(|c| c.get_id() == b.get_id())
+    };
+    if explicit(arg.get_id().as_str())
+        || cmd.get_arguments().any(|other| {
+            explicit(other.get_id().as_str()) && (conflicts(arg, other) || conflicts(other, arg))
+        })
+    {
+        return vec![];
+    }
+
+    let flag = format!("--{}", long);
+    let takes_value = matches!(arg.get_action(), ArgAction::Set | ArgAction::Append);
+    let values: Vec<&toml::Value> = match value {
+        toml::Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+
+    let mut out = vec![];
+    for v in values {
+        match v {
+            toml::Value::Boolean(b) if !takes_value => {
+                if *b {
+                    out.push(OsString::from(&flag));
+                }
+            }
+            // e.g. `verbose = 2` for -vv
+            toml::Value::Integer(n) if matches!(arg.get_action(), ArgAction::Count) => {
+                out.extend((0..*n).map(|_| OsString::from(&flag)));
+            }
+            toml::Value::String(s) => out.push(OsString::from(format!("{}={}", flag, s))),
+            other => out.push(OsString::from(format!("{}={}", flag, other))),
+        }
+    }
+    out
+}
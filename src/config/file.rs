@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::project::ProjectFile;
+use super::store::Credentials;
+use super::{globals, secrets};
+use crate::utils::output;
+
+/// Name of the profile backed by ~/.flexprice/credentials.json
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Where API keys and auth tokens are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStore {
+    /// The OS keyring, falling back to plaintext files when it's unavailable
+    #[default]
+    Keyring,
+    /// Plaintext in credentials.json / config.toml
+    File,
+}
+
+/// Contents of ~/.flexprice/config.toml
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConfigFile {
+    /// Profile used when `--profile` isn't given (`default` when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// Where `auth login` and friends store secrets (`keyring` when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_store: Option<CredentialStore>,
+    /// Default flag values, optionally nested per subcommand (`[defaults.invoices]`)
+    #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+    pub defaults: toml::Table,
+    /// Named credential sets, e.g. `[profiles.staging]`
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Credentials>,
+    /// Commands saved with `query save`, per profile, e.g. `[queries.staging]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub queries: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl ConfigFile {
+    /// Returns the path to ~/.flexprice/config.toml
+    pub fn path() -> PathBuf {
+        let home = dirs::home_dir().expect("Could not determine home directory");
+        home.join(".flexprice").join("config.toml")
+    }
+
+    /// Load config.toml, returning an empty config if the file doesn't exist
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    /// Save to ~/.flexprice/config.toml
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The profile for this invocation: `--profile`, then the project file's
+    /// `profile`, then `active_profile`, then `default`
+    pub fn selected_profile(&self) -> String {
+        globals::get()
+            .profile
+            .clone()
+            .or_else(|| ProjectFile::current().and_then(|p| p.profile.clone()))
+            .or_else(|| self.active_profile.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// Stored credentials for a profile, with secrets read from the keyring
+    pub fn credentials(&self, name: &str) -> anyhow::Result<Credentials> {
+        let mut creds = self.stored_credentials(name)?;
+        creds
+            .load_secrets(name)
+            .map_err(|e| anyhow::anyhow!("Can't load credentials for profile '{}': {:#}", name, e))?;
+        Ok(creds)
+    }
+
+    /// Credentials as written on disk, without secrets kept in the keyring
+    pub fn stored_credentials(&self, name: &str) -> anyhow::Result<Credentials> {
+        if name == DEFAULT_PROFILE {
+            return Ok(Credentials::load_from_file().unwrap_or_default());
+        }
+        self.profiles.get(name).cloned().ok_or_else(|| {
+            anyhow::anyhow!("Unknown profile '{}'. Run `flexprice config profile list` to see profiles.", name)
+        })
+    }
+
+    /// Store credentials under a profile, returning the file they were written to.
+    ///
+    /// Secrets go to the OS keyring unless `credential_store = "file"`; if the
+    /// keyring can't be used they're written to the file with a warning.
+    pub fn store_credentials(&mut self, name: &str, mut creds: Credentials) -> anyhow::Result<PathBuf> {
+        creds.keyring = false;
+        if self.credential_store.unwrap_or_default() == CredentialStore::Keyring && creds.is_authenticated() {
+            if let Err(e) = creds.move_secrets_to_keyring(name) {
+                output::warning(&format!(
+                    "{:#}; storing credentials in plaintext. Set `credential_store = \"file\"` in {} to silence this warning.",
+                    e,
+                    Self::path().display()
+                ));
+            }
+        }
+        if !creds.keyring {
+            // Drop any secrets left over from an earlier keyring login
+            let _ = secrets::delete(name);
+        }
+        self.write_credentials(name, creds)
+    }
+
+    /// Write credentials for a profile exactly as given
+    pub fn write_credentials(&mut self, name: &str, creds: Credentials) -> anyhow::Result<PathBuf> {
+        if name == DEFAULT_PROFILE {
+            creds.save()?;
+            return Ok(Credentials::credentials_path());
+        }
+        self.profiles.insert(name.to_string(), creds);
+        self.save()?;
+        Ok(Self::path())
+    }
+
+    /// Names of all known profiles, `default` first if credentials.json exists
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names = vec![];
+        if Credentials::credentials_path().exists() {
+            names.push(DEFAULT_PROFILE.to_string());
+        }
+        names.extend(self.profiles.keys().cloned());
+        names
+    }
+
+    /// All known profiles: the stored credentials as `default`, then config.toml profiles
+    pub fn all_profiles(&self) -> anyhow::Result<Vec<(String, Credentials)>> {
+        self.profile_names()
+            .into_iter()
+            .map(|name| Ok((name.clone(), self.credentials(&name)?)))
+            .collect()
+    }
+}
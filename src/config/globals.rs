@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::utils::output::{self, ErrorFormat, Format, SortKey};
+use crate::utils::time;
+use crate::utils::spinner::ProgressMode;
+
+/// Flags accepted by every command, readable from anywhere after parsing
+#[derive(Args, Debug, Clone, Default)]
+pub struct GlobalOptions {
+    /// Override the API base URL
+    #[arg(long, global = true)]
+    pub api_url: Option<String>,
+
+    /// Override the API key
+    #[arg(long, global = true)]
+    pub api_key: Option<String>,
+
+    /// Use a named profile from ~/.flexprice/config.toml
+    #[arg(long, global = true, env = "FLEXPRICE_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Send this command to an environment, by ID or name, without saving it
+    #[arg(long, global = true, value_name = "ID|NAME")]
+    pub env: Option<String>,
+
+    /// Refuse any request that could change billing state
+    #[arg(long, global = true, env = "FLEXPRICE_READ_ONLY", value_parser = clap::builder::BoolishValueParser::new())]
+    pub read_only: bool,
+
+    /// Print the method, URL, and body of each request that would change
+    /// something instead of sending it; reads still go through
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Log each API request to stderr: -v for method, URL, status, timing, and
+    /// request ID; -vv adds request and response headers (secrets redacted)
+    #[arg(long, short, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print only results, warnings, and errors: no spinners, progress, or success messages
+    #[arg(long, short, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Retry transient failures (429, 5xx, connection errors) this many times
+    #[arg(long, global = true, value_name = "N", default_value_t = 3)]
+    pub retries: u32,
+
+    /// Base delay before the first retry, doubled on each attempt (e.g. 500ms, 2s)
+    #[arg(long, global = true, value_name = "DELAY", default_value = "500ms", value_parser = parse_delay)]
+    pub retry_delay: Duration,
+
+    /// Reuse GET responses cached less than this long ago (e.g. 30s, 5m) and
+    /// revalidate older ones by ETag; off unless set
+    #[arg(long, global = true, value_name = "TTL", env = "FLEXPRICE_CACHE_TTL", value_parser = parse_ttl)]
+    pub cache_ttl: Option<Duration>,
+
+    /// Send every request to the server, ignoring the response cache
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Give up on a request that hasn't completed in this long (e.g. 90s, 2m)
+    #[arg(long, global = true, value_name = "DURATION", env = "FLEXPRICE_TIMEOUT", default_value = "30s", value_parser = parse_timeout)]
+    pub request_timeout: Duration,
+
+    /// Give up on connecting to the server after this long (e.g. 5s)
+    #[arg(long, global = true, value_name = "DURATION", default_value = "10s", value_parser = parse_timeout)]
+    pub connect_timeout: Duration,
+
+    /// Send requests through this HTTP(S) proxy, e.g. http://proxy.internal:3128;
+    /// otherwise the HTTP_PROXY and HTTPS_PROXY variables apply
+    #[arg(long, global = true, value_name = "URL", env = "FLEXPRICE_HTTP_PROXY", value_parser = parse_proxy)]
+    pub proxy: Option<String>,
+
+    /// Trust the CA certificates in this PEM file as well as the system's,
+    /// for servers behind a private certificate authority
+    #[arg(long, global = true, value_name = "PATH", env = "FLEXPRICE_CA_CERT")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Accept any TLS certificate, including self-signed and expired ones.
+    /// Only for testing against a server you control.
+    #[arg(long, global = true, env = "FLEXPRICE_INSECURE", value_parser = clap::builder::BoolishValueParser::new())]
+    pub insecure: bool,
+
+    /// Output format for results
+    #[arg(long, short, global = true, value_enum, default_value_t = Format::Table)]
+    pub output: Format,
+
+    /// Print only what a JMESPath expression selects from the `-o json` output:
+    /// "items[?invoice_status=='FINALIZED'].id" on a paged listing's
+    /// {items, pagination}, "[].id" on the bare array of `get --ids-file` or `--raw`
+    #[arg(long, global = true, value_name = "EXPR", value_parser = output::parse_query)]
+    pub query: Option<String>,
+
+    /// Only show these columns of a listing, in this order (e.g. id,name,status)
+    #[arg(long, global = true, value_name = "COLUMNS", value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Sort a listing by one of its columns, e.g. name or created_at:desc
+    #[arg(long, global = true, value_name = "COLUMN[:asc|desc]", value_parser = output::parse_sort)]
+    pub sort: Option<SortKey>,
+
+    /// Show data as the API returns it: timestamps instead of readable periods,
+    /// and listings without the pagination envelope in json and yaml
+    #[arg(long, global = true)]
+    pub raw: bool,
+
+    /// How to report a failure on stderr: a message, or JSON for scripts
+    #[arg(long, global = true, value_enum, value_name = "FORMAT", env = "FLEXPRICE_ERROR_FORMAT", default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+
+    /// How to report progress: spinners (auto), JSON events on stderr, or none
+    #[arg(long, global = true, value_enum, value_name = "MODE", default_value_t = ProgressMode::Auto)]
+    pub progress: ProgressMode,
+}
+
+fn parse_delay(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: u64 = num
+        .parse()
+        .map_err(|_| format!("invalid delay '{}': expected e.g. 500ms or 2s", s))?;
+    match unit {
+        "ms" | "" => Ok(Duration::from_millis(n)),
+        "s" => Ok(Duration::from_secs(n)),
+        _ => Err(format!("invalid delay unit '{}': use ms or s", unit)),
+    }
+}
+
+fn parse_ttl(s: &str) -> Result<Duration, String> {
+    time::parse_duration(s)
+        .map_err(|e| e.to_string())?
+        .to_std()
+        .map_err(|_| format!("invalid TTL '{}': must not be negative", s))
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let timeout = match s.trim() {
+        t if t.ends_with("ms") => parse_delay(t)?,
+        t if t.chars().all(|c| c.is_ascii_digit()) => {
+            return Err(format!("invalid timeout '{}': add a unit, e.g. {}s", s, t));
+        }
+        t => parse_ttl(t)?,
+    };
+    match timeout.is_zero() {
+        true => Err(format!("invalid timeout '{}': must be more than zero", s)),
+        false => Ok(timeout),
+    }
+}
+
+fn parse_proxy(s: &str) -> Result<String, String> {
+    let url = reqwest::Url::parse(s).map_err(|e| format!("invalid proxy URL '{}': {}", s, e))?;
+    match url.scheme() {
+        "http" | "https" => Ok(s.to_string()),
+        other => Err(format!("invalid proxy URL '{}': unsupported scheme '{}'", s, other)),
+    }
+}
+
+static GLOBALS: OnceLock<GlobalOptions> = OnceLock::new();
+
+/// Record the parsed global flags; called once from `main`
+pub fn init(opts: GlobalOptions) {
+    let _ = GLOBALS.set(opts);
+}
+
+/// The global flags for this invocation (all unset before `init`)
+pub fn get() -> &'static GlobalOptions {
+    GLOBALS.get_or_init(GlobalOptions::default)
+}
+
+static ENVIRONMENT_ID: OnceLock<String> = OnceLock::new();
+
+/// Record the environment ID that `--env` resolved to
+pub fn set_environment_id(id: String) {
+    let _ = ENVIRONMENT_ID.set(id);
+}
+
+/// The environment for this invocation: `--env` once resolved, else as given
+pub fn environment() -> Option<&'static str> {
+    ENVIRONMENT_ID.get().map(String::as_str).or(get().env.as_deref())
+}
@@ -1,2 +1,8 @@
+pub mod defaults;
+pub mod file;
+pub mod globals;
+pub mod project;
+pub mod secrets;
 pub mod store;
+pub use file::ConfigFile;
 pub use store::Credentials;
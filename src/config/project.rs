@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::utils::output;
+
+/// File name of a project's settings, written by `flexprice init`
+pub const FILE_NAME: &str = ".flexprice.toml";
+
+static CURRENT: OnceLock<Option<ProjectFile>> = OnceLock::new();
+
+/// Contents of .flexprice.toml, found in the current directory or a parent
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectFile {
+    /// Profile used when `--profile` isn't given, ahead of `active_profile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// API URL, overriding the profile's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Environment, overriding the profile's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment_id: Option<String>,
+    /// Default `--output` format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Default flag values for commands run in the project, layered over
+    /// `[defaults]` in ~/.flexprice/config.toml
+    #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+    pub defaults: toml::Table,
+}
+
+impl ProjectFile {
+    /// The nearest .flexprice.toml, from the current directory upwards
+    pub fn find() -> Option<PathBuf> {
+        let cwd = std::env::current_dir().ok()?;
+        cwd.ancestors().map(|dir| dir.join(FILE_NAME)).find(|path| path.is_file())
+    }
+
+    /// Load the nearest project file, if there is one
+    pub fn load() -> anyhow::Result<Option<Self>> {
+        let Some(path) = Self::find() else { return Ok(None) };
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("Invalid project file {}: {}", path.display(), e))
+    }
+
+    /// The project file for this invocation, loaded once; an invalid file is
+    /// reported and ignored
+    pub fn current() -> Option<&'static Self> {
+        CURRENT
+            .get_or_init(|| {
+                Self::load().unwrap_or_else(|e| {
+                    output::warning(&format!("{:#}", e));
+                    None
+                })
+            })
+            .as_ref()
+    }
+
+    /// Write to `path` with a header explaining the file
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let header = "# FlexPrice project settings, written by `flexprice init`.\n\
+                      # They apply to commands run in this directory and below, over the\n\
+                      # profile's settings; environment variables and flags still win.\n\n";
+        fs::write(path, format!("{}{}", header, toml::to_string_pretty(self)?))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::{Context, Result};
+use keyring::Entry;
+
+/// Service name under which entries appear in the OS keyring
+const SERVICE: &str = "flexprice-cli";
+
+/// Credential fields kept in the keyring rather than on disk
+const FIELDS: &[&str] = &["api_key", "auth_token"];
+
+fn entry(profile: &str, field: &str) -> Result<Entry> {
+    Entry::new(SERVICE, &format!("{}/{}", profile, field)).context("OS keyring unavailable")
+}
+
+/// Run a keyring call on its own thread. The Linux Secret Service backend
+/// blocks on an internal runtime, which panics inside the CLI's tokio runtime.
+fn isolated<T: Send>(f: impl FnOnce() -> Result<T> + Send) -> Result<T> {
+    std::thread::scope(|s| s.spawn(f).join().unwrap_or_else(|_| anyhow::bail!("OS keyring call panicked")))
+}
+
+/// Read a secret, `None` if the profile has no entry for it
+pub fn get(profile: &str, field: &str) -> Result<Option<String>> {
+    isolated(|| match entry(profile, field)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context("Failed to read from the OS keyring"),
+    })
+}
+
+/// Store a secret, or remove it when `value` is `None`
+pub fn set(profile: &str, field: &str, value: Option<&str>) -> Result<()> {
+    isolated(|| {
+        let entry = entry(profile, field)?;
+        match value {
+            Some(value) => entry.set_password(value).context("Failed to write to the OS keyring"),
+            None => match entry.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e).context("Failed to remove from the OS keyring"),
+            },
+        }
+    })
+}
+
+/// Remove every secret stored for a profile
+pub fn delete(profile: &str) -> Result<()> {
+    for field in FIELDS {
+        set(profile, field, None)?;
+    }
+    Ok(())
+}
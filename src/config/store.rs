@@ -1,7 +1,13 @@
+use base64::Engine;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+use super::file::ConfigFile;
+use super::project::ProjectFile;
+use super::{globals, secrets};
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Credentials {
     #[serde(default)]
@@ -16,6 +22,25 @@ pub struct Credentials {
     pub user_id: Option<String>,
     #[serde(default)]
     pub environment_id: Option<String>,
+    /// When `auth_token` stops being accepted, from the token's `exp` claim
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_expires_at: Option<DateTime<Utc>>,
+    /// Email the login session belongs to, asked for again when it expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// `api_key` and `auth_token` live in the OS keyring instead of this file
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keyring: bool,
+    /// Refuse requests that could change billing state
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// Client certificate for mutual-TLS gateways: a PEM file, or a PKCS#12
+    /// bundle unlocked with `FLEXPRICE_CLIENT_CERT_PASSWORD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+    /// PEM private key for `client_cert`, when it isn't in the same file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
 }
 
 impl Credentials {
@@ -25,38 +50,61 @@ impl Credentials {
         home.join(".flexprice").join("credentials.json")
     }
 
-    /// Load credentials with priority: CLI flags > .env in cwd > ~/.flexprice/credentials.json
-    pub fn load(
-        cli_api_url: Option<&str>,
-        cli_api_key: Option<&str>,
-    ) -> anyhow::Result<Self> {
-        // 1. Start with stored credentials
-        let mut creds = Self::load_from_file().unwrap_or_default();
-
-        // 2. Override with .env in cwd
-        if let Ok(val) = std::env::var("FLEXPRICE_API_URL") {
-            if !val.is_empty() {
-                creds.api_url = val;
+    /// Load credentials with priority: CLI flags > env / .env in cwd > project
+    /// file > selected profile.
+    ///
+    /// The profile is `--profile`, else the project file's `profile`, else
+    /// config.toml's `active_profile`, else the credentials file. An explicitly
+    /// requested profile isn't overridden by `FLEXPRICE_API_*` variables, so a
+    /// stray `.env` can't redirect it, nor by a project pinned to another profile.
+    pub fn load() -> anyhow::Result<Self> {
+        let globals = globals::get();
+        let config = ConfigFile::load()?;
+
+        // 1. Start with the selected profile's stored credentials
+        let mut creds = config.credentials(&config.selected_profile())?;
+
+        // 2. Override with the project file
+        if let Some(project) = ProjectFile::current() {
+            if globals.profile.is_none() || globals.profile == project.profile {
+                if let Some(url) = project.api_url.as_ref().filter(|url| !url.is_empty()) {
+                    creds.api_url = url.clone();
+                }
+                if let Some(env) = project.environment_id.as_ref().filter(|env| !env.is_empty()) {
+                    creds.environment_id = Some(env.clone());
+                }
             }
         }
-        if let Ok(val) = std::env::var("FLEXPRICE_API_KEY") {
-            if !val.is_empty() {
-                creds.api_key = Some(val);
+
+        // 3. Override with .env in cwd
+        if globals.profile.is_none() {
+            if let Ok(val) = std::env::var("FLEXPRICE_API_URL") {
+                if !val.is_empty() {
+                    creds.api_url = val;
+                }
             }
-        }
-        if let Ok(val) = std::env::var("FLEXPRICE_ENVIRONMENT_ID") {
-            if !val.is_empty() {
-                creds.environment_id = Some(val);
+            if let Ok(val) = std::env::var("FLEXPRICE_API_KEY") {
+                if !val.is_empty() {
+                    creds.api_key = Some(val);
+                }
+            }
+            if let Ok(val) = std::env::var("FLEXPRICE_ENVIRONMENT_ID") {
+                if !val.is_empty() {
+                    creds.environment_id = Some(val);
+                }
             }
         }
 
-        // 3. Override with CLI flags
-        if let Some(url) = cli_api_url {
+        // 4. Override with CLI flags
+        if let Some(ref url) = globals.api_url {
             creds.api_url = url.to_string();
         }
-        if let Some(key) = cli_api_key {
+        if let Some(ref key) = globals.api_key {
             creds.api_key = Some(key.to_string());
         }
+        if let Some(env) = globals::environment() {
+            creds.environment_id = Some(env.to_string());
+        }
 
         Ok(creds)
     }
@@ -92,11 +140,46 @@ impl Credentials {
         Ok(())
     }
 
+    /// Fill in `api_key` and `auth_token` from the keyring if they're stored there
+    pub fn load_secrets(&mut self, profile: &str) -> anyhow::Result<()> {
+        if self.keyring {
+            self.api_key = secrets::get(profile, "api_key")?;
+            self.auth_token = secrets::get(profile, "auth_token")?;
+        }
+        Ok(())
+    }
+
+    /// Move `api_key` and `auth_token` into the keyring, leaving them off this
+    /// struct so they aren't written to disk. Unchanged if the keyring fails.
+    pub fn move_secrets_to_keyring(&mut self, profile: &str) -> anyhow::Result<()> {
+        secrets::set(profile, "api_key", self.api_key.as_deref())?;
+        secrets::set(profile, "auth_token", self.auth_token.as_deref())?;
+        self.api_key = None;
+        self.auth_token = None;
+        self.keyring = true;
+        Ok(())
+    }
+
     /// Check if the user is authenticated (has API key or auth token)
     pub fn is_authenticated(&self) -> bool {
         self.api_key.is_some() || self.auth_token.is_some()
     }
 
+    /// When the login session ends: the stored expiry, else the token's own.
+    /// `None` with an API key or a token that doesn't say.
+    pub fn session_expires_at(&self) -> Option<DateTime<Utc>> {
+        if self.api_key.is_some() {
+            return None;
+        }
+        let token = self.auth_token.as_deref()?;
+        self.token_expires_at.or_else(|| token_expiry(token))
+    }
+
+    /// Whether the login session has ended, or will before a request could finish
+    pub fn session_expired(&self) -> bool {
+        self.session_expires_at().is_some_and(|at| at <= Utc::now() + chrono::Duration::seconds(30))
+    }
+
     /// Returns the auth header name and value
     pub fn get_auth_header(&self) -> Option<(&'static str, String)> {
         if let Some(ref key) = self.api_key {
@@ -108,6 +191,15 @@ impl Credentials {
         }
     }
 
+    /// A copy safe to share: the API key masked, the auth token removed
+    pub fn redacted(&self) -> Self {
+        Self {
+            api_key: self.api_key.as_ref().map(|_| self.masked_api_key()),
+            auth_token: self.auth_token.as_ref().map(|_| "(redacted)".to_string()),
+            ..self.clone()
+        }
+    }
+
     /// Mask the API key for display
     pub fn masked_api_key(&self) -> String {
         match &self.api_key {
@@ -119,3 +211,12 @@ impl Credentials {
         }
     }
 }
+
+/// The `exp` claim of a JWT. The signature isn't checked; this only decides
+/// when to log in again, and the server still validates every request.
+pub fn token_expiry(token: &str) -> Option<DateTime<Utc>> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    DateTime::from_timestamp(claims.get("exp")?.as_i64()?, 0)
+}
@@ -4,7 +4,7 @@ mod config;
 mod tui;
 mod utils;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
 /// ⚡ FlexPrice CLI — Usage-based billing, from your terminal.
 #[derive(Parser)]
@@ -20,13 +20,8 @@ struct Cli {
     #[command(subcommand)]
     command: Commands,
 
-    /// Override the API base URL
-    #[arg(long, global = true)]
-    api_url: Option<String>,
-
-    /// Override the API key
-    #[arg(long, global = true)]
-    api_key: Option<String>,
+    #[command(flatten)]
+    globals: config::globals::GlobalOptions,
 }
 
 #[derive(Subcommand)]
@@ -36,6 +31,8 @@ enum Commands {
         #[command(subcommand)]
         command: cli::auth::AuthCommands,
     },
+    /// Set up auth and an environment, and write .flexprice.toml and example payloads here
+    Init(cli::init::InitArgs),
     /// Manage customers
     Customers {
         #[command(subcommand)]
@@ -46,6 +43,24 @@ enum Commands {
         #[command(subcommand)]
         command: cli::plans::PlanCommands,
     },
+    /// Manage add-ons sold on top of plans
+    Addons {
+        #[command(subcommand)]
+        command: cli::addons::AddonCommands,
+    },
+    /// Manage plan prices
+    Prices {
+        #[command(subcommand)]
+        command: cli::prices::PriceCommands,
+    },
+    /// Estimate what usage would cost on a plan, or under several plans side by side
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Estimate {
+        #[command(subcommand)]
+        command: Option<cli::estimate::EstimateCommands>,
+        #[command(flatten)]
+        plan: cli::estimate::PlanEstimateArgs,
+    },
     /// Manage subscriptions
     Subscriptions {
         #[command(subcommand)]
@@ -56,6 +71,16 @@ enum Commands {
         #[command(subcommand)]
         command: cli::invoices::InvoiceCommands,
     },
+    /// Record, reconcile, and collect payments
+    Payments {
+        #[command(subcommand)]
+        command: cli::payments::PaymentCommands,
+    },
+    /// Manage tax rates and apply them to customers
+    Taxes {
+        #[command(subcommand)]
+        command: cli::taxes::TaxCommands,
+    },
     /// Manage meters
     Meters {
         #[command(subcommand)]
@@ -81,10 +106,75 @@ enum Commands {
         #[command(subcommand)]
         command: cli::entitlements::EntitlementCommands,
     },
-    /// Show current configuration
-    Config,
+    /// Explore webhook event types and sample payloads
+    Webhooks {
+        #[command(subcommand)]
+        command: cli::webhooks::WebhookCommands,
+    },
+    /// List and switch tenants, and view your tenant's FlexPrice usage and billing
+    #[command(visible_alias = "tenants")]
+    Tenant {
+        #[command(subcommand)]
+        command: cli::tenant::TenantCommands,
+    },
+    /// See who changed what in the tenant, and when
+    Audit {
+        #[command(subcommand)]
+        command: cli::audit::AuditCommands,
+    },
+    /// Follow background tasks such as imports, exports, and recomputations
+    Jobs {
+        #[command(subcommand)]
+        command: cli::jobs::JobCommands,
+    },
+    /// Create or update meters, features, plans, prices, and entitlements from a manifest
+    ///
+    /// With --dry-run, shows what would change without changing anything.
+    Apply(cli::apply::ApplyArgs),
+    /// Export customers, plans, prices, features, meters, entitlements, and subscriptions to files
+    Export(cli::export::ExportArgs),
+    /// Recreate an export snapshot in this or another profile's environment
+    ///
+    /// With --dry-run, shows what would be created without changing anything.
+    Import(cli::import::ImportArgs),
+    /// Compare meters, features, plans, prices, and entitlements across two profiles
+    ///
+    /// Exits non-zero when anything is missing, extra, or different, so it can gate CI.
+    Diff(cli::diff::DiffArgs),
+    /// Make a raw authenticated request, e.g. `flexprice api get /v1/customers`
+    Api(cli::api::ApiArgs),
+    /// Check a read command's JSON output against a snapshot (for CI)
+    Assert(cli::assert::AssertArgs),
+    /// Save commands under a name and re-run them, per profile
+    Query {
+        #[command(subcommand)]
+        command: cli::query::QueryCommands,
+    },
+    /// Check credentials, connectivity, clock, environment, permissions, and
+    /// server version, with a fix for each problem found
+    ///
+    /// Exits non-zero when any check fails.
+    Doctor,
+    /// Collect diagnostics for support tickets
+    Support {
+        #[command(subcommand)]
+        command: cli::support::SupportCommands,
+    },
+    /// Show current configuration and manage profiles
+    Config {
+        #[command(subcommand)]
+        command: Option<cli::config::ConfigCommands>,
+    },
     /// Launch the interactive TUI dashboard
-    Dashboard,
+    Dashboard {
+        /// Seconds between refreshes of the Events tab while it's open
+        #[arg(long, value_name = "SECS", default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        events_interval: u64,
+    },
+    /// Print a shell completion script, e.g. `source <(flexprice completions bash)`
+    Completions(cli::completions::CompletionsArgs),
+    /// Generate man pages
+    Manpages(cli::completions::ManpagesArgs),
 }
 
 fn get_styles() -> clap::builder::Styles {
@@ -110,44 +200,164 @@ async fn main() {
     // Load .env from cwd if it exists
     let _ = dotenvy::dotenv();
 
-    let cli = Cli::parse();
+    // Answer <TAB> requests from the shell script printed by `completions`
+    clap_complete::CompleteEnv::with_factory(Cli::command)
+        .var(cli::completions::COMPLETE_VAR)
+        .complete();
 
-    let result = match cli.command {
-        Commands::Auth { command } => cli::auth::handle(command).await,
-        Commands::Customers { command } => cli::customers::handle(command).await,
-        Commands::Plans { command } => cli::plans::handle(command).await,
-        Commands::Subscriptions { command } => cli::subscriptions::handle(command).await,
-        Commands::Invoices { command } => cli::invoices::handle(command).await,
-        Commands::Meters { command } => cli::meters::handle(command).await,
-        Commands::Events { command } => cli::events::handle(command).await,
-        Commands::Wallets { command } => cli::wallets::handle(command).await,
-        Commands::Features { command } => cli::features::handle(command).await,
-        Commands::Entitlements { command } => cli::entitlements::handle(command).await,
-        Commands::Config => handle_config(),
-        Commands::Dashboard => handle_dashboard().await,
+    // Fill in flags from `[defaults]` in config.toml, CLI flags still win
+    let args = config::defaults::apply(std::env::args_os().collect(), Cli::command());
+    // Usage errors exit with the validation code; 2 means an auth failure
+    let (cli, command_path) = Cli::command()
+        .try_get_matches_from(args)
+        .and_then(|matches| Ok((Cli::from_arg_matches(&matches)?, cli::command_path(&matches))))
+        .unwrap_or_else(|e| {
+            if !e.use_stderr() {
+                e.exit();
+            }
+            let _ = e.print();
+            std::process::exit(EXIT_VALIDATION);
+        });
+    utils::log::init(cli.globals.verbose);
+    config::globals::init(cli.globals);
+    // Keep color codes out of machine-readable output
+    if !utils::output::human() {
+        colored::control::set_override(false);
+    }
+
+    // Look up `--env NAME` once, before any command builds a client. `doctor`
+    // looks it up itself, to report a bad name as a failed check.
+    let uses_api = !matches!(
+        cli.command,
+        Commands::Config { .. }
+            | Commands::Support { .. }
+            | Commands::Doctor
+            | Commands::Query { .. }
+            | Commands::Completions(_)
+            | Commands::Manpages(_)
+    );
+    if uses_api {
+        if let Err(e) = cli::auth::resolve_environment().await {
+            std::process::exit(report(&e));
+        }
+    }
+    // Stop a command the server is too old for before it half-runs; `auth`
+    // and `init` may be setting up the very credentials this needs
+    if uses_api && !matches!(cli.command, Commands::Auth { .. } | Commands::Init(_)) {
+        if let Err(e) = api::capabilities::negotiate(&command_path).await {
+            std::process::exit(report(&e));
+        }
+    }
+
+    let result = run(cli.command).await;
+
+    let exit_code = match result {
+        // A `--query` that failed on the output has already said why
+        Ok(()) if utils::output::query_failed() => 1,
+        Ok(()) => 0,
+        // The request that stopped the command has already been printed
+        Err(e) if e.is::<api::client::DryRun>() => 0,
+        Err(e) => report(&e),
     };
+    utils::log::history(&std::env::args().collect::<Vec<_>>(), exit_code);
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+}
+
+/// Run the parsed command
+async fn run(command: Commands) -> anyhow::Result<()> {
+    match command {
+        Commands::Auth { command } => cli::auth::handle(command).await,
+        Commands::Init(args) => cli::init::handle(args).await,
+        Commands::Customers { command } => cli::customers::handle(command, connect()?).await,
+        Commands::Plans { command } => cli::plans::handle(command, connect()?).await,
+        Commands::Addons { command } => cli::addons::handle(command, connect()?).await,
+        Commands::Prices { command } => cli::prices::handle(command, connect()?).await,
+        Commands::Estimate { command, plan } => cli::estimate::handle(command, plan, connect()?).await,
+        Commands::Subscriptions { command } => cli::subscriptions::handle(command, connect()?).await,
+        Commands::Invoices { command } => cli::invoices::handle(command, connect()?).await,
+        Commands::Payments { command } => cli::payments::handle(command, connect()?).await,
+        Commands::Taxes { command } => cli::taxes::handle(command, connect()?).await,
+        Commands::Meters { command } => cli::meters::handle(command, connect()?).await,
+        Commands::Events { command } => cli::events::handle(command, connect()?).await,
+        Commands::Wallets { command } => cli::wallets::handle(command, connect()?).await,
+        Commands::Features { command } => cli::features::handle(command, connect()?).await,
+        Commands::Entitlements { command } => cli::entitlements::handle(command, connect()?).await,
+        Commands::Webhooks { command } => cli::webhooks::handle(command, connect()?).await,
+        Commands::Tenant { command } => cli::tenant::handle(command, connect()?).await,
+        Commands::Audit { command } => cli::audit::handle(command, connect()?).await,
+        Commands::Jobs { command } => cli::jobs::handle(command, connect()?).await,
+        Commands::Apply(args) => cli::apply::handle(args, connect()?).await,
+        Commands::Export(args) => cli::export::handle(args, connect()?).await,
+        Commands::Import(args) => cli::import::handle(args).await,
+        Commands::Diff(args) => cli::diff::handle(args).await,
+        Commands::Api(args) => cli::api::handle(args, connect()?).await,
+        Commands::Assert(args) => cli::assert::handle(args, Cli::command()).await,
+        Commands::Query { command } => cli::query::handle(command, Cli::command()),
+        Commands::Doctor => cli::doctor::handle().await,
+        Commands::Support { command } => cli::support::handle(command),
+        Commands::Config { command: None } => handle_config(),
+        Commands::Config { command: Some(command) } => cli::config::handle(command),
+        Commands::Dashboard { events_interval } => handle_dashboard(events_interval).await,
+        Commands::Completions(args) => cli::completions::completions(args, Cli::command()),
+        Commands::Manpages(args) => cli::completions::manpages(args, Cli::command()),
+    }
+}
+
+/// The authenticated client for a command's requests, built once so they all
+/// share its connection pool and renewed login session
+fn connect() -> anyhow::Result<api::ApiClient> {
+    api::ApiClient::new(cli::auth::require_auth()?)
+}
+
+/// Exit code for clap usage errors, the same as the API rejecting input
+const EXIT_VALIDATION: i32 = 4;
 
-    if let Err(e) = result {
-        utils::output::error(&format!("{:#}", e));
-        std::process::exit(1);
+/// Print a failed command's error in the `--error-format` and return its exit
+/// code: the API error's own code if one caused it, else 1
+fn report(e: &anyhow::Error) -> i32 {
+    let api_error = e.chain().find_map(|cause| cause.downcast_ref::<api::error::ApiClientError>());
+    let code = api_error.map_or(1, |err| err.exit_code());
+    match config::globals::get().error_format {
+        utils::output::ErrorFormat::Text => utils::output::error(&format!("{:#}", e)),
+        utils::output::ErrorFormat::Json => {
+            let failure = api_error.map(|err| err.failure());
+            let error = serde_json::json!({
+                "kind": api_error.map_or("error", |err| err.kind()),
+                "message": format!("{:#}", e),
+                "status": failure.and_then(|f| f.status),
+                "request_id": failure.and_then(|f| f.request_id.as_deref()),
+                "exit_code": code,
+            });
+            eprintln!("{}", serde_json::json!({ "error": error }));
+        }
     }
+    code
 }
 
 fn handle_config() -> anyhow::Result<()> {
-    let creds = config::Credentials::load(None, None)?;
+    let creds = config::Credentials::load()?;
+    let profile = config::ConfigFile::load()?.selected_profile();
     println!();
+    utils::output::info(&format!("Profile:     {}", profile));
     utils::output::info(&format!("API URL:     {}", if creds.api_url.is_empty() { "(not set)" } else { &creds.api_url }));
     utils::output::info(&format!("API Key:     {}", creds.masked_api_key()));
     utils::output::info(&format!("Auth Token:  {}", if creds.auth_token.is_some() { "(set)" } else { "(not set)" }));
     utils::output::info(&format!("Tenant ID:   {}", creds.tenant_id.as_deref().unwrap_or("(not set)")));
     utils::output::info(&format!("User ID:     {}", creds.user_id.as_deref().unwrap_or("(not set)")));
     utils::output::info(&format!("Env ID:      {}", creds.environment_id.as_deref().unwrap_or("(not set)")));
+    let read_only = creds.read_only || config::globals::get().read_only;
+    utils::output::info(&format!("Read-only:   {}", if read_only { "yes" } else { "no" }));
     utils::output::info(&format!("Config path: {}", config::Credentials::credentials_path().display()));
+    utils::output::info(&format!("Config file: {}", config::ConfigFile::path().display()));
+    let project = config::project::ProjectFile::find();
+    utils::output::info(&format!("Project:     {}", project.map(|p| p.display().to_string()).unwrap_or("(none)".into())));
     println!();
     Ok(())
 }
 
-async fn handle_dashboard() -> anyhow::Result<()> {
+async fn handle_dashboard(events_interval: u64) -> anyhow::Result<()> {
     let creds = cli::auth::require_auth()?;
-    tui::dashboard::run(creds).await
+    tui::dashboard::run(creds, std::time::Duration::from_secs(events_interval)).await
 }
@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::{stream, StreamExt};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType, Padding, Paragraph, Wrap},
+    Frame,
+};
+use serde_json::Value;
+
+use crate::api::client::ApiClient;
+use crate::api::models::{Customer, Invoice, ListResponse, MeterUsage};
+use crate::cli::meters::{fill_buckets, Granularity};
+use crate::utils::{chart, time};
+use super::theme::Theme;
+
+/// How often the Analytics tab re-queries while it is open
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time ranges `w` cycles through, with the bucket size charted for each
+const WINDOWS: &[(&str, Granularity)] = &[("24h", Granularity::Hour), ("7d", Granularity::Day), ("30d", Granularity::Day)];
+
+/// Customers queried for the top-customers chart; each costs one usage query
+const CUSTOMER_SAMPLE: usize = 50;
+const TOP_CUSTOMERS: usize = 8;
+
+/// Usage and revenue for the meter highlighted on the Analytics tab
+#[derive(Clone, Default)]
+pub struct Analytics {
+    /// Meter the charts were last loaded for
+    pub meter_id: Option<String>,
+    meter_name: String,
+    /// Index into `WINDOWS`
+    window: usize,
+    /// `(bucket start as unix seconds, value)`
+    series: Vec<(f64, f64)>,
+    total: f64,
+    top_customers: Vec<(String, f64)>,
+    /// Totals per currency: (invoiced, paid, outstanding)
+    revenue: BTreeMap<String, (f64, f64, f64)>,
+    invoice_count: usize,
+    error: Option<String>,
+    pub loaded_at: Option<Instant>,
+}
+
+impl Analytics {
+    fn range(&self) -> (&'static str, Granularity) {
+        WINDOWS[self.window]
+    }
+
+    pub fn next_window(&mut self) {
+        self.window = (self.window + 1) % WINDOWS.len();
+        self.loaded_at = None;
+    }
+
+    /// Whether the charts are missing, stale, or for a different meter
+    pub fn is_due(&self, meter_id: Option<&str>) -> bool {
+        self.meter_id.as_deref() != meter_id || self.loaded_at.is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL)
+    }
+
+    /// Whether these charts are for the meter and window `current` shows
+    pub fn is_for(&self, current: &Analytics) -> bool {
+        self.meter_id == current.meter_id && self.window == current.window
+    }
+
+    /// Point the charts at `meter`, dropping data that belongs to another one
+    pub fn select(&mut self, meter: Option<&Value>) {
+        let id = meter.and_then(|m| m.get("id")).and_then(|v| v.as_str()).map(String::from);
+        if self.meter_id != id {
+            *self = Analytics { window: self.window, ..Default::default() };
+        }
+        self.meter_name = meter
+            .and_then(|m| m.get("name"))
+            .and_then(|v| v.as_str())
+            .or(id.as_deref())
+            .unwrap_or_default()
+            .to_string();
+        self.meter_id = id;
+    }
+}
+
+/// Query usage for `meter` over the selected window, its heaviest customers,
+/// and invoice totals for the same period
+pub async fn load(client: &ApiClient, analytics: &mut Analytics, meter: &Value) {
+    analytics.select(Some(meter));
+    analytics.loaded_at = Some(Instant::now());
+
+    let (window, granularity) = analytics.range();
+    let end = Utc::now();
+    let start = match time::parse_duration(window).and_then(|d| time::before(end, d)) {
+        Ok(start) => start,
+        Err(e) => {
+            analytics.error = Some(format!("{:#}", e));
+            return;
+        }
+    };
+    let query = usage_query(meter, start, end);
+
+    let mut series_query = query.clone();
+    series_query["window_size"] = granularity.window_size().into();
+    let (series, customers, invoices) = tokio::join!(
+        client.post::<_, MeterUsage>("/v1/events/usage", &series_query),
+        top_customers(client, &query),
+        client.get::<ListResponse<Invoice>>("/v1/invoices"),
+    );
+
+    let mut errors = vec![];
+    match series {
+        Ok(usage) => {
+            let buckets = fill_buckets(&usage, start, end, granularity);
+            analytics.total = buckets.iter().fold(0.0, |sum, (_, v)| sum + v);
+            analytics.series = buckets.into_iter().map(|(at, v)| (at.timestamp() as f64, v)).collect();
+        }
+        Err(e) => {
+            analytics.series.clear();
+            errors.push(format!("Usage: {:#}", e));
+        }
+    }
+    match customers {
+        Ok(top) => analytics.top_customers = top,
+        Err(e) => {
+            analytics.top_customers.clear();
+            errors.push(format!("Customers: {:#}", e));
+        }
+    }
+    match invoices {
+        Ok(page) => {
+            let recent: Vec<Invoice> = page
+                .items
+                .into_iter()
+                .filter(|inv| inv.created_at.as_deref().and_then(|t| time::parse_instant(t).ok()).is_some_and(|at| at >= start))
+                .collect();
+            analytics.invoice_count = recent.len();
+            analytics.revenue = revenue(&recent);
+        }
+        Err(e) => {
+            analytics.revenue.clear();
+            errors.push(format!("Invoices: {:#}", e));
+        }
+    }
+    analytics.error = (!errors.is_empty()).then(|| errors.join("\n"));
+}
+
+/// `/v1/events/usage` body for a meter's event and aggregation
+fn usage_query(meter: &Value, start: DateTime<Utc>, end: DateTime<Utc>) -> Value {
+    let aggregation = meter.get("aggregation");
+    let mut query = serde_json::json!({
+        "event_name": meter.get("event_name").and_then(|v| v.as_str()).unwrap_or_default(),
+        "aggregation_type": aggregation.and_then(|a| a.get("type")).and_then(|v| v.as_str()).unwrap_or("COUNT"),
+        "start_time": start.to_rfc3339_opts(SecondsFormat::Secs, true),
+        "end_time": end.to_rfc3339_opts(SecondsFormat::Secs, true),
+    });
+    if let Some(field) = aggregation.and_then(|a| a.get("field")).and_then(|v| v.as_str()).filter(|f| !f.is_empty()) {
+        query["property_name"] = field.into();
+    }
+    query
+}
+
+/// Total usage per customer for the first page of customers, largest first
+async fn top_customers(client: &ApiClient, query: &Value) -> Result<Vec<(String, f64)>> {
+    let page: ListResponse<Customer> = client.get_with_query("/v1/customers", &[("limit", &CUSTOMER_SAMPLE.to_string())]).await?;
+    let mut totals: Vec<(String, f64)> = stream::iter(page.items.into_iter().filter_map(|c| c.external_id))
+        .map(|external_id| async move {
+            let mut body = query.clone();
+            body["external_customer_id"] = external_id.clone().into();
+            let usage = client.post::<_, MeterUsage>("/v1/events/usage", &body).await?;
+            Ok::<_, anyhow::Error>((external_id, usage.value.unwrap_or_default()))
+        })
+        .buffer_unordered(8)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_>>()?;
+    totals.retain(|(_, v)| *v > 0.0);
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1));
+    totals.truncate(TOP_CUSTOMERS);
+    Ok(totals)
+}
+
+/// Invoiced, paid, and outstanding amounts per currency, ignoring drafts and voids
+fn revenue(invoices: &[Invoice]) -> BTreeMap<String, (f64, f64, f64)> {
+    let mut totals: BTreeMap<String, (f64, f64, f64)> = BTreeMap::new();
+    for inv in invoices {
+        if matches!(inv.invoice_status.as_deref(), Some("DRAFT" | "VOIDED")) {
+            continue;
+        }
+        let amount = inv.amount_due.unwrap_or_default();
+        let entry = totals.entry(inv.currency.clone().unwrap_or_default().to_uppercase()).or_default();
+        entry.0 += amount;
+        if inv.payment_status.as_deref() == Some("SUCCEEDED") {
+            entry.1 += amount;
+        } else {
+            entry.2 += amount;
+        }
+    }
+    totals
+}
+
+pub fn render(f: &mut Frame, area: Rect, analytics: &Analytics) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(10), Constraint::Length(TOP_CUSTOMERS as u16 + 4)])
+        .split(area);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(layout[1]);
+
+    render_series(f, layout[0], analytics);
+    render_top_customers(f, bottom[0], analytics);
+    render_revenue(f, bottom[1], analytics);
+}
+
+fn panel(title: String) -> Block<'static> {
+    Block::default()
+        .title(Span::styled(title, Style::default().fg(Theme::ACCENT).add_modifier(Modifier::BOLD)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::BORDER))
+        .padding(Padding::new(1, 1, 0, 0))
+}
+
+fn render_series(f: &mut Frame, area: Rect, analytics: &Analytics) {
+    let (window, granularity) = analytics.range();
+    let title = format!(" {} · last {} · total {} ", analytics.meter_name, window, chart::format_value(analytics.total));
+    if analytics.meter_id.is_none() || analytics.series.is_empty() {
+        let text = match &analytics.error {
+            Some(err) => Span::styled(format!("✗ {}", err), Style::default().fg(Theme::ERROR)),
+            None if analytics.meter_id.is_none() => Span::styled("Select a meter to chart its usage", Style::default().fg(Theme::TEXT_MUTED)),
+            None => Span::styled(format!("{} Loading...", Theme::spinner()), Style::default().fg(Theme::WARNING)),
+        };
+        f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(panel(title)), area);
+        return;
+    }
+
+    let (first, last) = (analytics.series[0].0, analytics.series[analytics.series.len() - 1].0);
+    let peak = analytics.series.iter().fold(0.0_f64, |max, (_, v)| max.max(*v));
+    let y_max = if peak > 0.0 { peak * 1.1 } else { 1.0 };
+    let label = |secs: f64| {
+        let at = DateTime::from_timestamp(secs as i64, 0).unwrap_or_default();
+        Span::styled(granularity.label(at), Style::default().fg(Theme::TEXT_MUTED))
+    };
+    let value_label = |v: f64| Span::styled(chart::format_value((v * 100.0).round() / 100.0), Style::default().fg(Theme::TEXT_MUTED));
+
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Theme::ACCENT))
+        .data(&analytics.series);
+    let widget = Chart::new(vec![dataset])
+        .block(panel(title))
+        .x_axis(Axis::default()
+            .style(Style::default().fg(Theme::BORDER))
+            .bounds([first, last.max(first + 1.0)])
+            .labels(vec![label(first), label((first + last) / 2.0), label(last)]))
+        .y_axis(Axis::default()
+            .style(Style::default().fg(Theme::BORDER))
+            .bounds([0.0, y_max])
+            .labels(vec![value_label(0.0), value_label(y_max / 2.0), value_label(y_max)]));
+    f.render_widget(widget, area);
+}
+
+fn render_top_customers(f: &mut Frame, area: Rect, analytics: &Analytics) {
+    let block = panel(" Top customers ".to_string());
+    if analytics.top_customers.is_empty() {
+        let text = Paragraph::new(Span::styled("No usage from customers in this window", Style::default().fg(Theme::TEXT_MUTED)))
+            .block(block);
+        f.render_widget(text, area);
+        return;
+    }
+    let label_width = analytics.top_customers.iter().map(|(id, _)| id.chars().count()).max().unwrap_or(0).min(18);
+    let bars: Vec<Bar> = analytics
+        .top_customers
+        .iter()
+        .map(|(id, value)| {
+            let label: String = format!("{:<width$}", id.chars().take(label_width).collect::<String>(), width = label_width);
+            Bar::default()
+                .value(value.round() as u64)
+                .text_value(chart::format_value(*value))
+                .label(Line::from(label))
+                .style(Style::default().fg(Theme::PRIMARY))
+                .value_style(Style::default().fg(Theme::TEXT).bg(Theme::PRIMARY))
+        })
+        .collect();
+    let widget = BarChart::default()
+        .block(block)
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .label_style(Style::default().fg(Theme::TEXT_DIM))
+        .data(BarGroup::default().bars(&bars));
+    f.render_widget(widget, area);
+}
+
+fn render_revenue(f: &mut Frame, area: Rect, analytics: &Analytics) {
+    let (window, _) = analytics.range();
+    let dim = Style::default().fg(Theme::TEXT_DIM);
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{} invoices in the last {}", analytics.invoice_count, window),
+        Style::default().fg(Theme::TEXT_MUTED),
+    ))];
+    if analytics.revenue.is_empty() {
+        lines.push(Line::from(Span::styled("Nothing invoiced yet", dim)));
+    }
+    for (currency, (invoiced, paid, outstanding)) in &analytics.revenue {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<12}", "Invoiced"), dim),
+            Span::styled(format!("{:.2} {}", invoiced, currency), Style::default().fg(Theme::TEXT).add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<12}", "Paid"), dim),
+            Span::styled(format!("{:.2}", paid), Style::default().fg(Theme::ACCENT)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<12}", "Outstanding"), dim),
+            Span::styled(format!("{:.2}", outstanding), Style::default().fg(Theme::WARNING)),
+        ]));
+    }
+    let widget = Paragraph::new(lines).wrap(Wrap { trim: true }).block(panel(" Revenue ".to_string()));
+    f.render_widget(widget, area);
+}
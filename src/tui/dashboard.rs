@@ -1,4 +1,6 @@
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::time::{Duration, Instant};
 use anyhow::Result;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
@@ -8,70 +10,265 @@ use crossterm::{
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem, ListState, Padding, Paragraph, Wrap, Sparkline},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, Padding, Paragraph, Row, Table, TableState, Wrap},
     Frame, Terminal,
 };
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tabled::Tabled;
+use tokio::sync::mpsc;
 
 use crate::api::client::ApiClient;
+use crate::api::models::{self, Customer, Feature, Invoice, Meter, Plan, Subscription, Wallet};
+use crate::cli;
+use crate::cli::customers::CustomerRow;
+use crate::cli::events::EventRow;
+use crate::cli::features::FeatureRow;
+use crate::cli::invoices::InvoiceRow;
+use crate::cli::meters::MeterRow;
+use crate::cli::plans::PlanRow;
+use crate::cli::subscriptions::SubscriptionRow;
+use crate::cli::wallets::WalletRow;
 use crate::config::Credentials;
+use crate::utils::editor;
+use super::analytics::{self, Analytics};
+use super::entitlements::{self, CustomerEntitlements};
+use super::lines::{self, InvoiceLines};
+use super::pricing::{self, PlanPricing};
 use super::theme::Theme;
 
-const TABS: &[&str] = &[
-    "Customers",
-    "Plans",
-    "Subscriptions",
-    "Invoices",
-    "Meters",
-    "Wallets",
-    "Features",
-];
+type Tui = Terminal<CrosstermBackend<io::Stdout>>;
+
+/// A dashboard tab and the actions its API supports
+struct Resource {
+    name: &'static str,
+    singular: &'static str,
+    endpoint: &'static str,
+    /// JSON template for `n`, if the resource can be created here
+    template: Option<&'static str>,
+    /// Whether `e` may PUT changes back
+    editable: bool,
+    /// Whether `d` may DELETE it
+    deletable: bool,
+    /// Whether the detail panel charts usage for the selected meter
+    analytics: bool,
+    /// Whether the detail panel visualizes the selected plan's prices
+    pricing: bool,
+    /// Whether the detail panel gauges the selected customer's entitlement usage
+    entitlements: bool,
+    /// Whether the detail panel breaks down the selected invoice's line items
+    lines: bool,
+    /// Whether the list refetches every `--events-interval` while the tab is open
+    live: bool,
+    /// The list's columns, from the row its `list` command prints
+    columns: Columns,
+}
+
+/// A tab's table layout, borrowed from the CLI's `Tabled` row for the resource
+struct Columns {
+    headers: fn() -> Vec<String>,
+    cells: fn(&Value) -> Vec<String>,
+}
+
+impl Columns {
+    /// Columns of `R`, built from items parsed as `M`
+    const fn of<M: DeserializeOwned, R: Tabled + From<M>>() -> Self {
+        Self { headers: headers::<R>, cells: cells::<M, R> }
+    }
+}
+
+fn headers<R: Tabled>() -> Vec<String> {
+    R::headers().into_iter().map(String::from).collect()
+}
 
-const TAB_ENDPOINTS: &[&str] = &[
-    "/v1/customers",
-    "/v1/plans",
-    "/v1/subscriptions",
-    "/v1/invoices",
-    "/v1/meters",
-    "/v1/wallets",
-    "/v1/features",
+/// An item's cells without the terminal colors the CLI adds; an item the
+/// model can't parse leaves them empty
+fn cells<M: DeserializeOwned, R: Tabled + From<M>>(item: &Value) -> Vec<String> {
+    match serde_json::from_value::<M>(item.clone()) {
+        Ok(model) => R::from(model).fields().iter().map(|c| console::strip_ansi_codes(c).into_owned()).collect(),
+        Err(_) => vec![],
+    }
+}
+
+const RESOURCES: &[Resource] = &[
+    Resource { name: "Customers", singular: "customer", endpoint: "/v1/customers", template: Some(cli::customers::CREATE_TEMPLATE), editable: true, deletable: true, analytics: false, pricing: false, entitlements: true, lines: false, live: false, columns: Columns::of::<Customer, CustomerRow>() },
+    Resource { name: "Plans", singular: "plan", endpoint: "/v1/plans", template: Some(cli::plans::CREATE_TEMPLATE), editable: true, deletable: true, analytics: false, pricing: true, entitlements: false, lines: false, live: false, columns: Columns::of::<Plan, PlanRow>() },
+    Resource { name: "Subscriptions", singular: "subscription", endpoint: "/v1/subscriptions", template: Some(cli::subscriptions::CREATE_TEMPLATE), editable: false, deletable: false, analytics: false, pricing: false, entitlements: false, lines: false, live: false, columns: Columns::of::<Subscription, SubscriptionRow>() },
+    Resource { name: "Invoices", singular: "invoice", endpoint: "/v1/invoices", template: None, editable: false, deletable: false, analytics: false, pricing: false, entitlements: false, lines: true, live: false, columns: Columns::of::<Invoice, InvoiceRow>() },
+    Resource { name: "Meters", singular: "meter", endpoint: "/v1/meters", template: Some(cli::meters::CREATE_TEMPLATE), editable: true, deletable: true, analytics: false, pricing: false, entitlements: false, lines: false, live: false, columns: Columns::of::<Meter, MeterRow>() },
+    Resource { name: "Events", singular: "event", endpoint: "/v1/events", template: None, editable: false, deletable: false, analytics: false, pricing: false, entitlements: false, lines: false, live: true, columns: Columns::of::<models::Event, EventRow>() },
+    Resource { name: "Wallets", singular: "wallet", endpoint: "/v1/wallets", template: Some(cli::wallets::CREATE_TEMPLATE), editable: false, deletable: false, analytics: false, pricing: false, entitlements: false, lines: false, live: false, columns: Columns::of::<Wallet, WalletRow>() },
+    Resource { name: "Features", singular: "feature", endpoint: "/v1/features", template: Some(cli::features::CREATE_TEMPLATE), editable: true, deletable: true, analytics: false, pricing: false, entitlements: false, lines: false, live: false, columns: Columns::of::<Feature, FeatureRow>() },
+    Resource { name: "Analytics", singular: "meter", endpoint: "/v1/meters", template: None, editable: false, deletable: false, analytics: true, pricing: false, entitlements: false, lines: false, live: false, columns: Columns::of::<Meter, MeterRow>() },
 ];
 
+/// How long tab lists are served from the cache before they're refreshed in the background
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// The last list response for an endpoint
+struct Cached {
+    /// Response body, or why fetching it failed
+    body: Result<String, String>,
+    fetched_at: Instant,
+}
+
+/// Rows a later page is requested for
+const PAGE_SIZE: usize = 50;
+
+/// How close to the end of a list the selection gets before its next page is fetched
+const PREFETCH_ROWS: usize = 10;
+
+/// Items beyond an endpoint's first page, loaded as the selection nears the
+/// end of its list and kept when the first page is refreshed
+#[derive(Default)]
+struct Pages {
+    items: Vec<Value>,
+    /// Cursor after the last page, for lists paged by key (events)
+    last_key: Option<String>,
+    /// The last page has been loaded
+    done: bool,
+    /// A page fetch is in flight
+    loading: bool,
+    /// The last page fetch failed; not retried until `r`
+    failed: bool,
+}
+
+/// A fetch that finished in a spawned task, for the render loop to apply
+enum Update {
+    List {
+        endpoint: &'static str,
+        started: Instant,
+        /// Requested with `r` or after an edit rather than by the background refresh
+        forced: bool,
+        /// Response body, or why fetching it failed
+        body: Result<String, String>,
+    },
+    /// A later page of an endpoint's list
+    Page {
+        endpoint: &'static str,
+        /// Response body, or why fetching it failed
+        body: Result<String, String>,
+    },
+    Analytics(Box<Analytics>),
+    Pricing(Box<PlanPricing>),
+    Entitlements(Box<CustomerEntitlements>),
+    Lines(Box<InvoiceLines>),
+}
+
+/// Outcome of the last action, shown in the footer until the next key press
+enum Message {
+    Info(String),
+    Error(String),
+}
+
 pub struct App {
     client: ApiClient,
     creds: Credentials,
     active_tab: usize,
-    list_state: ListState,
-    data_items: Vec<String>,
+    list_state: TableState,
+    /// Each listed resource's cells, or one placeholder cell for a response without items
+    data_items: Vec<Vec<String>>,
+    /// The listed resources as returned by the API, parallel to `data_items`
+    items: Vec<Value>,
     detail_text: String,
+    /// Rows the Detail panel is scrolled down by
+    detail_scroll: u16,
+    /// Rows of text the Detail panel fit in the last frame, for paging
+    detail_height: u16,
+    /// Whether the Detail panel shows flattened `key: value` lines instead of pretty JSON
+    flat_detail: bool,
+    /// System clipboard, opened on the first copy and kept for the session,
+    /// since on Linux copied text only lasts as long as its owner
+    clipboard: Option<arboard::Clipboard>,
     loading: bool,
     error: Option<String>,
     should_quit: bool,
-    sparkline_data: Vec<u64>,
+    /// Charts for the Analytics tab
+    analytics: Analytics,
+    /// Pricing card for the Plans tab
+    pricing: PlanPricing,
+    /// Entitlement gauges for the Customers tab
+    entitlements: CustomerEntitlements,
+    /// Line item breakdown for the Invoices tab
+    lines: InvoiceLines,
+    message: Option<Message>,
+    /// How often the Events tab refetches while it's open
+    events_interval: Duration,
+    /// Whether `p` has paused the Events tab's refresh
+    events_paused: bool,
+    /// ID awaiting `y` to confirm deletion
+    confirm_delete: Option<String>,
+    /// Text typed after `/`, narrowing the active tab
+    filter: String,
+    /// Whether keys are going to the filter input
+    filtering: bool,
+    /// Indices into `data_items` that match `filter`, in display order
+    visible: Vec<usize>,
+    /// List responses by endpoint, so switching tabs doesn't wait on the API
+    cache: HashMap<&'static str, Cached>,
+    /// Pages loaded by scrolling, by endpoint
+    pages: HashMap<&'static str, Pages>,
+    /// Size of the active tab's whole list, when the API reports it
+    total: Option<u64>,
+    /// When the last background refresh started
+    refreshed_at: Option<Instant>,
+    /// Endpoints with a fetch in flight
+    fetching: HashSet<&'static str>,
+    /// Row to highlight once the active tab's forced reload arrives, e.g. a just-saved resource
+    select_on_load: Option<String>,
+    sender: mpsc::UnboundedSender<Update>,
+    /// Results of spawned fetches, drained before every draw
+    updates: mpsc::UnboundedReceiver<Update>,
 }
 
 impl App {
-    pub fn new(creds: Credentials) -> Result<Self> {
-        let client = ApiClient::new(creds.clone())?;
+    pub fn new(creds: Credentials, events_interval: Duration) -> Result<Self> {
+        let client = ApiClient::new(creds.clone())?.without_login_prompt().without_cache();
+        let (sender, updates) = mpsc::unbounded_channel();
         let mut s = Self {
             client,
             creds,
             active_tab: 0,
-            list_state: ListState::default(),
+            list_state: TableState::default(),
             data_items: vec![],
+            items: vec![],
             detail_text: String::new(),
+            detail_scroll: 0,
+            detail_height: 0,
+            flat_detail: false,
+            clipboard: None,
             loading: false,
             error: None,
             should_quit: false,
-            sparkline_data: vec![3, 7, 2, 9, 5, 12, 8, 4, 11, 6, 14, 3, 8, 10, 5],
+            analytics: Analytics::default(),
+            pricing: PlanPricing::default(),
+            entitlements: CustomerEntitlements::default(),
+            lines: InvoiceLines::default(),
+            message: None,
+            events_interval,
+            events_paused: false,
+            confirm_delete: None,
+            filter: String::new(),
+            filtering: false,
+            visible: vec![],
+            cache: HashMap::new(),
+            pages: HashMap::new(),
+            total: None,
+            refreshed_at: None,
+            fetching: HashSet::new(),
+            select_on_load: None,
+            sender,
+            updates,
         };
         s.list_state.select(Some(0));
         Ok(s)
     }
 
     fn next_tab(&mut self) {
-        self.active_tab = (self.active_tab + 1) % TABS.len();
+        self.active_tab = (self.active_tab + 1) % RESOURCES.len();
+        self.filter.clear();
         self.data_items.clear();
         self.detail_text.clear();
         self.error = None;
@@ -79,7 +276,8 @@ impl App {
     }
 
     fn prev_tab(&mut self) {
-        self.active_tab = if self.active_tab == 0 { TABS.len() - 1 } else { self.active_tab - 1 };
+        self.active_tab = if self.active_tab == 0 { RESOURCES.len() - 1 } else { self.active_tab - 1 };
+        self.filter.clear();
         self.data_items.clear();
         self.detail_text.clear();
         self.error = None;
@@ -87,45 +285,181 @@ impl App {
     }
 
     fn next_item(&mut self) {
-        if self.data_items.is_empty() { return; }
+        if self.visible.is_empty() { return; }
         let i = self.list_state.selected().unwrap_or(0);
-        self.list_state.select(Some((i + 1) % self.data_items.len()));
+        self.list_state.select(Some((i + 1) % self.visible.len()));
     }
 
     fn prev_item(&mut self) {
-        if self.data_items.is_empty() { return; }
+        if self.visible.is_empty() { return; }
         let i = self.list_state.selected().unwrap_or(0);
-        self.list_state.select(Some(if i == 0 { self.data_items.len() - 1 } else { i - 1 }));
+        self.list_state.select(Some(if i == 0 { self.visible.len() - 1 } else { i - 1 }));
+    }
+
+    fn resource(&self) -> &'static Resource {
+        &RESOURCES[self.active_tab]
+    }
+
+    /// Position in `data_items`/`items` of the highlighted row
+    fn selected_index(&self) -> Option<usize> {
+        self.visible.get(self.list_state.selected()?).copied()
+    }
+
+    /// The highlighted item as returned by the API, if any
+    fn selected_item(&self) -> Option<&Value> {
+        self.items.get(self.selected_index()?)
+    }
+
+    /// ID of the highlighted item, if any
+    fn selected_id(&self) -> Option<String> {
+        self.selected_item()?.get("id").and_then(|v| v.as_str()).map(String::from)
+    }
+
+    /// Recompute `visible` from `filter`, keeping the selection on the same
+    /// item when it still matches
+    fn apply_filter(&mut self) {
+        let selected = self.selected_index();
+        self.visible = (0..self.data_items.len())
+            .filter(|&i| matches_filter(&self.data_items[i].join("  "), self.items.get(i), &self.filter))
+            .collect();
+        let pos = selected.and_then(|idx| self.visible.iter().position(|&i| i == idx));
+        self.list_state.select(Some(pos.unwrap_or(0)));
+    }
+}
+
+/// Fields a bare filter is matched against, besides the row label
+const FILTER_FIELDS: &[&str] = &["id", "name", "email", "external_id"];
+
+/// Status lives under a different key for each resource
+const STATUS_FIELDS: &[&str] = &["status", "subscription_status", "invoice_status", "wallet_status"];
+
+/// Case-insensitive filter match. `field:text` matches one field of the item
+/// (`status` covers every resource's status key); plain text matches the row
+/// label or any of `FILTER_FIELDS` and the status.
+fn matches_filter(label: &str, item: Option<&Value>, filter: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+    if filter.is_empty() {
+        return true;
+    }
+    let field_contains = |key: &str, needle: &str| {
+        item.and_then(|item| item.get(key)).is_some_and(|v| {
+            let text = v.as_str().map(String::from).unwrap_or_else(|| v.to_string());
+            text.to_lowercase().contains(needle)
+        })
+    };
+    if let Some((key, needle)) = filter.split_once(':') {
+        if !key.is_empty() {
+            let keys: &[&str] = if key == "status" { STATUS_FIELDS } else { &[key] };
+            return keys.iter().any(|k| field_contains(k, needle));
+        }
     }
+    label.to_lowercase().contains(&filter)
+        || FILTER_FIELDS.iter().chain(STATUS_FIELDS).any(|k| field_contains(k, &filter))
 }
 
-pub async fn run(creds: Credentials) -> Result<()> {
+/// Split a cell into spans, highlighting the first occurrence of the filter
+/// text (or the value part of `field:text`)
+fn highlight(label: &str, filter: &str, plain: Style) -> Vec<Span<'static>> {
+    let needle = filter.trim().split_once(':').map_or(filter.trim(), |(_, v)| v).to_lowercase();
+    let lower = label.to_lowercase();
+    // Byte offsets only line up when lowercasing kept the length
+    let found = (!needle.is_empty() && lower.len() == label.len())
+        .then(|| lower.find(&needle))
+        .flatten();
+    let Some(start) = found else {
+        return vec![Span::styled(label.to_string(), plain)];
+    };
+    let end = start + needle.len();
+    vec![
+        Span::styled(label[..start].to_string(), plain),
+        Span::styled(label[start..end].to_string(), Style::default().fg(Theme::WARNING).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+        Span::styled(label[end..].to_string(), plain),
+    ]
+}
+
+pub async fn run(creds: Credentials, events_interval: Duration) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(creds)?;
+    let mut app = App::new(creds, events_interval)?;
 
-    // Initial data load
-    load_data(&mut app).await;
+    // Prefetch every tab; the active one shows as loading until its list arrives
+    start_refresh(&mut app);
+    show_tab(&mut app);
 
     loop {
+        while let Ok(update) = app.updates.try_recv() {
+            apply_update(&mut app, update);
+        }
+        if app.fetching.is_empty() && app.refreshed_at.is_none_or(|at| at.elapsed() >= CACHE_TTL) {
+            start_refresh(&mut app);
+        }
+        refresh_live(&mut app);
+        load_more(&mut app);
+        if app.client.session_expired() {
+            // Log in again on the normal screen, leaving the dashboard if that fails
+            suspend(&mut terminal)?;
+            app.client.renew_session().await?;
+            resume(&mut terminal)?;
+            app.analytics.loaded_at = None;
+            app.pricing.requested = false;
+            app.entitlements.requested = false;
+            app.lines.requested = false;
+            load_data(&mut app);
+            start_refresh(&mut app);
+            app.message = Some(Message::Info("Logged in again".to_string()));
+        }
+        refresh_analytics(&mut app);
+        refresh_pricing(&mut app);
+        refresh_entitlements(&mut app);
+        refresh_lines(&mut app);
         terminal.draw(|f| ui(f, &mut app))?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
                 if key.kind != KeyEventKind::Press { continue; }
+                app.message = None;
+                if let Some(id) = app.confirm_delete.take() {
+                    if key.code == KeyCode::Char('y') {
+                        delete_resource(&mut app, &id).await;
+                    }
+                    continue;
+                }
+                if app.filtering {
+                    match key.code {
+                        KeyCode::Esc => {
+                            app.filtering = false;
+                            app.filter.clear();
+                        }
+                        KeyCode::Enter => app.filtering = false,
+                        KeyCode::Backspace => { app.filter.pop(); }
+                        KeyCode::Char(c) => app.filter.push(c),
+                        KeyCode::Down => app.next_item(),
+                        KeyCode::Up => app.prev_item(),
+                        _ => {}
+                    }
+                    app.apply_filter();
+                    update_detail(&mut app);
+                    continue;
+                }
                 match key.code {
+                    KeyCode::Esc if !app.filter.is_empty() => {
+                        app.filter.clear();
+                        app.apply_filter();
+                        update_detail(&mut app);
+                    }
+                    KeyCode::Char('/') => app.filtering = true,
                     KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
                     KeyCode::Tab | KeyCode::Char('l') => {
                         app.next_tab();
-                        load_data(&mut app).await;
+                        show_tab(&mut app);
                     }
                     KeyCode::BackTab | KeyCode::Char('h') => {
                         app.prev_tab();
-                        load_data(&mut app).await;
+                        show_tab(&mut app);
                     }
                     KeyCode::Down | KeyCode::Char('j') => {
                         app.next_item();
@@ -136,7 +470,52 @@ pub async fn run(creds: Credentials) -> Result<()> {
                         update_detail(&mut app);
                     }
                     KeyCode::Char('r') => {
-                        load_data(&mut app).await;
+                        app.analytics.loaded_at = None;
+                        app.pricing.requested = false;
+                        app.entitlements.requested = false;
+                        app.lines.requested = false;
+                        load_data(&mut app);
+                    }
+                    KeyCode::Char('w') if app.resource().analytics => app.analytics.next_window(),
+                    KeyCode::Char('p') if app.resource().live => {
+                        app.events_paused = !app.events_paused;
+                        let state = if app.events_paused { "paused" } else { "resumed" };
+                        app.message = Some(Message::Info(format!("Live refresh {}", state)));
+                    }
+                    KeyCode::PageDown => app.detail_scroll = app.detail_scroll.saturating_add(app.detail_height.max(1)),
+                    KeyCode::PageUp => app.detail_scroll = app.detail_scroll.saturating_sub(app.detail_height.max(1)),
+                    KeyCode::Char('v') => {
+                        app.flat_detail = !app.flat_detail;
+                        update_detail(&mut app);
+                    }
+                    KeyCode::Char('y') => {
+                        if let Some(json) = app.selected_item().and_then(|item| serde_json::to_string_pretty(item).ok()) {
+                            copy(&mut app, json, "JSON");
+                        }
+                    }
+                    KeyCode::Char('c') => {
+                        if let Some(id) = app.selected_id() {
+                            copy(&mut app, id.clone(), &id);
+                        }
+                    }
+                    KeyCode::Char('n') => {
+                        if let Err(e) = edit_resource(&mut terminal, &mut app, None).await {
+                            app.message = Some(Message::Error(format!("{:#}", e)));
+                        }
+                    }
+                    KeyCode::Char('e') => {
+                        if let Some(id) = app.selected_id() {
+                            if let Err(e) = edit_resource(&mut terminal, &mut app, Some(id)).await {
+                                app.message = Some(Message::Error(format!("{:#}", e)));
+                            }
+                        }
+                    }
+                    KeyCode::Char('d') => {
+                        if !app.resource().deletable {
+                            app.message = Some(Message::Error(format!("{} can't be deleted from the dashboard", app.resource().name)));
+                        } else {
+                            app.confirm_delete = app.selected_id();
+                        }
                     }
                     _ => {}
                 }
@@ -153,64 +532,458 @@ pub async fn run(creds: Credentials) -> Result<()> {
     Ok(())
 }
 
-async fn load_data(app: &mut App) {
+/// Refetch a live tab's list in the background once the cached one is older
+/// than `events_interval`, unless `p` paused it
+fn refresh_live(app: &mut App) {
+    let res = app.resource();
+    if !res.live || app.events_paused || app.fetching.contains(res.endpoint) {
+        return;
+    }
+    if app.cache.get(res.endpoint).is_some_and(|c| c.fetched_at.elapsed() >= app.events_interval) {
+        fetch(app, res.endpoint, false);
+    }
+}
+
+/// Reload the Analytics charts in a spawned task when the highlighted meter
+/// changes or the current ones are older than `analytics::REFRESH_INTERVAL`
+fn refresh_analytics(app: &mut App) {
+    if !app.resource().analytics || app.loading || app.error.is_some() {
+        return;
+    }
+    let meter = app.selected_index().and_then(|idx| app.items.get(idx)).cloned();
+    let id = meter.as_ref().and_then(|m| m.get("id")).and_then(|v| v.as_str());
+    if !app.analytics.is_due(id) {
+        return;
+    }
+    app.analytics.select(meter.as_ref());
+    let Some(meter) = meter else { return };
+    // Not due again until this load lands or the interval passes
+    app.analytics.loaded_at = Some(Instant::now());
+    let (client, sender, mut analytics) = (app.client.clone(), app.sender.clone(), app.analytics.clone());
+    tokio::spawn(async move {
+        analytics::load(&client, &mut analytics, &meter).await;
+        let _ = sender.send(Update::Analytics(Box::new(analytics)));
+    });
+}
+
+/// Load the Plans tab's pricing card in a spawned task when the highlighted
+/// plan changes or `r` asks for it again
+fn refresh_pricing(app: &mut App) {
+    if !app.resource().pricing || app.loading || app.error.is_some() {
+        return;
+    }
+    let plan = app.selected_index().and_then(|idx| app.items.get(idx)).cloned();
+    let id = plan.as_ref().and_then(|p| p.get("id")).and_then(|v| v.as_str());
+    if !app.pricing.is_due(id) {
+        return;
+    }
+    let names = cached_names(app, &["/v1/meters", "/v1/features"]);
+    app.pricing.select(plan.as_ref(), names);
+    app.pricing.requested = true;
+    if plan.is_none() {
+        return;
+    }
+    let (client, sender, mut pricing) = (app.client.clone(), app.sender.clone(), app.pricing.clone());
+    tokio::spawn(async move {
+        pricing::load(&client, &mut pricing).await;
+        let _ = sender.send(Update::Pricing(Box::new(pricing)));
+    });
+}
+
+/// Load the Customers tab's entitlement gauges in a spawned task when the
+/// highlighted customer changes or `r` asks for them again
+fn refresh_entitlements(app: &mut App) {
+    if !app.resource().entitlements || app.loading || app.error.is_some() {
+        return;
+    }
+    let id = app.selected_id();
+    if !app.entitlements.is_due(id.as_deref()) {
+        return;
+    }
+    app.entitlements.select(id.clone());
+    app.entitlements.requested = true;
+    if id.is_none() {
+        return;
+    }
+    let (client, sender, mut entitlements) = (app.client.clone(), app.sender.clone(), app.entitlements.clone());
+    tokio::spawn(async move {
+        entitlements::load(&client, &mut entitlements).await;
+        let _ = sender.send(Update::Entitlements(Box::new(entitlements)));
+    });
+}
+
+/// Load the Invoices tab's line item breakdown in a spawned task when the
+/// highlighted invoice changes or `r` asks for it again
+fn refresh_lines(app: &mut App) {
+    if !app.resource().lines || app.loading || app.error.is_some() {
+        return;
+    }
+    let id = app.selected_id();
+    if !app.lines.is_due(id.as_deref()) {
+        return;
+    }
+    app.lines.select(id.clone());
+    app.lines.requested = true;
+    if id.is_none() {
+        return;
+    }
+    let (client, sender, mut lines) = (app.client.clone(), app.sender.clone(), app.lines.clone());
+    tokio::spawn(async move {
+        lines::load(&client, &mut lines).await;
+        let _ = sender.send(Update::Lines(Box::new(lines)));
+    });
+}
+
+/// Names by ID from the cached lists of `endpoints`
+fn cached_names(app: &App, endpoints: &[&str]) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    for endpoint in endpoints {
+        let Some(Ok(body)) = app.cache.get(endpoint).map(|c| &c.body) else { continue };
+        let Ok(json) = serde_json::from_str::<Value>(body) else { continue };
+        for item in json.get("items").and_then(|v| v.as_array()).into_iter().flatten() {
+            let id = item.get("id").and_then(|v| v.as_str());
+            let name = item.get("name").and_then(|v| v.as_str());
+            if let (Some(id), Some(name)) = (id, name) {
+                names.insert(id.to_string(), name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Leave the TUI so an external program can use the terminal
+fn suspend(terminal: &mut Tui) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    Ok(())
+}
+
+fn resume(terminal: &mut Tui) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+    Ok(())
+}
+
+/// Create a resource (`id` is `None`) or edit an existing one in `$EDITOR`,
+/// then send it to the API and refresh the list
+async fn edit_resource(terminal: &mut Tui, app: &mut App, id: Option<String>) -> Result<()> {
+    let res = app.resource();
+    let initial = match &id {
+        None => {
+            let Some(template) = res.template else {
+                anyhow::bail!("{} can't be created from the dashboard", res.name);
+            };
+            serde_json::from_str(template)?
+        }
+        Some(id) => {
+            if !res.editable {
+                anyhow::bail!("{} can't be edited from the dashboard", res.name);
+            }
+            let current: Value = app.client.get(&format!("{}/{}", res.endpoint, id)).await?;
+            editor::editable(current)
+        }
+    };
+
+    suspend(terminal)?;
+    let edited = editor::edit::<Value>(&initial);
+    resume(terminal)?;
+    let Some(body) = edited? else {
+        app.message = Some(Message::Info("Edit cancelled, no changes made".to_string()));
+        return Ok(());
+    };
+
+    let saved: Value = match &id {
+        None => app.client.post(res.endpoint, &body).await?,
+        Some(id) => app.client.put(&format!("{}/{}", res.endpoint, id), &body).await?,
+    };
+    let saved_id = saved.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let verb = if id.is_some() { "updated" } else { "created" };
+    app.select_on_load = Some(saved_id.clone());
+    load_data(app);
+    app.message = Some(Message::Info(format!("{} {} {}", capitalize(res.singular), saved_id, verb)));
+    Ok(())
+}
+
+async fn delete_resource(app: &mut App, id: &str) {
+    let res = app.resource();
+    match app.client.delete_empty(&format!("{}/{}", res.endpoint, id)).await {
+        Ok(()) => {
+            load_data(app);
+            app.message = Some(Message::Info(format!("{} {} deleted", capitalize(res.singular), id)));
+        }
+        Err(e) => app.message = Some(Message::Error(format!("{:#}", e))),
+    }
+}
+
+/// Put `text` on the system clipboard, reporting `what` was copied
+fn copy(app: &mut App, text: String, what: &str) {
+    let clipboard = match app.clipboard.take() {
+        Some(clipboard) => Ok(clipboard),
+        None => arboard::Clipboard::new(),
+    };
+    let result = clipboard.and_then(|mut clipboard| {
+        let result = clipboard.set_text(text);
+        app.clipboard = Some(clipboard);
+        result
+    });
+    app.message = Some(match result {
+        Ok(()) => Message::Info(format!("Copied {}", what)),
+        Err(e) => Message::Error(format!("Can't copy to the clipboard: {}", e)),
+    });
+}
+
+fn select_id(app: &mut App, id: &str) {
+    let pos = app.visible.iter().position(|&i| {
+        app.items.get(i).and_then(|item| item.get("id")).and_then(|v| v.as_str()) == Some(id)
+    });
+    if let Some(pos) = pos {
+        app.list_state.select(Some(pos));
+        update_detail(app);
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// Fetch every tab's list concurrently in the background
+fn start_refresh(app: &mut App) {
+    app.refreshed_at = Some(Instant::now());
+    let mut endpoints: Vec<&'static str> = RESOURCES.iter().map(|r| r.endpoint).collect();
+    endpoints.sort_unstable();
+    endpoints.dedup();
+    for endpoint in endpoints {
+        fetch(app, endpoint, false);
+    }
+}
+
+/// Fetch an endpoint's list in a spawned task, which reports back through
+/// `app.updates`. Unforced fetches are skipped while one is in flight.
+fn fetch(app: &mut App, endpoint: &'static str, forced: bool) {
+    if !app.fetching.insert(endpoint) && !forced {
+        return;
+    }
+    let (client, sender) = (app.client.clone(), app.sender.clone());
+    let started = Instant::now();
+    tokio::spawn(async move {
+        let body = client.get_text(endpoint).await.map_err(|e| e.to_string());
+        let _ = sender.send(Update::List { endpoint, started, forced, body });
+    });
+}
+
+/// Fetch the active tab's next page in a spawned task once the selection is
+/// within `PREFETCH_ROWS` of the end of what's loaded
+fn load_more(app: &mut App) {
+    let endpoint = app.resource().endpoint;
+    if app.loading || app.error.is_some() || app.items.is_empty() {
+        return;
+    }
+    let near_end = app.selected_index().is_some_and(|i| i + PREFETCH_ROWS >= app.items.len());
+    let complete = app.total.is_some_and(|total| app.items.len() as u64 >= total);
+    let pages = app.pages.entry(endpoint).or_default();
+    if !near_end || complete || pages.done || pages.loading || pages.failed {
+        return;
+    }
+    let path = match &pages.last_key {
+        Some(key) => format!("{}?page_size={}&iter_last_key={}", endpoint, PAGE_SIZE, key),
+        None => format!("{}?limit={}&offset={}", endpoint, PAGE_SIZE, app.items.len()),
+    };
+    pages.loading = true;
+    let (client, sender) = (app.client.clone(), app.sender.clone());
+    tokio::spawn(async move {
+        let body = client.get_text(&path).await.map_err(|e| e.to_string());
+        let _ = sender.send(Update::Page { endpoint, body });
+    });
+}
+
+/// Refetch the active tab's list, showing the loading state until it arrives
+fn load_data(app: &mut App) {
     app.loading = true;
     app.error = None;
+    let endpoint = app.resource().endpoint;
+    if let Some(pages) = app.pages.get_mut(endpoint) {
+        pages.failed = false;
+    }
+    fetch(app, endpoint, true);
+}
+
+/// Apply a finished fetch: cache lists and redraw the active tab keeping the
+/// same row highlighted, and keep charts and cards still meant for the
+/// highlighted meter, plan, or customer
+fn apply_update(app: &mut App, update: Update) {
+    match update {
+        Update::List { endpoint, started, forced, body } => {
+            app.fetching.remove(endpoint);
+            // Lists fetched since this one started are newer, and a failed
+            // background refresh shouldn't hide data that loaded before
+            let stale = app.cache.get(endpoint).is_some_and(|cached| {
+                cached.fetched_at > started || (!forced && body.is_err() && cached.body.is_ok())
+            });
+            if !stale {
+                // Until later pages are loaded, the first page's cursor is where they start
+                let pages = app.pages.entry(endpoint).or_default();
+                if pages.items.is_empty() {
+                    if let Some(Ok(json)) = body.as_ref().ok().map(|b| serde_json::from_str::<Value>(b)) {
+                        pages.last_key = json.get("iter_last_key").and_then(|v| v.as_str()).map(String::from);
+                        pages.done = json.get("has_more").and_then(|v| v.as_bool()) == Some(false);
+                    }
+                }
+                app.cache.insert(endpoint, Cached { body, fetched_at: started });
+            }
+            if endpoint != app.resource().endpoint {
+                return;
+            }
+            let pending = if forced { app.select_on_load.take() } else { None };
+            let selected = pending.or_else(|| app.selected_id());
+            show_tab(app);
+            if let Some(id) = selected {
+                select_id(app, &id);
+            }
+        }
+        Update::Page { endpoint, body } => {
+            let pages = app.pages.entry(endpoint).or_default();
+            pages.loading = false;
+            let json = body.and_then(|b| serde_json::from_str::<Value>(&b).map_err(|e| e.to_string()));
+            let json = match json {
+                Ok(json) => json,
+                Err(e) => {
+                    pages.failed = true;
+                    app.message = Some(Message::Error(format!("Loading more failed: {}", e)));
+                    return;
+                }
+            };
+            let items = list_items(&json).cloned().unwrap_or_default();
+            pages.last_key = json.get("iter_last_key").and_then(|v| v.as_str()).map(String::from);
+            pages.done = items.is_empty() || json.get("has_more").and_then(|v| v.as_bool()) == Some(false);
+            pages.items.extend(items);
+            if endpoint == app.resource().endpoint {
+                let selected = app.selected_id();
+                show_tab(app);
+                if let Some(id) = selected {
+                    select_id(app, &id);
+                }
+            }
+        }
+        Update::Analytics(analytics) => {
+            if analytics.is_for(&app.analytics) {
+                app.analytics = *analytics;
+            }
+        }
+        Update::Pricing(pricing) => {
+            if pricing.is_for(&app.pricing) {
+                app.pricing = *pricing;
+            }
+        }
+        Update::Entitlements(entitlements) => {
+            if entitlements.is_for(&app.entitlements) {
+                app.entitlements = *entitlements;
+            }
+        }
+        Update::Lines(lines) => {
+            if lines.is_for(&app.lines) {
+                app.lines = *lines;
+            }
+        }
+    }
+}
+
+/// Show the active tab's list from the cache, or a loading state until the
+/// background refresh delivers it
+fn show_tab(app: &mut App) {
+    let Some(body) = app.cache.get(app.resource().endpoint).map(|c| c.body.clone()) else {
+        app.loading = true;
+        return;
+    };
+    app.loading = false;
+    app.error = None;
+    app.total = None;
 
-    let endpoint = TAB_ENDPOINTS[app.active_tab];
-    match app.client.get_text(endpoint).await {
+    match body {
         Ok(body) => {
             // Parse as JSON, extract items
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&body) {
-                if let Some(items) = json.get("items").and_then(|v| v.as_array()) {
-                    app.data_items = items.iter().map(|item| {
-                        let id = item.get("id").and_then(|v| v.as_str()).unwrap_or("?");
-                        let name = item.get("name")
-                            .or_else(|| item.get("email"))
-                            .or_else(|| item.get("event_name"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("-");
-                        let status = item.get("status")
-                            .or_else(|| item.get("subscription_status"))
-                            .or_else(|| item.get("invoice_status"))
-                            .or_else(|| item.get("wallet_status"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("");
-                        if status.is_empty() {
-                            format!("{}  {}", id, name)
-                        } else {
-                            format!("{}  {}  [{}]", id, name, status)
-                        }
-                    }).collect();
-                    app.detail_text = serde_json::to_string_pretty(&json).unwrap_or_default();
+                if let Some(first) = list_items(&json) {
+                    let items = with_pages(first, app.pages.get(app.resource().endpoint));
+                    app.total = json.get("total_count")
+                        .or_else(|| json.pointer("/pagination/total"))
+                        .and_then(|v| v.as_u64());
+                    app.data_items = items.iter().map(app.resource().columns.cells).collect();
+                    app.items = items;
                 } else {
-                    app.data_items = vec!["(no items)".to_string()];
+                    app.items.clear();
+                    app.data_items = vec![vec!["(no items)".to_string()]];
                     app.detail_text = serde_json::to_string_pretty(&json).unwrap_or(body);
                 }
             } else {
-                app.data_items = vec!["(raw response)".to_string()];
+                app.items.clear();
+                app.data_items = vec![vec!["(raw response)".to_string()]];
                 app.detail_text = body;
             }
         }
         Err(e) => {
-            app.error = Some(format!("{}", e));
+            app.error = Some(e);
+            app.items.clear();
             app.data_items.clear();
             app.detail_text.clear();
         }
     }
-    app.loading = false;
-    app.list_state.select(Some(0));
+    app.visible.clear();
+    app.apply_filter();
     update_detail(app);
 }
 
+/// A list response's items; events come back under `events` rather than `items`
+fn list_items(json: &Value) -> Option<&Vec<Value>> {
+    json.get("items").or_else(|| json.get("events")).and_then(|v| v.as_array())
+}
+
+/// The first page followed by the pages loaded after it, skipping items the
+/// first page now holds (e.g. ones shifted down by newly created resources)
+fn with_pages(first: &[Value], pages: Option<&Pages>) -> Vec<Value> {
+    let id = |item: &Value| item.get("id").and_then(|v| v.as_str()).map(String::from);
+    let ids: HashSet<String> = first.iter().filter_map(id).collect();
+    let later = pages.into_iter().flat_map(|p| &p.items);
+    first.iter().cloned().chain(later.filter(|item| id(item).is_none_or(|i| !ids.contains(&i))).cloned()).collect()
+}
+
+/// Show the highlighted item in the Detail panel, back at the top unless
+/// it's unchanged (e.g. after a background refresh)
 fn update_detail(app: &mut App) {
-    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&app.detail_text) {
-        if let Some(items) = json.get("items").and_then(|v| v.as_array()) {
-            let idx = app.list_state.selected().unwrap_or(0);
-            if let Some(item) = items.get(idx) {
-                app.detail_text = serde_json::to_string_pretty(item).unwrap_or_default();
+    let text = match app.selected_item() {
+        Some(item) if app.flat_detail => {
+            let mut lines = vec![];
+            flatten(item, "", &mut lines);
+            lines.join("\n")
+        }
+        Some(item) => serde_json::to_string_pretty(item).unwrap_or_default(),
+        None if !app.items.is_empty() => String::new(),
+        None => return,
+    };
+    if text != app.detail_text {
+        app.detail_text = text;
+        app.detail_scroll = 0;
+    }
+}
+
+/// `key: value` lines for every scalar in `value`, with nested keys joined by
+/// dots and array positions in brackets, e.g. `metadata.region: eu` or `tiers[0].up_to: 1000`
+fn flatten(value: &Value, path: &str, lines: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, v) in map {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                flatten(v, &path, lines);
             }
         }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(v, &format!("{}[{}]", path, i), lines);
+            }
+        }
+        Value::String(text) => lines.push(format!("{}: {}", path, text)),
+        other => lines.push(format!("{}: {}", path, other)),
     }
 }
 
@@ -232,6 +1005,43 @@ fn ui(f: &mut Frame, app: &mut App) {
     render_header(f, main_layout[0], app);
     render_body(f, main_layout[1], app);
     render_footer(f, main_layout[2], app);
+
+    if let Some(ref id) = app.confirm_delete {
+        render_confirm(f, size, app.resource(), id);
+    }
+}
+
+fn render_confirm(f: &mut Frame, area: Rect, res: &Resource, id: &str) {
+    let width = (id.len() as u16 + 30).clamp(40, area.width);
+    let popup = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + area.height.saturating_sub(5) / 2,
+        width,
+        height: 5.min(area.height),
+    };
+    let text = vec![
+        Line::from(vec![
+            Span::styled(format!("Delete {} ", res.singular), Style::default().fg(Theme::TEXT)),
+            Span::styled(id, Style::default().fg(Theme::WARNING).add_modifier(Modifier::BOLD)),
+            Span::styled("?", Style::default().fg(Theme::TEXT)),
+        ]),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(Theme::ERROR).add_modifier(Modifier::BOLD)),
+            Span::styled(" delete   ", Style::default().fg(Theme::TEXT_DIM)),
+            Span::styled("any other key", Style::default().fg(Theme::PRIMARY)),
+            Span::styled(" cancel", Style::default().fg(Theme::TEXT_DIM)),
+        ]),
+    ];
+    let dialog = Paragraph::new(text)
+        .style(Style::default().bg(Theme::SURFACE))
+        .block(Block::default()
+            .title(Span::styled(" Confirm ", Style::default().fg(Theme::ERROR).add_modifier(Modifier::BOLD)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Theme::ERROR))
+            .padding(Padding::new(1, 1, 0, 0))
+        );
+    f.render_widget(Clear, popup);
+    f.render_widget(dialog, popup);
 }
 
 fn render_header(f: &mut Frame, area: Rect, app: &App) {
@@ -293,7 +1103,7 @@ fn render_body(f: &mut Frame, area: Rect, app: &mut App) {
         .split(area);
 
     // Sidebar — tabs
-    let tab_items: Vec<ListItem> = TABS.iter().enumerate().map(|(i, name)| {
+    let tab_items: Vec<ListItem> = RESOURCES.iter().enumerate().map(|(i, res)| {
         let style = if i == app.active_tab {
             Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)
         } else {
@@ -302,7 +1112,7 @@ fn render_body(f: &mut Frame, area: Rect, app: &mut App) {
         let prefix = if i == app.active_tab { " ▸ " } else { "   " };
         ListItem::new(Line::from(vec![
             Span::styled(prefix, Style::default().fg(Theme::PRIMARY)),
-            Span::styled(*name, style),
+            Span::styled(res.name, style),
         ]))
     }).collect();
 
@@ -317,10 +1127,10 @@ fn render_body(f: &mut Frame, area: Rect, app: &mut App) {
 
     // Resource list
     if app.loading {
-        let loading = Paragraph::new("  ⏳ Loading...")
+        let loading = Paragraph::new(format!("  {} Loading...", Theme::spinner()))
             .style(Style::default().fg(Theme::WARNING))
             .block(Block::default()
-                .title(Span::styled(format!(" {} ", TABS[app.active_tab]), Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)))
+                .title(Span::styled(format!(" {} ", app.resource().name), Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)))
                 .borders(Borders::RIGHT)
                 .border_style(Style::default().fg(Theme::BORDER))
                 .padding(Padding::new(1, 1, 1, 0))
@@ -331,74 +1141,183 @@ fn render_body(f: &mut Frame, area: Rect, app: &mut App) {
             .style(Style::default().fg(Theme::ERROR))
             .wrap(Wrap { trim: true })
             .block(Block::default()
-                .title(Span::styled(format!(" {} ", TABS[app.active_tab]), Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)))
+                .title(Span::styled(format!(" {} ", app.resource().name), Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)))
                 .borders(Borders::RIGHT)
                 .border_style(Style::default().fg(Theme::BORDER))
                 .padding(Padding::new(1, 1, 1, 0))
             );
         f.render_widget(error_text, body_layout[1]);
     } else {
-        let items: Vec<ListItem> = app.data_items.iter().map(|item| {
-            ListItem::new(Line::from(Span::styled(format!(" {}", item), Style::default().fg(Theme::TEXT))))
+        let headers = (app.resource().columns.headers)();
+        let status_columns: Vec<bool> = headers.iter().map(|h| h.contains("Status") || h == "Payment").collect();
+        let rows: Vec<Row> = app.visible.iter().map(|&i| {
+            Row::new(app.data_items[i].iter().enumerate().map(|(col, text)| {
+                let color = if status_columns.get(col) == Some(&true) { status_color(text) } else { Theme::TEXT };
+                Cell::from(Line::from(highlight(text, &app.filter, Style::default().fg(color))))
+            }))
         }).collect();
+        // Each column as wide as its widest visible cell, within reason
+        let widths: Vec<Constraint> = (0..headers.len().max(1))
+            .map(|col| {
+                let header = headers.get(col).map_or(0, |h| h.chars().count());
+                let widest = app.visible.iter()
+                    .filter_map(|&i| app.data_items[i].get(col))
+                    .map(|c| c.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                Constraint::Length(header.max(widest).min(MAX_COLUMN_WIDTH) as u16)
+            })
+            .collect();
+        let loaded = match app.total {
+            Some(total) if total > app.items.len() as u64 => format!("{} of {} loaded", app.items.len(), total),
+            _ => app.data_items.len().to_string(),
+        };
+        let mut title = if app.filter.is_empty() {
+            format!(" {} ({}) ", app.resource().name, loaded)
+        } else {
+            format!(" {} ({}/{}) ", app.resource().name, app.visible.len(), loaded)
+        };
+        if app.pages.get(app.resource().endpoint).is_some_and(|p| p.loading) {
+            title.push_str(&format!("{} ", Theme::spinner()));
+        }
+        if app.resource().live {
+            let state = if app.events_paused { "paused".to_string() } else { format!("live {}s", app.events_interval.as_secs()) };
+            title.push_str(&format!("· {} ", state));
+        }
 
-        let list = List::new(items)
-            .highlight_style(Style::default().fg(Theme::PRIMARY).bg(Theme::SURFACE_HOVER).add_modifier(Modifier::BOLD))
+        let header = Row::new(headers).style(Style::default().fg(Theme::TEXT_DIM).add_modifier(Modifier::BOLD));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .column_spacing(2)
+            .row_highlight_style(Style::default().fg(Theme::PRIMARY).bg(Theme::SURFACE_HOVER).add_modifier(Modifier::BOLD))
             .highlight_symbol("▸ ")
             .block(Block::default()
-                .title(Span::styled(format!(" {} ({}) ", TABS[app.active_tab], app.data_items.len()), Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)))
+                .title(Span::styled(title, Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)))
                 .borders(Borders::RIGHT)
                 .border_style(Style::default().fg(Theme::BORDER))
                 .padding(Padding::new(0, 0, 0, 0))
             );
-        f.render_stateful_widget(list, body_layout[1], &mut app.list_state);
+        f.render_stateful_widget(table, body_layout[1], &mut app.list_state);
     }
 
     // Detail panel
-    let detail_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Min(8), Constraint::Length(5)])
-        .split(body_layout[2]);
-
+    if app.resource().analytics {
+        analytics::render(f, body_layout[2], &app.analytics);
+        return;
+    }
+    if app.resource().pricing && !app.items.is_empty() {
+        pricing::render(f, body_layout[2], &app.pricing);
+        return;
+    }
+    let mut detail_area = body_layout[2];
+    if app.resource().entitlements && app.selected_id().is_some() {
+        let height = app.entitlements.height().min(detail_area.height / 2);
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(height)])
+            .split(detail_area);
+        entitlements::render(f, split[1], &app.entitlements);
+        detail_area = split[0];
+    }
+    if app.resource().lines && app.selected_id().is_some() {
+        let height = app.lines.height().min(detail_area.height * 2 / 3);
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(height)])
+            .split(detail_area);
+        lines::render(f, split[1], &app.lines);
+        detail_area = split[0];
+    }
+    // Inside the borders and padding; rows are counted as wrapped by width
+    let (width, height) = (detail_area.width.saturating_sub(4).max(1) as usize, detail_area.height.saturating_sub(2));
+    let rows: usize = app.detail_text.lines().map(|l| l.chars().count().div_ceil(width).max(1)).sum();
+    app.detail_height = height;
+    app.detail_scroll = app.detail_scroll.min(u16::try_from(rows).unwrap_or(u16::MAX).saturating_sub(height));
+    let view = if app.flat_detail { "Fields" } else { "JSON" };
+    let position = if rows > height as usize {
+        format!(" {}-{}/{} ", app.detail_scroll + 1, (app.detail_scroll as usize + height as usize).min(rows), rows)
+    } else {
+        String::new()
+    };
     let detail = Paragraph::new(Text::from(app.detail_text.clone()))
         .style(Style::default().fg(Theme::TEXT_DIM))
         .wrap(Wrap { trim: false })
+        .scroll((app.detail_scroll, 0))
         .block(Block::default()
-            .title(Span::styled(" Detail ", Style::default().fg(Theme::ACCENT).add_modifier(Modifier::BOLD)))
+            .title(Span::styled(format!(" Detail · {} ", view), Style::default().fg(Theme::ACCENT).add_modifier(Modifier::BOLD)))
+            .title_bottom(Line::styled(position, Style::default().fg(Theme::TEXT_MUTED)).right_aligned())
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Theme::BORDER))
             .padding(Padding::new(1, 1, 0, 0))
         );
-    f.render_widget(detail, detail_layout[0]);
+    f.render_widget(detail, detail_area);
+}
+
+/// Widest a list column grows before its cells are cut off
+const MAX_COLUMN_WIDTH: usize = 32;
+
+/// The theme's take on the CLI's status badges
+fn status_color(status: &str) -> Color {
+    match status.to_lowercase().as_str() {
+        "active" | "published" | "paid" | "finalized" | "completed" => Theme::ACCENT,
+        "draft" | "pending" => Theme::WARNING,
+        "cancelled" | "canceled" | "void" | "voided" | "inactive" | "failed" => Theme::ERROR,
+        "trialing" | "paused" | "processing" => Theme::INFO,
+        _ => Theme::TEXT,
+    }
+}
 
-    // Mini sparkline
-    let sparkline = Sparkline::default()
+fn render_footer(f: &mut Frame, area: Rect, app: &App) {
+    let shortcuts = match &app.message {
+        Some(Message::Info(msg)) => vec![Span::styled(format!("  ✓ {}", msg), Style::default().fg(Theme::ACCENT))],
+        Some(Message::Error(msg)) => vec![Span::styled(format!("  ✗ {}", msg), Style::default().fg(Theme::ERROR))],
+        None if app.filtering => vec![
+            Span::styled("  / ", Style::default().fg(Theme::WARNING).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("{}▏", app.filter), Style::default().fg(Theme::TEXT)),
+            Span::styled("   Enter keep  Esc clear  field:text matches one field", Style::default().fg(Theme::TEXT_MUTED)),
+        ],
+        None if !app.filter.is_empty() => {
+            let mut spans = vec![
+                Span::styled(format!("  Filter: {}", app.filter), Style::default().fg(Theme::WARNING)),
+                Span::styled("  Esc clear", Style::default().fg(Theme::TEXT_MUTED)),
+                Span::styled("  │", Style::default().fg(Theme::BORDER)),
+            ];
+            spans.extend(shortcuts(app.resource()));
+            spans
+        }
+        None => shortcuts(app.resource()),
+    };
+
+    let footer = Paragraph::new(Line::from(shortcuts))
         .block(Block::default()
-            .title(Span::styled(" Activity ", Style::default().fg(Theme::INFO)))
-            .borders(Borders::ALL)
+            .borders(Borders::TOP)
             .border_style(Style::default().fg(Theme::BORDER))
-        )
-        .data(&app.sparkline_data)
-        .style(Style::default().fg(Theme::ACCENT));
-    f.render_widget(sparkline, detail_layout[1]);
+            .padding(Padding::new(0, 0, 0, 0))
+        );
+    f.render_widget(footer, area);
 }
 
-fn render_footer(f: &mut Frame, area: Rect, _app: &App) {
-    let shortcuts = vec![
+fn shortcuts(res: &Resource) -> Vec<Span<'static>> {
+    let actions = if res.analytics {
+        "w Window (24h/7d/30d)"
+    } else if res.live {
+        "p Pause/Resume"
+    } else {
+        "n New  e Edit  d Delete"
+    };
+    vec![
         Span::styled("  ←/→ Tab", Style::default().fg(Theme::PRIMARY)),
         Span::styled("  │  ", Style::default().fg(Theme::BORDER)),
         Span::styled("↑/↓ Navigate", Style::default().fg(Theme::TEXT_DIM)),
         Span::styled("  │  ", Style::default().fg(Theme::BORDER)),
+        Span::styled("/ Filter", Style::default().fg(Theme::WARNING)),
+        Span::styled("  │  ", Style::default().fg(Theme::BORDER)),
+        Span::styled("PgUp/PgDn Scroll  v View  y/c Copy", Style::default().fg(Theme::TEXT_DIM)),
+        Span::styled("  │  ", Style::default().fg(Theme::BORDER)),
         Span::styled("r Refresh", Style::default().fg(Theme::ACCENT)),
         Span::styled("  │  ", Style::default().fg(Theme::BORDER)),
+        Span::styled(actions, Style::default().fg(Theme::INFO)),
+        Span::styled("  │  ", Style::default().fg(Theme::BORDER)),
         Span::styled("q Quit", Style::default().fg(Theme::ERROR)),
-    ];
-
-    let footer = Paragraph::new(Line::from(shortcuts))
-        .block(Block::default()
-            .borders(Borders::TOP)
-            .border_style(Style::default().fg(Theme::BORDER))
-            .padding(Padding::new(0, 0, 0, 0))
-        );
-    f.render_widget(footer, area);
+    ]
 }
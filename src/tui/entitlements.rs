@@ -0,0 +1,119 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph},
+    Frame,
+};
+
+use crate::api::client::ApiClient;
+use crate::api::models::{CustomerUsageSummary, FeatureUsage};
+use crate::cli::customers::usage_ratio;
+use crate::utils::chart::{self, UsageLevel};
+use super::theme::Theme;
+
+/// Entitlement usage for the customer highlighted on the Customers tab
+#[derive(Clone, Default)]
+pub struct CustomerEntitlements {
+    /// Customer the gauges were last loaded for
+    pub customer_id: Option<String>,
+    features: Vec<FeatureUsage>,
+    error: Option<String>,
+    /// Whether a load is in flight or has finished for this customer
+    pub requested: bool,
+    loaded: bool,
+}
+
+impl CustomerEntitlements {
+    /// Whether the gauges are missing or for a different customer
+    pub fn is_due(&self, customer_id: Option<&str>) -> bool {
+        self.customer_id.as_deref() != customer_id || !self.requested
+    }
+
+    /// Whether these gauges are for the customer `current` shows
+    pub fn is_for(&self, current: &CustomerEntitlements) -> bool {
+        self.customer_id == current.customer_id
+    }
+
+    /// Point the gauges at `customer_id`, dropping data that belongs to another one
+    pub fn select(&mut self, customer_id: Option<String>) {
+        if self.customer_id != customer_id {
+            *self = CustomerEntitlements { customer_id, ..Default::default() };
+        }
+    }
+
+    /// Rows the panel needs, borders included
+    pub fn height(&self) -> u16 {
+        self.features.len().max(1) as u16 + 2
+    }
+}
+
+/// Fetch the customer's entitled features with their usage and limits
+pub async fn load(client: &ApiClient, entitlements: &mut CustomerEntitlements) {
+    let Some(id) = entitlements.customer_id.clone() else { return };
+    match client.get::<CustomerUsageSummary>(&format!("/v1/customers/{}/usage", id)).await {
+        Ok(summary) => {
+            entitlements.features = summary.features;
+            entitlements.error = None;
+        }
+        Err(e) => entitlements.error = Some(format!("{:#}", e)),
+    }
+    entitlements.loaded = true;
+}
+
+pub fn render(f: &mut Frame, area: Rect, entitlements: &CustomerEntitlements) {
+    let block = Block::default()
+        .title(Span::styled(" Entitlements ", Style::default().fg(Theme::ACCENT).add_modifier(Modifier::BOLD)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::BORDER))
+        .padding(Padding::new(1, 1, 0, 0));
+    let muted = Style::default().fg(Theme::TEXT_MUTED);
+    let lines = match &entitlements.error {
+        Some(err) => vec![Line::from(Span::styled(format!("✗ {}", err), Style::default().fg(Theme::ERROR)))],
+        None if !entitlements.loaded => vec![Line::from(Span::styled(format!("{} Loading...", Theme::spinner()), Style::default().fg(Theme::WARNING)))],
+        None if entitlements.features.is_empty() => vec![Line::from(Span::styled("No entitlements", muted))],
+        None => {
+            let width = block.inner(area).width as usize;
+            entitlements.features.iter().map(|feature| gauge_line(feature, width)).collect()
+        }
+    };
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// `Seats  ███████░░░  72%  18/25`, or whether a feature without a limit is enabled
+fn gauge_line(usage: &FeatureUsage, width: usize) -> Line<'static> {
+    let name = usage.feature.name.clone().or(usage.feature.lookup_key.clone()).unwrap_or_else(|| usage.feature.id.clone());
+    let label = Span::styled(format!("{:<16.16} ", name), Style::default().fg(Theme::TEXT_DIM));
+    if usage.is_enabled == Some(false) {
+        return Line::from(vec![label, Span::styled("✗ disabled", Style::default().fg(Theme::ERROR))]);
+    }
+    let Some(ratio) = usage_ratio(usage) else {
+        let text = match usage.feature.feature_type.as_deref() {
+            Some("METERED") => format!("{} used, unlimited", chart::format_value(usage.current_usage.unwrap_or_default())),
+            _ => "✓ enabled".to_string(),
+        };
+        return Line::from(vec![label, Span::styled(text, Style::default().fg(Theme::ACCENT))]);
+    };
+
+    let color = match UsageLevel::of(ratio) {
+        UsageLevel::Ok => Theme::ACCENT,
+        UsageLevel::Warning => Theme::WARNING,
+        UsageLevel::Critical => Theme::ERROR,
+    };
+    let counts = format!(
+        "{}/{}",
+        chart::format_value(usage.current_usage.unwrap_or_default()),
+        chart::format_value(usage.total_limit.unwrap_or_default())
+    );
+    // Whatever the name, percentage, and counts leave over goes to the bar
+    let bar_width = width.saturating_sub(17 + 6 + counts.len() + 6).clamp(5, 30);
+    let filled = ((ratio.clamp(0.0, 1.0) * bar_width as f64).round() as usize).min(bar_width);
+    Line::from(vec![
+        label,
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled("░".repeat(bar_width - filled), Style::default().fg(Theme::SURFACE_HOVER)),
+        Span::styled(format!(" {:>4.0}% ", ratio * 100.0), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        Span::styled(counts, Style::default().fg(Theme::TEXT)),
+        Span::styled(if usage.is_soft_limit == Some(true) { " soft" } else { "" }, Style::default().fg(Theme::TEXT_MUTED)),
+    ])
+}
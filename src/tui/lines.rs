@@ -0,0 +1,127 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph},
+    Frame,
+};
+
+use crate::api::client::ApiClient;
+use crate::api::models::{Invoice, InvoiceLineItem};
+use crate::cli::invoices::{format_rate, line_description, totals, unit_price};
+use crate::utils::chart;
+use super::theme::Theme;
+
+/// Line items of the invoice highlighted on the Invoices tab
+#[derive(Clone, Default)]
+pub struct InvoiceLines {
+    /// Invoice the breakdown was last loaded for
+    pub invoice_id: Option<String>,
+    invoice: Option<Invoice>,
+    error: Option<String>,
+    /// Whether a load is in flight or has finished for this invoice
+    pub requested: bool,
+}
+
+impl InvoiceLines {
+    /// Whether the breakdown is missing or for a different invoice
+    pub fn is_due(&self, invoice_id: Option<&str>) -> bool {
+        self.invoice_id.as_deref() != invoice_id || !self.requested
+    }
+
+    /// Whether this breakdown is for the invoice `current` shows
+    pub fn is_for(&self, current: &InvoiceLines) -> bool {
+        self.invoice_id == current.invoice_id
+    }
+
+    /// Point the breakdown at `invoice_id`, dropping data that belongs to another one
+    pub fn select(&mut self, invoice_id: Option<String>) {
+        if self.invoice_id != invoice_id {
+            *self = InvoiceLines { invoice_id, ..Default::default() };
+        }
+    }
+
+    /// Rows the panel needs: header, lines, a gap, three totals, and borders
+    pub fn height(&self) -> u16 {
+        let lines = self.invoice.as_ref().map_or(0, |i| i.line_items.len());
+        lines.max(1) as u16 + 7
+    }
+}
+
+/// Fetch the invoice with its line items
+pub async fn load(client: &ApiClient, lines: &mut InvoiceLines) {
+    let Some(id) = lines.invoice_id.clone() else { return };
+    match client.get::<Invoice>(&format!("/v1/invoices/{}", id)).await {
+        Ok(invoice) => {
+            lines.invoice = Some(invoice);
+            lines.error = None;
+        }
+        Err(e) => lines.error = Some(format!("{:#}", e)),
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, lines: &InvoiceLines) {
+    let title = match lines.invoice.as_ref().and_then(|i| i.currency.as_deref()) {
+        Some(currency) => format!(" Line Items · {} ", currency.to_uppercase()),
+        None => " Line Items ".to_string(),
+    };
+    let block = Block::default()
+        .title(Span::styled(title, Style::default().fg(Theme::ACCENT).add_modifier(Modifier::BOLD)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::BORDER))
+        .padding(Padding::new(1, 1, 0, 0));
+    let muted = Style::default().fg(Theme::TEXT_MUTED);
+    let text = match (&lines.error, &lines.invoice) {
+        (Some(err), _) => vec![Line::from(Span::styled(format!("✗ {}", err), Style::default().fg(Theme::ERROR)))],
+        (None, None) => vec![Line::from(Span::styled(format!("{} Loading...", Theme::spinner()), Style::default().fg(Theme::WARNING)))],
+        (None, Some(invoice)) => {
+            let width = block.inner(area).width as usize;
+            breakdown(invoice, width, muted)
+        }
+    };
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Quantity, unit price, and amount columns, with the description taking
+/// whatever width they leave
+fn breakdown(invoice: &Invoice, width: usize, muted: Style) -> Vec<Line<'static>> {
+    let name_width = width.saturating_sub(3 * 11).max(12);
+    let mut text = vec![Line::from(Span::styled(
+        format!("{:<name_width$}{:>11}{:>11}{:>11}", "Description", "Qty", "Unit", "Amount"),
+        muted.add_modifier(Modifier::BOLD),
+    ))];
+    if invoice.line_items.is_empty() {
+        text.push(Line::from(Span::styled("No line items", muted)));
+    }
+    text.extend(invoice.line_items.iter().map(|l| line(l, name_width)));
+
+    let (subtotal, tax, total) = totals(invoice);
+    let label_width = name_width + 22;
+    text.push(Line::default());
+    for (label, amount, style) in [
+        ("Subtotal", subtotal, Style::default().fg(Theme::TEXT_DIM)),
+        ("Tax", tax, Style::default().fg(Theme::TEXT_DIM)),
+        ("Total", total, Style::default().fg(Theme::TEXT).add_modifier(Modifier::BOLD)),
+    ] {
+        text.push(Line::from(vec![
+            Span::styled(format!("{:>label_width$}", label), muted),
+            Span::styled(format!("{:>11.2}", amount), style),
+        ]));
+    }
+    text
+}
+
+fn line(l: &InvoiceLineItem, name_width: usize) -> Line<'static> {
+    let mut name = line_description(l);
+    if let Some(meter) = l.meter_display_name.as_deref().filter(|m| *m != name) {
+        name = format!("{} · {}", name, meter);
+    }
+    let name: String = name.chars().take(name_width.saturating_sub(1)).collect();
+    let quantity = l.quantity.map(chart::format_value).unwrap_or_default();
+    let unit = unit_price(l).map(format_rate).unwrap_or_default();
+    Line::from(vec![
+        Span::styled(format!("{:<name_width$}", name), Style::default().fg(Theme::TEXT_DIM)),
+        Span::styled(format!("{:>11}{:>11}", quantity, unit), Style::default().fg(Theme::TEXT)),
+        Span::styled(format!("{:>11.2}", l.amount.unwrap_or_default()), Style::default().fg(Theme::ACCENT)),
+    ])
+}
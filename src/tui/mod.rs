@@ -1,2 +1,6 @@
 pub mod theme;
+pub mod analytics;
+pub mod entitlements;
+pub mod lines;
+pub mod pricing;
 pub mod dashboard;
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Padding, Paragraph, Wrap},
+    Frame,
+};
+use serde_json::Value;
+
+use crate::api::client::ApiClient;
+use crate::api::query::QueryBuilder;
+use crate::api::models::{Entitlement, Price, PriceTier};
+use crate::utils::chart;
+use super::theme::Theme;
+
+/// Prices and entitlements for the plan highlighted on the Plans tab
+#[derive(Clone, Default)]
+pub struct PlanPricing {
+    /// Plan the card was last loaded for
+    pub plan_id: Option<String>,
+    plan_name: String,
+    description: String,
+    prices: Vec<Price>,
+    entitlements: Vec<Entitlement>,
+    /// Meter and feature names by ID, for labelling prices and entitlements
+    names: HashMap<String, String>,
+    error: Option<String>,
+    /// Whether a load is in flight or has finished for this plan
+    pub requested: bool,
+    loaded: bool,
+}
+
+impl PlanPricing {
+    /// Whether the card is missing or for a different plan
+    pub fn is_due(&self, plan_id: Option<&str>) -> bool {
+        self.plan_id.as_deref() != plan_id || !self.requested
+    }
+
+    /// Whether this card is for the plan `current` shows
+    pub fn is_for(&self, current: &PlanPricing) -> bool {
+        self.plan_id == current.plan_id
+    }
+
+    /// Point the card at `plan`, dropping data that belongs to another one
+    pub fn select(&mut self, plan: Option<&Value>, names: HashMap<String, String>) {
+        let id = plan.and_then(|p| p.get("id")).and_then(|v| v.as_str()).map(String::from);
+        if self.plan_id != id {
+            *self = PlanPricing::default();
+        }
+        let field = |key: &str| plan.and_then(|p| p.get(key)).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        self.plan_name = plan.and_then(|p| p.get("name")).and_then(|v| v.as_str()).or(id.as_deref()).unwrap_or_default().to_string();
+        self.description = field("description");
+        self.names = names;
+        self.plan_id = id;
+    }
+
+    fn name<'a>(&'a self, id: &'a str) -> &'a str {
+        self.names.get(id).map(String::as_str).unwrap_or(id)
+    }
+}
+
+/// Fetch the selected plan's prices and entitlements
+pub async fn load(client: &ApiClient, pricing: &mut PlanPricing) {
+    let Some(id) = pricing.plan_id.clone() else { return };
+    let (prices_path, entitlements_path) = (
+        QueryBuilder::new().param("plan_ids", &id).to_path("/v1/prices"),
+        QueryBuilder::new().param("plan_ids", &id).to_path("/v1/entitlements"),
+    );
+    let (prices, entitlements) = tokio::join!(
+        client.get_all::<Price>(&prices_path),
+        client.get_all::<Entitlement>(&entitlements_path),
+    );
+
+    let mut errors = vec![];
+    match prices {
+        Ok(prices) => pricing.prices = prices,
+        Err(e) => errors.push(format!("Prices: {:#}", e)),
+    }
+    match entitlements {
+        Ok(entitlements) => pricing.entitlements = entitlements,
+        Err(e) => errors.push(format!("Entitlements: {:#}", e)),
+    }
+    pricing.error = (!errors.is_empty()).then(|| errors.join("\n"));
+    pricing.loaded = true;
+}
+
+pub fn render(f: &mut Frame, area: Rect, pricing: &PlanPricing) {
+    let title = format!(" {} ", if pricing.plan_name.is_empty() { "Pricing" } else { &pricing.plan_name });
+    if pricing.plan_id.is_none() || !pricing.loaded {
+        let text = match &pricing.error {
+            Some(err) => Span::styled(format!("✗ {}", err), Style::default().fg(Theme::ERROR)),
+            None if pricing.plan_id.is_none() => Span::styled("Select a plan to see its pricing", Style::default().fg(Theme::TEXT_MUTED)),
+            None => Span::styled(format!("{} Loading...", Theme::spinner()), Style::default().fg(Theme::WARNING)),
+        };
+        f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(panel(title)), area);
+        return;
+    }
+
+    let tiered: Vec<&Price> = pricing.prices.iter().filter(|p| is_tiered(p)).collect();
+    let card = summary(pricing);
+    let mut constraints = vec![Constraint::Length(card.len() as u16 + 2)];
+    constraints.extend(tiered.iter().map(|_| Constraint::Fill(1)));
+    let layout = Layout::default().direction(Direction::Vertical).constraints(constraints).split(area);
+
+    let widget = Paragraph::new(card).wrap(Wrap { trim: false }).block(panel(title));
+    f.render_widget(widget, layout[0]);
+    for (price, area) in tiered.into_iter().zip(layout.iter().skip(1)) {
+        render_tiers(f, *area, pricing, price);
+    }
+}
+
+fn panel(title: String) -> Block<'static> {
+    Block::default()
+        .title(Span::styled(title, Style::default().fg(Theme::ACCENT).add_modifier(Modifier::BOLD)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::BORDER))
+        .padding(Padding::new(1, 1, 0, 0))
+}
+
+fn is_tiered(price: &Price) -> bool {
+    price.billing_model.as_deref() == Some("TIERED") && price.tiers.as_ref().is_some_and(|t| !t.is_empty())
+}
+
+/// Flat fees, per-unit and package prices, and entitlements, one per line
+fn summary(pricing: &PlanPricing) -> Vec<Line<'static>> {
+    let (dim, muted) = (Style::default().fg(Theme::TEXT_DIM), Style::default().fg(Theme::TEXT_MUTED));
+    let heading = |text: &str| Line::from(Span::styled(text.to_string(), Style::default().fg(Theme::PRIMARY).add_modifier(Modifier::BOLD)));
+    let mut lines = vec![];
+    if let Some(err) = &pricing.error {
+        lines.push(Line::from(Span::styled(format!("✗ {}", err), Style::default().fg(Theme::ERROR))));
+    }
+    if !pricing.description.is_empty() {
+        lines.push(Line::from(Span::styled(pricing.description.clone(), muted)));
+    }
+
+    let (fixed, usage): (Vec<&Price>, Vec<&Price>) = pricing.prices.iter().partition(|p| p.price_type.as_deref() == Some("FIXED"));
+    lines.push(heading("Flat fees"));
+    if fixed.is_empty() {
+        lines.push(Line::from(Span::styled("  None", muted)));
+    }
+    for price in fixed {
+        let label = price.description.clone().or_else(|| price.lookup_key.clone()).unwrap_or_else(|| "Fee".to_string());
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<20} ", label), dim),
+            Span::styled(money(price.amount.as_deref(), price), Style::default().fg(Theme::TEXT).add_modifier(Modifier::BOLD)),
+            Span::styled(per_period(price), muted),
+        ]));
+    }
+
+    if !usage.is_empty() {
+        lines.push(heading("Usage"));
+    }
+    for price in usage {
+        let meter = price.meter_id.as_deref().map_or("Usage", |id| pricing.name(id)).to_string();
+        let rate = match price.billing_model.as_deref() {
+            _ if is_tiered(price) => {
+                let tiers = price.tiers.as_deref().unwrap_or_default();
+                format!("{} tiers, {} (charted below)", tiers.len(), price.tier_mode.as_deref().unwrap_or("VOLUME").to_lowercase())
+            }
+            Some("PACKAGE") => {
+                let size = price.transform_quantity.as_ref().and_then(|t| t.get("divide_by")).and_then(Value::as_f64).unwrap_or(1.0);
+                format!("{} per {} units", money(price.amount.as_deref(), price), chart::format_value(size))
+            }
+            _ => format!("{} per unit", money(price.amount.as_deref(), price)),
+        };
+        let period = if is_tiered(price) { String::new() } else { per_period(price) };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<20} ", meter), dim),
+            Span::styled(rate, Style::default().fg(Theme::TEXT)),
+            Span::styled(period, muted),
+        ]));
+    }
+
+    lines.push(heading("Entitlements"));
+    if pricing.entitlements.is_empty() {
+        lines.push(Line::from(Span::styled("  None", muted)));
+    }
+    for ent in &pricing.entitlements {
+        let feature = ent.feature_id.as_deref().map_or("?", |id| pricing.name(id)).to_string();
+        let (mark, value) = if ent.is_enabled == Some(false) {
+            (Span::styled("✗ ", Style::default().fg(Theme::ERROR)), "disabled".to_string())
+        } else if ent.feature_type.as_deref() == Some("METERED") {
+            let limit = ent.usage_limit.map_or("unlimited".to_string(), |l| format!("up to {}", chart::format_value(l)));
+            (Span::styled("✓ ", Style::default().fg(Theme::ACCENT)), limit)
+        } else {
+            (Span::styled("✓ ", Style::default().fg(Theme::ACCENT)), "enabled".to_string())
+        };
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            mark,
+            Span::styled(format!("{:<18} ", feature), dim),
+            Span::styled(value, Style::default().fg(Theme::TEXT)),
+        ]));
+    }
+    lines
+}
+
+/// A tiered price as a stepped line of its unit price, each tier given the
+/// same width so small first tiers stay visible, with the tiers listed beneath
+fn render_tiers(f: &mut Frame, area: Rect, pricing: &PlanPricing, price: &Price) {
+    let tiers = price.tiers.as_deref().unwrap_or_default();
+    let meter = price.meter_id.as_deref().map_or("Usage", |id| pricing.name(id));
+    let mode = price.tier_mode.as_deref().unwrap_or("VOLUME").to_lowercase();
+    let block = panel(format!(" {} · {} tiers ", meter, mode));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let legend = tier_legend(tiers, price);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(4), Constraint::Length(legend.len() as u16)])
+        .split(inner);
+
+    let rates: Vec<f64> = tiers.iter().map(|t| amount(t.unit_amount.as_deref())).collect();
+    let steps: Vec<(f64, f64)> = rates
+        .iter()
+        .enumerate()
+        .flat_map(|(i, rate)| [(i as f64, *rate), (i as f64 + 1.0, *rate)])
+        .collect();
+    // The top of the axis is the highest rate, labelled as the API gives it
+    let peak = tiers.iter().zip(&rates).max_by(|a, b| a.1.total_cmp(b.1));
+    let y_max = peak.map_or(0.0, |(_, rate)| *rate);
+    let y_max = if y_max > 0.0 { y_max } else { 1.0 };
+    let top = peak.and_then(|(t, _)| t.unit_amount.clone()).unwrap_or_else(|| chart::format_value(y_max));
+    let muted = Style::default().fg(Theme::TEXT_MUTED);
+
+    // One label per tier boundary; evenly spaced labels line up with equal-width tiers
+    let mut x_labels = vec![Span::styled("0", muted)];
+    x_labels.extend(tiers.iter().map(|t| Span::styled(t.up_to.map_or("∞".to_string(), compact), muted)));
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Theme::ACCENT))
+        .data(&steps);
+    let widget = Chart::new(vec![dataset])
+        .x_axis(Axis::default()
+            .style(Style::default().fg(Theme::BORDER))
+            .bounds([0.0, tiers.len() as f64])
+            .labels(x_labels))
+        .y_axis(Axis::default()
+            .style(Style::default().fg(Theme::BORDER))
+            .bounds([0.0, y_max])
+            .labels(vec![Span::styled("0", muted), Span::styled(top, muted)]));
+    f.render_widget(widget, layout[0]);
+    f.render_widget(Paragraph::new(legend), layout[1]);
+}
+
+/// `up to 1000   0.01 USD / unit + 5 USD`, one line per tier
+fn tier_legend(tiers: &[PriceTier], price: &Price) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Theme::TEXT_DIM);
+    let mut floor = None;
+    tiers
+        .iter()
+        .map(|tier| {
+            let range = match (tier.up_to, floor) {
+                (Some(up), _) => format!("up to {}", up),
+                (None, Some(low)) => format!("above {}", low),
+                (None, None) => "any quantity".to_string(),
+            };
+            floor = tier.up_to;
+            let mut rate = format!("{} / unit", money(tier.unit_amount.as_deref(), price));
+            if amount(tier.flat_amount.as_deref()) != 0.0 {
+                rate.push_str(&format!(" + {}", money(tier.flat_amount.as_deref(), price)));
+            }
+            Line::from(vec![Span::styled(format!("{:<16} ", range), dim), Span::styled(rate, Style::default().fg(Theme::TEXT))])
+        })
+        .collect()
+}
+
+fn amount(value: Option<&str>) -> f64 {
+    value.and_then(|a| a.parse().ok()).unwrap_or_default()
+}
+
+/// `49 USD`, keeping the API's decimal string as given
+fn money(value: Option<&str>, price: &Price) -> String {
+    let currency = price.currency.as_deref().unwrap_or_default().to_uppercase();
+    format!("{} {}", value.unwrap_or("0"), currency).trim_end().to_string()
+}
+
+/// ` / month`, or nothing when the price has no billing period
+fn per_period(price: &Price) -> String {
+    let Some(period) = price.billing_period.as_deref() else { return String::new() };
+    let unit = match period {
+        "DAILY" => "day",
+        "WEEKLY" => "week",
+        "MONTHLY" => "month",
+        "QUARTERLY" => "quarter",
+        "HALF_YEARLY" => "half year",
+        "ANNUAL" => "year",
+        other => return format!(" / {}", other.to_lowercase()),
+    };
+    match price.billing_period_count {
+        Some(n) if n > 1 => format!(" / {} {}s", n, unit),
+        _ => format!(" / {}", unit),
+    }
+}
+
+/// Tier boundaries short enough to sit under the chart: `500`, `10k`, `2.5M`
+fn compact(n: u64) -> String {
+    let (value, suffix) = match n {
+        n if n >= 1_000_000_000 => (n as f64 / 1e9, "B"),
+        n if n >= 1_000_000 => (n as f64 / 1e6, "M"),
+        n if n >= 1_000 => (n as f64 / 1e3, "k"),
+        n => return n.to_string(),
+    };
+    format!("{}{}", (value * 10.0).round() / 10.0, suffix)
+}
@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use ratatui::style::Color;
 
 /// FlexPrice terminal theme — dark, modern, premium
@@ -21,4 +23,14 @@ impl Theme {
     pub const TEXT: Color = Color::Rgb(226, 232, 240);       // Cool gray
     pub const TEXT_DIM: Color = Color::Rgb(148, 163, 184);   // Dim gray
     pub const TEXT_MUTED: Color = Color::Rgb(100, 116, 139); // Muted
+
+    // ─── Motion ───────────────────────────────────────
+    const SPINNER: &'static [&'static str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+    /// The loading spinner's frame for the current moment, so it animates as
+    /// the dashboard redraws
+    pub fn spinner() -> &'static str {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        Self::SPINNER[(millis / 80) as usize % Self::SPINNER.len()]
+    }
 }
@@ -0,0 +1,203 @@
+use std::future::Future;
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+
+use crate::api::client::{ApiClient, DryRun};
+use crate::config::globals;
+use crate::utils::checkpoint::Checkpoint;
+use crate::utils::{output, spinner};
+
+/// Flags controlling how array payloads are processed
+#[derive(Args, Debug, Clone, Default)]
+pub struct BatchArgs {
+    /// Stop at the first failed item (default for array payloads)
+    #[arg(long, conflicts_with = "continue_on_error")]
+    pub fail_fast: bool,
+    /// Keep going after failures and summarize them at the end
+    #[arg(long)]
+    pub continue_on_error: bool,
+    /// Checkpoint file to record progress in (default: <FILE>.checkpoint.json)
+    #[arg(long, value_name = "PATH", conflicts_with = "resume")]
+    pub checkpoint: Option<String>,
+    /// Resume an interrupted run from its checkpoint file
+    #[arg(long, value_name = "CHECKPOINT")]
+    pub resume: Option<String>,
+}
+
+/// A `--json` payload: either a single object or an array of objects
+pub enum Payload {
+    Single(serde_json::Value),
+    Many(Vec<serde_json::Value>),
+}
+
+/// Read a JSON file, splitting top-level arrays into individual items
+pub fn read_payload(path: &str) -> Result<Payload> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+    let body: serde_json::Value = serde_json::from_str(&data)
+        .with_context(|| format!("Invalid JSON in {}", path))?;
+    Ok(match body {
+        serde_json::Value::Array(items) => Payload::Many(items),
+        other => Payload::Single(other),
+    })
+}
+
+/// Read import rows from a CSV file (header row → object keys) or a JSON
+/// file containing an object or an array of objects.
+///
+/// CSV columns named `metadata.<key>` are collected into a `metadata` object;
+/// empty cells are omitted.
+pub fn read_rows(path: &str) -> Result<Vec<serde_json::Value>> {
+    if !path.to_lowercase().ends_with(".csv") {
+        return Ok(match read_payload(path)? {
+            Payload::Single(item) => vec![item],
+            Payload::Many(items) => items,
+        });
+    }
+
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+    let headers = reader.headers()?.clone();
+    let mut rows = vec![];
+    for record in reader.records() {
+        let record = record.with_context(|| format!("Invalid CSV in {}", path))?;
+        let line = record.position().map_or(0, |p| p.line());
+        let mut row = serde_json::Map::new();
+        for (header, value) in headers.iter().zip(record.iter()) {
+            if value.is_empty() {
+                continue;
+            }
+            if let Some(key) = header.strip_prefix("metadata.") {
+                let Some(metadata) = row.entry("metadata").or_insert_with(|| serde_json::json!({})).as_object_mut() else {
+                    bail!("{} line {}: column '{}' can't be combined with a 'metadata' column", path, line, header);
+                };
+                metadata.insert(key.to_string(), value.into());
+            } else if header == "metadata" && row.contains_key("metadata") {
+                bail!("{} line {}: column 'metadata' can't be combined with 'metadata.<key>' columns", path, line);
+            } else {
+                row.insert(header.to_string(), value.into());
+            }
+        }
+        rows.push(serde_json::Value::Object(row));
+    }
+    Ok(rows)
+}
+
+/// Create each item with a POST to `path`, printing the new IDs; see `run`
+pub async fn create_many(
+    client: &ApiClient,
+    items: Vec<serde_json::Value>,
+    source: Option<&str>,
+    args: &BatchArgs,
+    label: &str,
+    path: &str,
+) -> Result<()> {
+    run(items, source, 1, args, label, |body| async move {
+        let created: serde_json::Value = client.post(path, &body).await?;
+        Ok(created.get("id").and_then(|id| id.as_str()).unwrap_or_default().to_string())
+    })
+    .await
+}
+
+/// Run `f` for each item, printing per-item results and a final summary.
+///
+/// `source` is the input file and `rows_per_item` how many input rows each
+/// item covers; both are recorded in the checkpoint so `--resume` can skip
+/// completed items. Items without a source file (written in $EDITOR or the
+/// wizard) aren't checkpointed. `f` returns the created ID (or a description
+/// of what was done), printed after `label`. Fails if any item failed.
+pub async fn run<F, Fut>(
+    items: Vec<serde_json::Value>,
+    source: Option<&str>,
+    rows_per_item: usize,
+    args: &BatchArgs,
+    label: &str,
+    mut f: F,
+) -> Result<()>
+where
+    F: FnMut(serde_json::Value) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let mut checkpoint = match (source, &args.resume) {
+        (Some(source), Some(path)) => Some(Checkpoint::resume(path, source, rows_per_item)?),
+        (Some(source), None) => Some(Checkpoint::new(source, rows_per_item, args.checkpoint.as_deref())),
+        (None, _) if args.resume.is_some() || args.checkpoint.is_some() => {
+            bail!("--checkpoint and --resume need the items in a file, given with --json")
+        }
+        (None, _) => None,
+    };
+    let total = items.len();
+    let resumed = checkpoint.as_ref().map_or(0, |c| c.processed.len());
+    if resumed > 0 {
+        output::info(&format!("Resuming: {} of {} items already processed", resumed, total));
+    }
+
+    let mut succeeded = 0;
+    let mut failures: Vec<(usize, String)> = vec![];
+
+    for (i, item) in items.into_iter().enumerate() {
+        if checkpoint.as_ref().is_some_and(|c| c.is_done(i)) {
+            continue;
+        }
+        let pos = format!("[{}/{}]", i + 1, total);
+        match f(item).await {
+            Ok(id) => {
+                succeeded += 1;
+                output::success(&format!("{} {}: {}", pos, label, id));
+                if let Some(checkpoint) = &mut checkpoint {
+                    checkpoint.record(i, id)?;
+                }
+            }
+            // Printed instead of sent; nothing to record or count as failed
+            Err(e) if e.is::<DryRun>() => continue,
+            Err(e) => {
+                output::error(&format!("{} {:#}", pos, e));
+                failures.push((i, format!("{:#}", e)));
+                if !args.continue_on_error {
+                    break;
+                }
+            }
+        }
+        spinner::emit(serde_json::json!({
+            "phase": "batch",
+            "done": resumed + succeeded + failures.len(),
+            "total": total,
+        }));
+    }
+
+    println!();
+    output::info(&format!(
+        "{} succeeded, {} failed, {} skipped, {} previously done (of {})",
+        succeeded,
+        failures.len(),
+        total - resumed - succeeded - failures.len(),
+        resumed,
+        total
+    ));
+    for (i, err) in &failures {
+        output::warning(&format!("item {}: {}", i + 1, err));
+    }
+
+    // Nothing was sent, so there is no progress to resume from
+    if globals::get().dry_run {
+        return Ok(());
+    }
+    if let Some(checkpoint) = &checkpoint {
+        if checkpoint.processed.len() == total {
+            checkpoint.remove()?;
+            return Ok(());
+        }
+        checkpoint.save()?;
+        output::info(&format!(
+            "Progress saved to {}. Re-run with `--resume {}` to continue.",
+            checkpoint.path().display(),
+            checkpoint.path().display()
+        ));
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!("{} of {} items failed", failures.len(), total)
+    }
+}
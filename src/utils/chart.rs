@@ -0,0 +1,93 @@
+use colored::Colorize;
+
+/// Partial blocks from one to eight eighths of a cell
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Sum adjacent values so at most `width` remain. Returns the values and how
+/// many inputs went into each.
+pub fn fit(values: &[f64], width: usize) -> (Vec<f64>, usize) {
+    let per = values.len().div_ceil(width.max(1)).max(1);
+    (values.chunks(per).map(|c| c.iter().sum()).collect(), per)
+}
+
+/// A vertical bar chart, one column per value, `height` rows tall, with the
+/// maximum and zero marked on a left axis and `start`/`end` labels beneath
+pub fn bars(values: &[f64], height: usize, start: &str, end: &str) -> String {
+    let height = height.max(1);
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let top = format_value(max);
+    let axis = top.len().max(1);
+
+    let mut lines = vec![];
+    for row in (0..height).rev() {
+        let label = match row {
+            r if r == height - 1 => top.clone(),
+            0 => "0".to_string(),
+            _ => String::new(),
+        };
+        let tick = if label.is_empty() { '│' } else { '┤' };
+        let cells: String = values.iter().map(|v| cell(*v, max, height, row)).collect();
+        lines.push(format!("  {:>axis$} {}{}", label, tick, cells.cyan()));
+    }
+    lines.push(format!("  {:>axis$} └{}", "", "─".repeat(values.len())));
+    let gap = values.len().saturating_sub(start.chars().count() + end.chars().count()).max(1);
+    lines.push(format!("  {:>axis$}  {}{}{}", "", start.dimmed(), " ".repeat(gap), end.dimmed()));
+    lines.join("\n")
+}
+
+/// The character for `row` (0 = bottom) of a column showing `value`
+fn cell(value: f64, max: f64, height: usize, row: usize) -> char {
+    if max <= 0.0 || value <= 0.0 {
+        return ' ';
+    }
+    // Any non-zero value shows at least a sliver
+    let eighths = ((value / max * (height * 8) as f64).round() as usize).max(1);
+    let floor = row * 8;
+    match eighths.saturating_sub(floor) {
+        0 => ' ',
+        n if n >= 8 => '█',
+        n => BLOCKS[n - 1],
+    }
+}
+
+/// A compact axis label: whole numbers as-is, fractions to two places
+pub fn format_value(v: f64) -> String {
+    if v.fract() == 0.0 {
+        format!("{}", v)
+    } else {
+        format!("{:.2}", v)
+    }
+}
+
+/// How close usage is to its limit, for coloring gauges
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UsageLevel {
+    Ok,
+    /// At least 75% of the limit used
+    Warning,
+    /// At least 90% used, or over the limit
+    Critical,
+}
+
+impl UsageLevel {
+    pub fn of(ratio: f64) -> Self {
+        match ratio {
+            r if r >= 0.9 => UsageLevel::Critical,
+            r if r >= 0.75 => UsageLevel::Warning,
+            _ => UsageLevel::Ok,
+        }
+    }
+}
+
+/// A `width`-cell bar filled to `ratio` (capped at full) followed by the
+/// percentage, colored by how close it is to the limit
+pub fn gauge(ratio: f64, width: usize) -> String {
+    let filled = ((ratio.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    let bar = format!("{}{}", "█".repeat(filled), "░".repeat(width - filled));
+    let text = format!("{} {:>3.0}%", bar, ratio * 100.0);
+    match UsageLevel::of(ratio) {
+        UsageLevel::Ok => text.green().to_string(),
+        UsageLevel::Warning => text.yellow().to_string(),
+        UsageLevel::Critical => text.red().to_string(),
+    }
+}
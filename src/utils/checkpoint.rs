@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Progress of a bulk run, persisted after every item so an interrupted run
+/// can be resumed without repeating work
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Input file the offsets refer to
+    pub source: String,
+    /// Number of input rows each item covers (1 unless rows are batched)
+    pub rows_per_item: usize,
+    /// Offsets of items that completed successfully
+    #[serde(default)]
+    pub processed: BTreeSet<usize>,
+    /// Created resource IDs (or row ranges for batched ingestion) by offset
+    #[serde(default)]
+    pub created: BTreeMap<usize, String>,
+    /// Where this checkpoint is written; not persisted
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Checkpoint {
+    /// Start a fresh checkpoint written next to `source` unless `path` is given
+    pub fn new(source: &str, rows_per_item: usize, path: Option<&str>) -> Self {
+        Self {
+            source: source.to_string(),
+            rows_per_item,
+            path: path
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(format!("{}.checkpoint.json", source))),
+            ..Default::default()
+        }
+    }
+
+    /// Load a checkpoint to resume, verifying it belongs to the same input
+    pub fn resume(path: &str, source: &str, rows_per_item: usize) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read checkpoint {}", path))?;
+        let mut cp: Checkpoint = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid checkpoint file {}", path))?;
+        if cp.source != source {
+            anyhow::bail!("Checkpoint {} was written for {}, not {}", path, cp.source, source);
+        }
+        if cp.rows_per_item != rows_per_item {
+            anyhow::bail!(
+                "Checkpoint {} was written with a batch size of {}; rerun with the same batch size",
+                path,
+                cp.rows_per_item
+            );
+        }
+        cp.path = PathBuf::from(path);
+        Ok(cp)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_done(&self, offset: usize) -> bool {
+        self.processed.contains(&offset)
+    }
+
+    /// Record a completed item and flush to disk
+    pub fn record(&mut self, offset: usize, created: String) -> Result<()> {
+        self.processed.insert(offset);
+        if !created.is_empty() {
+            self.created.insert(offset, created);
+        }
+        self.save()
+    }
+
+    /// Write atomically so a crash mid-write never corrupts the checkpoint
+    pub fn save(&self) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write checkpoint {}", self.path.display()))
+    }
+
+    /// Remove the checkpoint once the run has fully completed
+    pub fn remove(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use dialoguer::Confirm;
+use serde::de::DeserializeOwned;
+
+use crate::api::client::ApiClient;
+use crate::utils::batch::{self, Payload};
+use crate::utils::output;
+use crate::utils::wizard::Wizard;
+
+/// Server-managed fields dropped before editing an existing resource
+const READ_ONLY_FIELDS: &[&str] = &[
+    "id",
+    "tenant_id",
+    "environment_id",
+    "status",
+    "created_at",
+    "updated_at",
+    "created_by",
+    "updated_by",
+];
+
+/// Read a `--json` payload, or build one in `$EDITOR` from `template` when
+/// `edit` is set. Without either, `wizard` prompts for the fields if given.
+///
+/// Returns `None` if the user abandoned the edit or prompts.
+pub fn load_or_edit<T: DeserializeOwned>(
+    file: Option<&str>,
+    edit: bool,
+    template: &str,
+    wizard: Option<&Wizard>,
+) -> Result<Option<Payload>> {
+    if let Some(file) = file {
+        return batch::read_payload(file).map(Some);
+    }
+    let body = match wizard {
+        Some(wizard) if !edit => wizard.run::<T>()?,
+        _ => self::edit::<T>(&serde_json::from_str(template)?)?,
+    };
+    Ok(body.map(Payload::Single))
+}
+
+/// Build the body for an update: the `--json` file if given, otherwise the
+/// resource's current state at `path` edited in `$EDITOR`
+pub async fn update_body<T: DeserializeOwned>(
+    client: &ApiClient,
+    path: &str,
+    file: Option<&str>,
+) -> Result<Option<serde_json::Value>> {
+    if let Some(file) = file {
+        return match batch::read_payload(file)? {
+            Payload::Single(body) => Ok(Some(body)),
+            Payload::Many(_) => anyhow::bail!("{} must contain a single JSON object", file),
+        };
+    }
+    let current: serde_json::Value = client.get(path).await?;
+    edit::<T>(&editable(current))
+}
+
+/// Build a partial update body from field flags such as `--name`, or `None`
+/// when no flag was given
+pub fn field_updates(fields: &[(&str, Option<serde_json::Value>)]) -> Option<serde_json::Value> {
+    let body: serde_json::Map<String, serde_json::Value> = fields
+        .iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value.clone()?)))
+        .collect();
+    (!body.is_empty()).then_some(serde_json::Value::Object(body))
+}
+
+/// Prepare a fetched resource for editing by removing server-managed fields
+pub fn editable(mut current: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = current.as_object_mut() {
+        for field in READ_ONLY_FIELDS {
+            obj.remove(*field);
+        }
+    }
+    current
+}
+
+/// Open `$VISUAL`/`$EDITOR` on `initial` and return the saved object once it
+/// parses as `T`. Invalid input can be re-opened for fixing; returns `None`
+/// if the user saves without changes or gives up.
+pub fn edit<T: DeserializeOwned>(initial: &serde_json::Value) -> Result<Option<serde_json::Value>> {
+    let path = std::env::temp_dir().join(format!("flexprice-edit-{}.json", std::process::id()));
+    let original = serde_json::to_string_pretty(initial)?;
+    let mut content = original.clone();
+
+    let result = loop {
+        fs::write(&path, &content)?;
+        launch(&path)?;
+        content = fs::read_to_string(&path)?;
+
+        if content.trim().is_empty() || content == original {
+            output::info("Edit cancelled, no changes made.");
+            break None;
+        }
+        match validate::<T>(&content) {
+            Ok(value) => break Some(value),
+            Err(e) => {
+                output::error(&format!("{:#}", e));
+                let retry = Confirm::new()
+                    .with_prompt("Re-open the editor to fix it?")
+                    .default(true)
+                    .interact()?;
+                if !retry {
+                    break None;
+                }
+            }
+        }
+    };
+    let _ = fs::remove_file(&path);
+    Ok(result)
+}
+
+fn validate<T: DeserializeOwned>(content: &str) -> Result<serde_json::Value> {
+    let value: serde_json::Value = serde_json::from_str(content).context("Invalid JSON")?;
+    if !value.is_object() {
+        anyhow::bail!("Expected a JSON object");
+    }
+    serde_json::from_value::<T>(value.clone()).context("Payload doesn't match the resource schema")?;
+    Ok(value)
+}
+
+fn launch(path: &std::path::Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad".into() } else { "vi".into() });
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("$EDITOR is empty")?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+    if !status.success() {
+        anyhow::bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use std::io::{IsTerminal, Read};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use dialoguer::Confirm;
+use futures::stream::{self, StreamExt};
+use serde_json::Value;
+use tabled::Tabled;
+
+use crate::api::client::ApiClient;
+use crate::api::query;
+use crate::config::globals;
+use crate::utils::output::{self, Format};
+use crate::utils::spinner;
+
+/// Flags for `get` to fetch many resources at once instead of one ID
+#[derive(Args, Debug, Clone, Default)]
+pub struct IdsArgs {
+    /// File of IDs to fetch, one per line (`-` reads stdin)
+    #[arg(long, value_name = "FILE")]
+    pub ids_file: Option<String>,
+    /// Requests in flight at once with --ids-file
+    #[arg(long, default_value_t = 8, requires = "ids_file")]
+    pub concurrency: usize,
+}
+
+/// Flags for `delete` to remove many resources at once instead of one ID
+#[derive(Args, Debug, Clone, Default)]
+pub struct DeleteManyArgs {
+    /// File of IDs to delete, one per line (`-` reads stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "filters")]
+    pub ids_file: Option<String>,
+    /// Delete every resource whose FIELD matches PATTERN, where `*` and `?`
+    /// are wildcards, e.g. status=archived or name='test-*' (repeatable; all must match)
+    #[arg(long = "filter", value_name = "FIELD=PATTERN")]
+    pub filters: Vec<String>,
+    /// Requests in flight at once
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+    /// Delete without asking for confirmation
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+#[derive(Tabled, serde::Serialize)]
+struct DeleteRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Result")]
+    result: String,
+}
+
+/// IDs from a file or stdin, skipping blank lines and `#` comments
+fn read_ids(path: &str) -> Result<Vec<String>> {
+    let data = if path == "-" {
+        let mut data = String::new();
+        std::io::stdin().read_to_string(&mut data).context("Failed to read IDs from stdin")?;
+        data
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?
+    };
+    let ids: Vec<String> = data
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect();
+    if ids.is_empty() {
+        anyhow::bail!("No IDs found in {}", if path == "-" { "stdin" } else { path });
+    }
+    Ok(ids)
+}
+
+/// Fetch `{endpoint}/{id}` for every ID in `--ids-file` and print them as one
+/// array, or one line per resource as they arrive with `-o ndjson`. Failed IDs
+/// are reported on stderr and fail the command once the rest are printed.
+pub async fn get_many(client: &ApiClient, endpoint: &str, args: &IdsArgs) -> Result<()> {
+    let path = args.ids_file.as_deref().unwrap_or("-");
+    let ids = read_ids(path)?;
+    let total = ids.len();
+    let paths = ids.iter().map(|id| format!("{}/{}", endpoint, query::encode(id))).collect();
+    // `--query` runs once over the whole array, as with `-o json`
+    let streaming = output::format() == Format::Ndjson && globals::get().query.is_none();
+
+    let progress = spinner::create_progress(total as u64, "Fetching", "fetch");
+    let mut results = client.get_many::<Value>(paths, args.concurrency).enumerate();
+    let mut items = vec![];
+    let mut failed = 0;
+    while let Some((i, result)) = results.next().await {
+        progress.inc(1);
+        match result {
+            Ok(item) if streaming => progress.suspend(|| println!("{}", output::print_record(&item))),
+            Ok(item) => items.push(item),
+            Err(e) => {
+                failed += 1;
+                progress.suspend(|| output::error(&format!("{}: {:#}", ids[i], e)));
+            }
+        }
+    }
+    progress.finish_and_clear();
+
+    if !streaming {
+        println!("{}", output::print_detail(&items));
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} IDs could not be fetched", failed, total);
+    }
+    Ok(())
+}
+
+/// Delete `{endpoint}/{id}` for every ID in `--ids-file`, or for every listed
+/// resource matching all `--filter`s, after showing them and confirming.
+/// Failures are collected into the results table and fail the command at the end.
+pub async fn delete_many(client: &ApiClient, endpoint: &str, noun: &str, args: &DeleteManyArgs) -> Result<()> {
+    let targets: Vec<DeleteRow> = match &args.ids_file {
+        Some(path) => read_ids(path)?
+            .into_iter()
+            .map(|id| DeleteRow { id, name: String::new(), result: String::new() })
+            .collect(),
+        None => {
+            let filters = args.filters.iter().map(|f| parse_filter(f)).collect::<Result<Vec<_>>>()?;
+            let sp = spinner::create_spinner(&format!("Fetching {}...", noun));
+            let items: Vec<Value> = client.get_all(endpoint).await?;
+            sp.finish_and_clear();
+            items
+                .iter()
+                .filter(|item| filters.iter().all(|(field, pattern)| field_text(item, field).is_some_and(|v| glob_match(pattern, &v))))
+                .map(|item| DeleteRow {
+                    id: field_text(item, "id").unwrap_or_default(),
+                    name: field_text(item, "name").unwrap_or_default(),
+                    result: String::new(),
+                })
+                .collect()
+        }
+    };
+    if targets.is_empty() {
+        output::info(&format!("No {} match; nothing to delete.", noun));
+        return Ok(());
+    }
+
+    let total = targets.len();
+    if globals::get().dry_run {
+        println!("{}", output::print_table(&targets));
+        if output::human() {
+            println!();
+            output::info(&format!("Dry run: {} {} would be deleted.", total, noun));
+        }
+        return Ok(());
+    }
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Refusing to delete {} {} without confirmation; pass --yes", total, noun);
+        }
+        println!("{}", output::print_table(&targets));
+        println!();
+        let confirmed = Confirm::new()
+            .with_prompt(format!("  Delete {} {}?", total, noun))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            output::info("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let bar = spinner::create_progress(total as u64, "Deleting", "delete");
+    let mut results: Vec<(usize, DeleteRow, bool)> = stream::iter(targets.into_iter().enumerate())
+        .map(|(i, mut row)| {
+            let bar = bar.clone();
+            async move {
+                let result = client.delete_empty(&format!("{}/{}", endpoint, query::encode(&row.id))).await;
+                bar.inc(1);
+                let ok = result.is_ok();
+                row.result = match result {
+                    Ok(()) => "deleted".green().to_string(),
+                    Err(e) => format!("{}: {:#}", "failed".red(), e),
+                };
+                (i, row, ok)
+            }
+        })
+        .buffer_unordered(args.concurrency.max(1))
+        .collect()
+        .await;
+    bar.finish_and_clear();
+    results.sort_by_key(|(i, _, _)| *i);
+
+    let failed = results.iter().filter(|(_, _, ok)| !ok).count();
+    let rows: Vec<DeleteRow> = results.into_iter().map(|(_, row, _)| row).collect();
+    println!("{}", output::print_table(&rows));
+    if output::human() {
+        println!();
+        output::info(&format!("Deleted {} of {} {}, {} failed", total - failed, total, noun, failed));
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} {} could not be deleted", failed, total, noun);
+    }
+    Ok(())
+}
+
+/// Split `FIELD=PATTERN`
+fn parse_filter(filter: &str) -> Result<(String, String)> {
+    match filter.split_once('=') {
+        Some((field, pattern)) if !field.trim().is_empty() => Ok((field.trim().to_string(), pattern.to_string())),
+        _ => anyhow::bail!("Invalid --filter '{}'; expected FIELD=PATTERN, e.g. status=archived", filter),
+    }
+}
+
+/// A field as text, following dots into nested objects (`metadata.env`)
+fn field_text(item: &Value, field: &str) -> Option<String> {
+    let value = field.split('.').try_fold(item, |v, key| v.get(key))?;
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Case-insensitive match where `*` is any run of characters and `?` any one
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
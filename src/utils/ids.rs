@@ -0,0 +1,27 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A UUIDv7: the current Unix time in milliseconds followed by random bits,
+/// so IDs generated later sort later
+pub fn uuid_v7() -> String {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+    let mut bytes = [0u8; 16];
+    bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    fastrand::fill(&mut bytes[6..]);
+    bytes[6] = 0x70 | (bytes[6] & 0x0f);
+    bytes[8] = 0x80 | (bytes[8] & 0x3f);
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// 64-bit FNV-1a of `parts` as hex, stable across builds unlike `std`'s hashers
+pub fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // A separator keeps ("ab", "c") and ("a", "bc") apart
+        for byte in part.bytes().chain([0xff]) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,162 @@
+use std::fs::{self, OpenOptions};
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::prelude::*;
+
+use crate::utils::spinner;
+
+/// Logs are rotated to `<name>.1` once they grow past this size
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Flags whose values are replaced with `***` in the command history
+const SECRET_FLAGS: &[&str] = &["--api-key", "--password", "--token"];
+
+/// Subcommands whose positional arguments are secrets, e.g. `auth set-api-key <KEY>`;
+/// every later word that isn't a flag is replaced with `***`
+const SECRET_COMMANDS: &[&str] = &["set-api-key"];
+
+/// Headers whose values are replaced with `***` in `-vv` output
+const SECRET_HEADERS: &[&str] = &["authorization", "x-api-key", "cookie", "set-cookie", "proxy-authorization"];
+
+fn dir() -> PathBuf {
+    let home = dirs::home_dir().expect("Could not determine home directory");
+    home.join(".flexprice")
+}
+
+/// Returns the path to ~/.flexprice/cli.log (one line per API request)
+pub fn requests_path() -> PathBuf {
+    dir().join("cli.log")
+}
+
+/// Returns the path to ~/.flexprice/history.log (one line per invocation)
+pub fn history_path() -> PathBuf {
+    dir().join("history.log")
+}
+
+/// Send this crate's `tracing` events to stderr: request lines at `-v`,
+/// headers as well at `-vv`. Without `-v` nothing is logged there.
+pub fn init(verbosity: u8) {
+    let level = match verbosity {
+        0 => return,
+        1 => Level::INFO,
+        _ => Level::DEBUG,
+    };
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false)
+        .without_time()
+        .compact();
+    // Only our own events; the HTTP stack's internals are noise here
+    let filter = Targets::new().with_target(env!("CARGO_CRATE_NAME"), level);
+    tracing_subscriber::registry().with(layer.with_filter(filter)).init();
+}
+
+/// Record an API request, logging it to stderr with `-v`
+pub fn request(method: &str, url: &str, status: Option<u16>, elapsed: Duration, request_id: Option<&str>) {
+    let status = status.map(|s| s.to_string()).unwrap_or_else(|| "ERR".to_string());
+    let mut line = format!("{} {} -> {} ({}ms)", method, url, status, elapsed.as_millis());
+    if let Some(id) = request_id {
+        line.push_str(&format!(" request_id={}", id));
+    }
+    spinner::suspend_active(|| {
+        tracing::info!(
+            method = %method,
+            url = %url,
+            status = %status,
+            elapsed_ms = elapsed.as_millis() as u64,
+            request_id = %request_id.unwrap_or("-"),
+            "request"
+        )
+    });
+    append(&requests_path(), &line);
+}
+
+/// Log a GET answered from the response cache to stderr with `-v`; nothing
+/// was sent, so nothing goes to the request log
+pub fn cache_hit(url: &str, age: Duration) {
+    spinner::suspend_active(|| tracing::info!(url = %url, age_s = age.as_secs(), "cached"));
+}
+
+/// Log request or response headers with `-vv`, secrets redacted
+pub fn headers(direction: &str, headers: &HeaderMap) {
+    if !tracing::enabled!(Level::DEBUG) {
+        return;
+    }
+    spinner::suspend_active(|| {
+        for (name, value) in headers {
+            let value = if SECRET_HEADERS.contains(&name.as_str()) {
+                "***".into()
+            } else {
+                String::from_utf8_lossy(value.as_bytes())
+            };
+            tracing::debug!(header = %name, value = %value, "{}", direction);
+        }
+    });
+}
+
+/// Record a finished invocation with secrets redacted
+pub fn history(args: &[String], exit_code: i32) {
+    let args = redact_args(&args[1.min(args.len())..]);
+    append(&history_path(), &format!("flexprice {} (exit {})", args.join(" "), exit_code));
+}
+
+/// Command-line words with secret flag values and positionals replaced with
+/// `***`, and credentials taken out of `--proxy` URLs
+pub fn redact_args(args: &[String]) -> Vec<String> {
+    let mut redact_next = false;
+    let mut proxy_next = false;
+    let mut redact_positionals = false;
+    args.iter()
+        .map(|arg| {
+            if std::mem::take(&mut redact_next) || (redact_positionals && !arg.starts_with('-')) {
+                return "***".to_string();
+            }
+            if std::mem::take(&mut proxy_next) {
+                return strip_userinfo(arg);
+            }
+            if let Some(flag) = SECRET_FLAGS.iter().find(|f| arg.starts_with(&format!("{}=", f))) {
+                return format!("{}=***", flag);
+            }
+            if let Some(url) = arg.strip_prefix("--proxy=") {
+                return format!("--proxy={}", strip_userinfo(url));
+            }
+            redact_next = SECRET_FLAGS.contains(&arg.as_str());
+            proxy_next = arg == "--proxy";
+            redact_positionals |= SECRET_COMMANDS.contains(&arg.as_str());
+            arg.clone()
+        })
+        .collect()
+}
+
+/// `url` without a username or password; `***` if it has one but can't be parsed
+pub fn strip_userinfo(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) if !parsed.username().is_empty() || parsed.password().is_some() => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        Ok(_) => url.to_string(),
+        Err(_) if url.contains('@') => "***".to_string(),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Best-effort append; logging must never make a command fail
+fn append(path: &PathBuf, line: &str) {
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let _ = fs::rename(path, path.with_extension("log.1"));
+    }
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{} {}", chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true), line);
+    }
+}
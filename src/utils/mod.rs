@@ -1,2 +1,13 @@
+pub mod batch;
+pub mod chart;
+pub mod checkpoint;
+pub mod editor;
+pub mod fanout;
+pub mod ids;
+pub mod log;
 pub mod output;
+pub mod paging;
 pub mod spinner;
+pub mod time;
+pub mod wait;
+pub mod wizard;
@@ -1,35 +1,388 @@
+use std::cmp::Ordering;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
 use colored::Colorize;
-use tabled::{Table, settings::{Style, themes::Colorization, Color}};
+use serde_json::Value;
+use tabled::{Table, builder::Builder, settings::{Style, themes::Colorization, Color}};
 use tabled::settings::object::Rows;
 
-/// Format data as a pretty table or JSON based on output preference
-pub fn print_table<T: tabled::Tabled>(items: &[T], output_json: bool) -> String
+use crate::config::globals;
+use crate::utils::paging::Pagination;
+
+/// Output format selected with the global `--output`/`-o` flag
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Tables and colorized detail views
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+    /// One compact JSON object per line
+    Ndjson,
+}
+
+/// How a failed command reports its error, selected with `--error-format`
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// A colored line on stderr
+    #[default]
+    Text,
+    /// One JSON object on stderr with the error's kind, status, and exit code
+    Json,
+}
+
+/// The output format for this invocation
+pub fn format() -> Format {
+    globals::get().output
+}
+
+/// Whether output is meant for people rather than other programs. Decorative
+/// extras (headers, totals lines, hints) should only be printed when true.
+/// A `--query` always wants the data.
+pub fn human() -> bool {
+    format() == Format::Table && globals::get().query.is_none()
+}
+
+/// Check that a `--query` expression compiles
+pub fn parse_query(s: &str) -> Result<String, String> {
+    jmespath::compile(s).map(|_| s.to_string()).map_err(|e| e.to_string())
+}
+
+static QUERY_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Whether a `--query` failed on some output, so the command should exit non-zero
+pub fn query_failed() -> bool {
+    QUERY_FAILED.load(AtomicOrdering::Relaxed)
+}
+
+/// `value` narrowed by `--query`, in the selected format. A string result is
+/// printed bare in `table` and `csv` output so it can be captured in scripts.
+fn print_query(expression: &str, value: Value) -> String {
+    let result = jmespath::compile(expression)
+        .and_then(|expr| expr.search(value))
+        .map(|found| serde_json::to_value(&*found).unwrap_or_default());
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            error(&format!("--query failed: {}", e.to_string().trim_end()));
+            QUERY_FAILED.store(true, AtomicOrdering::Relaxed);
+            return String::new();
+        }
+    };
+    match (format(), result) {
+        (Format::Table | Format::Csv, Value::String(s)) => s,
+        (Format::Table | Format::Json, result) => serde_json::to_string_pretty(&result).unwrap_or_default(),
+        (Format::Yaml, result) => to_yaml(&result),
+        (Format::Ndjson, Value::Array(items)) => ndjson(&items),
+        (Format::Ndjson, result) => result.to_string(),
+        (Format::Csv, result) => detail_csv(result),
+    }
+}
+
+/// `--sort` column and direction
+#[derive(Clone, Debug)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+}
+
+/// Parse `COLUMN`, `COLUMN:asc`, or `COLUMN:desc`
+pub fn parse_sort(s: &str) -> Result<SortKey, String> {
+    let (column, direction) = s.split_once(':').unwrap_or((s, "asc"));
+    let descending = match direction.to_lowercase().as_str() {
+        "asc" => false,
+        "desc" => true,
+        _ => return Err(format!("invalid direction '{}': use asc or desc", direction)),
+    };
+    if column.trim().is_empty() {
+        return Err("missing column name".to_string());
+    }
+    Ok(SortKey { column: column.trim().to_string(), descending })
+}
+
+/// Render a listing in the selected output format, with `--sort` and
+/// `--columns` applied
+pub fn print_table<T>(items: &[T]) -> String
 where
-    T: serde::Serialize,
+    T: tabled::Tabled + serde::Serialize,
 {
-    if output_json {
-        serde_json::to_string_pretty(items).unwrap_or_else(|_| "[]".to_string())
-    } else if items.is_empty() {
-        format!("  {}", "No results found.".dimmed())
-    } else {
-        let mut table = Table::new(items);
-        table.with(Style::rounded());
-        table.with(Colorization::exact([Color::new("\x1b[1;36m", "\x1b[0m")], Rows::first()));
-        table.to_string()
+    if let Some(query) = &globals::get().query {
+        return print_query(query, listing(items));
+    }
+    if !items.is_empty() && shaping() {
+        return print_shaped(items);
+    }
+    match format() {
+        Format::Table if items.is_empty() => format!("  {}", "No results found.".dimmed()),
+        Format::Table => {
+            let mut table = Table::new(items);
+            style(&mut table);
+            table.to_string()
+        }
+        Format::Json => serde_json::to_string_pretty(items).unwrap_or_else(|_| "[]".to_string()),
+        Format::Yaml => to_yaml(items),
+        Format::Ndjson => ndjson(items),
+        Format::Csv => {
+            let header = T::headers().into_iter().map(|h| h.into_owned());
+            let rows = items.iter().map(|item| item.fields().into_iter().map(|f| f.into_owned()).collect());
+            to_csv(header.collect(), rows)
+        }
+    }
+}
+
+fn style(table: &mut Table) {
+    table.with(Style::rounded());
+    table.with(Colorization::exact([Color::new("\x1b[1;36m", "\x1b[0m")], Rows::first()));
+}
+
+/// A listing reordered and cut down to the requested columns. Rows are read
+/// as JSON objects, so a column can be named by its JSON field (`customer_id`)
+/// or its table header (`Customer`).
+struct Shaped {
+    values: Vec<Value>,
+    keys: Vec<String>,
+    headers: Vec<String>,
+    /// Whether each JSON field has its own table column, in the same order
+    aligned: bool,
+    /// Row indices in display order
+    order: Vec<usize>,
+    /// Indices into `keys` of the columns to show
+    selected: Vec<usize>,
+}
+
+impl Shaped {
+    fn new<T: tabled::Tabled + serde::Serialize>(items: &[T], sort: Option<&SortKey>, columns: &[String]) -> Self {
+        let values: Vec<Value> = items.iter().map(|item| serde_json::to_value(item).unwrap_or_default()).collect();
+        let keys: Vec<String> = values.first().and_then(Value::as_object).map(|o| o.keys().cloned().collect()).unwrap_or_default();
+        let headers: Vec<String> = T::headers().into_iter().map(|h| h.into_owned()).collect();
+        let aligned = headers.len() == keys.len();
+        let normalize = |s: &str| s.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+        let find = |name: &str| {
+            let name = normalize(name);
+            keys.iter()
+                .position(|k| normalize(k) == name)
+                .or_else(|| headers.iter().position(|h| normalize(h) == name).filter(|_| aligned))
+        };
+        let unknown = |flag: &str, name: &str| {
+            warning(&format!("No column '{}' for {}; available: {}", name, flag, keys.join(", ")));
+        };
+
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        if let Some(key) = sort {
+            match find(&key.column) {
+                Some(column) => order.sort_by(|&a, &b| {
+                    let cell = |i: usize| values[i].get(&keys[column]).filter(|v| !v.is_null());
+                    match (cell(a), cell(b)) {
+                        // Blanks go last whichever way the rest is sorted
+                        (None, None) => Ordering::Equal,
+                        (None, Some(_)) => Ordering::Greater,
+                        (Some(_), None) => Ordering::Less,
+                        (Some(x), Some(y)) if key.descending => compare(y, x),
+                        (Some(x), Some(y)) => compare(x, y),
+                    }
+                }),
+                None => unknown("--sort", &key.column),
+            }
+        }
+        let mut selected: Vec<usize> = vec![];
+        for name in columns {
+            match find(name) {
+                Some(column) => selected.push(column),
+                None => unknown("--columns", name),
+            }
+        }
+        if selected.is_empty() {
+            selected = (0..keys.len()).collect();
+        }
+        Self { values, keys, headers, aligned, order, selected }
+    }
+
+    /// The rows as JSON objects holding only the selected fields
+    fn objects(&self) -> Vec<Value> {
+        self.order
+            .iter()
+            .map(|&i| {
+                let row = self
+                    .selected
+                    .iter()
+                    .map(|&c| (self.keys[c].clone(), self.values[i].get(&self.keys[c]).cloned().unwrap_or_default()))
+                    .collect();
+                Value::Object(row)
+            })
+            .collect()
+    }
+}
+
+/// A listing as `-o json` prints it
+fn listing<T: tabled::Tabled + serde::Serialize>(items: &[T]) -> Value {
+    if items.is_empty() || !shaping() {
+        return serde_json::to_value(items).unwrap_or_default();
+    }
+    let opts = globals::get();
+    Value::Array(Shaped::new(items, opts.sort.as_ref(), &opts.columns).objects())
+}
+
+/// Whether `--sort` or `--columns` was given
+fn shaping() -> bool {
+    let opts = globals::get();
+    opts.sort.is_some() || !opts.columns.is_empty()
+}
+
+/// Render a listing with `--sort` and `--columns` applied. Tables and CSV keep
+/// each cell as the row type formats it whenever its fields and headers line
+/// up one to one.
+fn print_shaped<T>(items: &[T]) -> String
+where
+    T: tabled::Tabled + serde::Serialize,
+{
+    let opts = globals::get();
+    let shaped = Shaped::new(items, opts.sort.as_ref(), &opts.columns);
+    match format() {
+        Format::Table | Format::Csv => {
+            let Shaped { values, keys, headers, aligned, order, selected } = &shaped;
+            let header: Vec<String> = selected.iter().map(|&c| if *aligned { headers[c].clone() } else { keys[c].clone() }).collect();
+            let rows = order.iter().map(|&i| {
+                let cells: Vec<String> = if *aligned {
+                    items[i].fields().into_iter().map(|f| f.into_owned()).collect()
+                } else {
+                    keys.iter().map(|k| plain(values[i].get(k))).collect()
+                };
+                selected.iter().map(|&c| cells.get(c).cloned().unwrap_or_default()).collect::<Vec<_>>()
+            });
+            if format() == Format::Csv {
+                return to_csv(header, rows);
+            }
+            let mut builder = Builder::default();
+            builder.push_record(header);
+            for row in rows {
+                builder.push_record(row);
+            }
+            let mut table = builder.build();
+            style(&mut table);
+            table.to_string()
+        }
+        Format::Ndjson => ndjson(&shaped.objects()),
+        Format::Yaml => to_yaml(&shaped.objects()),
+        Format::Json => serde_json::to_string_pretty(&shaped.objects()).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+/// Order two cells: numerically when both read as numbers (amounts are often
+/// strings), otherwise as text without any color codes
+fn compare(a: &Value, b: &Value) -> Ordering {
+    let text = |v: &Value| console::strip_ansi_codes(&plain(Some(v))).into_owned();
+    let (a, b) = (text(a), text(b));
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// A JSON value as a table cell: strings unquoted, null empty
+fn plain(v: Option<&Value>) -> String {
+    match v {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Render a single item (or any JSON-serializable value) in the selected
+/// output format; `table` shows it as colorized JSON
+pub fn print_detail<T: serde::Serialize>(item: &T) -> String {
+    if let Some(query) = &globals::get().query {
+        return print_query(query, serde_json::to_value(item).unwrap_or_default());
+    }
+    match format() {
+        Format::Table => {
+            let json = serde_json::to_string_pretty(item).unwrap_or_else(|_| "{}".to_string());
+            colorize_json(&json)
+        }
+        Format::Json => serde_json::to_string_pretty(item).unwrap_or_else(|_| "{}".to_string()),
+        Format::Yaml => to_yaml(item),
+        Format::Ndjson => match serde_json::to_value(item) {
+            Ok(Value::Array(items)) => ndjson(&items),
+            Ok(value) => value.to_string(),
+            Err(_) => "{}".to_string(),
+        },
+        Format::Csv => detail_csv(serde_json::to_value(item).unwrap_or_default()),
     }
 }
 
-/// Print a single item as pretty JSON or a key-value display
-pub fn print_detail<T: serde::Serialize>(item: &T, output_json: bool) -> String {
-    if output_json {
-        serde_json::to_string_pretty(item).unwrap_or_else(|_| "{}".to_string())
-    } else {
-        // Use colored JSON for non-json output too (looks nice)
-        let json = serde_json::to_string_pretty(item).unwrap_or_else(|_| "{}".to_string());
-        colorize_json(&json)
+/// Like `print_detail`, but `table` shows plain pretty JSON, for payloads
+/// meant to be copied or piped as-is
+pub fn print_raw<T: serde::Serialize>(item: &T) -> String {
+    if globals::get().query.is_some() {
+        return print_detail(item);
+    }
+    match format() {
+        Format::Table => serde_json::to_string_pretty(item).unwrap_or_else(|_| "{}".to_string()),
+        _ => print_detail(item),
     }
 }
 
+/// Render one record of a stream (e.g. `events tail`) as a single line
+pub fn print_record<T: serde::Serialize>(item: &T) -> String {
+    if globals::get().query.is_some() {
+        return print_detail(item);
+    }
+    match format() {
+        Format::Json | Format::Ndjson => serde_json::to_string(item).unwrap_or_else(|_| "{}".to_string()),
+        _ => print_detail(item).trim_end().to_string(),
+    }
+}
+
+fn to_yaml<T: serde::Serialize + ?Sized>(value: &T) -> String {
+    serde_yaml::to_string(value).unwrap_or_default().trim_end().to_string()
+}
+
+fn ndjson<T: serde::Serialize>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| serde_json::to_string(item).unwrap_or_else(|_| "{}".to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// An object becomes one row, an array of objects one row each, with a
+/// column per key seen. Nested values are written as compact JSON.
+fn detail_csv(value: Value) -> String {
+    let records = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+    let mut header: Vec<String> = vec![];
+    for record in &records {
+        match record {
+            Value::Object(obj) => {
+                for key in obj.keys() {
+                    if !header.contains(key) {
+                        header.push(key.clone());
+                    }
+                }
+            }
+            _ if header.is_empty() => header.push("value".to_string()),
+            _ => {}
+        }
+    }
+    let rows = records.iter().map(|record| match record {
+        Value::Object(obj) => header.iter().map(|key| plain(obj.get(key))).collect(),
+        other => vec![plain(Some(other))],
+    });
+    to_csv(header.clone(), rows)
+}
+
+fn to_csv(header: Vec<String>, rows: impl Iterator<Item = Vec<String>>) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let _ = writer.write_record(&header);
+    for row in rows {
+        let _ = writer.write_record(&row);
+    }
+    let bytes = writer.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).trim_end().to_string()
+}
+
 /// Colorize a JSON string for terminal output
 fn colorize_json(json: &str) -> String {
     let mut result = String::new();
@@ -50,8 +403,11 @@ fn colorize_json(json: &str) -> String {
     result
 }
 
-/// Print a success message with a checkmark
+/// Print a success message with a checkmark, unless `--quiet` or `--query`
 pub fn success(msg: &str) {
+    if globals::get().quiet || globals::get().query.is_some() {
+        return;
+    }
     println!("  {} {}", "✓".green().bold(), msg);
 }
 
@@ -65,22 +421,153 @@ pub fn warning(msg: &str) {
     eprintln!("  {} {}", "⚠".yellow().bold(), msg);
 }
 
-/// Print an info message
+/// Print an info message, unless `--quiet` or `--query`
 pub fn info(msg: &str) {
+    if globals::get().quiet || globals::get().query.is_some() {
+        return;
+    }
     println!("  {} {}", "ℹ".blue().bold(), msg);
 }
 
 /// Status badge with color based on status string
 pub fn status_badge(status: &str) -> String {
     match status.to_lowercase().as_str() {
-        "active" | "published" | "paid" | "finalized" => format!("{}", status.green().bold()),
+        "active" | "published" | "paid" | "finalized" | "completed" => format!("{}", status.green().bold()),
         "draft" | "pending" => format!("{}", status.yellow()),
-        "cancelled" | "canceled" | "void" | "voided" | "inactive" => format!("{}", status.red()),
-        "trialing" | "paused" => format!("{}", status.blue()),
+        "cancelled" | "canceled" | "void" | "voided" | "inactive" | "failed" => format!("{}", status.red()),
+        "trialing" | "paused" | "processing" => format!("{}", status.blue()),
         _ => status.to_string(),
     }
 }
 
+/// Client-side totals for one currency in a listing
+#[derive(serde::Serialize)]
+pub struct CurrencySummary {
+    pub currency: String,
+    pub count: usize,
+    pub total: f64,
+    pub average: f64,
+}
+
+/// Group `(currency, amount)` pairs into per-currency count, sum, and average
+pub fn summarize<'a>(amounts: impl IntoIterator<Item = (&'a str, f64)>) -> Vec<CurrencySummary> {
+    let mut by_currency: std::collections::BTreeMap<String, (usize, f64)> = Default::default();
+    for (currency, amount) in amounts {
+        let entry = by_currency.entry(currency.to_uppercase()).or_default();
+        entry.0 += 1;
+        entry.1 += amount;
+    }
+    by_currency
+        .into_iter()
+        .map(|(currency, (count, total))| CurrencySummary {
+            currency,
+            count,
+            total,
+            average: total / count as f64,
+        })
+        .collect()
+}
+
+/// Render a totals footer, one line per currency
+pub fn print_summary(summary: &[CurrencySummary], noun: &str) -> String {
+    summary
+        .iter()
+        .map(|s| {
+            let total = format!("{:>12.2}", s.total);
+            let total = if s.total < 0.0 { total.red().bold() } else { total.green().bold() };
+            format!(
+                "  {:<4} {} {}  {}",
+                s.currency.cyan().bold(),
+                total,
+                format!("across {} {}{}", s.count, noun, if s.count == 1 { "" } else { "s" }).dimmed(),
+                format!("avg {:.2}", s.average).dimmed(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Print a listing, with a per-currency totals footer when `summary` is given.
+/// As JSON or YAML the output becomes `{"items": [...], "summary": [...]}`;
+/// CSV and NDJSON keep one row per item and leave the summary out.
+pub fn print_table_with_summary<T>(
+    items: &[T],
+    summary: Option<&[CurrencySummary]>,
+    noun: &str,
+) -> String
+where
+    T: tabled::Tabled + serde::Serialize,
+{
+    match (summary, format()) {
+        (None, _) | (Some(_), Format::Csv | Format::Ndjson) => print_table(items),
+        (Some(summary), Format::Json | Format::Yaml) => {
+            print_detail(&serde_json::json!({ "items": listing(items), "summary": summary }))
+        }
+        (Some(summary), Format::Table) if globals::get().query.is_some() => {
+            print_detail(&serde_json::json!({ "items": listing(items), "summary": summary }))
+        }
+        (Some(_), Format::Table) if items.is_empty() => print_table(items),
+        (Some(summary), Format::Table) => format!("{}\n\n{}", print_table(items), print_summary(summary, noun)),
+    }
+}
+
+/// Print one page of a listing, with an optional per-currency summary. Tables
+/// get a `Showing 25 of 1,204` footer when there are more results than shown.
+/// As JSON or YAML the output becomes `{"items": [...], "pagination": {...}}`
+/// (plus `"summary"`) unless `--raw`; CSV and NDJSON keep one row per item.
+pub fn print_page<T>(items: &[T], pagination: &Pagination, summary: Option<&[CurrencySummary]>, noun: &str) -> String
+where
+    T: tabled::Tabled + serde::Serialize,
+{
+    if globals::get().raw {
+        return print_table_with_summary(items, summary, noun);
+    }
+    if !human() {
+        return match format() {
+            Format::Csv | Format::Ndjson if globals::get().query.is_none() => print_table(items),
+            _ => {
+                let mut envelope = serde_json::json!({ "items": listing(items), "pagination": pagination });
+                if let Some(summary) = summary {
+                    envelope["summary"] = serde_json::to_value(summary).unwrap_or_default();
+                }
+                print_detail(&envelope)
+            }
+        };
+    }
+    let table = print_table_with_summary(items, summary, noun);
+    match page_footer(pagination) {
+        Some(footer) => format!("{}\n  {}", table, footer.dimmed()),
+        None => table,
+    }
+}
+
+/// `Showing 51–100 of 1,204 (use --page/--all)`, when the listing is one page of several
+fn page_footer(p: &Pagination) -> Option<String> {
+    let total = p.total?;
+    if p.offset == 0 && !p.has_more {
+        return None;
+    }
+    let shown = match (p.offset, p.count) {
+        (_, 0) => "0".to_string(),
+        (0, count) => thousands(count as i64),
+        (offset, count) => format!("{}–{}", thousands(offset as i64 + 1), thousands((offset + count as u64) as i64)),
+    };
+    Some(format!("Showing {} of {} (use --page/--all)", shown, thousands(total)))
+}
+
+/// `1204` as `1,204`
+fn thousands(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    if n < 0 { format!("-{}", out) } else { out }
+}
+
 /// The FlexPrice ASCII art banner
 pub fn print_banner() {
     let banner = r#"
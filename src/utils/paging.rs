@@ -0,0 +1,73 @@
+use anyhow::Result;
+use clap::Args;
+use serde::de::DeserializeOwned;
+
+use crate::api::client::ApiClient;
+use crate::api::models::ListResponse;
+use crate::api::query::QueryBuilder;
+
+/// Flags for `list` to pick one page of results, or fetch them all
+#[derive(Args, Debug, Clone)]
+pub struct PageArgs {
+    /// Page to show, counting from 1
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "all")]
+    pub page: u64,
+    /// Results per page
+    #[arg(long, value_name = "N", default_value_t = 50, value_parser = clap::value_parser!(u64).range(1..=1000))]
+    pub page_size: u64,
+    /// Fetch every page instead of one
+    #[arg(long)]
+    pub all: bool,
+}
+
+/// Where a listing sits in the full result set, as the `pagination` field of
+/// the `-o json` envelope
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Pagination {
+    /// Absent with `--all`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u64>,
+    pub offset: u64,
+    pub count: usize,
+    /// Null when the server doesn't report a total
+    pub total: Option<i64>,
+    pub has_more: bool,
+}
+
+impl Pagination {
+    /// A listing that holds every result
+    pub fn complete(count: usize) -> Self {
+        Self { page: None, page_size: None, offset: 0, count, total: Some(count as i64), has_more: false }
+    }
+}
+
+impl PageArgs {
+    /// Fetch the requested page of a `limit`/`offset` list endpoint, or every
+    /// page with `--all`
+    pub async fn fetch<T: DeserializeOwned>(&self, client: &ApiClient, path: &str) -> Result<(Vec<T>, Pagination)> {
+        if self.all {
+            let items: Vec<T> = client.get_all(path).await?;
+            let pagination = Pagination::complete(items.len());
+            return Ok((items, pagination));
+        }
+        let offset = (self.page - 1) * self.page_size;
+        let page_path = QueryBuilder::new().param("limit", self.page_size).param("offset", offset).to_path(path);
+        let resp: ListResponse<T> = client.get(&page_path).await?;
+        let count = resp.items.len();
+        let has_more = match resp.total_count {
+            Some(total) => (offset + count as u64) < total.max(0) as u64,
+            None => count as u64 >= self.page_size,
+        };
+        let pagination = Pagination {
+            page: Some(self.page),
+            page_size: Some(self.page_size),
+            offset,
+            count,
+            total: resp.total_count,
+            has_more,
+        };
+        Ok((resp.items, pagination))
+    }
+}
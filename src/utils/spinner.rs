@@ -1,15 +1,190 @@
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle, WeakProgressBar};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use crate::config::globals;
+
+/// How progress is reported, selected with the global `--progress` flag
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Spinners and progress bars on the terminal
+    #[default]
+    Auto,
+    /// One JSON object per update on stderr, no spinner art
+    Json,
+    /// Nothing
+    None,
+}
+
+fn mode() -> ProgressMode {
+    let opts = globals::get();
+    // --quiet hides spinners but leaves explicitly requested JSON events alone
+    if opts.quiet && opts.progress == ProgressMode::Auto {
+        return ProgressMode::None;
+    }
+    opts.progress
+}
+
+/// The most recently created spinner and its original message, so that code
+/// deep in the call stack (e.g. the API client) can report progress on it
+static ACTIVE: Mutex<Option<(WeakProgressBar, String)>> = Mutex::new(None);
+
 /// Create a styled spinner for loading operations
 pub fn create_spinner(msg: &str) -> ProgressBar {
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
-        ProgressStyle::with_template("  {spinner:.cyan} {msg}")
+        ProgressStyle::with_template("  {spinner:.cyan} {msg} {elapsed_hint:.dim}")
             .unwrap()
+            .with_key("elapsed_hint", elapsed_hint)
             .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏", "✓"]),
     );
     spinner.set_message(msg.to_string());
-    spinner.enable_steady_tick(Duration::from_millis(80));
+    if mode() == ProgressMode::Auto {
+        spinner.enable_steady_tick(Duration::from_millis(80));
+    } else {
+        spinner.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    *ACTIVE.lock().unwrap() = Some((spinner.downgrade(), msg.to_string()));
     spinner
 }
+
+/// Run `f` with the most recent spinner hidden, if it is still drawing, so
+/// lines written to stderr meanwhile don't tangle with it
+pub fn suspend_active<R>(f: impl FnOnce() -> R) -> R {
+    let active = ACTIVE.lock().unwrap().as_ref().and_then(|(bar, _)| bar.upgrade());
+    match active {
+        Some(bar) if !bar.is_finished() => bar.suspend(f),
+        _ => f(),
+    }
+}
+
+/// Create a progress bar for work with a known number of steps. `phase`
+/// names the work in `--progress json` events.
+pub fn create_progress(len: u64, msg: &str, phase: &str) -> Progress {
+    let bar = ProgressBar::new(len);
+    styled(bar, "  {msg} [{bar:30.cyan/blue}] {pos}/{len} ({eta})", msg, phase)
+}
+
+/// Create a progress bar counting bytes, for downloads whose size is set
+/// once the response arrives
+pub fn create_transfer(msg: &str, phase: &str) -> Progress {
+    let bar = ProgressBar::no_length();
+    styled(bar, "  {msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({binary_bytes_per_sec})", msg, phase)
+}
+
+fn styled(bar: ProgressBar, template: &str, msg: &str, phase: &str) -> Progress {
+    bar.set_style(ProgressStyle::with_template(template).unwrap().progress_chars("█▓░"));
+    bar.set_message(msg.to_string());
+    if mode() != ProgressMode::Auto {
+        bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    Progress { bar, phase: phase.into() }
+}
+
+/// A progress bar that, with `--progress json`, reports each update as an
+/// event on stderr instead of drawing
+#[derive(Clone)]
+pub struct Progress {
+    bar: ProgressBar,
+    phase: Arc<str>,
+}
+
+impl Progress {
+    pub fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+        self.report(None);
+    }
+
+    pub fn set_length(&self, len: u64) {
+        if self.bar.length() != Some(len) {
+            self.bar.set_length(len);
+            self.report(None);
+        }
+    }
+
+    pub fn set_position(&self, pos: u64) {
+        if self.bar.position() != pos {
+            self.bar.set_position(pos);
+            self.report(None);
+        }
+    }
+
+    /// Run `f` with the bar hidden, so lines printed meanwhile don't tangle with it
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.bar.suspend(f)
+    }
+
+    pub fn finish_and_clear(&self) {
+        self.bar.finish_and_clear();
+        self.report(Some("finished"));
+    }
+
+    pub fn abandon(&self) {
+        self.bar.abandon();
+        self.report(Some("abandoned"));
+    }
+
+    fn report(&self, state: Option<&str>) {
+        let mut event = serde_json::json!({
+            "phase": &*self.phase,
+            "done": self.bar.position(),
+            "total": self.bar.length(),
+        });
+        if let Some(state) = state {
+            event["state"] = state.into();
+        }
+        emit(event);
+    }
+}
+
+/// Write a progress event to stderr when `--progress json` is set
+pub fn emit(event: serde_json::Value) {
+    if mode() == ProgressMode::Json {
+        eprintln!("{}", event);
+    }
+}
+
+/// Show elapsed time once an operation has taken long enough to notice
+fn elapsed_hint(state: &ProgressState, w: &mut dyn Write) {
+    let secs = state.elapsed().as_secs();
+    if secs >= 2 {
+        let _ = write!(w, "({}s)", secs);
+    }
+}
+
+/// Wait out a retry backoff, counting down on the active spinner.
+///
+/// `attempt` is the upcoming attempt number (2 for the first retry).
+pub async fn backoff(attempt: u32, max_attempts: u32, delay: Duration) {
+    emit(serde_json::json!({
+        "phase": "retry",
+        "attempt": attempt,
+        "max_attempts": max_attempts,
+        "wait_ms": delay.as_millis() as u64,
+    }));
+    let active = ACTIVE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|(bar, msg)| bar.upgrade().map(|b| (b, msg.clone())));
+    let Some((bar, msg)) = active else {
+        tokio::time::sleep(delay).await;
+        return;
+    };
+
+    let mut remaining = delay;
+    while !remaining.is_zero() {
+        bar.set_message(format!(
+            "{} attempt {}/{} — retrying in {}s",
+            msg,
+            attempt,
+            max_attempts,
+            remaining.as_secs_f64().ceil() as u64
+        ));
+        let step = remaining.min(Duration::from_secs(1));
+        tokio::time::sleep(step).await;
+        remaining -= step;
+    }
+    bar.set_message(format!("{} attempt {}/{}", msg, attempt, max_attempts));
+}
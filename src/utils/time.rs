@@ -0,0 +1,162 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, SecondsFormat, TimeZone, Utc};
+
+/// A time range where either bound may be left to the server's default
+#[derive(Debug, Clone, Default)]
+pub struct Period {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl Period {
+    /// Limit the period to what had been recorded at `at` (`--as-of`).
+    ///
+    /// Without an explicit start the server would use its *current* billing
+    /// period, which may begin after `at`, so the calendar month containing
+    /// `at` is used instead.
+    pub fn until(self, at: DateTime<Utc>) -> Period {
+        let start = self.start.unwrap_or_else(|| {
+            let day = at.date_naive();
+            start_of_day(day.with_day(1).unwrap_or(day))
+        });
+        let end = self.end.map_or(at, |end| end.min(at));
+        Period { start: Some(start), end: Some(end) }
+    }
+}
+
+/// Parse a `--period` value.
+///
+/// Accepts `current` (the server's current billing period), a month
+/// (`2024-03`), or an explicit range `START..END` where each side is a date
+/// (`2024-03-01`) or an RFC 3339 timestamp. Either side of a range may be empty.
+pub fn parse_period(s: &str) -> Result<Period> {
+    let s = s.trim();
+    if s.is_empty() || s.eq_ignore_ascii_case("current") {
+        return Ok(Period::default());
+    }
+    if let Some((start, end)) = s.split_once("..") {
+        return Ok(Period {
+            start: if start.is_empty() { None } else { Some(parse_instant(start)?) },
+            end: if end.is_empty() { None } else { Some(parse_instant(end)?) },
+        });
+    }
+    if let Ok(month) = NaiveDate::parse_from_str(&format!("{}-01", s), "%Y-%m-%d") {
+        let next = month
+            .checked_add_months(Months::new(1))
+            .ok_or_else(|| anyhow::anyhow!("Invalid period '{}': the month after it is out of range", s))?;
+        return Ok(Period {
+            start: Some(start_of_day(month)),
+            end: Some(start_of_day(next)),
+        });
+    }
+    anyhow::bail!("Invalid period '{}': expected `current`, `YYYY-MM`, or `START..END`", s)
+}
+
+/// Parse a date (`2024-03-01`, midnight UTC) or an RFC 3339 timestamp
+pub fn parse_instant(s: &str) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(start_of_day(date));
+    }
+    anyhow::bail!("Invalid date '{}': expected YYYY-MM-DD or an RFC 3339 timestamp", s)
+}
+
+/// Cap the `end_time` of a usage query body at `at` (`--as-of`)
+pub fn cap_end_time(body: &mut serde_json::Value, at: DateTime<Utc>) {
+    let end = body
+        .get("end_time")
+        .and_then(|v| v.as_str())
+        .and_then(|s| parse_instant(s).ok())
+        .map_or(at, |end| end.min(at));
+    body["end_time"] = end.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+}
+
+/// Render a billing period for people, e.g.
+/// `Mar 1 → Apr 1 (monthly, 12 days remaining)`. Years are shown only for
+/// dates outside the current year. `None` if either bound doesn't parse.
+pub fn describe_period(start: &str, end: &str, cadence: Option<&str>, now: DateTime<Utc>) -> Option<String> {
+    let (start, end) = (parse_instant(start).ok()?, parse_instant(end).ok()?);
+    let date = |d: DateTime<Utc>| {
+        if d.year() == now.year() {
+            d.format("%b %-d").to_string()
+        } else {
+            d.format("%b %-d, %Y").to_string()
+        }
+    };
+    let relative = if now < start {
+        format!("starts in {}", days(start - now))
+    } else if now < end {
+        format!("{} remaining", days(end - now))
+    } else {
+        format!("ended {} ago", days(now - end))
+    };
+    let notes = match cadence.filter(|c| !c.is_empty()) {
+        Some(cadence) => format!("{}, {}", cadence.to_lowercase().replace('_', " "), relative),
+        None => relative,
+    };
+    Some(format!("{} → {} ({})", date(start), date(end), notes))
+}
+
+/// `item` as JSON with the `start` and `end` fields swapped for a single
+/// `field` holding a readable period, placed where `start` was
+pub fn replace_period<T: serde::Serialize>(item: &T, start: &str, end: &str, field: &str, period: String) -> serde_json::Value {
+    let value = serde_json::to_value(item).unwrap_or_default();
+    let Some(obj) = value.as_object() else { return value };
+    obj.iter()
+        .filter(|(k, _)| k.as_str() != end)
+        .map(|(k, v)| if k == start { (field.to_string(), period.clone().into()) } else { (k.clone(), v.clone()) })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn days(d: Duration) -> String {
+    match d.num_days() {
+        0 => "less than a day".to_string(),
+        1 => "1 day".to_string(),
+        n => format!("{} days", n),
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("valid time"))
+}
+
+/// Parse a short duration such as `90s`, `15m`, `24h`, `30d`, or `2w`
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: i64 = num
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration '{}': expected e.g. 30d, 24h, 15m", s))?;
+    let duration = match unit {
+        "s" => Duration::try_seconds(n),
+        "m" => Duration::try_minutes(n),
+        "h" => Duration::try_hours(n),
+        "d" | "" => Duration::try_days(n),
+        "w" => Duration::try_weeks(n),
+        _ => anyhow::bail!("Invalid duration unit '{}' in '{}': use s, m, h, d, or w", unit, s),
+    };
+    duration.ok_or_else(|| anyhow::anyhow!("Duration '{}' is out of range", s))
+}
+
+/// `d` before `at`, failing rather than overflowing for huge durations
+pub fn before(at: DateTime<Utc>, d: Duration) -> Result<DateTime<Utc>> {
+    at.checked_sub_signed(d).ok_or_else(|| anyhow::anyhow!("{} days before {} is out of range", d.num_days(), at.date_naive()))
+}
+
+/// `d` after `at`, failing rather than overflowing for huge durations
+pub fn after(at: DateTime<Utc>, d: Duration) -> Result<DateTime<Utc>> {
+    at.checked_add_signed(d).ok_or_else(|| anyhow::anyhow!("{} days after {} is out of range", d.num_days(), at.date_naive()))
+}
+
+/// Fail unless the times `d` either side of `at` exist, for spans that are
+/// later added to or taken from `at` without checks
+pub fn check_span(at: DateTime<Utc>, d: Duration) -> Result<()> {
+    before(at, d)?;
+    after(at, d)?;
+    Ok(())
+}
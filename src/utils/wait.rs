@@ -0,0 +1,115 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clap::Args;
+use serde_json::Value;
+
+use crate::api::client::ApiClient;
+use crate::utils::{output, spinner, time};
+
+/// Time between status checks
+const INTERVAL: Duration = Duration::from_secs(2);
+
+/// `--wait` for operations the server finishes in the background
+#[derive(Args)]
+pub struct WaitArgs {
+    /// Poll until the operation settles, printing each status change
+    #[arg(long)]
+    pub wait: bool,
+    /// How long --wait polls before giving up, e.g. 90s, 5m
+    #[arg(long, value_name = "DURATION", default_value = "60s", requires = "wait")]
+    pub timeout: String,
+}
+
+impl WaitArgs {
+    /// The `--timeout`, checked before anything is sent
+    pub fn timeout(&self) -> Result<Duration> {
+        Ok(time::parse_duration(&self.timeout)?.to_std()?)
+    }
+}
+
+/// Where a polled operation has got to
+pub enum Status<T> {
+    /// Still in progress, e.g. `processing`
+    Pending(String),
+    /// Won't change again; carries what the caller needs from the last check
+    Settled(String, T),
+}
+
+/// Call `check` until it reports a settled status, printing each change of
+/// status as it's seen. Returns `None` if interrupted with Ctrl-C and fails if
+/// `timeout` elapses first.
+pub async fn poll<T, F, Fut>(what: &str, timeout: Duration, mut check: F) -> Result<Option<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Status<T>>>,
+{
+    let started = Instant::now();
+    let sp = spinner::create_spinner(&format!("Waiting for {}...", what));
+    let mut last: Option<String> = None;
+    loop {
+        let status = match check().await {
+            Ok(status) => status,
+            Err(e) => {
+                sp.finish_and_clear();
+                return Err(e);
+            }
+        };
+        let (state, settled) = match status {
+            Status::Pending(state) => (state, None),
+            Status::Settled(state, value) => (state, Some(value)),
+        };
+        if last.as_deref() != Some(state.as_str()) {
+            let message = match &last {
+                None => format!("{}: {}", what, state),
+                Some(before) => format!("{}: {} → {}", what, before, state),
+            };
+            sp.suspend(|| output::info(&message));
+            last = Some(state);
+        }
+        if let Some(value) = settled {
+            sp.finish_and_clear();
+            return Ok(Some(value));
+        }
+
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            sp.finish_and_clear();
+            anyhow::bail!(
+                "Timed out after {}s waiting for {} ({})",
+                timeout.as_secs(),
+                what,
+                last.unwrap_or_default()
+            );
+        }
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                sp.finish_and_clear();
+                return Ok(None);
+            }
+            _ = tokio::time::sleep(INTERVAL.min(remaining)) => {}
+        }
+    }
+}
+
+/// Poll the resource at `path` until its `field` holds one of `terminal`
+/// (compared case-insensitively), returning the resource as last fetched
+pub async fn resource(
+    client: &ApiClient,
+    path: &str,
+    what: &str,
+    field: &str,
+    terminal: &[&str],
+    timeout: Duration,
+) -> Result<Option<Value>> {
+    poll(what, timeout, || async {
+        let current: Value = client.get(path).await?;
+        let state = current.get(field).and_then(Value::as_str).unwrap_or("unknown").to_uppercase();
+        Ok(match terminal.contains(&state.as_str()) {
+            true => Status::Settled(state.to_lowercase(), current),
+            false => Status::Pending(state.to_lowercase()),
+        })
+    })
+    .await
+}
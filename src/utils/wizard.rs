@@ -0,0 +1,218 @@
+use std::io::IsTerminal;
+
+use anyhow::{Context, Result};
+use dialoguer::{Confirm, Input, Select};
+use serde::de::DeserializeOwned;
+
+use crate::utils::output;
+
+/// An interactive prompt sequence that builds a create payload
+pub struct Wizard {
+    /// Resource name used in prompts, e.g. "customer"
+    pub kind: &'static str,
+    pub fields: &'static [Field],
+    /// Rules the payload must follow beyond the schema, checked before the preview
+    pub check: Option<fn(&serde_json::Value) -> Result<()>>,
+}
+
+/// One prompted field; `key` may be dotted (`aggregation.type`) to nest objects
+pub struct Field {
+    pub key: &'static str,
+    pub prompt: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+}
+
+pub enum FieldKind {
+    /// Free text with an optional default
+    Text(Option<&'static str>),
+    /// A number with a default
+    Number(f64),
+    /// One of a fixed set of values; the first is the default
+    Choice(&'static [&'static str]),
+    /// Comma-separated `key=value` pairs collected into an object
+    Map,
+    /// Comma-separated `key=value|value` pairs collected into
+    /// `[{"key": ..., "values": [...]}]`, the shape of meter filters
+    Filters,
+}
+
+impl Field {
+    pub const fn text(key: &'static str, prompt: &'static str) -> Self {
+        Self { key, prompt, kind: FieldKind::Text(None), required: false }
+    }
+
+    pub const fn number(key: &'static str, prompt: &'static str, default: f64) -> Self {
+        Self { key, prompt, kind: FieldKind::Number(default), required: true }
+    }
+
+    pub const fn choice(key: &'static str, prompt: &'static str, options: &'static [&'static str]) -> Self {
+        Self { key, prompt, kind: FieldKind::Choice(options), required: true }
+    }
+
+    pub const fn map(key: &'static str, prompt: &'static str) -> Self {
+        Self { key, prompt, kind: FieldKind::Map, required: false }
+    }
+
+    pub const fn filters(key: &'static str, prompt: &'static str) -> Self {
+        Self { key, prompt, kind: FieldKind::Filters, required: false }
+    }
+
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub const fn default(mut self, value: &'static str) -> Self {
+        self.kind = FieldKind::Text(Some(value));
+        self
+    }
+}
+
+impl Wizard {
+    /// Prompt for each field, check the payload parses as `T`, preview it, and
+    /// ask for confirmation. Returns `None` if the user declines.
+    pub fn run<T: DeserializeOwned>(&self) -> Result<Option<serde_json::Value>> {
+        start(self.kind)?;
+        let mut payload = serde_json::Map::new();
+        for field in self.fields {
+            if let Some(value) = prompt(field)? {
+                insert(&mut payload, field.key, value);
+            }
+        }
+        let payload = serde_json::Value::Object(payload);
+        serde_json::from_value::<T>(payload.clone())
+            .with_context(|| format!("Payload doesn't match the {} schema", self.kind))?;
+        if let Some(check) = self.check {
+            check(&payload)?;
+        }
+
+        Ok(confirm(self.kind, &payload)?.then_some(payload))
+    }
+}
+
+/// Check prompts can be shown and announce them; the first step of any
+/// create wizard
+pub fn start(kind: &str) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "No --json file given and stdin is not a terminal. Pass --json <FILE> or --edit."
+        );
+    }
+    output::info(&format!("Creating a {} (pass --json <FILE> to skip the prompts)", kind));
+    Ok(())
+}
+
+/// Preview a finished payload and ask whether to create it; the last step of
+/// any create wizard
+pub fn confirm(kind: &str, payload: &serde_json::Value) -> Result<bool> {
+    println!();
+    println!("{}", serde_json::to_string_pretty(payload)?);
+    let confirmed = Confirm::new()
+        .with_prompt(format!("  Create this {}?", kind))
+        .default(true)
+        .interact()?;
+    if !confirmed {
+        output::info("Cancelled.");
+    }
+    Ok(confirmed)
+}
+
+fn prompt(field: &Field) -> Result<Option<serde_json::Value>> {
+    let label = format!("  {}", field.prompt);
+    Ok(match field.kind {
+        FieldKind::Text(default) => {
+            let mut input = Input::<String>::new()
+                .with_prompt(label)
+                .allow_empty(!field.required);
+            if let Some(default) = default {
+                input = input.default(default.to_string());
+            }
+            let value = input.interact_text()?;
+            (!value.trim().is_empty()).then(|| value.trim().into())
+        }
+        FieldKind::Number(default) => {
+            let value = Input::<f64>::new()
+                .with_prompt(label)
+                .default(default)
+                .interact_text()?;
+            Some(value.into())
+        }
+        FieldKind::Choice(options) => {
+            let i = Select::new()
+                .with_prompt(label)
+                .items(options)
+                .default(0)
+                .interact()?;
+            Some(options[i].into())
+        }
+        FieldKind::Map => {
+            let value = Input::<String>::new()
+                .with_prompt(format!("{} (key=value, comma-separated)", label))
+                .allow_empty(true)
+                .validate_with(|s: &String| parse_map(s).map(|_| ()))
+                .interact_text()?;
+            let map = parse_map(&value).map_err(anyhow::Error::msg)?;
+            (!map.is_empty()).then_some(serde_json::Value::Object(map))
+        }
+        FieldKind::Filters => {
+            let value = Input::<String>::new()
+                .with_prompt(format!("{} (key=value|value, comma-separated)", label))
+                .allow_empty(true)
+                .validate_with(|s: &String| parse_filters(s).map(|_| ()))
+                .interact_text()?;
+            let filters = parse_filters(&value).map_err(anyhow::Error::msg)?;
+            (!filters.is_empty()).then_some(serde_json::Value::Array(filters))
+        }
+    })
+}
+
+fn parse_filters(s: &str) -> std::result::Result<Vec<serde_json::Value>, String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(parse_filter)
+        .collect()
+}
+
+/// Parse `key=value|value` into a meter filter
+pub fn parse_filter(s: &str) -> std::result::Result<serde_json::Value, String> {
+    let (key, values) = s
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' is not key=value", s))?;
+    let values: Vec<&str> = values.split('|').map(str::trim).filter(|v| !v.is_empty()).collect();
+    if key.trim().is_empty() || values.is_empty() {
+        return Err(format!("'{}' needs a property and at least one value", s));
+    }
+    Ok(serde_json::json!({ "key": key.trim(), "values": values }))
+}
+
+fn parse_map(s: &str) -> std::result::Result<serde_json::Map<String, serde_json::Value>, String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("'{}' is not key=value", pair))?;
+            Ok((k.trim().to_string(), v.trim().into()))
+        })
+        .collect()
+}
+
+/// Insert at a dotted key, creating intermediate objects
+fn insert(obj: &mut serde_json::Map<String, serde_json::Value>, key: &str, value: serde_json::Value) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            let child = obj
+                .entry(head)
+                .or_insert_with(|| serde_json::json!({}))
+                .as_object_mut()
+                .expect("nested key is an object");
+            insert(child, rest, value);
+        }
+        None => {
+            obj.insert(key.to_string(), value);
+        }
+    }
+}
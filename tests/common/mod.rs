@@ -0,0 +1,67 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A fresh HOME for one test, so nothing is read from the real ~/.flexprice
+pub fn home(name: &str) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("flexprice-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(home.join(".flexprice")).unwrap();
+    home
+}
+
+/// Run the CLI with `home` as HOME and, when given, `api_url` as the server
+pub fn flexprice(home: &Path, api_url: Option<&str>, args: &[&str]) -> Output {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_flexprice"));
+    cmd.args(args)
+        .env("HOME", home)
+        .env("NO_COLOR", "1")
+        .env_remove("FLEXPRICE_HTTP_PROXY")
+        .env_remove("FLEXPRICE_PROFILE");
+    match api_url {
+        Some(url) => cmd.env("FLEXPRICE_API_URL", url).env("FLEXPRICE_API_KEY", "test_key"),
+        None => cmd.env_remove("FLEXPRICE_API_URL").env_remove("FLEXPRICE_API_KEY"),
+    };
+    cmd.output().unwrap()
+}
+
+/// Serve `respond(path)` as JSON on a local port until the test exits, and
+/// return the base URL. `path` includes the query string.
+pub fn serve(respond: fn(&str) -> serde_json::Value) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            let _ = reader.read_exact(&mut body);
+            let path = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+            let body = respond(&path).to_string();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+    url
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use serde_json::{json, Value};
+
+fn server(path: &str) -> Value {
+    match path.split('?').next().unwrap_or_default() {
+        "/health" => json!({ "status": "ok" }),
+        "/v1/meters" => json!({ "items": [{ "id": "meter_api", "name": "api_call", "event_name": "api_call" }] }),
+        "/v1/plans/plan_global" => json!({ "id": "plan_global", "name": "Global" }),
+        "/v1/prices" => json!({ "items": [
+            { "id": "price_usd_fee", "amount": "100", "currency": "usd", "billing_period": "MONTHLY" },
+            { "id": "price_usd_api", "amount": "0.01", "currency": "usd", "billing_period": "MONTHLY",
+              "billing_model": "FLAT_FEE", "meter_id": "meter_api" },
+            { "id": "price_eur_fee", "amount": "90", "currency": "eur", "billing_period": "MONTHLY" },
+            { "id": "price_usd_annual", "amount": "1000", "currency": "usd", "billing_period": "ANNUAL" },
+        ] }),
+        _ => json!({ "items": [] }),
+    }
+}
+
+#[test]
+fn totals_are_kept_apart_by_currency_and_billing_period() {
+    let home = common::home("estimate");
+    let url = common::serve(server);
+    let out = common::flexprice(
+        &home,
+        Some(&url),
+        &["estimate", "--plan", "plan_global", "--usage", "api_call=1000", "-o", "json"],
+    );
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    let estimate: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let totals: Vec<(&str, &str, f64)> = estimate["totals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| (t["currency"].as_str().unwrap(), t["billing_period"].as_str().unwrap(), t["amount"].as_f64().unwrap()))
+        .collect();
+    assert_eq!(totals, [("EUR", "MONTHLY", 90.0), ("USD", "ANNUAL", 1000.0), ("USD", "MONTHLY", 110.0)]);
+    let _ = std::fs::remove_dir_all(&home);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use serde_json::{json, Value};
+
+fn server(path: &str) -> Value {
+    match path.split('?').next().unwrap_or_default() {
+        "/health" => json!({ "status": "ok" }),
+        "/v1/customers" => json!({
+            "items": [{ "id": "cust_1", "name": "Acme" }, { "id": "cust_2", "name": "Globex" }],
+            "total_count": 2,
+        }),
+        customer => json!({ "id": customer.trim_start_matches("/v1/customers/"), "name": "Acme" }),
+    }
+}
+
+/// What's printed for `args`, in each output format; `IDS` stands for a file
+/// listing both customers
+fn run(name: &str, args: &[&str]) -> Vec<(&'static str, String)> {
+    let home = common::home(name);
+    let ids = home.join("ids.txt");
+    std::fs::write(&ids, "cust_1\ncust_2\n").unwrap();
+    let url = common::serve(server);
+    let outputs = ["table", "json", "yaml", "csv", "ndjson"]
+        .into_iter()
+        .map(|format| {
+            let mut args: Vec<&str> = args.iter().map(|a| if *a == "IDS" { ids.to_str().unwrap() } else { a }).collect();
+            args.extend(["-o", format]);
+            let out = common::flexprice(&home, Some(&url), &args);
+            assert!(out.status.success(), "-o {}: {}", format, String::from_utf8_lossy(&out.stderr));
+            (format, String::from_utf8_lossy(&out.stdout).trim().to_string())
+        })
+        .collect();
+    let _ = std::fs::remove_dir_all(&home);
+    outputs
+}
+
+/// A string result is printed bare as a table, CSV, or YAML, and quoted as JSON
+fn assert_selects(outputs: Vec<(&str, String)>, expected: &str) {
+    for (format, printed) in outputs {
+        let wanted = match format {
+            "table" | "csv" | "yaml" => expected.to_string(),
+            _ => format!("{:?}", expected),
+        };
+        assert_eq!(printed, wanted, "-o {}", format);
+    }
+}
+
+#[test]
+fn paged_listings_are_queried_as_an_envelope() {
+    let outputs = run("query-paged", &["customers", "list", "--query", "join(',', items[].id)"]);
+    assert_selects(outputs, "cust_1,cust_2");
+    let outputs = run("query-pagination", &["customers", "list", "--query", "join('/', [to_string(pagination.count), to_string(pagination.total)])"]);
+    assert_selects(outputs, "2/2");
+}
+
+#[test]
+fn lists_fetched_whole_are_queried_as_an_array() {
+    let outputs = run("query-ids", &["customers", "get", "--ids-file", "IDS", "--query", "join(',', [].id)"]);
+    assert_selects(outputs, "cust_1,cust_2");
+    let outputs = run("query-raw", &["customers", "list", "--raw", "--query", "join(',', [].id)"]);
+    assert_selects(outputs, "cust_1,cust_2");
+}